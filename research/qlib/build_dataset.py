@@ -6,7 +6,10 @@ import pyarrow.parquet as pq
 from pathlib import Path
 import json
 
-from config import FEATURES_DIR, MANIFESTS_DIR, LABEL_COLUMNS, FEATURE_COLUMNS
+from config import (
+    FEATURES_DIR, MANIFESTS_DIR, LABEL_COLUMNS, FEATURE_COLUMNS,
+    DEPTH_FEATURE_COLUMNS, FEATURE_SCHEMA_VERSIONS,
+)
 
 
 def load_features(parquet_path: Path) -> pd.DataFrame:
@@ -24,9 +27,10 @@ def validate_schema(df: pd.DataFrame) -> bool:
         print(f"Missing required columns: {missing}")
         return False
     
-    version = df["schema_version"].iloc[0] if len(df) > 0 else None
-    if version != "features_v1":
-        print(f"Unexpected schema version: {version}")
+    versions = set(df["schema_version"].unique())
+    unknown = versions - set(FEATURE_SCHEMA_VERSIONS)
+    if unknown or not versions:
+        print(f"Unexpected schema versions: {sorted(unknown) or None}")
         return False
     
     return True
@@ -71,8 +75,10 @@ def create_splits(df: pd.DataFrame, train_pct: float = 0.7, valid_pct: float = 0
 
 def save_manifest(parquet_path: Path, df: pd.DataFrame, splits: dict, out_path: Path):
     """Save dataset manifest."""
+    depth_columns = [c for c in DEPTH_FEATURE_COLUMNS if c in df.columns and df[c].notna().any()]
     manifest = {
-        "schema_version": "manifest_v1",
+        "schema_version": "manifest_v2",
+        "feature_schema_version": "features_v2" if (df["schema_version"] == "features_v2").any() else "features_v1",
         "features_path": str(parquet_path),
         "asset": df["asset"].iloc[0] if len(df) > 0 else "unknown",
         "duration": df["duration"].iloc[0] if len(df) > 0 else "5m",
@@ -83,7 +89,7 @@ def save_manifest(parquet_path: Path, df: pd.DataFrame, splits: dict, out_path:
         "source_inputs": [str(parquet_path)],
         **splits,
         "label_columns": LABEL_COLUMNS,
-        "feature_columns": FEATURE_COLUMNS,
+        "feature_columns": FEATURE_COLUMNS + depth_columns,
     }
     
     with open(out_path, "w") as f:
@@ -48,3 +48,15 @@ FEATURE_COLUMNS = [
     "realized_vol_15s", "realized_vol_30s", "realized_vol_60s",
     "age_s", "time_remaining_s",
 ]
+
+# features_v2 level-depth columns, present when the export had book levels
+DEPTH_FEATURE_COLUMNS = [
+    f"{token}_{side}_depth_{k}"
+    for token in ("yes", "no") for side in ("bid", "ask") for k in range(1, 6)
+] + [
+    "yes_imbalance_1", "yes_imbalance_5", "no_imbalance_1", "no_imbalance_5",
+    "yes_microprice", "no_microprice", "yes_queue_mid", "no_queue_mid",
+    "yes_bid_depth_chg_5s", "yes_ask_depth_chg_5s", "no_bid_depth_chg_5s", "no_ask_depth_chg_5s",
+]
+
+FEATURE_SCHEMA_VERSIONS = ("features_v1", "features_v2")
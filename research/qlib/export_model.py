@@ -0,0 +1,68 @@
+"""Export a trained model to JSON for in-process scoring in Rust."""
+
+import argparse
+import json
+from pathlib import Path
+
+import joblib
+
+from config import MODELS_DIR
+
+
+def export_logistic(pipeline):
+    """StandardScaler + LogisticRegression pipeline weights."""
+    scaler = pipeline.named_steps["scaler"]
+    classifier = pipeline.named_steps["classifier"]
+    return {
+        "kind": "logistic",
+        "intercept": float(classifier.intercept_[0]),
+        "coefficients": [float(c) for c in classifier.coef_[0]],
+        "mean": [float(m) for m in scaler.mean_],
+        "scale": [float(s) for s in scaler.scale_],
+    }
+
+
+def export_trees(booster):
+    """LightGBM binary booster as its tree dump."""
+    dump = booster.dump_model()
+    objective = dump.get("objective", "")
+    if not objective.startswith("binary"):
+        raise ValueError(f"Only binary objectives are supported, got {objective!r}")
+    for tree in dump["tree_info"]:
+        if "||" in json.dumps(tree["tree_structure"]):
+            raise ValueError("Categorical splits are not supported")
+    return {
+        "kind": "trees",
+        "trees": [tree["tree_structure"] for tree in dump["tree_info"]],
+    }
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Export model JSON")
+    parser.add_argument("--model", required=True, help="Path to model file from train.py")
+    parser.add_argument("--output", default=None, help="Output JSON path")
+    args = parser.parse_args()
+
+    model_path = Path(args.model)
+    model_data = joblib.load(model_path)
+
+    if model_data["model_type"] == "sklearn":
+        model = export_logistic(model_data["model"])
+    else:  # lightgbm
+        model = export_trees(model_data["model"])
+
+    exported = {
+        "model_version": model_path.stem,
+        "target": model_data["target"],
+        "feature_columns": model_data["feature_columns"],
+        "model": model,
+    }
+
+    output_path = Path(args.output) if args.output else MODELS_DIR / f"{model_path.stem}.json"
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    output_path.write_text(json.dumps(exported))
+    print(f"Exported {model['kind']} model for {model_data['target']} to {output_path}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,460 @@
+//! Fill Simulation
+//!
+//! Fills backtest orders against recorded book depth instead of assuming
+//! unlimited size at the touch. Orders are submitted `latency_ms` after the
+//! decision, walk the book up to a slippage limit, may fill partially and pay
+//! the `CostModel` taker fee.
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::bot::feed_base::{as_f64, BookChangeSide, OutcomeSide};
+use crate::bot::research::CostModel;
+use crate::bot::shadow::{ShadowFill, ShadowFills, TokenSide, TouchFills};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FillModelKind {
+    /// Unlimited size at the best quote
+    TopOfBook,
+    /// Walk the recorded levels, filling partially when the book runs out
+    Depth,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct FillArgs {
+    /// How orders fill against the recorded book
+    #[arg(long, value_enum, default_value_t = FillModelKind::TopOfBook)]
+    pub fill_model: FillModelKind,
+
+    /// Submission latency: orders fill against the book this many ms after the decision
+    #[arg(long, default_value = "0")]
+    pub latency_ms: u64,
+
+    /// Taker fee rate (defaults to the cost model's rate with --fill-model depth, 0 otherwise)
+    #[arg(long)]
+    pub taker_fee: Option<f64>,
+
+    /// Deepest price beyond the touch an order may take (depth model only)
+    #[arg(long, default_value = "0.05")]
+    pub max_slippage: f64,
+}
+
+impl FillArgs {
+    pub fn model(&self) -> FillModel {
+        let mut cost = CostModel::default();
+        cost.taker_fee = self.taker_fee.unwrap_or(match self.fill_model {
+            FillModelKind::Depth => cost.taker_fee,
+            FillModelKind::TopOfBook => 0.0,
+        });
+        FillModel {
+            kind: self.fill_model,
+            latency_ms: self.latency_ms,
+            max_slippage: self.max_slippage,
+            cost,
+        }
+    }
+}
+
+/// `(price, size)` levels of one side of a book
+pub type Levels = Vec<(f64, f64)>;
+
+/// Result of one simulated taker order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub shares: f64,
+    pub avg_price: f64,
+    /// Shares times price, before fees
+    pub notional: f64,
+    pub fee: f64,
+    /// Part of the order that found no liquidity within the slippage limit,
+    /// in USD for buys and shares for sells
+    pub unfilled: f64,
+}
+
+impl Fill {
+    pub fn is_partial(&self) -> bool {
+        self.unfilled > 1e-9
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FillModel {
+    pub kind: FillModelKind,
+    pub latency_ms: u64,
+    pub max_slippage: f64,
+    pub cost: CostModel,
+}
+
+impl Default for FillModel {
+    fn default() -> Self {
+        FillArgs {
+            fill_model: FillModelKind::TopOfBook,
+            latency_ms: 0,
+            taker_fee: None,
+            max_slippage: 0.05,
+        }
+        .model()
+    }
+}
+
+impl FillModel {
+    /// Top of book with no fees or latency fills exactly as the strategies
+    /// assume, so backtests can skip replaying the book entirely.
+    pub fn is_frictionless(&self) -> bool {
+        self.kind == FillModelKind::TopOfBook && self.latency_ms == 0 && self.cost.taker_fee == 0.0
+    }
+
+    pub fn latency_secs(&self) -> f64 {
+        self.latency_ms as f64 / 1000.0
+    }
+
+    /// Spend up to `budget_usd` buying from `asks` (best first)
+    pub fn buy(&self, asks: &[(f64, f64)], budget_usd: f64) -> Option<Fill> {
+        let limit = asks.first()?.0 + self.max_slippage;
+        let mut remaining = budget_usd;
+        let (mut shares, mut notional) = (0.0, 0.0);
+        for (price, size) in self.levels(asks) {
+            if price <= 0.0 || price > limit + 1e-9 || remaining <= 1e-9 {
+                break;
+            }
+            let take = (remaining / price).min(size);
+            shares += take;
+            notional += take * price;
+            remaining -= take * price;
+        }
+        self.fill(shares, notional, remaining.max(0.0))
+    }
+
+    /// Sell up to `shares` into `bids` (best first)
+    pub fn sell(&self, bids: &[(f64, f64)], shares: f64) -> Option<Fill> {
+        let limit = bids.first()?.0 - self.max_slippage;
+        let mut remaining = shares;
+        let mut notional = 0.0;
+        for (price, size) in self.levels(bids) {
+            if price <= 0.0 || price < limit - 1e-9 || remaining <= 1e-9 {
+                break;
+            }
+            let take = remaining.min(size);
+            notional += take * price;
+            remaining -= take;
+        }
+        self.fill(shares - remaining, notional, remaining.max(0.0))
+    }
+
+    fn levels(&self, levels: &[(f64, f64)]) -> Levels {
+        match self.kind {
+            FillModelKind::Depth => levels.to_vec(),
+            FillModelKind::TopOfBook => levels.iter().take(1).map(|(price, _)| (*price, f64::INFINITY)).collect(),
+        }
+    }
+
+    fn fill(&self, shares: f64, notional: f64, unfilled: f64) -> Option<Fill> {
+        if shares <= 1e-9 {
+            return None;
+        }
+        Some(Fill {
+            shares,
+            avg_price: notional / shares,
+            notional,
+            fee: self.cost.calculate_fee(notional, true),
+            unfilled,
+        })
+    }
+}
+
+/// Fill outcomes over a backtest run
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct FillStats {
+    pub fees_paid: f64,
+    pub partial_fills: usize,
+    pub missed_entries: usize,
+}
+
+/// Executes backtest orders with `FillModel` against the replayed book as
+/// of `latency_ms` after the decision. Without a book (the frictionless
+/// model) orders fill at the touch.
+pub struct BookFills<'a> {
+    model: &'a FillModel,
+    book: Option<&'a mut BookReplay>,
+    /// Exchange time of the snapshot being decided on
+    ts: f64,
+    pub stats: &'a mut FillStats,
+}
+
+impl<'a> BookFills<'a> {
+    pub fn new(model: &'a FillModel, book: Option<&'a mut BookReplay>, ts: f64, stats: &'a mut FillStats) -> Self {
+        Self { model, book, ts, stats }
+    }
+
+    fn levels(&mut self, side: TokenSide) -> Option<(Levels, Levels)> {
+        let outcome = match side {
+            TokenSide::Yes => OutcomeSide::Yes,
+            TokenSide::No => OutcomeSide::No,
+        };
+        let fill_ts = self.ts + self.model.latency_secs();
+        Some(self.book.as_mut()?.levels_at(fill_ts, outcome))
+    }
+
+    fn record(&mut self, fill: Fill) -> ShadowFill {
+        if fill.is_partial() {
+            self.stats.partial_fills += 1;
+        }
+        self.stats.fees_paid += fill.fee;
+        ShadowFill { shares: fill.shares, avg_price: fill.avg_price, notional: fill.notional, fee: fill.fee }
+    }
+}
+
+impl ShadowFills for BookFills<'_> {
+    fn buy(&mut self, side: TokenSide, touch: f64, stake_usd: f64) -> Option<ShadowFill> {
+        let Some((_, asks)) = self.levels(side) else {
+            return TouchFills.buy(side, touch, stake_usd);
+        };
+        match self.model.buy(&asks, stake_usd) {
+            Some(fill) => Some(self.record(fill)),
+            None => {
+                self.stats.missed_entries += 1;
+                None
+            }
+        }
+    }
+
+    fn sell(&mut self, side: TokenSide, touch: f64, shares: f64) -> Option<ShadowFill> {
+        let Some((bids, _)) = self.levels(side) else {
+            return TouchFills.sell(side, touch, shares);
+        };
+        let fill = self.model.sell(&bids, shares)?;
+        Some(self.record(fill))
+    }
+}
+
+/// One recorded change to a token's book
+#[derive(Debug, Clone)]
+pub enum BookUpdate {
+    /// Best bid/ask only; stands in for the levels when none were recorded
+    Quote { bid: f64, ask: f64 },
+    Snapshot { bids: Levels, asks: Levels },
+    Change { side: BookChangeSide, price: f64, size: f64 },
+}
+
+impl BookUpdate {
+    /// Parse a PMXT `bids`/`asks` JSON array of `[price, size]` pairs or
+    /// `{price, size}` objects.
+    pub fn parse_levels(json: &str) -> Option<Levels> {
+        let value: Value = serde_json::from_str(json).ok()?;
+        let levels = value
+            .as_array()?
+            .iter()
+            .filter_map(|level| match level {
+                Value::Array(pair) => Some((as_f64(pair.first()?)?, as_f64(pair.get(1)?)?)),
+                Value::Object(_) => Some((as_f64(level.get("price")?)?, as_f64(level.get("size")?)?)),
+                _ => None,
+            })
+            .collect();
+        Some(levels)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct TokenBook {
+    bid: f64,
+    ask: f64,
+    /// Price-sorted ascending; empty when the source only recorded quotes
+    bids: Levels,
+    asks: Levels,
+}
+
+impl TokenBook {
+    fn apply(&mut self, update: &BookUpdate) {
+        match update {
+            BookUpdate::Quote { bid, ask } => {
+                self.bid = *bid;
+                self.ask = *ask;
+            }
+            BookUpdate::Snapshot { bids, asks } => {
+                self.bids = sorted_levels(bids);
+                self.asks = sorted_levels(asks);
+            }
+            BookUpdate::Change { side, price, size } => {
+                let levels = match side {
+                    BookChangeSide::Buy => &mut self.bids,
+                    BookChangeSide::Sell => &mut self.asks,
+                };
+                set_level(levels, *price, *size);
+            }
+        }
+    }
+
+    /// Bids best first; levels through the recorded quote are stale and dropped
+    fn bids(&self) -> Levels {
+        let live: Levels = self
+            .bids
+            .iter()
+            .rev()
+            .filter(|(price, _)| self.bid <= 0.0 || *price <= self.bid + 1e-9)
+            .copied()
+            .collect();
+        if live.is_empty() && self.bid > 0.0 {
+            return vec![(self.bid, f64::INFINITY)];
+        }
+        live
+    }
+
+    /// Asks best first
+    fn asks(&self) -> Levels {
+        let live: Levels = self
+            .asks
+            .iter()
+            .filter(|(price, _)| self.ask <= 0.0 || *price >= self.ask - 1e-9)
+            .copied()
+            .collect();
+        if live.is_empty() && self.ask > 0.0 {
+            return vec![(self.ask, f64::INFINITY)];
+        }
+        live
+    }
+}
+
+fn sorted_levels(levels: &[(f64, f64)]) -> Levels {
+    let mut sorted: Levels =
+        levels.iter().copied().filter(|(_, size)| *size > 0.0).collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    sorted
+}
+
+fn set_level(levels: &mut Levels, price: f64, size: f64) {
+    match levels.binary_search_by(|(p, _)| p.total_cmp(&price)) {
+        Ok(i) if size > 0.0 => levels[i].1 = size,
+        Ok(i) => {
+            levels.remove(i);
+        }
+        Err(i) if size > 0.0 => levels.insert(i, (price, size)),
+        Err(_) => {}
+    }
+}
+
+/// Book of both tokens of one market replayed forward in time. Lookups must
+/// be made with non-decreasing timestamps.
+#[derive(Debug, Default)]
+pub struct BookReplay {
+    updates: Vec<(f64, OutcomeSide, BookUpdate)>,
+    cursor: usize,
+    yes: TokenBook,
+    no: TokenBook,
+}
+
+impl BookReplay {
+    pub fn push(&mut self, ts: f64, side: OutcomeSide, update: BookUpdate) {
+        self.updates.push((ts, side, update));
+    }
+
+    fn advance(&mut self, ts: f64) {
+        while let Some((update_ts, side, update)) = self.updates.get(self.cursor) {
+            if *update_ts > ts {
+                break;
+            }
+            match side {
+                OutcomeSide::Yes => self.yes.apply(update),
+                OutcomeSide::No => self.no.apply(update),
+            }
+            self.cursor += 1;
+        }
+    }
+
+    /// `(bids, asks)` of `side`, best first, as of `ts`
+    pub fn levels_at(&mut self, ts: f64, side: OutcomeSide) -> (Levels, Levels) {
+        self.advance(ts);
+        let book = match side {
+            OutcomeSide::Yes => &self.yes,
+            OutcomeSide::No => &self.no,
+        };
+        (book.bids(), book.asks())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth_model() -> FillModel {
+        FillArgs {
+            fill_model: FillModelKind::Depth,
+            latency_ms: 250,
+            taker_fee: Some(0.02),
+            max_slippage: 0.03,
+        }
+        .model()
+    }
+
+    #[test]
+    fn buy_walks_levels_and_stops_at_slippage_limit() {
+        let asks = [(0.50, 2.0), (0.52, 3.0), (0.60, 100.0)];
+        let fill = depth_model().buy(&asks, 5.0).unwrap();
+        assert!((fill.shares - 5.0).abs() < 1e-9);
+        assert!((fill.notional - 2.56).abs() < 1e-9);
+        assert!((fill.avg_price - 0.512).abs() < 1e-9);
+        assert!((fill.fee - 0.0512).abs() < 1e-9);
+        assert!(fill.is_partial());
+        assert!((fill.unfilled - 2.44).abs() < 1e-9);
+
+        let top = FillModel::default().buy(&asks, 5.0).unwrap();
+        assert!((top.shares - 10.0).abs() < 1e-9);
+        assert!(!top.is_partial());
+        assert!(FillModel::default().is_frictionless());
+    }
+
+    #[test]
+    fn replay_applies_changes_in_time_order() {
+        let mut book = BookReplay::default();
+        book.push(1.0, OutcomeSide::Yes, BookUpdate::Snapshot {
+            bids: vec![(0.48, 10.0), (0.47, 5.0)],
+            asks: vec![(0.50, 4.0), (0.51, 8.0)],
+        });
+        book.push(1.0, OutcomeSide::Yes, BookUpdate::Quote { bid: 0.48, ask: 0.50 });
+        book.push(2.0, OutcomeSide::Yes, BookUpdate::Change { side: BookChangeSide::Sell, price: 0.50, size: 0.0 });
+        book.push(2.0, OutcomeSide::Yes, BookUpdate::Quote { bid: 0.48, ask: 0.51 });
+        book.push(2.0, OutcomeSide::No, BookUpdate::Quote { bid: 0.49, ask: 0.52 });
+
+        let (bids, asks) = book.levels_at(1.5, OutcomeSide::Yes);
+        assert_eq!(bids, vec![(0.48, 10.0), (0.47, 5.0)]);
+        assert_eq!(asks, vec![(0.50, 4.0), (0.51, 8.0)]);
+
+        let (_, asks) = book.levels_at(2.0, OutcomeSide::Yes);
+        assert_eq!(asks, vec![(0.51, 8.0)]);
+        // Quote-only tokens fall back to unlimited size at the touch
+        let (bids, _) = book.levels_at(2.0, OutcomeSide::No);
+        assert_eq!(bids, vec![(0.49, f64::INFINITY)]);
+
+        assert_eq!(
+            BookUpdate::parse_levels(r#"[["0.5","10"],{"price":"0.49","size":"3"}]"#),
+            Some(vec![(0.5, 10.0), (0.49, 3.0)])
+        );
+    }
+
+    #[test]
+    fn book_fills_execute_after_latency_and_count_misses() {
+        let mut book = BookReplay::default();
+        book.push(1.0, OutcomeSide::Yes, BookUpdate::Snapshot { bids: vec![(0.48, 10.0)], asks: vec![(0.50, 4.0)] });
+        book.push(1.0, OutcomeSide::Yes, BookUpdate::Quote { bid: 0.48, ask: 0.50 });
+        book.push(1.2, OutcomeSide::Yes, BookUpdate::Change { side: BookChangeSide::Sell, price: 0.52, size: 10.0 });
+        book.push(1.2, OutcomeSide::Yes, BookUpdate::Change { side: BookChangeSide::Sell, price: 0.50, size: 0.0 });
+        book.push(1.2, OutcomeSide::Yes, BookUpdate::Quote { bid: 0.48, ask: 0.52 });
+
+        let model = depth_model();
+        let mut stats = FillStats::default();
+        let mut fills = BookFills::new(&model, Some(&mut book), 1.0, &mut stats);
+        // The decision saw 0.50, but the order lands 250ms later at 0.52
+        let fill = fills.buy(TokenSide::Yes, 0.50, 2.6).unwrap();
+        assert!((fill.avg_price - 0.52).abs() < 1e-9);
+        assert!((fill.fee - 0.052).abs() < 1e-9);
+        assert!(fills.buy(TokenSide::No, 0.50, 1.0).is_none());
+        assert_eq!(stats.missed_entries, 1);
+        assert!((stats.fees_paid - 0.052).abs() < 1e-9);
+
+        let mut stats = FillStats::default();
+        let frictionless = FillModel::default();
+        let touch = BookFills::new(&frictionless, None, 1.0, &mut stats).sell(TokenSide::Yes, 0.40, 5.0).unwrap();
+        assert_eq!((touch.shares, touch.avg_price, touch.fee), (5.0, 0.40, 0.0));
+    }
+}
@@ -1,9 +1,12 @@
 pub mod data;
+pub mod fills;
 pub mod metrics;
+pub mod optimizer;
 pub mod pmxt;
 pub mod replay;
 
 pub use data::{BeckerParser, MarketData, Trade};
+pub use fills::{BookFills, BookReplay, BookUpdate, FillArgs, FillModel, FillModelKind, FillStats};
 pub use metrics::{BacktestMetrics, TradeResult};
 pub use pmxt::{fetch_btc_updown, PmxtFetcher, PmxtRow};
 pub use replay::{BacktestConfig, BacktestEngine};
@@ -0,0 +1,432 @@
+//! Parameter Optimizer
+//!
+//! Search spaces over any serde-serializable strategy config, addressed by
+//! field path (`min_heai`, `kernels.0.params.beta`), and the grid, random and
+//! TPE (tree-structured Parzen estimator) samplers that walk them.
+//!
+//! Random and TPE search work in unit coordinates, one per parameter in
+//! `[0, 1)`, decoded to config values on apply. Choice parameters map the
+//! unit interval onto equal-width bins.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMethod {
+    /// Every combination; ranges split into `--grid-points` values or by step
+    Grid,
+    /// Independent uniform samples
+    Random,
+    /// Tree-structured Parzen estimator, seeded with random samples
+    Tpe,
+}
+
+impl SearchMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Grid => "grid",
+            Self::Random => "random",
+            Self::Tpe => "tpe",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamDomain {
+    /// Numeric range, inclusive; `integer` is taken from the config's field type
+    Range { low: f64, high: f64, step: Option<f64>, integer: bool },
+    Choice(Vec<Value>),
+}
+
+/// One searched parameter: `path=low:high[:step]` or `path=a,b,c`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSpec {
+    pub path: String,
+    pub domain: ParamDomain,
+}
+
+impl FromStr for ParamSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (path, spec) = s
+            .split_once('=')
+            .with_context(|| format!("Expected path=low:high[:step] or path=a,b,c, got {s}"))?;
+        let path = path.trim().to_string();
+        if path.is_empty() {
+            anyhow::bail!("Missing parameter path in {s}");
+        }
+
+        if spec.contains(':') {
+            let bounds = spec
+                .split(':')
+                .map(|v| v.trim().parse::<f64>().with_context(|| format!("Bad number {v:?} in {s}")))
+                .collect::<Result<Vec<_>>>()?;
+            let (low, high, step) = match bounds[..] {
+                [low, high] => (low, high, None),
+                [low, high, step] if step > 0.0 => (low, high, Some(step)),
+                _ => anyhow::bail!("Expected low:high or low:high:step with step > 0 in {s}"),
+            };
+            if !low.is_finite() || !high.is_finite() {
+                anyhow::bail!("Range bounds must be finite in {s}");
+            }
+            if low > high {
+                anyhow::bail!("Empty range in {s}");
+            }
+            return Ok(Self { path, domain: ParamDomain::Range { low, high, step, integer: false } });
+        }
+
+        let choices: Vec<Value> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_string())))
+            .collect();
+        if choices.is_empty() {
+            anyhow::bail!("No values in {s}");
+        }
+        Ok(Self { path, domain: ParamDomain::Choice(choices) })
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |node, key| match node {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.').try_fold(value, |node, key| match node {
+        Value::Object(map) => map.get_mut(key),
+        Value::Array(items) => items.get_mut(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Dotted paths of every scalar field, for error messages
+fn scalar_paths(value: &Value, prefix: &str, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                scalar_paths(child, &path, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                scalar_paths(child, &format!("{prefix}.{i}"), out);
+            }
+        }
+        _ => out.push(prefix.to_string()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchSpace {
+    pub params: Vec<ParamSpec>,
+}
+
+impl SearchSpace {
+    /// Check every path against the serialized base config and take integer
+    /// ranges from the field's type
+    pub fn resolve(mut params: Vec<ParamSpec>, base: &Value) -> Result<Self> {
+        if params.is_empty() {
+            anyhow::bail!("No --param given");
+        }
+        for param in &mut params {
+            let Some(current) = lookup(base, &param.path) else {
+                let mut available = Vec::new();
+                scalar_paths(base, "", &mut available);
+                anyhow::bail!("Unknown parameter {}; config fields: {}", param.path, available.join(", "));
+            };
+            if let ParamDomain::Range { integer, .. } = &mut param.domain {
+                if !current.is_number() {
+                    anyhow::bail!("Parameter {} is not numeric ({})", param.path, current);
+                }
+                *integer = current.is_i64() || current.is_u64();
+            }
+        }
+        Ok(Self { params })
+    }
+
+    pub fn dims(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Config values for a point in unit coordinates
+    pub fn decode(&self, unit: &[f64]) -> Vec<Value> {
+        self.params
+            .iter()
+            .zip(unit)
+            .map(|(param, u)| {
+                let u = u.clamp(0.0, 1.0);
+                match &param.domain {
+                    ParamDomain::Range { low, high, step, integer } => {
+                        let mut x = low + u * (high - low);
+                        if let Some(step) = step {
+                            x = (low + ((x - low) / step).round() * step).min(*high);
+                        }
+                        number(x, *integer)
+                    }
+                    ParamDomain::Choice(choices) => {
+                        let idx = ((u * choices.len() as f64) as usize).min(choices.len() - 1);
+                        choices[idx].clone()
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Every combination of the grid values of each parameter
+    pub fn grid(&self, points_per_range: usize) -> Vec<Vec<Value>> {
+        let axes: Vec<Vec<Value>> = self.params.iter().map(|p| axis(&p.domain, points_per_range)).collect();
+        axes.iter().fold(vec![Vec::new()], |acc, axis| {
+            acc.iter()
+                .flat_map(|prefix| {
+                    axis.iter().map(move |v| {
+                        let mut point = prefix.clone();
+                        point.push(v.clone());
+                        point
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// The base config with a point's values written in
+    pub fn apply(&self, base: &Value, point: &[Value]) -> Value {
+        let mut config = base.clone();
+        for (param, value) in self.params.iter().zip(point) {
+            if let Some(slot) = lookup_mut(&mut config, &param.path) {
+                *slot = value.clone();
+            }
+        }
+        config
+    }
+
+    /// `path=value` pairs of a point
+    pub fn describe(&self, point: &[Value]) -> String {
+        self.params
+            .iter()
+            .zip(point)
+            .map(|(param, value)| format!("{}={}", param.path, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// `{path: value}` object of a point
+    pub fn describe_json(&self, point: &[Value]) -> Value {
+        Value::Object(self.params.iter().zip(point).map(|(param, value)| (param.path.clone(), value.clone())).collect())
+    }
+}
+
+fn number(x: f64, integer: bool) -> Value {
+    if integer {
+        Value::from(x.round() as i64)
+    } else {
+        serde_json::Number::from_f64(x).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn axis(domain: &ParamDomain, points: usize) -> Vec<Value> {
+    match domain {
+        ParamDomain::Choice(choices) => choices.clone(),
+        ParamDomain::Range { low, high, step, integer } => {
+            let mut values: Vec<f64> = match step {
+                Some(step) => {
+                    let n = ((high - low) / step + 1e-9).floor() as usize;
+                    (0..=n).map(|i| low + i as f64 * step).collect()
+                }
+                None if points <= 1 || high == low => vec![*low],
+                None => (0..points).map(|i| low + (high - low) * i as f64 / (points - 1) as f64).collect(),
+            };
+            if *integer {
+                values = values.iter().map(|v| v.round()).collect();
+                values.dedup();
+            }
+            values.into_iter().map(|v| number(v, *integer)).collect()
+        }
+    }
+}
+
+pub fn random_point(dims: usize, rng: &mut impl Rng) -> Vec<f64> {
+    (0..dims).map(|_| rng.random::<f64>()).collect()
+}
+
+/// Tree-structured Parzen estimator over unit coordinates. Completed trials
+/// are split at the `gamma` quantile of score into good and bad; candidates
+/// drawn around good trials are ranked by the density ratio `l(x)/g(x)`,
+/// with each dimension modelled independently.
+#[derive(Debug, Clone, Copy)]
+pub struct Tpe {
+    pub gamma: f64,
+    pub candidates: usize,
+    /// Trials sampled uniformly before the model is used
+    pub startup: usize,
+}
+
+impl Default for Tpe {
+    fn default() -> Self {
+        Self { gamma: 0.25, candidates: 24, startup: 10 }
+    }
+}
+
+/// Parzen density on one dimension: Gaussian kernels at the observations
+/// plus one uniform prior component
+struct Parzen {
+    centers: Vec<f64>,
+    bandwidth: f64,
+}
+
+impl Parzen {
+    fn new(centers: Vec<f64>) -> Self {
+        let n = centers.len().max(1) as f64;
+        let mean = centers.iter().sum::<f64>() / n;
+        let std = (centers.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / n).sqrt();
+        // Scott's rule, kept wide enough to explore on few or identical points
+        let bandwidth = (1.06 * std * n.powf(-0.2)).clamp(0.05, 0.5);
+        Self { centers, bandwidth }
+    }
+
+    fn density(&self, x: f64) -> f64 {
+        let norm = 1.0 / (self.bandwidth * (2.0 * std::f64::consts::PI).sqrt());
+        let kernels: f64 = self
+            .centers
+            .iter()
+            .map(|c| norm * (-0.5 * ((x - c) / self.bandwidth).powi(2)).exp())
+            .sum();
+        (kernels + 1.0) / (self.centers.len() as f64 + 1.0)
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        let pick = rng.random_range(0..=self.centers.len());
+        if pick == self.centers.len() {
+            return rng.random::<f64>();
+        }
+        let noise = Normal::new(0.0, self.bandwidth).expect("positive bandwidth");
+        (self.centers[pick] + noise.sample(rng)).clamp(0.0, 1.0 - 1e-9)
+    }
+}
+
+impl Tpe {
+    /// Next point to evaluate given `(unit point, score)` history, higher
+    /// scores being better
+    pub fn suggest(&self, history: &[(Vec<f64>, f64)], dims: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let finite: Vec<&(Vec<f64>, f64)> = history.iter().filter(|(_, s)| s.is_finite()).collect();
+        if finite.len() < self.startup.max(2) {
+            return random_point(dims, rng);
+        }
+        let mut ranked = finite;
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let n_good = ((self.gamma * ranked.len() as f64).ceil() as usize).clamp(1, ranked.len() - 1);
+        let (good, bad) = ranked.split_at(n_good);
+
+        let models: Vec<(Parzen, Parzen)> = (0..dims)
+            .map(|d| {
+                (
+                    Parzen::new(good.iter().map(|(x, _)| x[d]).collect()),
+                    Parzen::new(bad.iter().map(|(x, _)| x[d]).collect()),
+                )
+            })
+            .collect();
+
+        (0..self.candidates.max(1))
+            .map(|_| {
+                let x: Vec<f64> = models.iter().map(|(l, _)| l.sample(rng)).collect();
+                let ratio: f64 = models
+                    .iter()
+                    .zip(&x)
+                    .map(|((l, g), xi)| l.density(*xi).ln() - g.density(*xi).ln())
+                    .sum();
+                (x, ratio)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(x, _)| x)
+            .unwrap_or_else(|| random_point(dims, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use serde_json::json;
+
+    fn space(specs: &[&str], base: &Value) -> SearchSpace {
+        let params = specs.iter().map(|s| s.parse().unwrap()).collect();
+        SearchSpace::resolve(params, base).unwrap()
+    }
+
+    #[test]
+    fn parses_ranges_and_choices() {
+        let range: ParamSpec = "min_heai=0.01:0.2:0.01".parse().unwrap();
+        assert_eq!(
+            range.domain,
+            ParamDomain::Range { low: 0.01, high: 0.2, step: Some(0.01), integer: false }
+        );
+        let choice: ParamSpec = "mode=Fused,Qlib,3".parse().unwrap();
+        assert_eq!(
+            choice.domain,
+            ParamDomain::Choice(vec![json!("Fused"), json!("Qlib"), json!(3)])
+        );
+        assert!("min_heai=0.3:0.1".parse::<ParamSpec>().is_err());
+        assert!("min_heai=NaN:0.1".parse::<ParamSpec>().is_err());
+        assert!("min_heai".parse::<ParamSpec>().is_err());
+    }
+
+    #[test]
+    fn resolves_paths_and_integer_fields_against_the_config() {
+        let base = json!({"strategy": "hawkes-flow", "min_heai": 0.03, "vpin_window": 50});
+        let space = space(&["min_heai=0.0:0.1", "vpin_window=10:100"], &base);
+        assert_eq!(space.decode(&[0.5, 0.5]), vec![json!(0.05), json!(55)]);
+
+        let config = space.apply(&base, &[json!(0.08), json!(20)]);
+        assert_eq!(config, json!({"strategy": "hawkes-flow", "min_heai": 0.08, "vpin_window": 20}));
+
+        let unknown = SearchSpace::resolve(vec!["nope=0:1".parse().unwrap()], &base);
+        assert!(unknown.unwrap_err().to_string().contains("vpin_window"));
+    }
+
+    #[test]
+    fn grid_covers_every_combination() {
+        let base = json!({"a": 0.0, "n": 1, "mode": "x"});
+        let space = space(&["a=0:1", "n=1:3:1", "mode=x,y"], &base);
+        let grid = space.grid(3);
+        assert_eq!(grid.len(), 3 * 3 * 2);
+        assert_eq!(grid[0], vec![json!(0.0), json!(1), json!("x")]);
+        assert_eq!(grid.last().unwrap(), &vec![json!(1.0), json!(3), json!("y")]);
+    }
+
+    #[test]
+    fn tpe_concentrates_near_the_optimum() {
+        let objective = |x: &[f64]| -((x[0] - 0.8).powi(2) + (x[1] - 0.2).powi(2));
+        let tpe = Tpe::default();
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut history = Vec::new();
+        for _ in 0..60 {
+            let x = tpe.suggest(&history, 2, &mut rng);
+            let score = objective(&x);
+            history.push((x, score));
+        }
+        let best_random = history[..tpe.startup].iter().map(|(_, s)| *s).fold(f64::MIN, f64::max);
+        let best = history.iter().map(|(_, s)| *s).fold(f64::MIN, f64::max);
+        assert!(best > -0.005, "best {best}");
+        assert!(best > best_random);
+
+        // Later suggestions sit mostly in the good region
+        let late_close = history[40..]
+            .iter()
+            .filter(|(x, _)| (x[0] - 0.8).abs() < 0.2 && (x[1] - 0.2).abs() < 0.2)
+            .count();
+        assert!(late_close >= 10, "{late_close} of 20 near the optimum");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::bot::backtest::data::{BeckerParser, MarketData, PriceSnapshot};
+use crate::bot::backtest::fills::FillModel;
 use crate::bot::backtest::metrics::{BacktestMetrics, ParameterSweepResult, TradeResult};
 use crate::bot::candles::CandleEngine;
 use crate::bot::indicators::IndicatorEngine;
@@ -15,6 +16,7 @@ pub struct BacktestConfig {
     pub min_time_remaining: i64,
     pub position_size_usd: f64,
     pub starting_capital: f64,
+    pub fill_model: FillModel,
 }
 
 impl Default for BacktestConfig {
@@ -27,6 +29,7 @@ impl Default for BacktestConfig {
             min_time_remaining: 30,
             position_size_usd: 1.0,
             starting_capital: 100.0,
+            fill_model: FillModel::default(),
         }
     }
 }
@@ -52,6 +55,8 @@ struct SimPosition {
     entry_price: f64,
     entry_timestamp: i64,
     size_usd: f64,
+    shares: f64,
+    entry_fee: f64,
 }
 
 impl Default for SimPosition {
@@ -61,6 +66,8 @@ impl Default for SimPosition {
             entry_price: 0.0,
             entry_timestamp: 0,
             size_usd: 0.0,
+            shares: 0.0,
+            entry_fee: 0.0,
         }
     }
 }
@@ -70,11 +77,13 @@ impl SimPosition {
         self.side.is_some()
     }
 
-    fn pnl(&self, exit_price: f64) -> f64 {
+    /// USD PnL when the shares are sold or redeemed for `proceeds` (net of
+    /// exit fees), including the fee paid on entry
+    fn pnl(&self, proceeds: f64) -> f64 {
         if !self.is_active() || self.entry_price < 0.0001 {
             return 0.0;
         }
-        (exit_price - self.entry_price) / self.entry_price
+        proceeds - self.size_usd - self.entry_fee
     }
 
     fn reset(&mut self) {
@@ -82,6 +91,8 @@ impl SimPosition {
         self.entry_price = 0.0;
         self.entry_timestamp = 0;
         self.size_usd = 0.0;
+        self.shares = 0.0;
+        self.entry_fee = 0.0;
     }
 }
 
@@ -121,7 +132,7 @@ impl BacktestEngine {
 
         let end_timestamp = snapshots.last().map(|s| s.timestamp).unwrap_or(0);
 
-        for snapshot in snapshots {
+        for (index, snapshot) in snapshots.iter().enumerate() {
             let epoch_seconds = snapshot.timestamp as u64;
             let time_remaining = end_timestamp - snapshot.timestamp;
 
@@ -174,25 +185,45 @@ impl BacktestEngine {
                             continue;
                         }
 
+                        let fill_at = self.fill_snapshot(snapshots, index);
+                        let ask = match side {
+                            TokenSide::Yes => fill_at.yes_ask,
+                            TokenSide::No => fill_at.no_ask,
+                        };
+                        let Some(fill) = self
+                            .config
+                            .fill_model
+                            .buy(&[(ask, f64::INFINITY)], self.config.position_size_usd)
+                        else {
+                            continue;
+                        };
+
                         position.side = Some(side);
-                        position.entry_price = entry_price;
+                        position.entry_price = fill.avg_price;
                         position.entry_timestamp = snapshot.timestamp;
-                        position.size_usd = self.config.position_size_usd;
-                        self.bankroll -= self.config.position_size_usd;
+                        position.size_usd = fill.notional;
+                        position.shares = fill.shares;
+                        position.entry_fee = fill.fee;
+                        self.bankroll -= fill.notional + fill.fee;
                     }
 
                     if position.is_active() && signal.exit == ExitSignal::FullExit {
-                        let exit_price = match position.side {
-                            Some(TokenSide::Yes) => snapshot.yes_bid,
-                            Some(TokenSide::No) => snapshot.no_bid,
+                        let fill_at = self.fill_snapshot(snapshots, index);
+                        let bid = match position.side {
+                            Some(TokenSide::Yes) => fill_at.yes_bid,
+                            Some(TokenSide::No) => fill_at.no_bid,
                             None => 0.0,
                         };
+                        let fill = (bid > 0.0001)
+                            .then(|| self.config.fill_model.sell(&[(bid, f64::INFINITY)], position.shares))
+                            .flatten();
 
-                        if exit_price > 0.0001 {
-                            let pnl_pct = position.pnl(exit_price);
-                            let pnl_usd = pnl_pct * position.size_usd;
+                        if let Some(fill) = fill {
+                            let exit_price = fill.avg_price;
+                            let pnl_usd = position.pnl(fill.notional - fill.fee);
+                            let pnl_pct = pnl_usd / position.size_usd;
 
-                            self.bankroll += position.size_usd + pnl_usd;
+                            self.bankroll += fill.notional - fill.fee;
 
                             let side_str = match position.side {
                                 Some(TokenSide::Yes) => "YES",
@@ -241,9 +272,10 @@ impl BacktestEngine {
                 }
             };
 
-            let pnl_pct = position.pnl(settlement_price);
-            let pnl_usd = pnl_pct * position.size_usd;
-            self.bankroll += position.size_usd + pnl_usd;
+            let proceeds = position.shares * settlement_price;
+            let pnl_usd = position.pnl(proceeds);
+            let pnl_pct = pnl_usd / position.size_usd;
+            self.bankroll += proceeds;
 
             let side_str = match position.side {
                 Some(TokenSide::Yes) => "YES",
@@ -289,6 +321,17 @@ impl BacktestEngine {
         BacktestMetrics::from_trades(self.trades.clone(), self.config.starting_capital)
     }
 
+    /// Snapshot an order decided at `snapshots[index]` fills against once the
+    /// configured latency has elapsed. Becker snapshots only carry quotes, so
+    /// fills here never run out of size.
+    fn fill_snapshot<'a>(&self, snapshots: &'a [PriceSnapshot], index: usize) -> &'a PriceSnapshot {
+        let fill_ts = snapshots[index].timestamp as f64 + self.config.fill_model.latency_secs();
+        snapshots[index..]
+            .iter()
+            .find(|s| s.timestamp as f64 >= fill_ts)
+            .unwrap_or(&snapshots[snapshots.len() - 1])
+    }
+
     fn snapshots_from_market(&self, market: &MarketData) -> Vec<PriceSnapshot> {
         let trades = &market.trades;
         if trades.is_empty() {
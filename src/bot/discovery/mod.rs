@@ -16,6 +16,8 @@ use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Duration};
 
 use crate::bot::feed::MarketSnapshot;
+use crate::bot::research::{CryptoBinaryMarketSpec, SupportedAsset, SupportedDuration};
+use crate::bot::strategy::graph_builder::TemporalGraphBuilder;
 
 // ── Constants ──────────────────────────────────────────────────────────────────
 
@@ -35,6 +37,98 @@ pub use crate::bot::pipeline::CryptoAsset;
 
 // ── Structs ────────────────────────────────────────────────────────────────────
 
+/// Up/Down market family to discover and roll over, e.g. ETH 15m.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketTarget {
+    pub asset: SupportedAsset,
+    pub duration: SupportedDuration,
+}
+
+impl MarketTarget {
+    pub fn new(asset: SupportedAsset, duration: SupportedDuration) -> Self {
+        Self { asset, duration }
+    }
+
+    /// Slug prefix of the rolling markets, e.g. `eth-updown-15m-`
+    pub fn slug_prefix(&self) -> String {
+        format!("{}-updown-{}-", self.asset.as_str(), self.duration.as_str())
+    }
+
+    pub fn duration_seconds(&self) -> i64 {
+        self.duration.seconds()
+    }
+
+    pub fn matches_slug(&self, slug: &str) -> bool {
+        slug.starts_with(&self.slug_prefix())
+    }
+
+    pub fn matches_question(&self, question: &str) -> bool {
+        let normalized = question.to_ascii_lowercase();
+        self.asset
+            .question_names()
+            .iter()
+            .any(|name| normalized.contains(name))
+            && normalized.contains("up")
+            && normalized.contains("down")
+            && self
+                .duration
+                .question_names()
+                .iter()
+                .any(|name| contains_unprefixed(&normalized, name))
+    }
+
+    pub fn matches_market(&self, market: &Market) -> bool {
+        market.slug.as_deref().is_some_and(|slug| self.matches_slug(slug))
+            || market
+                .question
+                .as_deref()
+                .is_some_and(|question| self.matches_question(question))
+    }
+}
+
+impl Default for MarketTarget {
+    fn default() -> Self {
+        Self::new(SupportedAsset::Btc, SupportedDuration::M5)
+    }
+}
+
+impl std::str::FromStr for MarketTarget {
+    type Err = String;
+
+    /// Parse `ASSET:DURATION`, e.g. `eth:15m` (`-` and `/` also accepted)
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (asset, duration) = s
+            .split_once([':', '-', '/'])
+            .ok_or_else(|| format!("expected ASSET:DURATION (e.g. eth:15m), got '{s}'"))?;
+        Ok(Self::new(
+            <SupportedAsset as ValueEnum>::from_str(asset.trim(), true)?,
+            <SupportedDuration as ValueEnum>::from_str(duration.trim(), true)?,
+        ))
+    }
+}
+
+impl std::fmt::Display for MarketTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.asset.as_str().to_ascii_uppercase(),
+            self.duration.as_str()
+        )
+    }
+}
+
+/// `haystack` contains `needle` not directly preceded by a digit, so "5m"
+/// does not match inside "15m".
+fn contains_unprefixed(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(idx, _)| {
+        !haystack[..idx]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
 pub struct WatchedMarket {
     pub label: String,
     pub slug: String,
@@ -42,6 +136,26 @@ pub struct WatchedMarket {
     pub no_token_id: U256,
     pub condition_id: Option<String>,
     pub end_time: DateTime<Utc>,
+    pub duration_seconds: i64,
+    /// Price to beat listed in the market's question or description
+    pub strike_price: Option<f64>,
+}
+
+impl WatchedMarket {
+    /// Start of the market window, derived from its end time and duration
+    pub fn start_ts(&self) -> i64 {
+        self.end_time.timestamp() - self.duration_seconds
+    }
+
+    /// Research spec for building features, keyed by condition ID. `None`,
+    /// with a warning, when discovery did not report the condition ID.
+    pub fn research_spec(&self) -> Option<CryptoBinaryMarketSpec> {
+        let Some(condition_id) = self.condition_id.as_deref() else {
+            eprintln!("[warn] {} has no condition ID; features are not built for it", self.slug);
+            return None;
+        };
+        CryptoBinaryMarketSpec::from_slug(&self.slug, condition_id, self.start_ts(), self.end_time.timestamp())
+    }
 }
 
 pub use crate::bot::pipeline::DiscoveredMarket;
@@ -52,9 +166,9 @@ pub use crate::bot::pipeline::GammaMarket;
 
 // ── Live discovery ─────────────────────────────────────────────────────────────
 
-pub async fn discover_market_loop(client: &gamma::Client) -> WatchedMarket {
+pub async fn discover_market_loop(client: &gamma::Client, target: MarketTarget) -> WatchedMarket {
     loop {
-        match discover_active_btc_market(client).await {
+        match discover_active_market(client, target).await {
             Ok(market) => {
                 println!(
                     "Watching: {} [{}] (YES {}, NO {})",
@@ -63,19 +177,22 @@ pub async fn discover_market_loop(client: &gamma::Client) -> WatchedMarket {
                 return market;
             }
             Err(err) => {
-                eprintln!("[warn] Could not find active BTC 5m market: {err:#}");
+                eprintln!("[warn] Could not find active {target} market: {err:#}");
                 sleep(Duration::from_secs(2)).await;
             }
         }
     }
 }
 
-pub async fn discover_active_btc_market(client: &gamma::Client) -> Result<WatchedMarket> {
-    if let Some(market) = discover_by_time_slugs(client).await? {
-        return market_to_watched(market);
+pub async fn discover_active_market(
+    client: &gamma::Client,
+    target: MarketTarget,
+) -> Result<WatchedMarket> {
+    if let Some(market) = discover_by_time_slugs(client, target).await? {
+        return market_to_watched(market, target);
     }
 
-    let mut candidates = search_candidates(client).await?;
+    let mut candidates = search_candidates(client, target).await?;
     if candidates.is_empty() {
         candidates = list_open_market_candidates(client).await?;
     }
@@ -83,24 +200,28 @@ pub async fn discover_active_btc_market(client: &gamma::Client) -> Result<Watche
     let now = Utc::now();
     let market = candidates
         .into_iter()
-        .filter(is_btc_updown_slug_or_question)
+        .filter(|market| target.matches_market(market))
         .filter(|market| is_active_now(market, &now))
         .min_by_key(|market| market.end_date);
 
     match market {
-        Some(market) => market_to_watched(market),
+        Some(market) => market_to_watched(market, target),
         None => Err(anyhow::anyhow!(
-            "no matching active BTC 5m market found"
+            "no matching active {target} market found"
         )),
     }
 }
 
-async fn discover_by_time_slugs(client: &gamma::Client) -> Result<Option<Market>> {
+async fn discover_by_time_slugs(
+    client: &gamma::Client,
+    target: MarketTarget,
+) -> Result<Option<Market>> {
     let now = Utc::now();
     let mut active: Vec<Market> = Vec::new();
+    let prefix = target.slug_prefix();
 
-    for ts in candidate_slug_timestamps(now.timestamp()) {
-        let slug = format!("{BTC_UPDOWN_SLUG_PREFIX}{ts}");
+    for ts in candidate_slug_timestamps(now.timestamp(), target.duration_seconds()) {
+        let slug = format!("{prefix}{ts}");
         let request = MarketBySlugRequest::builder().slug(slug).build();
 
         match client.market_by_slug(&request).await {
@@ -120,17 +241,16 @@ async fn discover_by_time_slugs(client: &gamma::Client) -> Result<Option<Market>
     Ok(active.into_iter().min_by_key(|market| market.end_date))
 }
 
-pub fn candidate_slug_timestamps(now_ts: i64) -> Vec<i64> {
-    let base = now_ts.div_euclid(FIVE_MINUTES_SECONDS) * FIVE_MINUTES_SECONDS;
-    [-900, -600, -300, 0, 300, 600, 900]
-        .into_iter()
-        .map(|offset| base + offset)
-        .collect()
+/// Window-aligned slug timestamps around `now_ts`, three windows either side.
+pub fn candidate_slug_timestamps(now_ts: i64, window_seconds: i64) -> Vec<i64> {
+    let base = now_ts.div_euclid(window_seconds) * window_seconds;
+    (-3..=3).map(|offset| base + offset * window_seconds).collect()
 }
 
-async fn search_candidates(client: &gamma::Client) -> Result<Vec<Market>> {
+async fn search_candidates(client: &gamma::Client, target: MarketTarget) -> Result<Vec<Market>> {
+    let query = target.slug_prefix();
     let request = SearchRequest::builder()
-        .q("btc-updown-5m")
+        .q(query.trim_end_matches('-').to_string())
         .limit_per_type(50)
         .build();
     let results = client.search(&request).await?;
@@ -147,25 +267,6 @@ async fn list_open_market_candidates(client: &gamma::Client) -> Result<Vec<Marke
     Ok(client.markets(&request).await?)
 }
 
-pub fn is_btc_updown_slug_or_question(market: &Market) -> bool {
-    market
-        .slug
-        .as_deref()
-        .is_some_and(|slug| slug.starts_with(BTC_UPDOWN_SLUG_PREFIX))
-        || market.question.as_deref().is_some_and(is_btc_up_down_5m)
-}
-
-pub fn is_btc_up_down_5m(question: &str) -> bool {
-    let normalized = question.to_ascii_lowercase();
-    (normalized.contains("btc") || normalized.contains("bitcoin"))
-        && normalized.contains("up")
-        && normalized.contains("down")
-        && (normalized.contains("5m")
-            || normalized.contains("5 min")
-            || normalized.contains("5-minute")
-            || normalized.contains("five minute"))
-}
-
 pub fn is_active_now(market: &Market, now: &DateTime<Utc>) -> bool {
     if market.closed == Some(true) || market.active == Some(false) {
         return false;
@@ -176,7 +277,7 @@ pub fn is_active_now(market: &Market, now: &DateTime<Utc>) -> bool {
     starts_ok && ends_ok
 }
 
-pub fn market_to_watched(market: Market) -> Result<WatchedMarket> {
+pub fn market_to_watched(market: Market, target: MarketTarget) -> Result<WatchedMarket> {
     let (yes_token_id, no_token_id) = select_binary_tokens(&market)?;
     let fallback_slug = format!("market-{}", market.id);
     let end_time = market
@@ -184,14 +285,20 @@ pub fn market_to_watched(market: Market) -> Result<WatchedMarket> {
         .context("market end date is missing")?;
 
     let condition_id = market.condition_id.map(|c| format!("0x{}", alloy::hex::encode(c.as_slice())));
+    let strike_price = [market.description.as_deref(), market.question.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(TemporalGraphBuilder::extract_strike_price);
 
     Ok(WatchedMarket {
-        label: market_label(&market),
+        label: market_label(&market, target),
         slug: market.slug.unwrap_or(fallback_slug),
         yes_token_id,
         no_token_id,
         condition_id,
         end_time,
+        duration_seconds: target.duration_seconds(),
+        strike_price,
     })
 }
 
@@ -228,20 +335,20 @@ pub fn select_binary_tokens(market: &Market) -> Result<(U256, U256)> {
     Ok((token_ids[yes_index], token_ids[no_index]))
 }
 
-pub fn market_label(market: &Market) -> String {
+pub fn market_label(market: &Market, target: MarketTarget) -> String {
     if market
         .slug
         .as_deref()
-        .is_some_and(|slug| slug.starts_with(BTC_UPDOWN_SLUG_PREFIX))
+        .is_some_and(|slug| target.matches_slug(slug))
     {
         if let Some(question) = market.question.as_deref() {
-            return btc_updown_label_from_question(question);
+            return updown_label_from_question(question, target);
         }
     }
 
     if let (Some(start), Some(end)) = (market.start_date.as_ref(), market.end_date.as_ref()) {
         return format!(
-            "BTC 5m {:02}:{:02}-{:02}:{:02}",
+            "{target} {:02}:{:02}-{:02}:{:02}",
             start.hour(),
             start.minute(),
             end.hour(),
@@ -255,10 +362,10 @@ pub fn market_label(market: &Market) -> String {
         .unwrap_or_else(|| format!("Market {}", market.id))
 }
 
-pub fn btc_updown_label_from_question(question: &str) -> String {
+pub fn updown_label_from_question(question: &str, target: MarketTarget) -> String {
     question.split_once(" - ").map_or_else(
         || question.to_string(),
-        |(_, suffix)| format!("BTC 5m {suffix}"),
+        |(_, suffix)| format!("{target} {suffix}"),
     )
 }
 
@@ -355,5 +462,69 @@ pub async fn fetch_snapshot(
 
 // Migrated to crate::bot::pipeline
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_slug_prefix_and_window() {
+        let target = MarketTarget::new(SupportedAsset::Eth, SupportedDuration::M15);
+        assert_eq!(target.slug_prefix(), "eth-updown-15m-");
+        assert_eq!(target.to_string(), "ETH 15m");
+        assert!(target.matches_slug("eth-updown-15m-1700000100"));
+        assert!(!target.matches_slug("btc-updown-15m-1700000100"));
+        assert!(!MarketTarget::default().matches_slug("btc-updown-15m-1700000100"));
+    }
+
+    #[test]
+    fn target_parses_asset_and_duration() {
+        let target: MarketTarget = "sol:1h".parse().unwrap();
+        assert_eq!(target, MarketTarget::new(SupportedAsset::Sol, SupportedDuration::H1));
+        let target: MarketTarget = "ETH-15m".parse().unwrap();
+        assert_eq!(target.slug_prefix(), "eth-updown-15m-");
+        assert!("doge:5m".parse::<MarketTarget>().is_err());
+        assert!("btc".parse::<MarketTarget>().is_err());
+    }
+
+    #[test]
+    fn candidate_timestamps_align_to_duration() {
+        let ts = candidate_slug_timestamps(1_700_000_123, 900);
+        assert_eq!(ts.len(), 7);
+        assert!(ts.iter().all(|t| t % 900 == 0));
+        assert_eq!(ts[3], 1_700_000_123 / 900 * 900);
+        assert_eq!(ts[4] - ts[3], 900);
+    }
 
+    #[test]
+    fn question_match_distinguishes_durations() {
+        let m5 = MarketTarget::default();
+        let m15 = MarketTarget::new(SupportedAsset::Btc, SupportedDuration::M15);
+        let question = "Bitcoin Up or Down 15m - 3:00PM-3:15PM ET";
+        assert!(m15.matches_question(question));
+        assert!(!m5.matches_question(question));
+        assert!(m5.matches_question("BTC up or down 5 min"));
+    }
 
+    #[test]
+    fn watched_market_takes_the_strike_from_its_description() {
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "slug": "btc-updown-15m-900",
+            "question": "Bitcoin Up or Down - 15m",
+            "description": "Resolves Up if the close is at or above the price to beat: $71,250.50.",
+            "endDate": "2026-01-01T00:15:00Z",
+            "outcomes": "[\"Up\", \"Down\"]",
+            "clobTokenIds": "[\"1\", \"2\"]"
+        }))
+        .unwrap();
+        let target = MarketTarget::new(SupportedAsset::Btc, SupportedDuration::M15);
+
+        let mut watched = market_to_watched(market, target).unwrap();
+        assert_eq!(watched.strike_price, Some(71_250.50));
+
+        // Features are keyed by condition ID, never by slug
+        assert!(watched.research_spec().is_none());
+        watched.condition_id = Some("0xabc".to_string());
+        assert_eq!(watched.research_spec().unwrap().condition_id, "0xabc");
+    }
+}
@@ -2,9 +2,10 @@
 //!
 //! Order execution, fill handling, and feed health monitoring.
 
-use crate::bot::discovery::{WatchedMarket, FIVE_MINUTES_SECONDS, fetch_snapshot};
-use crate::bot::feed::DualSnapshot;
+use crate::bot::discovery::{WatchedMarket, fetch_snapshot};
+use crate::bot::feed::{DualSnapshot, UserEvent};
 use crate::bot::logging::{EngineEvent, EngineEventLoggers};
+use crate::bot::order_tracker::{confirm_taker_fill, reconcile_order, OrderLifecycle, TrackedOrder};
 use crate::bot::risk::{
     best_ask_price, best_bid_price, decimal_to_f64, EntryContext, FilterReason, GateDecision,
     GatekeeperState, TradeDirection,
@@ -12,8 +13,11 @@ use crate::bot::risk::{
 use crate::bot::shadow::TokenSide;
 use crate::bot::signal::{EntrySignal, ExitSignal};
 use crate::bot::strategy::Direction;
-use anyhow::Result;
+use crate::persistence::sqlite::SqliteStore;
+use crate::persistence::{PositionState, StateStore, TradeLog};
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use polymarket_client_sdk::auth::state::Authenticated;
 use polymarket_client_sdk::auth::Normal;
 use polymarket_client_sdk::clob;
@@ -98,6 +102,15 @@ pub enum ExecutionMode {
     Live,
 }
 
+/// How live entries and exits reach the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExecutionStyle {
+    /// FOK market orders that cross the spread
+    Taker,
+    /// Resting GTC post-only limit orders at or inside the touch
+    Maker,
+}
+
 /// Feed health status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FeedHealth {
@@ -152,6 +165,53 @@ pub struct PendingSettlement {
     pub end_time: DateTime<Utc>,
     pub sell_attempts: u32,
     pub created_at: DateTime<Utc>,
+    pub entry_order_id: Option<String>,
+    pub entry_timestamp: u64,
+    pub entry_slippage_bps: Option<i64>,
+    pub entry_latency_ms: Option<i64>,
+    /// Sell awaiting its fills from the user channel
+    pub exit_order: Option<TrackedOrder>,
+    /// Partial exits made before the market ended, booked with the settlement
+    pub prior_exits: RoundTrip,
+}
+
+impl PendingSettlement {
+    /// Queue the open position of a market that reached its end time
+    pub fn from_position(
+        market: &WatchedMarket,
+        position: &LivePosition,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let (token_id, token_side) = match position.token_side? {
+            TokenSide::Yes => (market.yes_token_id, TokenSide::Yes),
+            TokenSide::No => (market.no_token_id, TokenSide::No),
+        };
+
+        Some(Self {
+            market_slug: market.slug.clone(),
+            token_side,
+            token_id,
+            shares: position.shares,
+            entry_price: position.entry_price,
+            condition_id: market.condition_id.clone(),
+            end_time: market.end_time,
+            sell_attempts: 0,
+            created_at: now,
+            entry_order_id: position.last_trade_id.clone(),
+            entry_timestamp: position.entry_timestamp,
+            entry_slippage_bps: position.entry_slippage_bps,
+            entry_latency_ms: position.entry_latency_ms,
+            exit_order: None,
+            prior_exits: RoundTrip::default(),
+        })
+    }
+}
+
+/// Fold a user-channel event into the settlement sells it concerns
+pub fn apply_settlement_event(pending: &mut [PendingSettlement], event: &UserEvent) {
+    for order in pending.iter_mut().filter_map(|p| p.exit_order.as_mut()) {
+        order.apply_user_event(event);
+    }
 }
 
 pub struct LivePosition {
@@ -163,6 +223,8 @@ pub struct LivePosition {
     pub yes_blocked: bool,
     pub no_blocked: bool,
     pub last_trade_id: Option<String>,
+    pub entry_slippage_bps: Option<i64>,
+    pub entry_latency_ms: Option<i64>,
 }
 
 impl Default for LivePosition {
@@ -176,6 +238,8 @@ impl Default for LivePosition {
             yes_blocked: false,
             no_blocked: false,
             last_trade_id: None,
+            entry_slippage_bps: None,
+            entry_latency_ms: None,
         }
     }
 }
@@ -198,6 +262,8 @@ impl LivePosition {
         self.shares = 0.0;
         self.entry_timestamp = 0;
         self.last_trade_id = None;
+        self.entry_slippage_bps = None;
+        self.entry_latency_ms = None;
         self.last_exit_timestamp = timestamp;
     }
 
@@ -208,11 +274,34 @@ impl LivePosition {
         self.entry_timestamp = 0;
         self.last_exit_timestamp = 0;
         self.last_trade_id = None;
+        self.entry_slippage_bps = None;
+        self.entry_latency_ms = None;
         self.yes_blocked = false;
         self.no_blocked = false;
     }
 }
 
+/// Partial exits of an open position, booked as one trade once it closes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RoundTrip {
+    pub shares: f64,
+    pub proceeds: f64,
+    pub pnl_usd: f64,
+}
+
+impl RoundTrip {
+    pub fn add(&mut self, shares: f64, price: f64, entry_price: f64) {
+        self.shares += shares;
+        self.proceeds += shares * price;
+        self.pnl_usd += (price - entry_price) * shares;
+    }
+
+    /// Volume-weighted price of all exits so far
+    pub fn exit_price(&self) -> f64 {
+        if self.shares > 0.0 { self.proceeds / self.shares } else { 0.0 }
+    }
+}
+
 pub struct OrderResult {
     pub order_id: String,
     pub filled_amount: Option<Decimal>,
@@ -229,6 +318,218 @@ pub async fn get_usdc_balance(client: &clob::Client<Authenticated<Normal>>) -> R
     Ok(decimal_to_f64(human_balance))
 }
 
+/// Query the conditional token balance held for `token_id`, in shares.
+pub async fn get_conditional_balance(
+    client: &clob::Client<Authenticated<Normal>>,
+    token_id: U256,
+) -> Result<f64> {
+    // Balance is in raw units (micro-shares), divide by 10^6
+    const DECIMALS: u32 = 6;
+    let request = BalanceAllowanceRequest::builder()
+        .asset_type(AssetType::Conditional)
+        .token_id(token_id)
+        .build();
+    let balance = client.balance_allowance(request).await?;
+    let divisor = Decimal::from(10u64.pow(DECIMALS));
+    let human_balance = balance.balance / divisor;
+    Ok(decimal_to_f64(human_balance))
+}
+
+// ── State persistence ───────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn persist_entry(
+    store: Option<&dyn StateStore>,
+    market_slug: &str,
+    token_id: U256,
+    token_side: TokenSide,
+    shares: f64,
+    entry_price: f64,
+    size_usd: f64,
+    order_id: &str,
+    timestamp: u64,
+    slippage_bps: Option<i64>,
+    latency_ms: Option<i64>,
+) {
+    let Some(store) = store else {
+        return;
+    };
+    let state = PositionState {
+        token_id,
+        market_slug: market_slug.to_string(),
+        token_side: token_side.into(),
+        size: shares,
+        entry_price,
+        size_usd,
+        entry_order_id: order_id.to_string(),
+        entry_timestamp: timestamp as i64,
+        entry_slippage_bps: slippage_bps,
+        entry_latency_ms: latency_ms,
+    };
+    if let Err(err) = store.save_position(&state) {
+        eprintln!("[warn] Failed to persist position for {}: {err:#}", market_slug);
+    }
+}
+
+/// Record a closed trade, drop its open position row and roll the result
+/// into the persisted bot state.
+pub(crate) fn persist_exit(store: Option<&dyn StateStore>, token_id: U256, trade: &TradeLog) {
+    let Some(store) = store else {
+        return;
+    };
+    if let Err(err) = store.save_trade(trade) {
+        eprintln!("[warn] Failed to persist trade for {}: {err:#}", trade.market_slug);
+    }
+    forget_position(Some(store), token_id);
+
+    // Roll the daily PnL over first so an exit after midnight UTC counts
+    // against the new day's loss limit
+    if let Err(err) = store.check_and_reset_daily(trade.timestamp_exit) {
+        eprintln!("[warn] Failed to reset daily PnL: {err:#}");
+    }
+    let result = store.get_bot_state().and_then(|mut state| {
+        state.total_trades += 1;
+        state.total_pnl += trade.pnl_usd;
+        state.daily_pnl += trade.pnl_usd;
+        state.bankroll += trade.pnl_usd;
+        state.peak_bankroll = state.peak_bankroll.max(state.bankroll);
+        if trade.pnl_usd > 0.0 {
+            state.winning_trades += 1;
+            state.consecutive_losses = 0;
+        } else {
+            state.consecutive_losses += 1;
+        }
+        store.update_bot_state(&state)
+    });
+    if let Err(err) = result {
+        eprintln!("[warn] Failed to update bot state: {err:#}");
+    }
+}
+
+pub(crate) fn forget_position(store: Option<&dyn StateStore>, token_id: U256) {
+    if let Some(store) = store {
+        if let Err(err) = store.remove_position(token_id) {
+            eprintln!("[warn] Failed to remove persisted position {}: {err:#}", token_id);
+        }
+    }
+}
+
+/// Open the live state database, roll the daily counters and seed the
+/// gatekeeper's daily PnL so loss limits survive restarts.
+pub fn open_state_store(
+    path: &str,
+    usdc_balance: f64,
+    gatekeeper: &mut GatekeeperState,
+    now: DateTime<Utc>,
+) -> Result<SqliteStore> {
+    let store = SqliteStore::new(path)
+        .with_context(|| format!("Failed to open state database {}", path))?;
+    store.check_and_reset_daily(now.timestamp())?;
+    store.update_bankroll(usdc_balance)?;
+
+    let bot_state = store.get_bot_state()?;
+    gatekeeper.restore_daily_pnl(now.timestamp() as u64, bot_state.daily_pnl);
+    println!(
+        "[LIVE] State: {} | {} trades | daily PnL ${:.2}",
+        path, bot_state.total_trades, bot_state.daily_pnl
+    );
+
+    Ok(store)
+}
+
+/// Reload persisted positions after a restart and reconcile them against the
+/// actual CLOB balances. Positions in a currently watched market are restored
+/// into that market's `LivePosition`; everything else is queued for auto-sell.
+pub async fn recover_positions(
+    store: &dyn StateStore,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+    markets: &mut [(&WatchedMarket, &mut LivePosition)],
+    pending: &mut Vec<PendingSettlement>,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let mut recovered = 0;
+
+    for mut state in store.load_positions()? {
+        let actual_balance = get_conditional_balance(clob_client, state.token_id)
+            .await
+            .with_context(|| format!("Failed to fetch balance for {}", state.market_slug))?;
+        let shares = (actual_balance * 100.0).floor() / 100.0;
+
+        if shares < 0.01 {
+            println!(
+                "[RECOVER] {} | {} | No balance on CLOB, dropping stale position",
+                state.market_slug, state.token_side
+            );
+            store.remove_position(state.token_id)?;
+            continue;
+        }
+
+        if (shares - state.size).abs() >= 0.01 {
+            println!(
+                "[RECOVER] {} | {} | Stored {:.4} shares, CLOB holds {:.4}",
+                state.market_slug, state.token_side, state.size, shares
+            );
+            state.size = shares;
+            store.save_position(&state)?;
+        }
+
+        let token_side = TokenSide::from(&state.token_side);
+        let entry_timestamp = state.entry_timestamp.max(0) as u64;
+
+        let live_slot = markets.iter_mut().find(|(market, position)| {
+            market.slug == state.market_slug && now < market.end_time && !position.is_active()
+        });
+
+        if let Some((_, position)) = live_slot {
+            position.token_side = Some(token_side);
+            position.entry_price = state.entry_price;
+            position.shares = shares;
+            position.entry_timestamp = entry_timestamp;
+            position.last_trade_id = Some(state.entry_order_id.clone());
+            position.entry_slippage_bps = state.entry_slippage_bps;
+            position.entry_latency_ms = state.entry_latency_ms;
+            println!(
+                "[RECOVER] {} | {} | {:.4} shares @ {:.4} | Restored as active position",
+                state.market_slug, state.token_side, shares, state.entry_price
+            );
+        } else {
+            println!(
+                "[RECOVER] {} | {} | {:.4} shares @ {:.4} | Auto-sell queued",
+                state.market_slug, state.token_side, shares, state.entry_price
+            );
+            pending.push(PendingSettlement {
+                market_slug: state.market_slug.clone(),
+                token_side,
+                token_id: state.token_id,
+                shares,
+                entry_price: state.entry_price,
+                condition_id: None,
+                end_time: now,
+                sell_attempts: 0,
+                created_at: now,
+                entry_order_id: Some(state.entry_order_id.clone()),
+                entry_timestamp,
+                entry_slippage_bps: state.entry_slippage_bps,
+                entry_latency_ms: state.entry_latency_ms,
+                exit_order: None,
+                prior_exits: RoundTrip::default(),
+            });
+        }
+        recovered += 1;
+    }
+
+    Ok(recovered)
+}
+
+/// Seconds a settlement sell may go without a user-channel fill before its
+/// fills are pulled from the trades endpoint instead
+const SETTLE_CONFIRM_TIMEOUT_S: i64 = 10;
+
+/// Sell the tokens of markets that ended, sized from the on-chain token
+/// balance. With `streamed`, fills the user channel pushes into `exit_order`
+/// settle a sell without polling; without it, or when the channel stays
+/// silent, the order is reconciled against the trades endpoint instead.
+#[allow(clippy::too_many_arguments)]
 pub async fn try_settle_pending(
     pending: &mut Vec<PendingSettlement>,
     read_client: &clob::Client,
@@ -236,13 +537,44 @@ pub async fn try_settle_pending(
     signer: &(impl polymarket_client_sdk::auth::Signer + Sync),
     gatekeeper: &mut GatekeeperState,
     event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    streamed: bool,
     now: DateTime<Utc>,
 ) {
     let mut settled = Vec::new();
 
     for i in 0..pending.len() {
         let p = &mut pending[i];
-        
+
+        if let Some(order) = p.exit_order.as_mut() {
+            let waited_s = (now.timestamp_millis() - order.submitted_at_ms) / 1000;
+            if order.fills.is_empty()
+                && (!streamed || waited_s >= SETTLE_CONFIRM_TIMEOUT_S)
+                && let Err(err) = reconcile_order(clob_client, order).await
+            {
+                eprintln!("[warn] Failed to reconcile settlement sell {}: {err:#}", order.order_id);
+            }
+            // FOK sells fill completely or not at all
+            let (shares, price) = match order.avg_fill_price() {
+                Some(price) => (order.filled_size(), price),
+                // Acknowledged as matched before its trade was published
+                None if order.status == OrderLifecycle::Filled => {
+                    ((p.shares * 100.0).floor() / 100.0, order.reference_price)
+                }
+                None => {
+                    if order.status == OrderLifecycle::Cancelled || waited_s >= SETTLE_CONFIRM_TIMEOUT_S {
+                        println!("[PENDING] {} | {:?} | Sell {} did not fill, retrying", p.market_slug, p.token_side, order.order_id);
+                        p.exit_order = None;
+                    }
+                    continue;
+                }
+            };
+            let order = order.clone();
+            record_settlement(p, &order, shares, price, gatekeeper, event_loggers, state_store, now);
+            settled.push(i);
+            continue;
+        }
+
         // Only try to sell every 5 seconds
         if p.sell_attempts > 0 && (now - p.created_at).num_seconds() % 5 != 0 {
             continue;
@@ -281,20 +613,10 @@ pub async fn try_settle_pending(
             continue;
         }
 
-        // Query ACTUAL token balance (don't trust tracked shares)
-        let balance_request = BalanceAllowanceRequest::builder()
-            .asset_type(AssetType::Conditional)
-            .token_id(p.token_id)
-            .build();
-        
-        let actual_balance = match clob_client.balance_allowance(balance_request).await {
-            Ok(b) => {
-                // Balance is in raw units (micro-shares), divide by 10^6
-                const DECIMALS: u32 = 6;
-                let divisor = Decimal::from(10u64.pow(DECIMALS));
-                let human_balance = b.balance / divisor;
-                decimal_to_f64(human_balance)
-            }
+        // Query the ACTUAL token balance; tracked shares can drift from
+        // what the wallet holds
+        let held = match get_conditional_balance(clob_client, p.token_id).await {
+            Ok(balance) => balance,
             Err(err) => {
                 eprintln!("[AUTO-SELL] {} | Failed to fetch balance: {:?}", p.market_slug, err);
                 continue;
@@ -302,60 +624,30 @@ pub async fn try_settle_pending(
         };
 
         // Round DOWN to 2 decimals
-        let shares_to_sell = (actual_balance * 100.0).floor() / 100.0;
+        let shares_to_sell = (held * 100.0).floor() / 100.0;
         
         if shares_to_sell < 0.01 {
             println!("[SETTLED] {} | {:?} | No shares left (balance: {:.6})", 
-                p.market_slug, p.token_side, actual_balance);
+                p.market_slug, p.token_side, held);
+            forget_position(state_store, p.token_id);
             settled.push(i);
             continue;
         }
 
-        let side_name = match p.token_side {
-            TokenSide::Yes => "YES",
-            TokenSide::No => "NO",
-        };
-
         println!(
-            "[AUTO-SELL] {} | {:?} | {:.4} shares @ {:.4} (actual: {:.6})",
-            p.market_slug, p.token_side, shares_to_sell, bid_price, actual_balance
+            "[AUTO-SELL] {} | {:?} | {:.4} shares @ {:.4} (held: {:.6})",
+            p.market_slug, p.token_side, shares_to_sell, bid_price, held
         );
 
         match place_market_sell(clob_client, signer, p.token_id, shares_to_sell).await {
             Ok(result) => {
-                let pnl_pct = (bid_price - p.entry_price) / p.entry_price * 100.0;
-                let pnl_usd = pnl_pct / 100.0 * (shares_to_sell * p.entry_price);
-                gatekeeper.record_trade_result(now.timestamp() as u64, pnl_usd);
-                println!(
-                    "[SETTLED] {} | {} | {:.2}% | ${:.2} | Order: {}",
-                    p.market_slug, side_name, pnl_pct, pnl_usd, result.order_id
-                );
-                if let Some(loggers) = event_loggers {
-                    loggers.log_execution(EngineEvent::PendingSettlement {
-                        ts: now.timestamp() as u64,
-                        market_slug: p.market_slug.clone(),
-                        side: side_name.to_string(),
-                        bid_price,
-                        shares: shares_to_sell,
-                    });
-                    loggers.log_execution(EngineEvent::LiveExit {
-                        ts: now.timestamp() as u64,
-                        market_slug: p.market_slug.clone(),
-                        side: side_name.to_string(),
-                        price: bid_price,
-                        pnl_usd,
-                        order_id: Some(result.order_id.clone()),
-                    });
-                    if gatekeeper.emergency_halt {
-                        loggers.log_execution(EngineEvent::EmergencyHalt {
-                            ts: now.timestamp() as u64,
-                            market_slug: p.market_slug.clone(),
-                            daily_pnl: gatekeeper.daily_pnl,
-                            reason: "daily loss limit".to_string(),
-                        });
-                    }
-                }
-                settled.push(i);
+                p.exit_order = Some(TrackedOrder::submitted(
+                    result.order_id,
+                    p.token_id,
+                    OrderSide::Sell,
+                    bid_price,
+                    now.timestamp_millis(),
+                ));
             }
             Err(err) => {
                 eprintln!("[AUTO-SELL FAILED] {} | {:?} | {:?}", p.market_slug, p.token_side, err);
@@ -369,6 +661,120 @@ pub async fn try_settle_pending(
     }
 }
 
+/// Book a filled settlement sell: risk state, persistence and event logs
+#[allow(clippy::too_many_arguments)]
+fn record_settlement(
+    p: &PendingSettlement,
+    order: &TrackedOrder,
+    shares: f64,
+    price: f64,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    now: DateTime<Utc>,
+) {
+    let order_id = &order.order_id;
+    let side_name = match p.token_side {
+        TokenSide::Yes => "YES",
+        TokenSide::No => "NO",
+    };
+    let mut trip = p.prior_exits;
+    trip.add(shares, price, p.entry_price);
+    let pnl_usd = trip.pnl_usd;
+    let pnl_pct = (trip.exit_price() - p.entry_price) / p.entry_price * 100.0;
+    gatekeeper.record_trade_result(now.timestamp() as u64, pnl_usd);
+    println!(
+        "[SETTLED] {} | {} | {:.2}% | ${:.2} | Order: {}",
+        p.market_slug, side_name, pnl_pct, pnl_usd, order_id
+    );
+    persist_exit(
+        state_store,
+        p.token_id,
+        &TradeLog {
+            id: None,
+            market_slug: p.market_slug.clone(),
+            token_side: p.token_side.into(),
+            entry_price: p.entry_price,
+            exit_price: trip.exit_price(),
+            size_usd: trip.shares * p.entry_price,
+            pnl_usd,
+            timestamp_entry: p.entry_timestamp as i64,
+            timestamp_exit: now.timestamp(),
+            order_id_entry: p.entry_order_id.clone().unwrap_or_default(),
+            order_id_exit: Some(order_id.to_string()),
+            slippage_entry_bps: p.entry_slippage_bps,
+            slippage_exit_bps: order.slippage_bps(),
+            latency_entry_ms: p.entry_latency_ms,
+            latency_exit_ms: order.latency_ms(),
+        },
+    );
+    if let Some(loggers) = event_loggers {
+        loggers.log_execution(EngineEvent::PendingSettlement {
+            ts: now.timestamp() as u64,
+            market_slug: p.market_slug.clone(),
+            side: side_name.to_string(),
+            bid_price: price,
+            shares,
+        });
+        loggers.log_execution(EngineEvent::LiveExit {
+            ts: now.timestamp() as u64,
+            market_slug: p.market_slug.clone(),
+            side: side_name.to_string(),
+            price: trip.exit_price(),
+            pnl_usd,
+            order_id: Some(order_id.to_string()),
+        });
+        if gatekeeper.emergency_halt {
+            loggers.log_execution(EngineEvent::EmergencyHalt {
+                ts: now.timestamp() as u64,
+                market_slug: p.market_slug.clone(),
+                daily_pnl: gatekeeper.daily_pnl,
+                reason: "daily loss limit".to_string(),
+            });
+        }
+    }
+}
+
+/// Reconcile a filled taker order against the trades endpoint and log the
+/// measured fill. Falls back to an empty fill record if the CLOB can't be
+/// queried, so callers keep their quoted price.
+#[allow(clippy::too_many_arguments)]
+async fn track_taker_fill(
+    client: &clob::Client<Authenticated<Normal>>,
+    order_result: &OrderResult,
+    token_id: U256,
+    side: OrderSide,
+    reference_price: f64,
+    submitted_at_ms: i64,
+    event_loggers: Option<&EngineEventLoggers>,
+    market: &WatchedMarket,
+    timestamp: u64,
+) -> TrackedOrder {
+    let mut tracked = TrackedOrder::submitted(
+        order_result.order_id.clone(),
+        token_id,
+        side,
+        reference_price,
+        submitted_at_ms,
+    );
+    if let Err(err) = confirm_taker_fill(client, &mut tracked).await {
+        eprintln!("[warn] Failed to reconcile fills for {}: {err:#}", order_result.order_id);
+    }
+    if let Some(loggers) = event_loggers {
+        loggers.log_execution(EngineEvent::OrderUpdate {
+            ts: timestamp,
+            market_slug: market.slug.clone(),
+            order_id: tracked.order_id.clone(),
+            status: format!("{:?}", tracked.status),
+            avg_price: tracked.avg_fill_price(),
+            filled_size: tracked.filled_size(),
+            slippage_bps: tracked.slippage_bps(),
+            latency_ms: tracked.latency_ms(),
+        });
+    }
+    tracked
+}
+
 pub async fn place_market_buy(
     client: &clob::Client<Authenticated<Normal>>,
     signer: &(impl polymarket_client_sdk::auth::Signer + Sync),
@@ -433,6 +839,7 @@ pub async fn handle_live_signals(
     position: &mut LivePosition,
     gatekeeper: &mut GatekeeperState,
     event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
     market: &WatchedMarket,
     timestamp: u64,
     size_usd: f64,
@@ -462,14 +869,14 @@ pub async fn handle_live_signals(
         let yes_ask = best_ask_price(&dual_snapshot.yes).unwrap_or(0.0);
         let no_ask = best_ask_price(&dual_snapshot.no).unwrap_or(0.0);
 
-        let contract_age = (timestamp as i64) - (market.end_time.timestamp() - FIVE_MINUTES_SECONDS);
+        let contract_age = (timestamp as i64) - market.start_ts();
         let decision = gatekeeper.check_entry(
             snapshot_side,
             &EntryContext {
                 timestamp,
                 time_remaining,
                 min_time_remaining: 30,
-                max_time_remaining: 280,
+                max_time_remaining: market.duration_seconds - 20,
                 contract_age,
                 yes_ask,
                 no_ask,
@@ -547,27 +954,62 @@ pub async fn handle_live_signals(
             return;
         }
 
+        let submitted_at_ms = Utc::now().timestamp_millis();
         match place_market_buy(clob_client, signer, token_id, size_usd).await {
             Ok(order_result) => {
-                let filled_size = order_result.filled_amount.map(decimal_to_f64).unwrap_or(size_usd / entry_price);
+                let tracked = track_taker_fill(
+                    clob_client,
+                    &order_result,
+                    token_id,
+                    OrderSide::Buy,
+                    entry_price,
+                    submitted_at_ms,
+                    event_loggers,
+                    market,
+                    timestamp,
+                )
+                .await;
+                let fill_price = tracked.avg_fill_price().unwrap_or(entry_price);
+                let filled_size = match tracked.filled_size() {
+                    size if size > 0.0 => size,
+                    _ => order_result.filled_amount.map(decimal_to_f64).unwrap_or(size_usd / entry_price),
+                };
                 // Round DOWN to avoid trying to sell more than we have
                 let filled_rounded = (filled_size * 100.0).floor() / 100.0;
                 position.token_side = Some(token_side);
-                position.entry_price = entry_price;
+                position.entry_price = fill_price;
                 position.shares = filled_rounded;
                 position.entry_timestamp = timestamp;
                 position.last_trade_id = Some(order_result.order_id.clone());
+                position.entry_slippage_bps = tracked.slippage_bps();
+                position.entry_latency_ms = tracked.latency_ms();
 
                 println!(
-                    "[ORDER FILLED] {} | {} @ {:.4} | {} shares | Order: {}",
-                    market.label, side_name, entry_price, filled_rounded, order_result.order_id
+                    "[ORDER FILLED] {} | {} @ {:.4} | {} shares | slippage {}bps | {}ms | Order: {}",
+                    market.label, side_name, fill_price, filled_rounded,
+                    position.entry_slippage_bps.map_or("?".to_string(), |v| v.to_string()),
+                    position.entry_latency_ms.map_or("?".to_string(), |v| v.to_string()),
+                    order_result.order_id
+                );
+                persist_entry(
+                    state_store,
+                    &market.slug,
+                    token_id,
+                    token_side,
+                    filled_rounded,
+                    fill_price,
+                    size_usd,
+                    &order_result.order_id,
+                    timestamp,
+                    position.entry_slippage_bps,
+                    position.entry_latency_ms,
                 );
                 if let Some(loggers) = event_loggers {
                     loggers.log_execution(EngineEvent::LiveEntry {
                         ts: timestamp,
                         market_slug: market.slug.clone(),
                         side: side_name.to_string(),
-                        price: entry_price,
+                        price: fill_price,
                         size_usd,
                         order_id: Some(order_result.order_id.clone()),
                     });
@@ -605,25 +1047,14 @@ pub async fn handle_live_signals(
             };
 
             // Query ACTUAL token balance before selling (don't trust tracked shares)
-            let balance_request = BalanceAllowanceRequest::builder()
-                .asset_type(AssetType::Conditional)
-                .token_id(token_id)
-                .build();
-            
-            let actual_shares = match clob_client.balance_allowance(balance_request).await {
-                Ok(b) => {
-                    // Balance is in raw units (micro-shares), divide by 10^6
-                    const DECIMALS: u32 = 6;
-                    let divisor = Decimal::from(10u64.pow(DECIMALS));
-                    let human_balance = b.balance / divisor;
-                    let bal = decimal_to_f64(human_balance);
-                    (bal * 100.0).floor() / 100.0 // Round DOWN to 2 decimals
-                }
+            let actual_shares = match get_conditional_balance(clob_client, token_id).await {
+                Ok(bal) => (bal * 100.0).floor() / 100.0, // Round DOWN to 2 decimals
                 Err(_) => position.shares, // Fallback to tracked if query fails
             };
 
             if actual_shares < 0.01 {
                 println!("[NO SHARES] {} | {:?} | Balance too small", market.label, position.token_side);
+                forget_position(state_store, token_id);
                 position.reset(timestamp, false);
                 return;
             }
@@ -654,14 +1085,53 @@ pub async fn handle_live_signals(
                 return;
             }
 
+            let submitted_at_ms = Utc::now().timestamp_millis();
             match place_market_sell(clob_client, signer, token_id, actual_shares).await {
                 Ok(order_result) => {
+                    let tracked = track_taker_fill(
+                        clob_client,
+                        &order_result,
+                        token_id,
+                        OrderSide::Sell,
+                        exit_price,
+                        submitted_at_ms,
+                        event_loggers,
+                        market,
+                        timestamp,
+                    )
+                    .await;
+                    let exit_price = tracked.avg_fill_price().unwrap_or(exit_price);
+                    let pnl_pct = (exit_price - position.entry_price) / position.entry_price * 100.0;
+                    let pnl_usd = pnl_pct / 100.0 * (actual_shares * position.entry_price);
                     let was_loss = pnl_pct < 0.0;
                     gatekeeper.record_trade_result(timestamp, pnl_usd);
                     println!(
                         "[EXIT FILLED] {} | {:.2}% | ${:.2} | Order: {}",
                         market.label, pnl_pct, pnl_usd, order_result.order_id
                     );
+                    if let Some(side) = position.token_side {
+                        persist_exit(
+                            state_store,
+                            token_id,
+                            &TradeLog {
+                                id: None,
+                                market_slug: market.slug.clone(),
+                                token_side: side.into(),
+                                entry_price: position.entry_price,
+                                exit_price,
+                                size_usd: actual_shares * position.entry_price,
+                                pnl_usd,
+                                timestamp_entry: position.entry_timestamp as i64,
+                                timestamp_exit: timestamp as i64,
+                                order_id_entry: position.last_trade_id.clone().unwrap_or_default(),
+                                order_id_exit: Some(order_result.order_id.clone()),
+                                slippage_entry_bps: position.entry_slippage_bps,
+                                slippage_exit_bps: tracked.slippage_bps(),
+                                latency_entry_ms: position.entry_latency_ms,
+                                latency_exit_ms: tracked.latency_ms(),
+                            },
+                        );
+                    }
                     if let Some(loggers) = event_loggers {
                         loggers.log_execution(EngineEvent::LiveExit {
                             ts: timestamp,
@@ -690,3 +1160,40 @@ pub async fn handle_live_signals(
         ExitSignal::None => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_after_midnight_counts_against_the_new_day() {
+        let store = SqliteStore::in_memory().unwrap();
+        let mut state = store.get_bot_state().unwrap();
+        state.daily_pnl = -0.50;
+        state.daily_start_timestamp = 1_700_000_000;
+        store.update_bot_state(&state).unwrap();
+
+        let trade = TradeLog {
+            id: None,
+            market_slug: "btc-updown-5m-test".to_string(),
+            token_side: crate::persistence::TokenSide::Yes,
+            entry_price: 0.50,
+            exit_price: 0.40,
+            size_usd: 1.0,
+            pnl_usd: -0.20,
+            timestamp_entry: 1_700_000_000 + 86_400,
+            timestamp_exit: 1_700_000_000 + 86_400 + 60,
+            order_id_entry: "entry".to_string(),
+            order_id_exit: None,
+            slippage_entry_bps: None,
+            slippage_exit_bps: None,
+            latency_entry_ms: None,
+            latency_exit_ms: None,
+        };
+        persist_exit(Some(&store), U256::from(1u64), &trade);
+
+        let state = store.get_bot_state().unwrap();
+        assert!((state.daily_pnl + 0.20).abs() < 1e-12);
+        assert!((state.total_pnl + 0.20).abs() < 1e-12);
+    }
+}
@@ -1,18 +1,24 @@
 //! Market Feed Module
 //!
-//! Provides WebSocket and REST-based feeds for Polymarket market data.
+//! Provides WebSocket and REST-based feeds for Polymarket market data and
+//! the authenticated user channel.
 
 pub mod multi_market_feed;
+pub mod user_feed;
 
 // Re-export common types from the feed_base for compatibility
 pub use crate::bot::feed_base::{
-    BookDeltaEvent, BookChangeSide, DualBookState, DualSnapshot, LiveFeedMode,
+    BookDeltaEvent, BookChangeSide, BookDepth, DualBookState, DualSnapshot, LiveFeedMode,
     LiveStrategyInputSource, MarketSnapshot, MarketWebsocketFeed, OutcomeSide,
     PollingSnapshotSource, ReplayMode, ReplaySnapshotSource, StrategyInputSource,
-    WebsocketSnapshotSource, parse_market_ws_value,
+    TokenDepth, TradePrintEvent, WebsocketSnapshotSource, DEPTH_LEVELS, parse_market_ws_trades, parse_market_ws_value,
 };
 
 pub use multi_market_feed::{
     MarketEvent, MarketSubscription, MultiMarketWebsocketFeed, MultiMarketAggregator,
     FeedStats,
 };
+
+pub use user_feed::{
+    UserEvent, UserOrderAction, UserOrderEvent, UserTradeEvent, UserWebsocketFeed,
+};
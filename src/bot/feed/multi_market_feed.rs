@@ -6,7 +6,7 @@
 //! This is essential for the temporal arbitrage strategy which needs
 //! to monitor multiple nested timeframes at once.
 
-use crate::bot::feed_base::DualBookState;
+use crate::bot::feed_base::{DualBookState, DualSnapshot};
 use crate::bot::feed_base::MarketSnapshot;
 use crate::bot::logging::JsonlEventLogger;
 use crate::bot::strategy::Direction;
@@ -14,12 +14,16 @@ use anyhow::Result;
 use futures_util::{StreamExt, SinkExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use polymarket_client_sdk::types::Decimal;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 const CLOB_MARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+/// First reconnect delay, doubled on each failed attempt up to the maximum
+const RECONNECT_BACKOFF_MIN: std::time::Duration = std::time::Duration::from_secs(1);
+const RECONNECT_BACKOFF_MAX: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// Timeframe enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -116,6 +120,44 @@ impl MarketEvent {
     pub fn is_broken_book(&self, tolerance: f64) -> bool {
         (self.yes_ask + self.no_ask - 1.0).abs() > tolerance
     }
+
+    /// Swap the YES and NO sides. Events parsed from a NO token quote are
+    /// built as if they were YES quotes and need flipping.
+    pub fn flipped(self) -> Self {
+        let mut event = Self::new(
+            self.condition_id,
+            self.no_bid,
+            self.no_ask,
+            self.yes_bid,
+            self.yes_ask,
+            self.ts,
+        );
+        event.yes_bid_depth = self.yes_bid_depth;
+        event.yes_ask_depth = self.yes_ask_depth;
+        event
+    }
+
+    /// Convert into the dual snapshot consumed by the strategy engines
+    pub fn to_dual_snapshot(&self) -> DualSnapshot {
+        fn side(bid: f64, ask: f64, bid_depth: f64, ask_depth: f64) -> MarketSnapshot {
+            let best_bid = Decimal::from_f64_retain(bid);
+            let best_ask = Decimal::from_f64_retain(ask);
+            MarketSnapshot {
+                midpoint: Decimal::from_f64_retain((bid + ask) / 2.0),
+                best_bid,
+                best_ask,
+                spread: Decimal::from_f64_retain(ask - bid),
+                top5_bid_depth: Decimal::from_f64_retain(bid_depth).unwrap_or(Decimal::ZERO),
+                top5_ask_depth: Decimal::from_f64_retain(ask_depth).unwrap_or(Decimal::ZERO),
+            }
+        }
+
+        DualSnapshot {
+            yes: side(self.yes_bid, self.yes_ask, self.yes_bid_depth, self.yes_ask_depth),
+            no: side(self.no_bid, self.no_ask, 0.0, 0.0),
+            ts_exchange: self.ts as f64 * 1000.0,
+        }
+    }
 }
 
 /// Shared state for multi-market feed
@@ -145,8 +187,23 @@ pub struct MultiMarketWebsocketFeed {
     state: Arc<Mutex<MultiMarketFeedState>>,
     /// Event receiver
     event_rx: mpsc::UnboundedReceiver<MarketEvent>,
+    /// Subscription changes for the running WebSocket task
+    changes: mpsc::UnboundedSender<SubscriptionChange>,
     /// Join handle for the WebSocket task
-    _join_handle: tokio::task::JoinHandle<()>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+/// Subscription change applied by the WebSocket task
+enum SubscriptionChange {
+    Add(MarketSubscription),
+    Remove(String),
+}
+
+impl Drop for MultiMarketWebsocketFeed {
+    fn drop(&mut self) {
+        // The reconnect loop never exits on its own
+        self.join_handle.abort();
+    }
 }
 
 impl MultiMarketWebsocketFeed {
@@ -158,12 +215,8 @@ impl MultiMarketWebsocketFeed {
         let state = Arc::new(Mutex::new(MultiMarketFeedState::default()));
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
-        // Collect all token IDs
-        let all_tokens: Vec<String> = subscriptions.iter()
-            .flat_map(|m| vec![m.yes_token_id.clone(), m.no_token_id.clone()])
-            .collect();
-
         // Build market map and token mapping
+        let mut markets = HashMap::new();
         {
             let mut state_guard = state.lock().await;
             for sub in subscriptions {
@@ -172,47 +225,78 @@ impl MultiMarketWebsocketFeed {
                 state_guard.token_to_market.insert(sub.no_token_id.clone(), condition_id.clone());
                 state_guard.markets.insert(sub.condition_id.clone(), sub.clone());
                 state_guard.book_states.insert(sub.condition_id.clone(), DualBookState::default());
+                markets.insert(condition_id, sub);
             }
         }
 
-        // Extract token_to_market mapping for the websocket task
-        let token_to_market = {
-            let state_guard = state.lock().await;
-            state_guard.token_to_market.clone()
-        };
-
         // Spawn WebSocket task
-        eprintln!("[multi-market-feed] Subscribing to {} tokens: {:?}", all_tokens.len(), all_tokens);
+        eprintln!("[multi-market-feed] Subscribing to {} markets", markets.len());
+        let (changes, change_rx) = mpsc::unbounded_channel();
         let join_handle = tokio::spawn(async move {
-            Self::websocket_task(all_tokens, token_to_market, event_tx, logger).await;
+            Self::websocket_task(markets, change_rx, event_tx, logger).await;
         });
 
         Ok(Self {
             state,
             event_rx,
-            _join_handle: join_handle,
+            changes,
+            join_handle,
         })
     }
 
+    /// Token-to-market mapping and NO token set for the subscribed markets
+    fn token_routes(markets: &HashMap<String, MarketSubscription>) -> (HashMap<String, String>, HashSet<String>) {
+        let mut token_to_market = HashMap::new();
+        let mut no_tokens = HashSet::new();
+        for market in markets.values() {
+            token_to_market.insert(market.yes_token_id.clone(), market.condition_id.clone());
+            token_to_market.insert(market.no_token_id.clone(), market.condition_id.clone());
+            no_tokens.insert(market.no_token_id.clone());
+        }
+        (token_to_market, no_tokens)
+    }
+
+    /// Apply a subscription change, returning the message that tells a live socket about it
+    fn apply_change(markets: &mut HashMap<String, MarketSubscription>, change: SubscriptionChange) -> Option<Value> {
+        let (operation, market) = match change {
+            SubscriptionChange::Add(sub) => {
+                markets.insert(sub.condition_id.clone(), sub.clone());
+                ("subscribe", sub)
+            }
+            SubscriptionChange::Remove(condition_id) => ("unsubscribe", markets.remove(&condition_id)?),
+        };
+        Some(serde_json::json!({
+            "assets_ids": [market.yes_token_id, market.no_token_id],
+            "operation": operation
+        }))
+    }
+
     /// WebSocket task
     async fn websocket_task(
-        tokens: Vec<String>,
-        token_to_market: HashMap<String, String>,
+        mut markets: HashMap<String, MarketSubscription>,
+        mut changes: mpsc::UnboundedReceiver<SubscriptionChange>,
         event_tx: mpsc::UnboundedSender<MarketEvent>,
         logger: Option<JsonlEventLogger>,
     ) {
-        let subscribe_msg = serde_json::json!({
-            "type": "market",
-            "assets_ids": tokens
-        });
-
-        eprintln!("[multi-market-feed] Token-to-market mapping has {} entries", token_to_market.len());
-
+        let mut backoff = RECONNECT_BACKOFF_MIN;
         loop {
+            // Changes made while disconnected go out with the next subscription
+            while let Ok(change) = changes.try_recv() {
+                Self::apply_change(&mut markets, change);
+            }
+            let (mut token_to_market, mut no_tokens) = Self::token_routes(&markets);
+            let tokens: Vec<&String> = token_to_market.keys().collect();
+            let subscribe_msg = serde_json::json!({
+                "type": "market",
+                "assets_ids": tokens
+            });
+
             // Connect to WebSocket
             let stream = connect_async(CLOB_MARKET_WS_URL).await;
             let Ok((ws_stream, _)) = stream else {
-                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                eprintln!("[multi-market-feed] Connect failed, retrying in {}s", backoff.as_secs());
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX);
                 continue;
             };
 
@@ -220,14 +304,27 @@ impl MultiMarketWebsocketFeed {
 
             // Send subscription message
             if write.send(Message::Text(subscribe_msg.to_string().into())).await.is_err() {
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX);
                 continue;
             }
+            backoff = RECONNECT_BACKOFF_MIN;
 
             // Process messages
             let mut msg_count = 0;
-            while let Some(message) = read.next().await {
-                let Ok(message) = message else { break };
+            loop {
+                let message = tokio::select! {
+                    message = read.next() => message,
+                    Some(change) = changes.recv() => {
+                        let Some(update) = Self::apply_change(&mut markets, change) else { continue };
+                        (token_to_market, no_tokens) = Self::token_routes(&markets);
+                        if write.send(Message::Text(update.to_string().into())).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                let Some(Ok(message)) = message else { break };
 
                 let payload = match message {
                     Message::Text(text) => text,
@@ -251,7 +348,7 @@ impl MultiMarketWebsocketFeed {
                 };
 
                 // Process events
-                if let Err(err) = Self::process_ws_message(&value, &token_to_market, &event_tx, &logger) {
+                if let Err(err) = Self::process_ws_message(&value, &token_to_market, &no_tokens, &event_tx, &logger) {
                     eprintln!("[multi-market-feed] Error processing message: {}", err);
                 }
             }
@@ -264,16 +361,17 @@ impl MultiMarketWebsocketFeed {
     fn process_ws_message(
         value: &Value,
         token_to_market: &HashMap<String, String>,
+        no_tokens: &HashSet<String>,
         event_tx: &mpsc::UnboundedSender<MarketEvent>,
         logger: &Option<JsonlEventLogger>,
     ) -> Result<()> {
         // Handle array of events
         if let Some(items) = value.as_array() {
             for item in items {
-                Self::process_single_event(item, token_to_market, event_tx, logger)?;
+                Self::process_single_event(item, token_to_market, no_tokens, event_tx, logger)?;
             }
         } else {
-            Self::process_single_event(value, token_to_market, event_tx, logger)?;
+            Self::process_single_event(value, token_to_market, no_tokens, event_tx, logger)?;
         }
 
         Ok(())
@@ -283,12 +381,13 @@ impl MultiMarketWebsocketFeed {
     fn process_single_event(
         value: &Value,
         token_to_market: &HashMap<String, String>,
+        no_tokens: &HashSet<String>,
         event_tx: &mpsc::UnboundedSender<MarketEvent>,
         logger: &Option<JsonlEventLogger>,
     ) -> Result<()> {
         // Try Polymarket's actual WebSocket format first
         // {"market":"0x...","price_changes":[{"asset_id":"...","price":"0.7",...}]}
-        if let Some(events) = Self::parse_polymarket_ws_format(value, token_to_market) {
+        if let Some(events) = Self::parse_polymarket_ws_format(value, token_to_market, no_tokens) {
             for evt in events {
                 eprintln!("[multi-market-feed] Sending event for condition_id={}, yes_mid={}",
                     evt.condition_id.chars().take(16).collect::<String>(), evt.yes_mid);
@@ -316,6 +415,14 @@ impl MultiMarketWebsocketFeed {
             }
         };
 
+        // Quotes on the NO token were parsed as YES quotes
+        let is_no_token = value
+            .get("asset_id")
+            .or_else(|| value.get("token_id"))
+            .and_then(|v| v.as_str())
+            .is_some_and(|token_id| no_tokens.contains(token_id));
+        let event = if is_no_token { event.map(MarketEvent::flipped) } else { event };
+
         if let Some(evt) = event {
             if let Some(logger) = logger {
                 logger.log("multi_market_event", &evt);
@@ -331,6 +438,7 @@ impl MultiMarketWebsocketFeed {
     fn parse_polymarket_ws_format(
         value: &Value,
         token_to_market: &HashMap<String, String>,
+        no_tokens: &HashSet<String>,
     ) -> Option<Vec<MarketEvent>> {
         // Check if this is the polymarket format (has "market" and "price_changes" fields)
         let market_id = value.get("market")?.as_str()?;
@@ -369,14 +477,15 @@ impl MultiMarketWebsocketFeed {
                 let no_bid = (1.0 - yes_ask).max(0.001);
                 let no_ask = (1.0 - yes_bid).max(0.001);
 
-                events.push(MarketEvent::new(
+                let event = MarketEvent::new(
                     condition_id.clone(),
                     yes_bid,
                     yes_ask,
                     no_bid,
                     no_ask,
                     now,
-                ));
+                );
+                events.push(if no_tokens.contains(token_id) { event.flipped() } else { event });
             }
 
             if !events.is_empty() {
@@ -566,6 +675,7 @@ impl MultiMarketWebsocketFeed {
         state.token_to_market.insert(subscription.no_token_id.clone(), subscription.condition_id.clone());
         state.markets.insert(subscription.condition_id.clone(), subscription.clone());
         state.book_states.insert(subscription.condition_id.clone(), DualBookState::default());
+        let _ = self.changes.send(SubscriptionChange::Add(subscription));
 
         Ok(())
     }
@@ -579,6 +689,7 @@ impl MultiMarketWebsocketFeed {
             state.token_to_market.remove(&market.no_token_id);
         }
         state.book_states.remove(condition_id);
+        let _ = self.changes.send(SubscriptionChange::Remove(condition_id.to_string()));
 
         Ok(())
     }
@@ -740,6 +851,18 @@ mod tests {
         assert!(broken.is_broken_book(0.01));
     }
 
+    #[test]
+    fn test_market_event_flipped() {
+        let event = MarketEvent::new("test".to_string(), 0.30, 0.32, 0.68, 0.70, 1000).flipped();
+        assert!((event.yes_bid - 0.68).abs() < f64::EPSILON);
+        assert!((event.no_ask - 0.32).abs() < f64::EPSILON);
+        assert!((event.yes_mid - 0.69).abs() < 1e-9);
+
+        let snapshot = event.to_dual_snapshot();
+        assert!(snapshot.yes.best_bid.is_some());
+        assert_eq!(snapshot.ts_exchange, 1_000_000.0);
+    }
+
     #[test]
     fn test_multi_market_feed_state_default() {
         let state = MultiMarketFeedState::default();
@@ -748,6 +871,32 @@ mod tests {
         assert_eq!(state.token_to_market.len(), 0);
     }
 
+    #[test]
+    fn test_subscription_changes_update_routes() {
+        let sub = MarketSubscription {
+            condition_id: "test-1".to_string(),
+            yes_token_id: "yes-1".to_string(),
+            no_token_id: "no-1".to_string(),
+            timeframe: Timeframe::M5,
+            start_time: 1000,
+            end_time: 1300,
+            strike_price: 0.0,
+        };
+        let mut markets = HashMap::new();
+
+        let add = MultiMarketWebsocketFeed::apply_change(&mut markets, SubscriptionChange::Add(sub)).unwrap();
+        assert_eq!(add["operation"], "subscribe");
+        assert_eq!(add["assets_ids"], serde_json::json!(["yes-1", "no-1"]));
+        let (token_to_market, no_tokens) = MultiMarketWebsocketFeed::token_routes(&markets);
+        assert_eq!(token_to_market.get("no-1").map(String::as_str), Some("test-1"));
+        assert!(no_tokens.contains("no-1"));
+
+        let remove = MultiMarketWebsocketFeed::apply_change(&mut markets, SubscriptionChange::Remove("test-1".to_string())).unwrap();
+        assert_eq!(remove["operation"], "unsubscribe");
+        assert!(markets.is_empty());
+        assert!(MultiMarketWebsocketFeed::apply_change(&mut markets, SubscriptionChange::Remove("test-1".to_string())).is_none());
+    }
+
     #[tokio::test]
     async fn test_multi_market_aggregator() {
         let aggregator = MultiMarketAggregator::new();
@@ -0,0 +1,415 @@
+//! User Channel Feed
+//!
+//! Authenticated CLOB websocket carrying our own order placements, updates,
+//! cancellations and trade matches, so fills can be acted on as they happen
+//! instead of being discovered by polling.
+
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use polymarket_client_sdk::auth::{Credentials, ExposeSecret};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::bot::feed_base::{as_f64, parse_change_side, BookChangeSide};
+use crate::bot::logging::JsonlEventLogger;
+
+const CLOB_USER_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
+
+/// The server drops idle user connections, so keep-alives go out this often
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A subscription nobody objected to within this long counts as accepted
+const AUTH_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UserOrderAction {
+    Placement,
+    Update,
+    Cancellation,
+}
+
+/// One of our orders was placed, partially matched or cancelled
+#[derive(Debug, Clone, Serialize)]
+pub struct UserOrderEvent {
+    pub order_id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: BookChangeSide,
+    pub action: UserOrderAction,
+    pub price: f64,
+    pub original_size: f64,
+    pub size_matched: f64,
+    pub ts_ms: i64,
+}
+
+/// Our share of a match on the maker side of a trade
+#[derive(Debug, Clone, Serialize)]
+pub struct UserMakerFill {
+    pub order_id: String,
+    pub price: f64,
+    pub matched_amount: f64,
+}
+
+/// A trade involving one of our orders, as taker or maker
+#[derive(Debug, Clone, Serialize)]
+pub struct UserTradeEvent {
+    pub trade_id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: BookChangeSide,
+    pub price: f64,
+    pub size: f64,
+    /// MATCHED, MINED, CONFIRMED, RETRYING or FAILED
+    pub status: String,
+    pub taker_order_id: Option<String>,
+    pub maker_orders: Vec<UserMakerFill>,
+    pub ts_ms: i64,
+}
+
+impl UserTradeEvent {
+    pub fn is_failed(&self) -> bool {
+        self.status.eq_ignore_ascii_case("FAILED")
+    }
+
+    /// Fills of `order_id` in this trade as `(fill_id, price, size)`, keyed the
+    /// same way as fills reconciled from the REST trades endpoint.
+    pub fn fills_for(&self, order_id: &str) -> Vec<(String, f64, f64)> {
+        let mut fills = Vec::new();
+        if self.taker_order_id.as_deref() == Some(order_id) {
+            fills.push((self.trade_id.clone(), self.price, self.size));
+        }
+        for maker in self.maker_orders.iter().filter(|m| m.order_id == order_id) {
+            fills.push((
+                format!("{}:{}", self.trade_id, maker.order_id),
+                maker.price,
+                maker.matched_amount,
+            ));
+        }
+        fills
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum UserEvent {
+    Order(UserOrderEvent),
+    Trade(UserTradeEvent),
+}
+
+pub struct UserWebsocketFeed {
+    rx: mpsc::UnboundedReceiver<UserEvent>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl UserWebsocketFeed {
+    /// Subscribe to the user channel. An empty `markets` list streams events
+    /// for every market the API key trades. Fails if the first connection
+    /// cannot be made or the server rejects the credentials; later
+    /// disconnects are retried, and a later rejection ends the feed.
+    pub async fn connect(
+        credentials: &Credentials,
+        markets: Vec<String>,
+        logger: Option<JsonlEventLogger>,
+    ) -> Result<Self> {
+        let subscribe = serde_json::json!({
+            "type": "user",
+            "markets": markets,
+            "auth": {
+                "apiKey": credentials.key().to_string(),
+                "secret": credentials.secret().expose_secret(),
+                "passphrase": credentials.passphrase().expose_secret(),
+            }
+        })
+        .to_string();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = oneshot::channel::<Result<(), String>>();
+
+        let join_handle = tokio::spawn(async move {
+            // Outcome of the first subscription, until reported
+            let mut ready = Some(ready_tx);
+            loop {
+                let stream = connect_async(CLOB_USER_WS_URL).await;
+                let Ok((ws_stream, _)) = stream else {
+                    if let Some(ready) = ready.take() {
+                        let _ = ready.send(Err("could not connect to the user channel".to_string()));
+                        return;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                    continue;
+                };
+
+                let (mut write, mut read) = ws_stream.split();
+                if write.send(Message::Text(subscribe.clone())).await.is_err() {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    continue;
+                }
+
+                let mut ping = interval(PING_INTERVAL);
+                ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                ping.tick().await;
+                // The server only answers a subscription to reject it
+                let accept_after = tokio::time::sleep(AUTH_GRACE);
+                tokio::pin!(accept_after);
+
+                loop {
+                    let message = tokio::select! {
+                        _ = &mut accept_after, if ready.is_some() => {
+                            if let Some(ready) = ready.take() {
+                                let _ = ready.send(Ok(()));
+                            }
+                            continue;
+                        }
+                        _ = ping.tick() => {
+                            if write.send(Message::Text("PING".into())).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                        message = read.next() => message,
+                    };
+                    let Some(Ok(message)) = message else {
+                        break;
+                    };
+
+                    let payload = match message {
+                        Message::Text(text) => text.to_string(),
+                        Message::Binary(bin) => String::from_utf8_lossy(&bin).to_string(),
+                        Message::Ping(_) | Message::Pong(_) => continue,
+                        Message::Close(frame) => {
+                            let reason = frame.map(|f| f.reason.to_string()).unwrap_or_default();
+                            if let Some(ready) = ready.take() {
+                                let _ = ready.send(Err(format!("user channel closed on subscribe: {reason}")));
+                                return;
+                            }
+                            break;
+                        }
+                        Message::Frame(_) => continue,
+                    };
+
+                    if let Some(reason) = rejection(&payload) {
+                        match ready.take() {
+                            Some(ready) => {
+                                let _ = ready.send(Err(reason));
+                            }
+                            None => eprintln!("[warn] User channel rejected the credentials: {reason}"),
+                        }
+                        return;
+                    }
+                    let parsed: serde_json::Result<Value> = serde_json::from_str(&payload);
+                    let Ok(value) = parsed else {
+                        continue;
+                    };
+                    if let Some(ready) = ready.take() {
+                        let _ = ready.send(Ok(()));
+                    }
+
+                    for event in parse_user_ws_value(&value) {
+                        if let Some(logger) = &logger {
+                            logger.log("user_events", &event);
+                        }
+                        if tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+        });
+
+        match ready_rx.await {
+            Ok(Ok(())) => Ok(Self { rx, join_handle }),
+            Ok(Err(reason)) => anyhow::bail!("User channel subscription failed: {reason}"),
+            Err(_) => anyhow::bail!("User channel task ended before subscribing"),
+        }
+    }
+
+    pub async fn recv(&mut self) -> Option<UserEvent> {
+        self.rx.recv().await
+    }
+
+    pub fn try_recv(&mut self) -> Option<UserEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    pub async fn shutdown(self) {
+        self.join_handle.abort();
+        let _ = self.join_handle.await;
+    }
+}
+
+/// Reason the server refused the subscription, if `payload` is a refusal.
+/// Anything but events and keep-alive replies is one: plain-text errors such
+/// as `INVALID AUTH` or a JSON object carrying an `error`.
+fn rejection(payload: &str) -> Option<String> {
+    let trimmed = payload.trim();
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("PONG") {
+        return None;
+    }
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::Object(map)) => map.get("error").map(|error| match error {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }),
+        Ok(_) => None,
+        Err(_) => Some(trimmed.to_string()),
+    }
+}
+
+pub fn parse_user_ws_value(value: &Value) -> Vec<UserEvent> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(parse_user_ws_value).collect(),
+        Value::Object(map) => parse_user_ws_object(map).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_user_ws_object(object: &serde_json::Map<String, Value>) -> Option<UserEvent> {
+    match object.get("event_type").and_then(Value::as_str)? {
+        "order" => parse_order_message(object).map(UserEvent::Order),
+        "trade" => parse_trade_message(object).map(UserEvent::Trade),
+        _ => None,
+    }
+}
+
+fn parse_order_message(object: &serde_json::Map<String, Value>) -> Option<UserOrderEvent> {
+    let action = match object.get("type").and_then(Value::as_str)?.to_ascii_uppercase().as_str() {
+        "PLACEMENT" => UserOrderAction::Placement,
+        "UPDATE" => UserOrderAction::Update,
+        "CANCELLATION" => UserOrderAction::Cancellation,
+        _ => return None,
+    };
+
+    Some(UserOrderEvent {
+        order_id: str_field(object, "id")?,
+        market: str_field(object, "market").unwrap_or_default(),
+        asset_id: str_field(object, "asset_id")?,
+        side: object.get("side").and_then(Value::as_str).and_then(parse_change_side)?,
+        action,
+        price: object.get("price").and_then(as_f64)?,
+        original_size: object.get("original_size").and_then(as_f64).unwrap_or(0.0),
+        size_matched: object.get("size_matched").and_then(as_f64).unwrap_or(0.0),
+        ts_ms: event_ts_ms(object, &["timestamp"]),
+    })
+}
+
+fn parse_trade_message(object: &serde_json::Map<String, Value>) -> Option<UserTradeEvent> {
+    let maker_orders = object
+        .get("maker_orders")
+        .and_then(Value::as_array)
+        .map(|makers| {
+            makers
+                .iter()
+                .filter_map(|maker| {
+                    Some(UserMakerFill {
+                        order_id: maker.get("order_id").and_then(Value::as_str)?.to_string(),
+                        price: maker.get("price").and_then(as_f64)?,
+                        matched_amount: maker.get("matched_amount").and_then(as_f64)?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(UserTradeEvent {
+        trade_id: str_field(object, "id")?,
+        market: str_field(object, "market").unwrap_or_default(),
+        asset_id: str_field(object, "asset_id")?,
+        side: object.get("side").and_then(Value::as_str).and_then(parse_change_side)?,
+        price: object.get("price").and_then(as_f64)?,
+        size: object.get("size").and_then(as_f64)?,
+        status: str_field(object, "status").unwrap_or_default().to_ascii_uppercase(),
+        taker_order_id: str_field(object, "taker_order_id").filter(|id| !id.is_empty()),
+        maker_orders,
+        ts_ms: event_ts_ms(object, &["matchtime", "match_time", "timestamp"]),
+    })
+}
+
+fn str_field(object: &serde_json::Map<String, Value>, key: &str) -> Option<String> {
+    object.get(key).and_then(Value::as_str).map(ToOwned::to_owned)
+}
+
+/// First present timestamp field in milliseconds; the channel mixes seconds
+/// and milliseconds depending on the message.
+fn event_ts_ms(object: &serde_json::Map<String, Value>, keys: &[&str]) -> i64 {
+    keys.iter()
+        .find_map(|key| object.get(*key).and_then(as_f64))
+        .map(|ts| if ts < 1e11 { ts * 1000.0 } else { ts } as i64)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_order_cancellation() {
+        let value = serde_json::json!({
+            "event_type": "order",
+            "type": "CANCELLATION",
+            "id": "0xorder",
+            "market": "0xmarket",
+            "asset_id": "123",
+            "side": "BUY",
+            "price": "0.47",
+            "original_size": "10",
+            "size_matched": "4",
+            "timestamp": "1700000000"
+        });
+
+        let events = parse_user_ws_value(&value);
+        let [UserEvent::Order(order)] = events.as_slice() else {
+            panic!("expected one order event, got {events:?}");
+        };
+        assert_eq!(order.action, UserOrderAction::Cancellation);
+        assert_eq!(order.side, BookChangeSide::Buy);
+        assert!((order.size_matched - 4.0).abs() < 1e-9);
+        assert_eq!(order.ts_ms, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn auth_errors_are_rejections() {
+        assert_eq!(rejection("INVALID AUTH").as_deref(), Some("INVALID AUTH"));
+        assert_eq!(rejection(r#"{"error":"invalid api key"}"#).as_deref(), Some("invalid api key"));
+        assert_eq!(rejection("PONG"), None);
+        assert_eq!(rejection(r#"[{"event_type":"order"}]"#), None);
+        assert_eq!(rejection(r#"{"event_type":"trade"}"#), None);
+    }
+
+    #[test]
+    fn trade_fills_match_taker_and_maker_orders() {
+        let value = serde_json::json!([{
+            "event_type": "trade",
+            "id": "trade-1",
+            "market": "0xmarket",
+            "asset_id": "123",
+            "side": "SELL",
+            "price": "0.52",
+            "size": "10",
+            "status": "MATCHED",
+            "taker_order_id": "0xtaker",
+            "matchtime": "1700000000123",
+            "maker_orders": [
+                { "order_id": "0xmaker", "price": "0.52", "matched_amount": "6" },
+                { "order_id": "0xother", "price": "0.53", "matched_amount": "4" }
+            ]
+        }, { "event_type": "book" }]);
+
+        let events = parse_user_ws_value(&value);
+        assert_eq!(events.len(), 1);
+        let UserEvent::Trade(trade) = &events[0] else {
+            panic!("expected a trade event");
+        };
+        assert_eq!(trade.ts_ms, 1_700_000_000_123);
+        assert_eq!(trade.fills_for("0xtaker"), vec![("trade-1".to_string(), 0.52, 10.0)]);
+        assert_eq!(
+            trade.fills_for("0xmaker"),
+            vec![("trade-1:0xmaker".to_string(), 0.52, 6.0)]
+        );
+        assert!(trade.fills_for("0xunknown").is_empty());
+    }
+}
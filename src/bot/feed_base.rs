@@ -57,9 +57,35 @@ pub struct BookDeltaEvent {
     pub change_side: Option<BookChangeSide>,
     pub top5_bid_depth: Option<f64>,
     pub top5_ask_depth: Option<f64>,
+    /// Full `(price, size)` ladders of a book message; `None` for deltas
+    pub bids: Option<Vec<(f64, f64)>>,
+    pub asks: Option<Vec<(f64, f64)>>,
     pub source: &'static str,
 }
 
+/// A `last_trade_price` print: one match on one outcome token
+#[derive(Debug, Clone, Serialize)]
+pub struct TradePrintEvent {
+    pub market_id: Option<String>,
+    pub token_id: String,
+    pub side: OutcomeSide,
+    pub ts_exchange: f64,
+    pub price: f64,
+    pub size: f64,
+    /// Side of the taker that crossed the spread
+    pub taker_side: BookChangeSide,
+    pub source: &'static str,
+}
+
+impl TradePrintEvent {
+    /// Size signed by its pressure on YES: buying YES or selling NO is
+    /// positive, selling YES or buying NO negative
+    pub fn signed_yes_volume(&self) -> f64 {
+        let bullish = (self.side == OutcomeSide::Yes) == (self.taker_side == BookChangeSide::Buy);
+        if bullish { self.size } else { -self.size }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct MarketSnapshot {
     pub midpoint: Option<Decimal>,
@@ -77,27 +103,94 @@ pub struct DualSnapshot {
     pub ts_exchange: f64,
 }
 
-#[derive(Debug, Clone)]
+/// Book levels the depth features read
+pub const DEPTH_LEVELS: usize = 5;
+
+/// Sizes of one token's best levels, best first; 0 past the last level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TokenDepth {
+    pub bid_sizes: [f64; DEPTH_LEVELS],
+    pub ask_sizes: [f64; DEPTH_LEVELS],
+}
+
+impl TokenDepth {
+    /// Depth from `(price, size)` levels in any order; empty and
+    /// non-finite levels are dropped
+    pub fn from_levels(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Self {
+        fn best_sizes(levels: &[(f64, f64)], descending: bool) -> [f64; DEPTH_LEVELS] {
+            let mut live: Vec<(f64, f64)> =
+                levels.iter().copied().filter(|(_, size)| *size > 0.0 && size.is_finite()).collect();
+            live.sort_by(|a, b| if descending { b.0.total_cmp(&a.0) } else { a.0.total_cmp(&b.0) });
+            let mut sizes = [0.0; DEPTH_LEVELS];
+            for (slot, (_, size)) in sizes.iter_mut().zip(live) {
+                *slot = size;
+            }
+            sizes
+        }
+        Self { bid_sizes: best_sizes(bids, true), ask_sizes: best_sizes(asks, false) }
+    }
+
+    /// Cumulative bid size through the `levels` best levels
+    pub fn bid_depth(&self, levels: usize) -> f64 {
+        self.bid_sizes.iter().take(levels).sum()
+    }
+
+    /// Cumulative ask size through the `levels` best levels
+    pub fn ask_depth(&self, levels: usize) -> f64 {
+        self.ask_sizes.iter().take(levels).sum()
+    }
+}
+
+/// Level depth of both outcome tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BookDepth {
+    pub yes: TokenDepth,
+    pub no: TokenDepth,
+}
+
+#[derive(Debug, Clone, Default)]
 struct TokenBookState {
     best_bid: Option<f64>,
     best_ask: Option<f64>,
     top5_bid_depth: Option<f64>,
     top5_ask_depth: Option<f64>,
+    /// Price-sorted ascending, from the last book message on
+    bids: Option<Vec<(f64, f64)>>,
+    asks: Option<Vec<(f64, f64)>>,
     last_ts_exchange: f64,
 }
 
-impl Default for TokenBookState {
-    fn default() -> Self {
-        Self {
-            best_bid: None,
-            best_ask: None,
-            top5_bid_depth: None,
-            top5_ask_depth: None,
-            last_ts_exchange: 0.0,
+impl TokenBookState {
+    /// Level depth once a book message has been seen; levels through the
+    /// current quote are stale and dropped
+    fn depth(&self) -> Option<TokenDepth> {
+        let (bids, asks) = (self.bids.as_ref()?, self.asks.as_ref()?);
+        let best_bid = self.best_bid.unwrap_or(f64::INFINITY);
+        let best_ask = self.best_ask.unwrap_or(0.0);
+        let bids: Vec<(f64, f64)> = bids.iter().copied().filter(|(price, _)| *price <= best_bid + 1e-9).collect();
+        let asks: Vec<(f64, f64)> = asks.iter().copied().filter(|(price, _)| *price >= best_ask - 1e-9).collect();
+        Some(TokenDepth::from_levels(&bids, &asks))
+    }
+}
+
+/// Apply a level change to an ascending ladder; size 0 removes the level
+fn set_level(levels: &mut Vec<(f64, f64)>, price: f64, size: f64) {
+    match levels.binary_search_by(|(p, _)| p.total_cmp(&price)) {
+        Ok(i) if size > 0.0 => levels[i].1 = size,
+        Ok(i) => {
+            levels.remove(i);
         }
+        Err(i) if size > 0.0 => levels.insert(i, (price, size)),
+        Err(_) => {}
     }
 }
 
+fn sorted_ladder(levels: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut sorted: Vec<(f64, f64)> = levels.iter().copied().filter(|(_, size)| *size > 0.0).collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    sorted
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct DualBookState {
     yes: TokenBookState,
@@ -119,9 +212,26 @@ impl DualBookState {
         if let Some(depth) = event.top5_ask_depth {
             state.top5_ask_depth = Some(depth);
         }
+        if let (Some(bids), Some(asks)) = (&event.bids, &event.asks) {
+            state.bids = Some(sorted_ladder(bids));
+            state.asks = Some(sorted_ladder(asks));
+        } else if let (Some(price), Some(size), Some(side)) = (event.change_price, event.change_size, event.change_side) {
+            let ladder = match side {
+                BookChangeSide::Buy => state.bids.as_mut(),
+                BookChangeSide::Sell => state.asks.as_mut(),
+            };
+            if let Some(ladder) = ladder {
+                set_level(ladder, price, size);
+            }
+        }
         state.last_ts_exchange = event.ts_exchange;
     }
 
+    /// Level depth of both tokens, once each has had a book message
+    pub fn depth(&self) -> Option<BookDepth> {
+        Some(BookDepth { yes: self.yes.depth()?, no: self.no.depth()? })
+    }
+
     pub fn snapshot(&self) -> Option<DualSnapshot> {
         let yes_bid = self.yes.best_bid?;
         let yes_ask = self.yes.best_ask?;
@@ -164,6 +274,18 @@ impl MarketSnapshot {
 pub trait StrategyInputSource {
     fn next_snapshot<'a>(&'a mut self) -> BoxFuture<'a, Result<Option<DualSnapshot>>>;
     fn current_time(&self) -> Option<u64>;
+
+    /// Trade prints received since the last call. Sources without a trade
+    /// stream (polling, replays) have none.
+    fn take_trades(&mut self) -> Vec<TradePrintEvent> {
+        Vec::new()
+    }
+
+    /// Level depth behind the latest snapshot, for sources that track
+    /// full books
+    fn book_depth(&self) -> Option<BookDepth> {
+        None
+    }
 }
 
 pub struct PollingSnapshotSource<'a> {
@@ -207,6 +329,7 @@ impl StrategyInputSource for PollingSnapshotSource<'_> {
 
 pub struct MarketWebsocketFeed {
     rx: mpsc::UnboundedReceiver<BookDeltaEvent>,
+    trade_rx: mpsc::UnboundedReceiver<TradePrintEvent>,
     join_handle: tokio::task::JoinHandle<()>,
 }
 
@@ -220,6 +343,7 @@ impl MarketWebsocketFeed {
         let yes = yes_token_id.to_string();
         let no = no_token_id.to_string();
         let (tx, rx) = mpsc::unbounded_channel();
+        let (trade_tx, trade_rx) = mpsc::unbounded_channel();
 
         let join_handle = tokio::spawn(async move {
             loop {
@@ -266,13 +390,21 @@ impl MarketWebsocketFeed {
                             return;
                         }
                     }
+                    for trade in parse_market_ws_trades(&value, market_id.as_deref(), &yes, &no) {
+                        if let Some(logger) = &logger {
+                            logger.log("raw_trade_events", &trade);
+                        }
+                        if trade_tx.send(trade).is_err() {
+                            return;
+                        }
+                    }
                 }
 
                 tokio::time::sleep(std::time::Duration::from_secs(1)).await;
             }
         });
 
-        Ok(Self { rx, join_handle })
+        Ok(Self { rx, trade_rx, join_handle })
     }
 
     pub async fn recv(&mut self) -> Option<BookDeltaEvent> {
@@ -283,6 +415,10 @@ impl MarketWebsocketFeed {
         self.rx.try_recv().ok()
     }
 
+    pub fn try_recv_trade(&mut self) -> Option<TradePrintEvent> {
+        self.trade_rx.try_recv().ok()
+    }
+
     pub async fn shutdown(self) {
         self.join_handle.abort();
         let _ = self.join_handle.await;
@@ -292,6 +428,7 @@ impl MarketWebsocketFeed {
 pub struct WebsocketSnapshotSource {
     feed: MarketWebsocketFeed,
     book_state: DualBookState,
+    trades: Vec<TradePrintEvent>,
     last_ts: Option<u64>,
 }
 
@@ -305,6 +442,7 @@ impl WebsocketSnapshotSource {
         Ok(Self {
             feed: MarketWebsocketFeed::connect(market_id, yes_token_id, no_token_id, logger).await?,
             book_state: DualBookState::default(),
+            trades: Vec::new(),
             last_ts: None,
         })
     }
@@ -320,6 +458,9 @@ impl StrategyInputSource for WebsocketSnapshotSource {
             while let Some(event) = self.feed.try_recv() {
                 self.book_state.apply(&event);
             }
+            while let Some(trade) = self.feed.try_recv_trade() {
+                self.trades.push(trade);
+            }
             let snapshot = self.book_state.snapshot();
             self.last_ts = snapshot.as_ref().map(|item| item.ts_exchange.floor() as u64);
             Ok(snapshot)
@@ -329,6 +470,14 @@ impl StrategyInputSource for WebsocketSnapshotSource {
     fn current_time(&self) -> Option<u64> {
         self.last_ts
     }
+
+    fn take_trades(&mut self) -> Vec<TradePrintEvent> {
+        std::mem::take(&mut self.trades)
+    }
+
+    fn book_depth(&self) -> Option<BookDepth> {
+        self.book_state.depth()
+    }
 }
 
 pub enum LiveStrategyInputSource<'a> {
@@ -374,6 +523,20 @@ impl StrategyInputSource for LiveStrategyInputSource<'_> {
             Self::Websocket(source) => source.current_time(),
         }
     }
+
+    fn take_trades(&mut self) -> Vec<TradePrintEvent> {
+        match self {
+            Self::Poll(source) => source.take_trades(),
+            Self::Websocket(source) => source.take_trades(),
+        }
+    }
+
+    fn book_depth(&self) -> Option<BookDepth> {
+        match self {
+            Self::Poll(source) => source.book_depth(),
+            Self::Websocket(source) => source.book_depth(),
+        }
+    }
 }
 
 pub struct ReplaySnapshotSource {
@@ -421,6 +584,60 @@ pub fn parse_market_ws_value(
     }
 }
 
+/// Trade prints (`last_trade_price` messages) for the two outcome tokens
+pub fn parse_market_ws_trades(
+    value: &Value,
+    market_id: Option<&str>,
+    yes_token_id: &str,
+    no_token_id: &str,
+) -> Vec<TradePrintEvent> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .flat_map(|item| parse_market_ws_trades(item, market_id, yes_token_id, no_token_id))
+            .collect(),
+        Value::Object(map) => parse_trade_message(map, market_id, yes_token_id, no_token_id)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_trade_message(
+    object: &serde_json::Map<String, Value>,
+    market_id: Option<&str>,
+    yes_token_id: &str,
+    no_token_id: &str,
+) -> Option<TradePrintEvent> {
+    let event_type = object
+        .get("event_type")
+        .and_then(Value::as_str)
+        .or_else(|| object.get("type").and_then(Value::as_str))?;
+    if event_type != "last_trade_price" {
+        return None;
+    }
+    let token_id = object
+        .get("asset_id")
+        .and_then(Value::as_str)
+        .or_else(|| object.get("token_id").and_then(Value::as_str))?;
+    let side = outcome_side_from_token(token_id, yes_token_id, no_token_id)?;
+    let size = object.get("size").and_then(as_f64).filter(|size| *size > 0.0)?;
+
+    Some(TradePrintEvent {
+        market_id: market_id.map(ToOwned::to_owned),
+        token_id: token_id.to_string(),
+        side,
+        ts_exchange: object
+            .get("timestamp")
+            .and_then(as_f64)
+            .unwrap_or_else(current_ts_seconds),
+        price: object.get("price").and_then(as_f64)?,
+        size,
+        taker_side: object.get("side").and_then(Value::as_str).and_then(parse_change_side)?,
+        source: "websocket_last_trade",
+    })
+}
+
 fn parse_market_ws_object(
     object: &serde_json::Map<String, Value>,
     market_id: Option<&str>,
@@ -472,6 +689,8 @@ fn parse_book_message(
     let best_ask = extract_best_price(&sells, false)?;
     let top5_bid_depth = sum_depth(&buys, 5, true);
     let top5_ask_depth = sum_depth(&sells, 5, false);
+    let bids = parse_levels(&buys);
+    let asks = parse_levels(&sells);
     let ts_exchange = object
         .get("timestamp")
         .and_then(as_f64)
@@ -489,6 +708,8 @@ fn parse_book_message(
         change_side: None,
         top5_bid_depth: Some(top5_bid_depth),
         top5_ask_depth: Some(top5_ask_depth),
+        bids: Some(bids),
+        asks: Some(asks),
         source: "websocket_book",
     })
 }
@@ -527,6 +748,8 @@ fn parse_price_change_message(
             .and_then(parse_change_side),
         top5_bid_depth: None,
         top5_ask_depth: None,
+        bids: None,
+        asks: None,
         source: "websocket_price_change",
     })
 }
@@ -541,7 +764,7 @@ fn outcome_side_from_token(token_id: &str, yes_token_id: &str, no_token_id: &str
     }
 }
 
-fn parse_change_side(raw: &str) -> Option<BookChangeSide> {
+pub(crate) fn parse_change_side(raw: &str) -> Option<BookChangeSide> {
     match raw.to_ascii_uppercase().as_str() {
         "BUY" => Some(BookChangeSide::Buy),
         "SELL" => Some(BookChangeSide::Sell),
@@ -570,7 +793,8 @@ fn extract_best_price(levels: &[Value], is_bid: bool) -> Option<f64> {
     }
 }
 
-fn sum_depth(levels: &[Value], top_n: usize, descending: bool) -> f64 {
+/// `(price, size)` pairs of a book message side, as sent
+fn parse_levels(levels: &[Value]) -> Vec<(f64, f64)> {
     let mut parsed = Vec::new();
     for level in levels {
         let (price, size) = match level {
@@ -585,6 +809,11 @@ fn sum_depth(levels: &[Value], top_n: usize, descending: bool) -> f64 {
             parsed.push((price, size.max(0.0)));
         }
     }
+    parsed
+}
+
+fn sum_depth(levels: &[Value], top_n: usize, descending: bool) -> f64 {
+    let mut parsed = parse_levels(levels);
     parsed.sort_by(|left, right| {
         if descending {
             right.0.partial_cmp(&left.0).unwrap_or(std::cmp::Ordering::Equal)
@@ -595,7 +824,7 @@ fn sum_depth(levels: &[Value], top_n: usize, descending: bool) -> f64 {
     parsed.into_iter().take(top_n).map(|(_, size)| size).sum()
 }
 
-fn as_f64(value: &Value) -> Option<f64> {
+pub(crate) fn as_f64(value: &Value) -> Option<f64> {
     match value {
         Value::Number(number) => number.as_f64(),
         Value::String(text) => text.parse::<f64>().ok(),
@@ -606,3 +835,81 @@ fn as_f64(value: &Value) -> Option<f64> {
 fn current_ts_seconds() -> f64 {
     chrono::Utc::now().timestamp_millis() as f64 / 1000.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_trade_price_parses_to_signed_prints() {
+        let value = serde_json::json!([
+            {
+                "event_type": "last_trade_price",
+                "asset_id": "111",
+                "price": "0.62",
+                "size": "25",
+                "side": "SELL",
+                "timestamp": "1700000000123"
+            },
+            {
+                "event_type": "last_trade_price",
+                "asset_id": "222",
+                "price": "0.38",
+                "size": "10",
+                "side": "SELL",
+                "timestamp": "1700000000456"
+            },
+            { "event_type": "price_change", "asset_id": "111", "changes": [] }
+        ]);
+        let trades = parse_market_ws_trades(&value, Some("m"), "111", "222");
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].side, OutcomeSide::Yes);
+        assert_eq!(trades[0].taker_side, BookChangeSide::Sell);
+        assert!((trades[0].signed_yes_volume() + 25.0).abs() < 1e-9);
+        // Selling NO is YES-side pressure
+        assert!((trades[1].signed_yes_volume() - 10.0).abs() < 1e-9);
+        // Trades never leak into the book delta stream
+        assert!(parse_market_ws_value(&value, Some("m"), "111", "222").is_empty());
+    }
+
+    #[test]
+    fn book_state_tracks_levels_through_price_changes() {
+        let book = serde_json::json!({
+            "event_type": "book",
+            "asset_id": "111",
+            "timestamp": "1700000000",
+            "bids": [["0.48", "10"], ["0.50", "4"], ["0.49", "6"]],
+            "asks": [{"price": "0.52", "size": "3"}, {"price": "0.53", "size": "7"}]
+        });
+        let no_book = serde_json::json!({
+            "event_type": "book", "asset_id": "222", "timestamp": "1700000000",
+            "bids": [["0.47", "5"]], "asks": [["0.50", "8"]]
+        });
+        let mut state = DualBookState::default();
+        for event in parse_market_ws_value(&serde_json::json!([book]), None, "111", "222") {
+            state.apply(&event);
+        }
+        // Depth waits for both tokens
+        assert!(state.depth().is_none());
+        for event in parse_market_ws_value(&no_book, None, "111", "222") {
+            state.apply(&event);
+        }
+        let depth = state.depth().unwrap();
+        assert_eq!(depth.yes.bid_sizes, [4.0, 6.0, 10.0, 0.0, 0.0]);
+        assert_eq!(depth.yes.ask_sizes, [3.0, 7.0, 0.0, 0.0, 0.0]);
+        assert_eq!(depth.yes.bid_depth(2), 10.0);
+
+        // The best bid is lifted: its level goes and the quote moves down
+        let change = serde_json::json!({
+            "event_type": "price_change", "asset_id": "111", "timestamp": "1700000001",
+            "best_bid": "0.49", "best_ask": "0.52",
+            "change_price": "0.50", "change_size": "0", "change_side": "BUY"
+        });
+        for event in parse_market_ws_value(&change, None, "111", "222") {
+            state.apply(&event);
+        }
+        let depth = state.depth().unwrap();
+        assert_eq!(depth.yes.bid_sizes, [6.0, 10.0, 0.0, 0.0, 0.0]);
+        assert_eq!(depth.no.ask_depth(DEPTH_LEVELS), 8.0);
+    }
+}
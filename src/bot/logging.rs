@@ -1,10 +1,12 @@
-use serde::Serialize;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fs::{create_dir_all, File, OpenOptions};
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, LineWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EngineEvent {
     BookUpdate {
@@ -62,6 +64,16 @@ pub enum EngineEvent {
         pnl_usd: f64,
         order_id: Option<String>,
     },
+    OrderUpdate {
+        ts: u64,
+        market_slug: String,
+        order_id: String,
+        status: String,
+        avg_price: Option<f64>,
+        filled_size: f64,
+        slippage_bps: Option<i64>,
+        latency_ms: Option<i64>,
+    },
     PendingSettlement {
         ts: u64,
         market_slug: String,
@@ -128,6 +140,50 @@ impl JsonlEventLogger {
     }
 }
 
+/// Appends records as JSON lines, one flushed line per record, so several
+/// runs can share a file
+pub struct JsonlAppender {
+    writer: LineWriter<File>,
+}
+
+impl JsonlAppender {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self { writer: LineWriter::new(file) })
+    }
+
+    pub fn append<T: Serialize>(&mut self, record: &T) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Read records from a JSON array export or a JSON-lines log
+pub fn read_json_records<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let records = if content.trim_start().starts_with('[') {
+        serde_json::from_str(&content)?
+    } else {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<_>>()?
+    };
+    Ok(records)
+}
+
 #[derive(Clone)]
 pub struct EngineEventLoggers {
     market: JsonlEventLogger,
@@ -158,7 +214,7 @@ impl EngineEventLoggers {
     }
 }
 
-fn split_log_paths(path: &Path) -> (PathBuf, PathBuf, PathBuf) {
+pub(crate) fn split_log_paths(path: &Path) -> (PathBuf, PathBuf, PathBuf) {
     if path.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
         let parent = path.parent().unwrap_or_else(|| Path::new("."));
         let stem = path
@@ -0,0 +1,1105 @@
+//! Maker Execution
+//!
+//! Posts GTC limit orders at or inside the best price instead of crossing the
+//! spread with FOK market orders. Resting orders are repriced as the book
+//! moves, cancelled on signal flip or near expiry, and every (partial) fill is
+//! folded back into the market's `LivePosition`.
+
+use crate::bot::discovery::WatchedMarket;
+use crate::bot::execution::{
+    LivePosition, OrderResult, OrderSide, RoundTrip, forget_position, persist_entry, persist_exit,
+};
+use crate::bot::feed::{DualSnapshot, MarketSnapshot, UserEvent};
+use crate::bot::logging::{EngineEvent, EngineEventLoggers};
+use crate::bot::order_tracker::{reconcile_order, TrackedFill, TrackedOrder};
+use crate::bot::risk::{
+    best_ask_price, best_bid_price, EntryContext, GateDecision, GatekeeperState,
+    TradeDirection,
+};
+use crate::bot::shadow::TokenSide;
+use crate::bot::signal::{EntrySignal, ExitSignal, SignalState};
+use crate::persistence::{StateStore, TradeLog};
+use anyhow::Result;
+use chrono::Utc;
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::Normal;
+use polymarket_client_sdk::clob;
+use polymarket_client_sdk::clob::types::{OrderStatusType, OrderType, Side};
+use polymarket_client_sdk::types::{Decimal, U256};
+
+/// Smallest share increment the CLOB accepts
+const MIN_SHARES: f64 = 0.01;
+const STREAMED_RECONCILE_MS: i64 = 10_000;
+
+/// Tuning for maker order placement and repricing
+#[derive(Debug, Clone, Copy)]
+pub struct MakerConfig {
+    /// Price increment of the market
+    pub tick_size: f64,
+    /// Ticks to improve on the best price when joining the book (0 = join)
+    pub improve_ticks: u32,
+    /// Ticks the book may move away from a resting order before it is
+    /// repriced; orders still at the touch keep their queue position
+    pub reprice_ticks: u32,
+    /// Cancel all resting orders this many seconds before market end
+    pub cancel_before_expiry_s: i64,
+    /// Reject instead of crossing if the order would take liquidity
+    pub post_only: bool,
+}
+
+impl Default for MakerConfig {
+    fn default() -> Self {
+        Self {
+            tick_size: 0.01,
+            improve_ticks: 0,
+            reprice_ticks: 1,
+            cancel_before_expiry_s: 20,
+            post_only: true,
+        }
+    }
+}
+
+/// A GTC limit order resting on the book
+#[derive(Debug, Clone)]
+pub struct RestingOrder {
+    pub order_id: String,
+    pub token_id: U256,
+    pub token_side: TokenSide,
+    pub side: OrderSide,
+    pub price: f64,
+    pub shares: f64,
+    /// Shares matched so far and folded into the position
+    pub filled_shares: f64,
+    /// Lifecycle and fills as reported by the CLOB
+    pub tracked: TrackedOrder,
+    /// Tracked fills already folded into the position
+    folded_fills: usize,
+    last_reconcile_ms: i64,
+}
+
+impl RestingOrder {
+    fn posted(
+        order_id: String,
+        token_id: U256,
+        token_side: TokenSide,
+        side: OrderSide,
+        price: f64,
+        shares: f64,
+        submitted_at_ms: i64,
+    ) -> Self {
+        Self {
+            tracked: TrackedOrder::submitted(order_id.clone(), token_id, side, price, submitted_at_ms),
+            order_id,
+            token_id,
+            token_side,
+            side,
+            price,
+            shares,
+            filled_shares: 0.0,
+            folded_fills: 0,
+            last_reconcile_ms: submitted_at_ms,
+        }
+    }
+
+    pub fn remaining_shares(&self) -> f64 {
+        (self.shares - self.filled_shares).max(0.0)
+    }
+}
+
+/// What to do with a resting order given the current book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MakerAction {
+    Hold,
+    Reprice { price: f64 },
+    Cancel { reason: CancelReason },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    SignalFlip,
+    NearExpiry,
+    NoQuote,
+}
+
+/// Resting entry and exit orders of one market
+#[derive(Debug, Default)]
+pub struct MakerState {
+    pub entry: Option<RestingOrder>,
+    pub exit: Option<RestingOrder>,
+    /// Fills arrive over the user channel; REST polling is only a fallback
+    pub streamed: bool,
+    /// Partial exits of the open position, booked once it closes
+    pub round_trip: RoundTrip,
+}
+
+impl MakerState {
+    pub fn has_resting(&self) -> bool {
+        self.entry.is_some() || self.exit.is_some()
+    }
+
+    /// Apply a user-channel event to whichever resting order it concerns
+    pub fn apply_user_event(&mut self, event: &UserEvent) {
+        for order in self.entry.iter_mut().chain(self.exit.iter_mut()) {
+            order.tracked.apply_user_event(event);
+        }
+    }
+
+    fn resting_ids(&self) -> Vec<&str> {
+        self.entry
+            .iter()
+            .chain(self.exit.iter())
+            .map(|o| o.order_id.as_str())
+            .collect()
+    }
+}
+
+fn round_to_tick(price: f64, tick: f64) -> f64 {
+    (price / tick).round() * tick
+}
+
+/// Maker price for `side` on `snapshot`: the touch improved by
+/// `improve_ticks`, but never at or through the opposite side so the order
+/// cannot take liquidity.
+pub fn quote_price(side: OrderSide, snapshot: &MarketSnapshot, config: &MakerConfig) -> Option<f64> {
+    let tick = config.tick_size;
+    let improve = f64::from(config.improve_ticks) * tick;
+    let bid = best_bid_price(snapshot).filter(|p| *p > 0.0);
+    let ask = best_ask_price(snapshot).filter(|p| *p > 0.0);
+
+    let price = match side {
+        OrderSide::Buy => {
+            let joined = bid.map(|b| b + improve);
+            match (joined, ask) {
+                (Some(p), Some(a)) => p.min(a - tick),
+                (Some(p), None) => p,
+                (None, Some(a)) => a - tick,
+                (None, None) => return None,
+            }
+        }
+        OrderSide::Sell => {
+            let joined = ask.map(|a| a - improve);
+            match (joined, bid) {
+                (Some(p), Some(b)) => p.max(b + tick),
+                (Some(p), None) => p,
+                (None, Some(b)) => b + tick,
+                (None, None) => return None,
+            }
+        }
+    };
+
+    let price = round_to_tick(price, tick);
+    (price >= tick && price <= 1.0 - tick).then_some(price)
+}
+
+/// Queue-aware repricing decision for a resting order.
+///
+/// An order at the touch is left alone so it keeps its place in the queue.
+/// It is moved only once the maker quote has drifted `reprice_ticks` away
+/// from it in either direction.
+pub fn decide(
+    order: &RestingOrder,
+    snapshot: &MarketSnapshot,
+    time_remaining: i64,
+    config: &MakerConfig,
+) -> MakerAction {
+    if time_remaining <= config.cancel_before_expiry_s {
+        return MakerAction::Cancel {
+            reason: CancelReason::NearExpiry,
+        };
+    }
+
+    // Our own order is part of the book: if it still sets the touch we lead
+    // the queue and moving would only give that up
+    let touch = match order.side {
+        OrderSide::Buy => best_bid_price(snapshot),
+        OrderSide::Sell => best_ask_price(snapshot),
+    };
+    if touch.is_some_and(|p| (p - order.price).abs() < config.tick_size / 2.0) {
+        return MakerAction::Hold;
+    }
+
+    let Some(target) = quote_price(order.side, snapshot, config) else {
+        return MakerAction::Cancel {
+            reason: CancelReason::NoQuote,
+        };
+    };
+
+    let threshold = f64::from(config.reprice_ticks.max(1)) * config.tick_size - 1e-9;
+    let drift = match order.side {
+        // Positive drift = the book moved away from us and we no longer lead
+        OrderSide::Buy => target - order.price,
+        OrderSide::Sell => order.price - target,
+    };
+
+    if drift.abs() >= threshold {
+        MakerAction::Reprice { price: target }
+    } else {
+        MakerAction::Hold
+    }
+}
+
+/// Post a GTC limit order for `shares` at `price`
+pub async fn place_limit_order(
+    client: &clob::Client<Authenticated<Normal>>,
+    signer: &(impl polymarket_client_sdk::auth::Signer + Sync),
+    token_id: U256,
+    side: OrderSide,
+    price: f64,
+    shares: f64,
+    post_only: bool,
+) -> Result<OrderResult> {
+    let shares_rounded = (shares * 100.0).floor() / 100.0;
+    if shares_rounded < MIN_SHARES {
+        anyhow::bail!("Order size too small: {}", shares);
+    }
+    let side = match side {
+        OrderSide::Buy => Side::Buy,
+        OrderSide::Sell => Side::Sell,
+    };
+
+    let order = client
+        .limit_order()
+        .token_id(token_id)
+        .side(side)
+        .price(Decimal::try_from(price)?.round_dp(4))
+        .size(Decimal::try_from(shares_rounded)?)
+        .order_type(OrderType::GTC)
+        .post_only(post_only)
+        .build()
+        .await?;
+
+    let signed_order = client.sign(signer, order).await?;
+    let result = client.post_order(signed_order).await?;
+    if !result.success {
+        anyhow::bail!(
+            "Limit order rejected: {}",
+            result.error_msg.unwrap_or_else(|| format!("{:?}", result.status))
+        );
+    }
+
+    Ok(OrderResult {
+        order_id: result.order_id,
+        filled_amount: Some(result.taking_amount),
+    })
+}
+
+/// Fills matched since the last poll and whether the order is still resting.
+/// Streamed orders only hit the REST endpoints every `STREAMED_RECONCILE_MS`
+/// to catch events missed across a reconnect.
+async fn poll_order(
+    client: &clob::Client<Authenticated<Normal>>,
+    order: &mut RestingOrder,
+    streamed: bool,
+) -> Result<(Vec<TrackedFill>, bool)> {
+    let now_ms = Utc::now().timestamp_millis();
+    if !streamed || now_ms - order.last_reconcile_ms >= STREAMED_RECONCILE_MS {
+        reconcile_order(client, &mut order.tracked).await?;
+        order.last_reconcile_ms = now_ms;
+    }
+    let fresh = order.tracked.fills[order.folded_fills..].to_vec();
+    order.folded_fills = order.tracked.fills.len();
+    order.filled_shares += fresh.iter().map(|f| f.size).sum::<f64>();
+    Ok((fresh, !order.tracked.status.is_terminal()))
+}
+
+fn log_order_update(
+    event_loggers: Option<&EngineEventLoggers>,
+    market: &WatchedMarket,
+    order: &TrackedOrder,
+    timestamp: u64,
+) {
+    if let Some(loggers) = event_loggers {
+        loggers.log_execution(EngineEvent::OrderUpdate {
+            ts: timestamp,
+            market_slug: market.slug.clone(),
+            order_id: order.order_id.clone(),
+            status: format!("{:?}", order.status),
+            avg_price: order.avg_fill_price(),
+            filled_size: order.filled_size(),
+            slippage_bps: order.slippage_bps(),
+            latency_ms: order.latency_ms(),
+        });
+    }
+}
+
+/// Cancel every resting order, e.g. on shutdown or market rollover, and
+/// fold the fills that matched since the last poll into the position
+#[allow(clippy::too_many_arguments)]
+pub async fn cancel_all(
+    client: &clob::Client<Authenticated<Normal>>,
+    state: &mut MakerState,
+    position: &mut LivePosition,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+) -> Result<()> {
+    let ids = state.resting_ids();
+    if !ids.is_empty() {
+        client.cancel_orders(&ids).await?;
+    }
+    for mut order in state.entry.take().into_iter().chain(state.exit.take()) {
+        if let Err(err) = fold_final_fills(
+            &mut order,
+            position,
+            &mut state.round_trip,
+            gatekeeper,
+            event_loggers,
+            state_store,
+            market,
+            timestamp,
+            client,
+        )
+        .await
+        {
+            eprintln!("[warn] Failed to reconcile canceled maker order {}: {err:#}", order.order_id);
+        }
+        order.tracked.apply_status(&OrderStatusType::Canceled, Utc::now().timestamp_millis());
+        log_order_update(event_loggers, market, &order.tracked, timestamp);
+    }
+    Ok(())
+}
+
+fn side_name(token_side: TokenSide) -> &'static str {
+    match token_side {
+        TokenSide::Yes => "YES",
+        TokenSide::No => "NO",
+    }
+}
+
+/// Fold newly matched entry shares into the position at a size-weighted price
+fn apply_entry_fill(
+    position: &mut LivePosition,
+    order: &RestingOrder,
+    fill: &TrackedFill,
+    timestamp: u64,
+) {
+    let prior_cost = position.shares * position.entry_price;
+    position.shares += fill.size;
+    position.entry_price = (prior_cost + fill.size * fill.price) / position.shares;
+    if position.token_side.is_none() {
+        position.entry_timestamp = timestamp;
+        position.entry_latency_ms = order.tracked.latency_ms();
+    }
+    position.entry_slippage_bps = order.tracked.slippage_bps();
+    position.token_side = Some(order.token_side);
+    position.last_trade_id = Some(order.order_id.clone());
+}
+
+fn total_size(fills: &[TrackedFill]) -> f64 {
+    fills.iter().map(|f| f.size).sum()
+}
+
+/// Fold fresh fills of a resting entry into the position and persist it
+fn fold_entry_fills(
+    order: &RestingOrder,
+    fills: &[TrackedFill],
+    position: &mut LivePosition,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+) {
+    if fills.is_empty() {
+        return;
+    }
+    let delta = total_size(fills);
+    for fill in fills {
+        apply_entry_fill(position, order, fill, timestamp);
+    }
+    println!(
+        "[MAKER FILL] {} | BUY {} {:.2}/{:.2} @ {:.4} | Order: {}",
+        market.label, side_name(order.token_side), order.filled_shares, order.shares, position.entry_price, order.order_id
+    );
+    persist_entry(
+        state_store,
+        &market.slug,
+        order.token_id,
+        order.token_side,
+        position.shares,
+        position.entry_price,
+        position.shares * position.entry_price,
+        &order.order_id,
+        position.entry_timestamp,
+        position.entry_slippage_bps,
+        position.entry_latency_ms,
+    );
+    if let Some(loggers) = event_loggers {
+        loggers.log_execution(EngineEvent::LiveEntry {
+            ts: timestamp,
+            market_slug: market.slug.clone(),
+            side: side_name(order.token_side).to_string(),
+            price: position.entry_price,
+            size_usd: delta * position.entry_price,
+            order_id: Some(order.order_id.clone()),
+        });
+    }
+}
+
+/// Book fresh fills of a resting exit against the position. Returns true
+/// once the position is closed; the partial exits are recorded as a single
+/// trade at that point, so the round trip is judged on its total PnL.
+#[allow(clippy::too_many_arguments)]
+fn fold_exit_fills(
+    order: &RestingOrder,
+    fills: &[TrackedFill],
+    position: &mut LivePosition,
+    round_trip: &mut RoundTrip,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+) -> bool {
+    if fills.is_empty() {
+        return false;
+    }
+    let delta = total_size(fills).min(position.shares);
+    let fill_price = fills.iter().map(|f| f.price * f.size).sum::<f64>() / total_size(fills);
+    let pnl_pct = (fill_price - position.entry_price) / position.entry_price * 100.0;
+    round_trip.add(delta, fill_price, position.entry_price);
+    println!(
+        "[MAKER FILL] {} | SELL {} {:.2}/{:.2} @ {:.4} | {:.2}% | ${:.2} | Order: {}",
+        market.label,
+        side_name(order.token_side),
+        order.filled_shares,
+        order.shares,
+        fill_price,
+        pnl_pct,
+        (fill_price - position.entry_price) * delta,
+        order.order_id
+    );
+
+    position.shares -= delta;
+    if position.shares >= MIN_SHARES {
+        // The rest of the position is still held
+        persist_entry(
+            state_store,
+            &market.slug,
+            order.token_id,
+            order.token_side,
+            position.shares,
+            position.entry_price,
+            position.shares * position.entry_price,
+            position.last_trade_id.as_deref().unwrap_or_default(),
+            position.entry_timestamp,
+            position.entry_slippage_bps,
+            position.entry_latency_ms,
+        );
+        return false;
+    }
+
+    let trip = std::mem::take(round_trip);
+    let exit_price = trip.exit_price();
+    gatekeeper.record_trade_result(timestamp, trip.pnl_usd);
+    persist_exit(
+        state_store,
+        order.token_id,
+        &TradeLog {
+            id: None,
+            market_slug: market.slug.clone(),
+            token_side: order.token_side.into(),
+            entry_price: position.entry_price,
+            exit_price,
+            size_usd: trip.shares * position.entry_price,
+            pnl_usd: trip.pnl_usd,
+            timestamp_entry: position.entry_timestamp as i64,
+            timestamp_exit: timestamp as i64,
+            order_id_entry: position.last_trade_id.clone().unwrap_or_default(),
+            order_id_exit: Some(order.order_id.clone()),
+            slippage_entry_bps: position.entry_slippage_bps,
+            slippage_exit_bps: order.tracked.slippage_bps(),
+            latency_entry_ms: position.entry_latency_ms,
+            latency_exit_ms: order.tracked.latency_ms(),
+        },
+    );
+    if let Some(loggers) = event_loggers {
+        loggers.log_execution(EngineEvent::LiveExit {
+            ts: timestamp,
+            market_slug: market.slug.clone(),
+            side: side_name(order.token_side).to_string(),
+            price: exit_price,
+            pnl_usd: trip.pnl_usd,
+            order_id: Some(order.order_id.clone()),
+        });
+    }
+    position.reset(timestamp, trip.pnl_usd < 0.0);
+    true
+}
+
+/// Poll the CLOB for fills on resting orders and apply them to `position`.
+#[allow(clippy::too_many_arguments)]
+async fn sync_fills(
+    state: &mut MakerState,
+    position: &mut LivePosition,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+) {
+    if let Some(order) = state.entry.as_mut() {
+        match poll_order(clob_client, order, state.streamed).await {
+            Ok((fills, is_live)) => {
+                fold_entry_fills(order, &fills, position, event_loggers, state_store, market, timestamp);
+                if !is_live {
+                    log_order_update(event_loggers, market, &order.tracked, timestamp);
+                    state.entry = None;
+                }
+            }
+            Err(err) => eprintln!("[warn] Failed to poll maker order {}: {err:#}", order.order_id),
+        }
+    }
+
+    if let Some(order) = state.exit.as_mut() {
+        match poll_order(clob_client, order, state.streamed).await {
+            Ok((fills, is_live)) => {
+                let closed = fold_exit_fills(
+                    order,
+                    &fills,
+                    position,
+                    &mut state.round_trip,
+                    gatekeeper,
+                    event_loggers,
+                    state_store,
+                    market,
+                    timestamp,
+                );
+                if closed || !is_live {
+                    log_order_update(event_loggers, market, &order.tracked, timestamp);
+                    state.exit = None;
+                }
+            }
+            Err(err) => eprintln!("[warn] Failed to poll maker order {}: {err:#}", order.order_id),
+        }
+    }
+}
+
+/// Reconcile a canceled order and fold the fills that matched before the
+/// cancel landed. Returns true once an exit's fills closed the position.
+#[allow(clippy::too_many_arguments)]
+async fn fold_final_fills(
+    order: &mut RestingOrder,
+    position: &mut LivePosition,
+    round_trip: &mut RoundTrip,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+) -> Result<bool> {
+    let (fills, _) = poll_order(clob_client, order, false).await?;
+    Ok(match order.side {
+        OrderSide::Buy => {
+            fold_entry_fills(order, &fills, position, event_loggers, state_store, market, timestamp);
+            false
+        }
+        OrderSide::Sell => fold_exit_fills(
+            order,
+            &fills,
+            position,
+            round_trip,
+            gatekeeper,
+            event_loggers,
+            state_store,
+            market,
+            timestamp,
+        ),
+    })
+}
+
+/// Cancel a resting order and fold its final fills, keeping it tracked if
+/// the cancel fails
+#[allow(clippy::too_many_arguments)]
+async fn cancel_resting(
+    slot: &mut Option<RestingOrder>,
+    reason: CancelReason,
+    position: &mut LivePosition,
+    round_trip: &mut RoundTrip,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+) {
+    let Some(order) = slot.as_mut() else {
+        return;
+    };
+    match clob_client.cancel_order(&order.order_id).await {
+        Ok(_) => {
+            if let Err(err) = fold_final_fills(
+                order,
+                position,
+                round_trip,
+                gatekeeper,
+                event_loggers,
+                state_store,
+                market,
+                timestamp,
+                clob_client,
+            )
+            .await
+            {
+                eprintln!("[warn] Failed to reconcile canceled maker order {}: {err:#}", order.order_id);
+            }
+            println!(
+                "[MAKER CANCEL] {} | {:?} {} @ {:.4} | {:.2}/{:.2} filled | {:?}",
+                market.label, order.side, side_name(order.token_side), order.price, order.filled_shares, order.shares, reason
+            );
+            if reason == CancelReason::NearExpiry {
+                order.tracked.expire();
+            } else {
+                order.tracked.apply_status(&OrderStatusType::Canceled, Utc::now().timestamp_millis());
+            }
+            log_order_update(event_loggers, market, &order.tracked, timestamp);
+            *slot = None;
+        }
+        Err(err) => eprintln!("[MAKER CANCEL FAILED] {} | {:?}", order.order_id, err),
+    }
+}
+
+/// Apply a repricing decision to a resting order. A reprice is a cancel,
+/// then the fills that matched before the cancel landed are applied, then a
+/// fresh post for the unfilled remainder.
+#[allow(clippy::too_many_arguments)]
+async fn manage_resting(
+    slot: &mut Option<RestingOrder>,
+    snapshot: &MarketSnapshot,
+    time_remaining: i64,
+    config: &MakerConfig,
+    position: &mut LivePosition,
+    round_trip: &mut RoundTrip,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    timestamp: u64,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+    signer: &(impl polymarket_client_sdk::auth::Signer + Sync),
+) {
+    let Some(order) = slot.as_ref() else {
+        return;
+    };
+    match decide(order, snapshot, time_remaining, config) {
+        MakerAction::Hold => {}
+        MakerAction::Cancel { reason } => {
+            cancel_resting(
+                slot,
+                reason,
+                position,
+                round_trip,
+                gatekeeper,
+                event_loggers,
+                state_store,
+                market,
+                timestamp,
+                clob_client,
+            )
+            .await
+        }
+        MakerAction::Reprice { price } => {
+            let mut order = order.clone();
+            if let Err(err) = clob_client.cancel_order(&order.order_id).await {
+                eprintln!("[MAKER CANCEL FAILED] {} | {:?}", order.order_id, err);
+                return;
+            }
+            *slot = None;
+
+            // Reposting before the old order's final fills are known could
+            // buy or sell more than intended
+            let closed = match fold_final_fills(
+                &mut order,
+                position,
+                round_trip,
+                gatekeeper,
+                event_loggers,
+                state_store,
+                market,
+                timestamp,
+                clob_client,
+            )
+            .await
+            {
+                Ok(closed) => closed,
+                Err(err) => {
+                    eprintln!("[warn] Failed to reconcile canceled maker order {}, not reposting: {err:#}", order.order_id);
+                    return;
+                }
+            };
+            order.tracked.apply_status(&OrderStatusType::Canceled, Utc::now().timestamp_millis());
+            log_order_update(event_loggers, market, &order.tracked, timestamp);
+            let remaining = match order.side {
+                OrderSide::Buy => order.remaining_shares(),
+                OrderSide::Sell if closed => 0.0,
+                OrderSide::Sell => order.remaining_shares().min(position.shares),
+            };
+            if remaining < MIN_SHARES {
+                return;
+            }
+
+            let submitted_at_ms = Utc::now().timestamp_millis();
+            match place_limit_order(clob_client, signer, order.token_id, order.side, price, remaining, config.post_only).await {
+                Ok(result) => {
+                    println!(
+                        "[MAKER REPRICE] {} | {:?} {} {:.4} -> {:.4} | {:.2} shares",
+                        market.label, order.side, side_name(order.token_side), order.price, price, remaining
+                    );
+                    *slot = Some(RestingOrder::posted(
+                        result.order_id,
+                        order.token_id,
+                        order.token_side,
+                        order.side,
+                        price,
+                        remaining,
+                        submitted_at_ms,
+                    ));
+                }
+                Err(err) => eprintln!("[MAKER REPRICE FAILED] {} | {:?}", market.label, err),
+            }
+        }
+    }
+}
+
+/// Maker counterpart of `handle_live_signals`: called on every tick with the
+/// latest book so resting orders are repriced and fills are picked up even
+/// when no new signal fired.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_maker_signals(
+    signal: Option<&SignalState>,
+    dual_snapshot: &DualSnapshot,
+    state: &mut MakerState,
+    position: &mut LivePosition,
+    gatekeeper: &mut GatekeeperState,
+    event_loggers: Option<&EngineEventLoggers>,
+    state_store: Option<&dyn StateStore>,
+    market: &WatchedMarket,
+    config: &MakerConfig,
+    timestamp: u64,
+    size_usd: f64,
+    clob_client: &clob::Client<Authenticated<Normal>>,
+    signer: &(impl polymarket_client_sdk::auth::Signer + Sync),
+) {
+    let time_remaining = (market.end_time.timestamp() - Utc::now().timestamp()).max(0);
+
+    sync_fills(state, position, gatekeeper, event_loggers, state_store, market, timestamp, clob_client).await;
+
+    // Signal flip: drop a resting entry the strategy no longer wants
+    if let (Some(signal), Some(order)) = (signal, state.entry.as_ref()) {
+        let flipped = match order.token_side {
+            TokenSide::Yes => signal.entry == EntrySignal::Short,
+            TokenSide::No => signal.entry == EntrySignal::Long,
+        } || signal.exit == ExitSignal::FullExit;
+        if flipped {
+            cancel_resting(
+                &mut state.entry,
+                CancelReason::SignalFlip,
+                position,
+                &mut state.round_trip,
+                gatekeeper,
+                event_loggers,
+                state_store,
+                market,
+                timestamp,
+                clob_client,
+            )
+            .await;
+        }
+    }
+
+    let snapshot_for = |side: TokenSide| match side {
+        TokenSide::Yes => &dual_snapshot.yes,
+        TokenSide::No => &dual_snapshot.no,
+    };
+    if let Some(side) = state.entry.as_ref().map(|o| o.token_side) {
+        manage_resting(
+            &mut state.entry,
+            snapshot_for(side),
+            time_remaining,
+            config,
+            position,
+            &mut state.round_trip,
+            gatekeeper,
+            event_loggers,
+            state_store,
+            market,
+            timestamp,
+            clob_client,
+            signer,
+        )
+        .await;
+    }
+    if let Some(side) = state.exit.as_ref().map(|o| o.token_side) {
+        manage_resting(
+            &mut state.exit,
+            snapshot_for(side),
+            time_remaining,
+            config,
+            position,
+            &mut state.round_trip,
+            gatekeeper,
+            event_loggers,
+            state_store,
+            market,
+            timestamp,
+            clob_client,
+            signer,
+        )
+        .await;
+    }
+
+    let Some(signal) = signal else {
+        return;
+    };
+    if time_remaining <= config.cancel_before_expiry_s {
+        return;
+    }
+
+    if signal.entry != EntrySignal::None && !position.is_active() && state.entry.is_none() {
+        let (token_id, token_side, direction, direction_locked) = match signal.entry {
+            EntrySignal::Long => (market.yes_token_id, TokenSide::Yes, TradeDirection::Yes, position.yes_blocked),
+            EntrySignal::Short => (market.no_token_id, TokenSide::No, TradeDirection::No, position.no_blocked),
+            EntrySignal::None => return,
+        };
+        let snapshot_side = snapshot_for(token_side);
+
+        let decision = gatekeeper.check_entry(
+            snapshot_side,
+            &EntryContext {
+                timestamp,
+                time_remaining,
+                min_time_remaining: 30,
+                max_time_remaining: market.duration_seconds - 20,
+                contract_age: (timestamp as i64) - market.start_ts(),
+                yes_ask: best_ask_price(&dual_snapshot.yes).unwrap_or(0.0),
+                no_ask: best_ask_price(&dual_snapshot.no).unwrap_or(0.0),
+                bankroll_available: size_usd,
+                position_size_usd: size_usd,
+                direction_locked,
+                direction,
+            },
+        );
+        if let Some(loggers) = event_loggers {
+            let (decision, reason) = match &decision {
+                GateDecision::Approved { reason } => ("approved", reason.clone()),
+                GateDecision::Blocked { reason } => ("blocked", format!("{reason:?}")),
+            };
+            loggers.log_strategy(EngineEvent::GateDecision {
+                ts: timestamp,
+                market_slug: market.slug.clone(),
+                decision: decision.to_string(),
+                reason,
+            });
+        }
+        if let GateDecision::Blocked { reason } = decision {
+            println!("[FILTER BLOCKED] {:?} | Reason: {:?}", token_side, reason);
+            return;
+        }
+
+        let Some(price) = quote_price(OrderSide::Buy, snapshot_side, config) else {
+            println!("[NO LIQUIDITY] No maker quote for {:?}", token_side);
+            return;
+        };
+        let shares = (size_usd / price * 100.0).floor() / 100.0;
+
+        println!(
+            "[SIGNAL] {} | BID {} @ {:.4} | {:.2} shares",
+            market.label, side_name(token_side), price, shares
+        );
+        let submitted_at_ms = Utc::now().timestamp_millis();
+        match place_limit_order(clob_client, signer, token_id, OrderSide::Buy, price, shares, config.post_only).await {
+            Ok(result) => {
+                println!("[MAKER POSTED] {} | BUY {} @ {:.4} | Order: {}", market.label, side_name(token_side), price, result.order_id);
+                state.entry = Some(RestingOrder::posted(
+                    result.order_id,
+                    token_id,
+                    token_side,
+                    OrderSide::Buy,
+                    price,
+                    shares,
+                    submitted_at_ms,
+                ));
+            }
+            Err(err) => eprintln!("[ORDER FAILED] {:?}", err),
+        }
+        return;
+    }
+
+    if signal.exit == ExitSignal::FullExit && position.is_active() && state.exit.is_none() {
+        let Some(token_side) = position.token_side else {
+            return;
+        };
+        let token_id = match token_side {
+            TokenSide::Yes => market.yes_token_id,
+            TokenSide::No => market.no_token_id,
+        };
+        // Stop accumulating before offering the position back
+        if state.entry.is_some() {
+            cancel_resting(
+                &mut state.entry,
+                CancelReason::SignalFlip,
+                position,
+                &mut state.round_trip,
+                gatekeeper,
+                event_loggers,
+                state_store,
+                market,
+                timestamp,
+                clob_client,
+            )
+            .await;
+        }
+        if position.shares < MIN_SHARES {
+            forget_position(state_store, token_id);
+            position.reset(timestamp, false);
+            return;
+        }
+
+        let Some(price) = quote_price(OrderSide::Sell, snapshot_for(token_side), config) else {
+            println!("[NO EXIT QUOTE] {:?}", token_side);
+            return;
+        };
+        println!(
+            "[SIGNAL] {} | OFFER {} @ {:.4} | {:.2} shares",
+            market.label, side_name(token_side), price, position.shares
+        );
+        let submitted_at_ms = Utc::now().timestamp_millis();
+        match place_limit_order(clob_client, signer, token_id, OrderSide::Sell, price, position.shares, config.post_only).await {
+            Ok(result) => {
+                println!("[MAKER POSTED] {} | SELL {} @ {:.4} | Order: {}", market.label, side_name(token_side), price, result.order_id);
+                state.exit = Some(RestingOrder::posted(
+                    result.order_id,
+                    token_id,
+                    token_side,
+                    OrderSide::Sell,
+                    price,
+                    position.shares,
+                    submitted_at_ms,
+                ));
+            }
+            Err(err) => eprintln!("[EXIT FAILED] {:?} - Position remains open!", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bid: i64, ask: i64) -> MarketSnapshot {
+        MarketSnapshot {
+            midpoint: Some(Decimal::new(bid + ask, 2) / Decimal::from(2)),
+            best_bid: Some(Decimal::new(bid, 2)),
+            best_ask: Some(Decimal::new(ask, 2)),
+            spread: Some(Decimal::new(ask - bid, 2)),
+            top5_bid_depth: Decimal::new(50000, 2),
+            top5_ask_depth: Decimal::new(50000, 2),
+        }
+    }
+
+    fn resting(side: OrderSide, price: f64) -> RestingOrder {
+        RestingOrder::posted("0x1".to_string(), U256::ZERO, TokenSide::Yes, side, price, 10.0, 0)
+    }
+
+    fn fill(price: f64, size: f64) -> TrackedFill {
+        TrackedFill {
+            trade_id: format!("{price}:{size}"),
+            price,
+            size,
+            ts_ms: 0,
+        }
+    }
+
+    #[test]
+    fn quote_joins_touch_without_crossing() {
+        let config = MakerConfig::default();
+        let snapshot = book(48, 52);
+        assert!((quote_price(OrderSide::Buy, &snapshot, &config).unwrap() - 0.48).abs() < 1e-9);
+        assert!((quote_price(OrderSide::Sell, &snapshot, &config).unwrap() - 0.52).abs() < 1e-9);
+
+        let improving = MakerConfig { improve_ticks: 5, ..config };
+        // Improvement stops one tick short of the opposite side
+        assert!((quote_price(OrderSide::Buy, &snapshot, &improving).unwrap() - 0.51).abs() < 1e-9);
+        assert!((quote_price(OrderSide::Sell, &snapshot, &improving).unwrap() - 0.49).abs() < 1e-9);
+    }
+
+    #[test]
+    fn order_at_touch_holds_queue_position() {
+        let config = MakerConfig::default();
+        let order = resting(OrderSide::Buy, 0.48);
+        assert_eq!(decide(&order, &book(48, 52), 120, &config), MakerAction::Hold);
+    }
+
+    #[test]
+    fn order_reprices_when_book_moves_away() {
+        let config = MakerConfig::default();
+        let order = resting(OrderSide::Buy, 0.48);
+        match decide(&order, &book(50, 53), 120, &config) {
+            MakerAction::Reprice { price } => assert!((price - 0.50).abs() < 1e-9),
+            other => panic!("expected reprice, got {other:?}"),
+        }
+        // Touch dropped below us (e.g. our order was partly lifted): stop overpaying
+        match decide(&order, &book(45, 49), 120, &config) {
+            MakerAction::Reprice { price } => assert!((price - 0.45).abs() < 1e-9),
+            other => panic!("expected reprice, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn order_cancels_near_expiry() {
+        let config = MakerConfig::default();
+        let order = resting(OrderSide::Sell, 0.52);
+        assert_eq!(
+            decide(&order, &book(48, 52), 10, &config),
+            MakerAction::Cancel { reason: CancelReason::NearExpiry }
+        );
+    }
+
+    #[test]
+    fn partial_fills_average_entry_price() {
+        let mut position = LivePosition::default();
+        let order = resting(OrderSide::Buy, 0.40);
+        apply_entry_fill(&mut position, &order, &fill(0.40, 4.0), 100);
+        apply_entry_fill(&mut position, &order, &fill(0.50, 6.0), 110);
+
+        assert_eq!(position.token_side, Some(TokenSide::Yes));
+        assert!((position.shares - 10.0).abs() < 1e-9);
+        assert!((position.entry_price - 0.46).abs() < 1e-9);
+        assert_eq!(position.entry_timestamp, 100);
+    }
+
+    #[test]
+    fn round_trip_loss_is_judged_on_all_partial_exits() {
+        use chrono::TimeZone;
+
+        let market = WatchedMarket {
+            label: "BTC 5m".to_string(),
+            slug: "btc-updown-5m-0".to_string(),
+            yes_token_id: U256::ZERO,
+            no_token_id: U256::from(1u8),
+            condition_id: None,
+            end_time: Utc.timestamp_opt(300, 0).unwrap(),
+            duration_seconds: 300,
+            strike_price: None,
+        };
+        let mut gatekeeper = GatekeeperState::new(100.0, 60);
+        let mut position = LivePosition::default();
+        let entry = resting(OrderSide::Buy, 0.50);
+        apply_entry_fill(&mut position, &entry, &fill(0.50, 10.0), 100);
+
+        // +$0.60 on six shares, then -$0.20 on the last four: a winning trade
+        let exit = resting(OrderSide::Sell, 0.60);
+        let mut trip = RoundTrip::default();
+        assert!(!fold_exit_fills(&exit, &[fill(0.60, 6.0)], &mut position, &mut trip, &mut gatekeeper, None, None, &market, 110));
+        assert_eq!(gatekeeper.daily_pnl, 0.0);
+        assert!(fold_exit_fills(&exit, &[fill(0.45, 4.0)], &mut position, &mut trip, &mut gatekeeper, None, None, &market, 120));
+        assert!(position.token_side.is_none());
+        assert!(!position.yes_blocked);
+        assert!(gatekeeper.cooldown_until.is_none());
+        assert!((gatekeeper.daily_pnl - 0.40).abs() < 1e-9);
+        assert_eq!(trip, RoundTrip::default());
+    }
+}
@@ -6,15 +6,24 @@ pub mod feed;
 pub mod feed_base;
 pub mod indicators;
 pub mod logging;
+pub mod maker;
 pub mod market_classifier;
 pub mod pipeline;
+pub mod portfolio;
 pub mod monte_carlo;
+pub mod order_tracker;
 pub mod pricing;
 pub mod recording;
+pub mod replay_log;
 pub mod research;
+pub mod resolution;
 pub mod risk;
 pub mod shadow;
 pub mod signal;
+pub mod sizing;
 pub mod strategy;
 pub mod strategy_runner;
+pub mod temporal_arb;
+#[cfg(test)]
+pub(crate) mod test_util;
 pub mod validation;
@@ -0,0 +1,193 @@
+//! Block Bootstrap
+//!
+//! Resamples a backtest's actual trade return sequence in contiguous blocks so
+//! losing streaks and volatility clustering carry into the simulated paths.
+//! With regime conditioning each trade is labelled calm or volatile by the
+//! rolling dispersion of returns, and each block is drawn from the regime a
+//! two-state Markov chain fitted to those labels says comes next.
+
+use crate::bot::backtest::metrics::TradeResult;
+use anyhow::{Context, Result};
+use rand::Rng;
+use serde_json::Value;
+
+pub const CALM: usize = 0;
+pub const VOLATILE: usize = 1;
+
+/// Trades of an exported backtest: a bare trade list, or any export object
+/// with a `trades` list (`backtest`, `backtest-pmxt`, `backtest-recording`)
+pub fn load_trades(path: &str) -> Result<Vec<TradeResult>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let value: Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+    let trades = match value {
+        Value::Array(_) => value,
+        Value::Object(mut map) => map
+            .remove("trades")
+            .with_context(|| format!("{} has no trades list", path))?,
+        _ => anyhow::bail!("{} is neither a trade list nor a backtest export", path),
+    };
+    Ok(serde_json::from_value(trades)?)
+}
+
+#[derive(Debug, Clone)]
+struct RegimeModel {
+    labels: Vec<usize>,
+    /// Trade indices labelled with each regime
+    starts: [Vec<usize>; 2],
+    /// Row-stochastic regime transition matrix, add-one smoothed
+    transition: [[f64; 2]; 2],
+    /// Share of trades in the volatile regime
+    volatile_share: f64,
+}
+
+impl RegimeModel {
+    fn fit(returns: &[f64], window: usize) -> Self {
+        let window = window.max(2);
+        let dispersion: Vec<f64> = (0..returns.len())
+            .map(|i| {
+                let slice = &returns[(i + 1).saturating_sub(window)..=i];
+                let mean = slice.iter().sum::<f64>() / slice.len() as f64;
+                (slice.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / slice.len() as f64).sqrt()
+            })
+            .collect();
+        let mut sorted = dispersion.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+        let labels: Vec<usize> = dispersion.iter().map(|d| if *d > median { VOLATILE } else { CALM }).collect();
+
+        let mut counts = [[1.0; 2]; 2];
+        for pair in labels.windows(2) {
+            counts[pair[0]][pair[1]] += 1.0;
+        }
+        let transition = counts.map(|row| {
+            let total = row[0] + row[1];
+            [row[0] / total, row[1] / total]
+        });
+        let mut starts = [Vec::new(), Vec::new()];
+        for (i, label) in labels.iter().enumerate() {
+            starts[*label].push(i);
+        }
+        let volatile_share = starts[VOLATILE].len() as f64 / labels.len().max(1) as f64;
+        Self { labels, starts, transition, volatile_share }
+    }
+}
+
+/// Moving-block bootstrap over a trade return sequence (returns as a
+/// fraction of the stake), wrapping circularly so every trade is equally
+/// likely to be drawn
+#[derive(Debug, Clone)]
+pub struct BlockBootstrap {
+    returns: Vec<f64>,
+    block_len: usize,
+    regimes: Option<RegimeModel>,
+}
+
+impl BlockBootstrap {
+    pub fn new(returns: Vec<f64>, block_len: Option<usize>) -> Result<Self> {
+        if returns.is_empty() {
+            anyhow::bail!("No trades to resample");
+        }
+        let block_len = block_len.unwrap_or_else(|| Self::auto_block_len(returns.len())).clamp(1, returns.len());
+        Ok(Self { returns, block_len, regimes: None })
+    }
+
+    pub fn from_trades(trades: &[TradeResult], block_len: Option<usize>) -> Result<Self> {
+        Self::new(trades.iter().map(|t| t.pnl_percent).collect(), block_len)
+    }
+
+    /// Cube root of the sample size, the usual rate for block length
+    pub fn auto_block_len(n: usize) -> usize {
+        ((n as f64).cbrt().round() as usize).max(1)
+    }
+
+    /// Condition blocks on calm/volatile regimes from `window`-trade rolling
+    /// return dispersion
+    pub fn with_regimes(mut self, window: usize) -> Self {
+        self.regimes = Some(RegimeModel::fit(&self.returns, window));
+        self
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.regimes {
+            Some(model) => format!(
+                "block bootstrap of {} trades (block {}, regime-aware: {:.0}% volatile, P(stay volatile) {:.2})",
+                self.returns.len(),
+                self.block_len,
+                model.volatile_share * 100.0,
+                model.transition[VOLATILE][VOLATILE]
+            ),
+            None => format!("block bootstrap of {} trades (block {})", self.returns.len(), self.block_len),
+        }
+    }
+
+    /// `n` trade returns built from whole blocks, the last one truncated
+    pub fn sample_path(&self, n: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let len = self.returns.len();
+        let mut path = Vec::with_capacity(n);
+        let mut regime = self
+            .regimes
+            .as_ref()
+            .map(|model| if rng.random::<f64>() < model.volatile_share { VOLATILE } else { CALM });
+
+        while path.len() < n {
+            let start = match (&self.regimes, regime) {
+                (Some(model), Some(current)) if !model.starts[current].is_empty() => {
+                    let candidates = &model.starts[current];
+                    candidates[rng.random_range(0..candidates.len())]
+                }
+                _ => rng.random_range(0..len),
+            };
+            let take = self.block_len.min(n - path.len());
+            path.extend((0..take).map(|k| self.returns[(start + k) % len]));
+
+            if let (Some(model), Some(current)) = (&self.regimes, regime.as_mut()) {
+                let last = model.labels[(start + take - 1) % len];
+                *current = if rng.random::<f64>() < model.transition[last][VOLATILE] { VOLATILE } else { CALM };
+            }
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn blocks_keep_consecutive_trades_together() {
+        let returns: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let bootstrap = BlockBootstrap::new(returns, Some(4)).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        let path = bootstrap.sample_path(22, &mut rng);
+        assert_eq!(path.len(), 22);
+        for block in path.chunks(4) {
+            for pair in block.windows(2) {
+                assert_eq!((pair[0] as usize + 1) % 20, pair[1] as usize);
+            }
+        }
+        assert_eq!(BlockBootstrap::auto_block_len(125), 5);
+    }
+
+    #[test]
+    fn regimes_label_volatile_stretches_and_persist() {
+        // A calm stretch followed by a volatile one
+        let returns: Vec<f64> = (0..200)
+            .map(|i| if i < 100 { 0.01 } else if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+        let bootstrap = BlockBootstrap::new(returns, Some(5)).unwrap().with_regimes(6);
+        let model = bootstrap.regimes.as_ref().unwrap();
+        assert!(model.labels[..100].iter().all(|l| *l == CALM));
+        assert!(model.labels[106..].iter().all(|l| *l == VOLATILE));
+        assert!(model.transition[VOLATILE][VOLATILE] > 0.9);
+
+        // Calm blocks are followed by calm blocks almost always
+        let mut rng = StdRng::seed_from_u64(9);
+        let path = bootstrap.sample_path(2000, &mut rng);
+        let switches = path.chunks(5).collect::<Vec<_>>().windows(2)
+            .filter(|w| (w[0][0].abs() > 0.1) != (w[1][0].abs() > 0.1))
+            .count();
+        assert!(switches < 30, "{switches} regime switches");
+    }
+}
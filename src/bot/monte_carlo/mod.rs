@@ -1,3 +1,5 @@
+pub mod bootstrap;
 pub mod simulator;
 
+pub use bootstrap::{load_trades, BlockBootstrap};
 pub use simulator::{MonteCarloSimulator, SimulationConfig, SimulationResult};
@@ -1,3 +1,4 @@
+use super::bootstrap::BlockBootstrap;
 use crate::bot::backtest::metrics::{BacktestMetrics, TradeResult};
 use rand::prelude::*;
 use rand_distr::{Distribution, Normal};
@@ -103,21 +104,55 @@ pub struct SimulationResult {
     pub worst_case_return: f64,
     pub median_return_pct: f64,
     pub risk_of_ruin_pct: f64,
+    /// How trade returns were drawn
+    #[serde(default)]
+    pub resampling: String,
+    /// Median trades until capital fell below the ruin threshold, over ruined paths
+    #[serde(default)]
+    pub median_trades_to_ruin: Option<f64>,
+    /// Trades from peak back to a new high, over drawdowns of at least `RECOVERY_MIN_DRAWDOWN`
+    #[serde(default)]
+    pub median_recovery_trades: Option<f64>,
+    #[serde(default)]
+    pub p95_recovery_trades: Option<f64>,
+    /// Share of paths ending inside such a drawdown
+    #[serde(default)]
+    pub unrecovered_pct: f64,
+    /// Longest run of trades spent below the previous peak, median over paths
+    #[serde(default)]
+    pub median_max_underwater_trades: f64,
     pub equity_paths: Vec<Vec<f64>>,
     pub final_capitals: Vec<f64>,
 }
 
+/// Drawdown depth below which recoveries are not counted
+pub const RECOVERY_MIN_DRAWDOWN: f64 = 0.05;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathStatistics {
     pub final_capital: f64,
     pub max_drawdown: f64,
     pub total_return_pct: f64,
     pub survived: bool,
+    pub ruin_trade: Option<usize>,
+    /// Lengths of completed recoveries from drawdowns deeper than `RECOVERY_MIN_DRAWDOWN`
+    pub recovery_trades: Vec<usize>,
+    pub max_underwater_trades: usize,
+    /// Ended inside a drawdown deeper than `RECOVERY_MIN_DRAWDOWN`
+    pub unrecovered: bool,
+}
+
+fn percentile(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    Some(sorted[((sorted.len() as f64 * q) as usize).min(sorted.len() - 1)])
 }
 
 pub struct MonteCarloSimulator {
     config: SimulationConfig,
     rng: StdRng,
+    bootstrap: Option<BlockBootstrap>,
 }
 
 impl MonteCarloSimulator {
@@ -125,6 +160,7 @@ impl MonteCarloSimulator {
         Self {
             config,
             rng: StdRng::from_os_rng(),
+            bootstrap: None,
         }
     }
 
@@ -132,12 +168,23 @@ impl MonteCarloSimulator {
         Self {
             config,
             rng: StdRng::seed_from_u64(seed),
+            bootstrap: None,
         }
     }
 
+    /// Resample actual trade returns instead of the win/loss distributions
+    pub fn with_bootstrap(mut self, bootstrap: BlockBootstrap) -> Self {
+        self.bootstrap = Some(bootstrap);
+        self
+    }
+
     pub fn run(&mut self) -> SimulationResult {
         let mut final_capitals = Vec::with_capacity(self.config.num_simulations);
         let mut max_drawdowns = Vec::with_capacity(self.config.num_simulations);
+        let mut ruin_trades = Vec::new();
+        let mut recoveries = Vec::new();
+        let mut underwater = Vec::with_capacity(self.config.num_simulations);
+        let mut unrecovered = 0;
         let mut equity_paths = Vec::new();
 
         let ruin_threshold = self.config.starting_capital * 0.10;
@@ -149,51 +196,35 @@ impl MonteCarloSimulator {
             .expect("Invalid loss distribution");
 
         for _ in 0..self.config.num_simulations {
-            let path_stats = self.simulate_path(&win_dist, &loss_dist, ruin_threshold);
+            let returns = self.sample_returns(&win_dist, &loss_dist);
+            let keep_path = equity_paths.len() < 100;
+            let (path_stats, path) = self.simulate_path(&returns, ruin_threshold, keep_path);
 
             final_capitals.push(path_stats.final_capital);
             max_drawdowns.push(path_stats.max_drawdown);
+            underwater.push(path_stats.max_underwater_trades as f64);
+            recoveries.extend(path_stats.recovery_trades.iter().map(|t| *t as f64));
+            if path_stats.unrecovered {
+                unrecovered += 1;
+            }
 
             if !path_stats.survived {
                 ruin_count += 1;
             }
+            if let Some(trade) = path_stats.ruin_trade {
+                ruin_trades.push(trade as f64);
+            }
 
-            if equity_paths.len() < 100 {
-                let mut path = vec![self.config.starting_capital];
-                let mut capital = self.config.starting_capital;
-                let mut cap_count = 0;
-
-                for _ in 0..self.config.num_trades_per_sim {
-                    if capital < ruin_threshold {
-                        break;
-                    }
-
-                    let is_win: bool = self.rng.random::<f64>() < self.config.win_rate;
-                    let pnl_pct = if is_win {
-                        win_dist.sample(&mut self.rng)
-                    } else {
-                        loss_dist.sample(&mut self.rng)
-                    };
-
-                    let position_size = capital * self.config.position_size_pct;
-                    let pnl_usd = pnl_pct * position_size;
-                    capital += pnl_usd;
-                    capital = capital.max(0.0);
-
-                    path.push(capital);
-                    cap_count += 1;
-
-                    if cap_count >= self.config.num_trades_per_sim {
-                        break;
-                    }
-                }
-
+            if keep_path {
                 equity_paths.push(path);
             }
         }
 
         final_capitals.sort_by(|a, b| a.partial_cmp(b).unwrap());
         max_drawdowns.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for values in [&mut ruin_trades, &mut recoveries, &mut underwater] {
+            values.sort_by(|a, b| a.total_cmp(b));
+        }
 
         let n = final_capitals.len();
         let mean = final_capitals.iter().sum::<f64>() / n as f64;
@@ -227,58 +258,100 @@ impl MonteCarloSimulator {
             median_return_pct: ((final_capitals[n / 2] / self.config.starting_capital) - 1.0)
                 * 100.0,
             risk_of_ruin_pct: (ruin_count as f64 / n as f64) * 100.0,
+            resampling: self
+                .bootstrap
+                .as_ref()
+                .map_or_else(|| "parametric win/loss".to_string(), |b| b.describe()),
+            median_trades_to_ruin: percentile(&ruin_trades, 0.5),
+            median_recovery_trades: percentile(&recoveries, 0.5),
+            p95_recovery_trades: percentile(&recoveries, 0.95),
+            unrecovered_pct: unrecovered as f64 / n as f64 * 100.0,
+            median_max_underwater_trades: percentile(&underwater, 0.5).unwrap_or(0.0),
             equity_paths,
             final_capitals,
         }
     }
 
-    fn simulate_path(
-        &mut self,
-        win_dist: &Normal<f64>,
-        loss_dist: &Normal<f64>,
-        ruin_threshold: f64,
-    ) -> PathStatistics {
+    /// Trade returns of one path, as a fraction of the stake
+    fn sample_returns(&mut self, win_dist: &Normal<f64>, loss_dist: &Normal<f64>) -> Vec<f64> {
+        let n = self.config.num_trades_per_sim;
+        if let Some(bootstrap) = &self.bootstrap {
+            return bootstrap.sample_path(n, &mut self.rng);
+        }
+        (0..n)
+            .map(|_| {
+                let is_win: bool = self.rng.random::<f64>() < self.config.win_rate;
+                if is_win {
+                    win_dist.sample(&mut self.rng)
+                } else {
+                    loss_dist.sample(&mut self.rng)
+                }
+            })
+            .collect()
+    }
+
+    fn simulate_path(&self, returns: &[f64], ruin_threshold: f64, keep_path: bool) -> (PathStatistics, Vec<f64>) {
         let mut capital = self.config.starting_capital;
         let mut peak = capital;
         let mut max_drawdown = 0.0;
-
-        for _ in 0..self.config.num_trades_per_sim {
+        let mut path = if keep_path { vec![capital] } else { Vec::new() };
+        let mut ruin_trade = None;
+        let mut recovery_trades = Vec::new();
+        let mut max_underwater_trades = 0;
+        // Trades since the last peak and the deepest drawdown fraction since then
+        let mut underwater = 0;
+        let mut episode_depth: f64 = 0.0;
+
+        for (trade, pnl_pct) in returns.iter().enumerate() {
             if capital < ruin_threshold {
+                ruin_trade = Some(trade);
                 break;
             }
 
-            let is_win: bool = self.rng.random::<f64>() < self.config.win_rate;
-
-            let pnl_pct = if is_win {
-                win_dist.sample(&mut self.rng)
-            } else {
-                loss_dist.sample(&mut self.rng)
-            };
-
             let position_size = capital * self.config.position_size_pct;
             let pnl_usd = pnl_pct * position_size;
             capital += pnl_usd;
             capital = capital.max(0.0);
+            if keep_path {
+                path.push(capital);
+            }
 
-            if capital > peak {
+            if capital >= peak {
+                if episode_depth >= RECOVERY_MIN_DRAWDOWN {
+                    recovery_trades.push(underwater + 1);
+                }
                 peak = capital;
+                underwater = 0;
+                episode_depth = 0.0;
+            } else {
+                underwater += 1;
+                max_underwater_trades = max_underwater_trades.max(underwater);
             }
 
             let drawdown = (peak - capital) / peak * 100.0;
+            episode_depth = episode_depth.max(drawdown / 100.0);
             if drawdown > max_drawdown {
                 max_drawdown = drawdown;
             }
         }
+        if ruin_trade.is_none() && capital < ruin_threshold {
+            ruin_trade = Some(returns.len());
+        }
 
         let survived = capital >= ruin_threshold;
         let total_return = (capital / self.config.starting_capital - 1.0) * 100.0;
 
-        PathStatistics {
+        let stats = PathStatistics {
             final_capital: capital,
             max_drawdown,
             total_return_pct: total_return,
             survived,
-        }
+            ruin_trade,
+            recovery_trades,
+            max_underwater_trades,
+            unrecovered: episode_depth >= RECOVERY_MIN_DRAWDOWN,
+        };
+        (stats, path)
     }
 
     pub fn run_kelly_analysis(&mut self, max_fraction: f64, steps: usize) -> Vec<KellyResult> {
@@ -290,6 +363,7 @@ impl MonteCarloSimulator {
             config.position_size_pct = fraction;
 
             let mut sim = MonteCarloSimulator::new(config);
+            sim.bootstrap = self.bootstrap.clone();
             let result = sim.run();
 
             results.push(KellyResult {
@@ -318,6 +392,9 @@ impl SimulationResult {
         println!("Simulations: {}", self.config.num_simulations);
         println!("Trades per simulation: {}", self.config.num_trades_per_sim);
         println!("Starting Capital: ${:.2}", self.config.starting_capital);
+        if !self.resampling.is_empty() {
+            println!("Resampling: {}", self.resampling);
+        }
         println!(
             "Position Size: {:.1}% of capital",
             self.config.position_size_pct * 100.0
@@ -348,6 +425,18 @@ impl SimulationResult {
         println!("=== DRAWDOWN ===");
         println!("Median Max DD: {:.2}%", self.median_max_drawdown);
         println!("Worst Case DD: {:.2}%", self.worst_case_drawdown);
+        println!("--------------------------------------------");
+        println!("=== RUIN & RECOVERY ===");
+        let trades = |v: Option<f64>| v.map_or("-".to_string(), |t| format!("{:.0} trades", t));
+        println!("Median Trades to Ruin: {}", trades(self.median_trades_to_ruin));
+        println!(
+            "Recovery from >={:.0}% DD: median {}, p95 {}",
+            RECOVERY_MIN_DRAWDOWN * 100.0,
+            trades(self.median_recovery_trades),
+            trades(self.p95_recovery_trades)
+        );
+        println!("Ending Unrecovered: {:.2}%", self.unrecovered_pct);
+        println!("Median Longest Underwater: {:.0} trades", self.median_max_underwater_trades);
         println!("============================================");
     }
 
@@ -397,3 +486,30 @@ pub fn print_kelly_analysis(results: &[KellyResult]) {
     }
     println!("==========================================");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_statistics_track_recovery_and_ruin() {
+        let config = SimulationConfig {
+            starting_capital: 100.0,
+            position_size_pct: 0.5,
+            ..Default::default()
+        };
+        let sim = MonteCarloSimulator::seeded(config, 1);
+
+        // -10%, -5%, then back above the peak on the fourth trade
+        let (stats, path) = sim.simulate_path(&[-0.2, -0.1, 0.1, 0.3, 0.0], 10.0, true);
+        assert_eq!(path.len(), 6);
+        assert_eq!(stats.recovery_trades, vec![4]);
+        assert_eq!(stats.max_underwater_trades, 3);
+        assert!(!stats.unrecovered);
+        assert!(stats.ruin_trade.is_none());
+
+        let (ruined, _) = sim.simulate_path(&[-2.0, 0.5, 0.5], 10.0, false);
+        assert_eq!(ruined.ruin_trade, Some(1));
+        assert!(!ruined.survived && ruined.unrecovered);
+    }
+}
@@ -0,0 +1,342 @@
+//! Order Tracker
+//!
+//! Order lifecycle state machine fed from the CLOB `orders` and `trades`
+//! endpoints. Turns what the exchange reports into measured average fill
+//! price, slippage against the decision price and submit-to-fill latency.
+
+use crate::bot::execution::OrderSide;
+use crate::bot::feed::{UserEvent, UserOrderAction};
+use crate::bot::risk::decimal_to_f64;
+use anyhow::Result;
+use chrono::Utc;
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::Normal;
+use polymarket_client_sdk::clob;
+use polymarket_client_sdk::clob::types::request::TradesRequest;
+use polymarket_client_sdk::clob::types::OrderStatusType;
+use polymarket_client_sdk::types::U256;
+use serde::Serialize;
+use tokio::time::{sleep, Duration};
+
+/// Where an order is in its life on the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderLifecycle {
+    /// Sent, not yet acknowledged
+    Submitted,
+    /// Resting on the book with nothing matched
+    Live,
+    /// Some size matched, remainder still working
+    PartiallyFilled,
+    Filled,
+    /// Cancelled by us or killed unfilled (FOK/FAK); may carry partial fills
+    Cancelled,
+    /// Still open when its market ended
+    Expired,
+}
+
+impl OrderLifecycle {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Filled | Self::Cancelled | Self::Expired)
+    }
+}
+
+/// One match against a tracked order
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedFill {
+    pub trade_id: String,
+    pub price: f64,
+    pub size: f64,
+    pub ts_ms: i64,
+}
+
+/// An order and everything observed about it since submission
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedOrder {
+    pub order_id: String,
+    pub token_id: U256,
+    pub side: OrderSide,
+    /// Price the strategy decided on (ask for buys, bid for sells)
+    pub reference_price: f64,
+    pub submitted_at_ms: i64,
+    pub first_fill_at_ms: Option<i64>,
+    pub status: OrderLifecycle,
+    pub fills: Vec<TrackedFill>,
+}
+
+impl TrackedOrder {
+    pub fn submitted(
+        order_id: impl Into<String>,
+        token_id: U256,
+        side: OrderSide,
+        reference_price: f64,
+        submitted_at_ms: i64,
+    ) -> Self {
+        Self {
+            order_id: order_id.into(),
+            token_id,
+            side,
+            reference_price,
+            submitted_at_ms,
+            first_fill_at_ms: None,
+            status: OrderLifecycle::Submitted,
+            fills: Vec::new(),
+        }
+    }
+
+    pub fn filled_size(&self) -> f64 {
+        self.fills.iter().map(|f| f.size).sum()
+    }
+
+    /// Size-weighted average price over all fills
+    pub fn avg_fill_price(&self) -> Option<f64> {
+        let size = self.filled_size();
+        if size <= 0.0 {
+            return None;
+        }
+        Some(self.fills.iter().map(|f| f.price * f.size).sum::<f64>() / size)
+    }
+
+    /// Slippage of the average fill against the reference price, in bps.
+    /// Positive means the fill was worse than the decision price.
+    pub fn slippage_bps(&self) -> Option<i64> {
+        let avg = self.avg_fill_price()?;
+        if self.reference_price <= 0.0 {
+            return None;
+        }
+        let diff = match self.side {
+            OrderSide::Buy => avg - self.reference_price,
+            OrderSide::Sell => self.reference_price - avg,
+        };
+        Some((diff / self.reference_price * 10_000.0).round() as i64)
+    }
+
+    /// Milliseconds from submission to the first observed fill
+    pub fn latency_ms(&self) -> Option<i64> {
+        self.first_fill_at_ms
+            .map(|ts| (ts - self.submitted_at_ms).max(0))
+    }
+
+    /// Record a match, ignoring trades already seen. Returns whether the fill was new.
+    pub fn apply_fill(&mut self, trade_id: &str, price: f64, size: f64, ts_ms: i64) -> bool {
+        if size <= 0.0 || self.fills.iter().any(|f| f.trade_id == trade_id) {
+            return false;
+        }
+        // Trade timestamps have second resolution and may predate the submit
+        let ts_ms = ts_ms.max(self.submitted_at_ms);
+        self.first_fill_at_ms = Some(self.first_fill_at_ms.map_or(ts_ms, |t| t.min(ts_ms)));
+        self.fills.push(TrackedFill {
+            trade_id: trade_id.to_string(),
+            price,
+            size,
+            ts_ms,
+        });
+        if !self.status.is_terminal() {
+            self.status = OrderLifecycle::PartiallyFilled;
+        }
+        true
+    }
+
+    /// Advance the state machine from an exchange-reported status
+    pub fn apply_status(&mut self, status: &OrderStatusType, now_ms: i64) {
+        if self.status.is_terminal() {
+            return;
+        }
+        self.status = match status {
+            OrderStatusType::Matched => {
+                // The ack is the earliest we know a taker order matched
+                self.first_fill_at_ms.get_or_insert(now_ms);
+                OrderLifecycle::Filled
+            }
+            OrderStatusType::Canceled | OrderStatusType::Unmatched => OrderLifecycle::Cancelled,
+            OrderStatusType::Live | OrderStatusType::Delayed if self.fills.is_empty() => {
+                OrderLifecycle::Live
+            }
+            OrderStatusType::Live | OrderStatusType::Delayed => OrderLifecycle::PartiallyFilled,
+            _ => self.status,
+        };
+    }
+
+    /// Fold a user-channel event into the state machine. Returns whether it
+    /// concerned this order.
+    pub fn apply_user_event(&mut self, event: &UserEvent) -> bool {
+        match event {
+            UserEvent::Order(order) if order.order_id == self.order_id => {
+                match order.action {
+                    UserOrderAction::Cancellation if !self.status.is_terminal() => {
+                        self.status = OrderLifecycle::Cancelled;
+                    }
+                    UserOrderAction::Placement if self.status == OrderLifecycle::Submitted => {
+                        self.status = OrderLifecycle::Live;
+                    }
+                    UserOrderAction::Update
+                        if order.original_size > 0.0 && order.size_matched >= order.original_size =>
+                    {
+                        self.status = OrderLifecycle::Filled;
+                    }
+                    _ => {}
+                }
+                true
+            }
+            UserEvent::Trade(trade) if !trade.is_failed() => {
+                let fills = trade.fills_for(&self.order_id);
+                for (fill_id, price, size) in &fills {
+                    self.apply_fill(fill_id, *price, *size, trade.ts_ms);
+                }
+                !fills.is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    /// Mark a still-working order as expired, e.g. when its market ended
+    pub fn expire(&mut self) {
+        if !self.status.is_terminal() {
+            self.status = OrderLifecycle::Expired;
+        }
+    }
+}
+
+/// Pull the order status and our trades on its token from the CLOB and fold
+/// them into `order`.
+pub async fn reconcile_order(
+    client: &clob::Client<Authenticated<Normal>>,
+    order: &mut TrackedOrder,
+) -> Result<()> {
+    let open_order = client.order(&order.order_id).await?;
+    order.apply_status(&open_order.status, Utc::now().timestamp_millis());
+
+    let request = TradesRequest::builder()
+        .asset_id(order.token_id)
+        .after(order.submitted_at_ms / 1000 - 1)
+        .build();
+    let order_id = order.order_id.clone();
+    let mut cursor = None;
+    loop {
+        let page = client.trades(&request, cursor).await?;
+        for trade in &page.data {
+            let ts_ms = trade.match_time.timestamp_millis();
+            if trade.taker_order_id == order_id {
+                order.apply_fill(&trade.id, decimal_to_f64(trade.price), decimal_to_f64(trade.size), ts_ms);
+            }
+            for maker in trade.maker_orders.iter().filter(|m| m.order_id == order_id) {
+                order.apply_fill(
+                    &format!("{}:{}", trade.id, maker.order_id),
+                    decimal_to_f64(maker.price),
+                    decimal_to_f64(maker.matched_amount),
+                    ts_ms,
+                );
+            }
+        }
+        // The API signals the last page with a base64 "-1" cursor
+        if page.data.is_empty() || page.next_cursor.is_empty() || page.next_cursor == "LTE=" {
+            break;
+        }
+        cursor = Some(page.next_cursor);
+    }
+    Ok(())
+}
+
+/// Reconcile a just-posted taker order, retrying briefly while the trades
+/// endpoint catches up with the match.
+pub async fn confirm_taker_fill(
+    client: &clob::Client<Authenticated<Normal>>,
+    order: &mut TrackedOrder,
+) -> Result<()> {
+    const ATTEMPTS: u32 = 3;
+    for attempt in 1..=ATTEMPTS {
+        reconcile_order(client, order).await?;
+        if order.status.is_terminal() && (order.status != OrderLifecycle::Filled || !order.fills.is_empty()) {
+            return Ok(());
+        }
+        if attempt < ATTEMPTS {
+            sleep(Duration::from_millis(250)).await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: OrderSide) -> TrackedOrder {
+        TrackedOrder::submitted("0xabc", U256::ZERO, side, 0.50, 1_000_000)
+    }
+
+    #[test]
+    fn partial_fills_then_matched() {
+        let mut order = order(OrderSide::Buy);
+        order.apply_status(&OrderStatusType::Live, 1_000_050);
+        assert_eq!(order.status, OrderLifecycle::Live);
+
+        assert!(order.apply_fill("t1", 0.50, 4.0, 1_000_200));
+        assert_eq!(order.status, OrderLifecycle::PartiallyFilled);
+        // Duplicate trade ids from repeated polling are ignored
+        assert!(!order.apply_fill("t1", 0.50, 4.0, 1_000_200));
+
+        order.apply_fill("t2", 0.52, 6.0, 1_000_900);
+        order.apply_status(&OrderStatusType::Matched, 1_001_000);
+        assert_eq!(order.status, OrderLifecycle::Filled);
+        assert!((order.filled_size() - 10.0).abs() < 1e-9);
+        assert!((order.avg_fill_price().unwrap() - 0.512).abs() < 1e-9);
+        assert_eq!(order.latency_ms(), Some(200));
+    }
+
+    #[test]
+    fn slippage_is_signed_by_side() {
+        let mut buy = order(OrderSide::Buy);
+        buy.apply_fill("t1", 0.51, 1.0, 1_000_000);
+        assert_eq!(buy.slippage_bps(), Some(200));
+
+        let mut sell = order(OrderSide::Sell);
+        sell.apply_fill("t1", 0.51, 1.0, 1_000_000);
+        assert_eq!(sell.slippage_bps(), Some(-200));
+    }
+
+    #[test]
+    fn terminal_states_are_sticky() {
+        let mut order = order(OrderSide::Sell);
+        order.apply_status(&OrderStatusType::Unmatched, 1_000_100);
+        assert_eq!(order.status, OrderLifecycle::Cancelled);
+        order.apply_status(&OrderStatusType::Live, 1_000_200);
+        order.expire();
+        assert_eq!(order.status, OrderLifecycle::Cancelled);
+        assert_eq!(order.latency_ms(), None);
+    }
+
+    #[test]
+    fn user_channel_events_drive_lifecycle() {
+        let mut order = order(OrderSide::Buy);
+        let trade = serde_json::json!({
+            "event_type": "trade", "id": "t1", "asset_id": "0", "side": "BUY",
+            "price": "0.49", "size": "10", "status": "MATCHED", "taker_order_id": "0xother",
+            "matchtime": "1001", "maker_orders": [
+                { "order_id": "0xabc", "price": "0.50", "matched_amount": "4" }
+            ]
+        });
+        let events = crate::bot::feed::user_feed::parse_user_ws_value(&trade);
+        assert!(order.apply_user_event(&events[0]));
+        // The same match is reported again as MINED and CONFIRMED
+        assert!(order.apply_user_event(&events[0]));
+        assert!((order.filled_size() - 4.0).abs() < 1e-9);
+        assert_eq!(order.status, OrderLifecycle::PartiallyFilled);
+        assert_eq!(order.latency_ms(), Some(1_000));
+
+        let cancel = serde_json::json!({
+            "event_type": "order", "type": "CANCELLATION", "id": "0xabc", "asset_id": "0",
+            "side": "BUY", "price": "0.50", "original_size": "10", "size_matched": "4"
+        });
+        let events = crate::bot::feed::user_feed::parse_user_ws_value(&cancel);
+        assert!(order.apply_user_event(&events[0]));
+        assert_eq!(order.status, OrderLifecycle::Cancelled);
+    }
+
+    #[test]
+    fn fill_timestamps_clamp_to_submission() {
+        let mut order = order(OrderSide::Buy);
+        // match_time is truncated to whole seconds
+        order.apply_fill("t1", 0.50, 1.0, 999_000);
+        assert_eq!(order.latency_ms(), Some(0));
+    }
+}
@@ -0,0 +1,616 @@
+//! PMXT Data Catalog
+//!
+//! Local index of the hourly PMXT orderbook archive. `bot data sync` fills
+//! the gaps of a time range in the data directory and records each hour's
+//! row count, size, checksum and markets per asset and duration in
+//! `catalog.duckdb`. Backtests given `--catalog` select hours by time range
+//! and read the indexed markets instead of scanning a directory and
+//! rediscovering markets through Gamma.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::temporal::{resolve_hour_markets, HourMarket};
+use super::{load_parquet_market_stats_for_ids, sql_literal, BacktestPmxtArgs, CryptoAsset, DiscoveredMarket};
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+
+const PMXT_ARCHIVE_URL: &str = "https://r2.pmxt.dev";
+const PMXT_FILE_PATTERN: &str = "polymarket_orderbook_%Y-%m-%dT%H.parquet";
+const CATALOG_FILE: &str = "catalog.duckdb";
+
+#[derive(Args, Clone)]
+pub struct DataArgs {
+    #[command(subcommand)]
+    pub command: DataCommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum DataCommand {
+    /// Download and index the PMXT hours missing from a time range
+    Sync(DataSyncArgs),
+    /// Show catalogued hours, gaps and markets per asset and duration
+    Status(DataStatusArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct DataSyncArgs {
+    /// Data directory holding PMXT hours and the catalog
+    #[arg(long, default_value = "data/pmxt")]
+    pub data_dir: String,
+
+    /// Archive the hour files are downloaded from
+    #[arg(long, default_value = PMXT_ARCHIVE_URL)]
+    pub base_url: String,
+
+    /// Name of each hour file in the archive, as a strftime pattern of the hour
+    #[arg(long, default_value = PMXT_FILE_PATTERN)]
+    pub file_pattern: String,
+
+    /// First hour to sync (ISO format: 2026-03-02T00:00:00Z)
+    #[arg(long)]
+    pub from: String,
+
+    /// End of the range, exclusive (ISO format: 2026-03-03T00:00:00Z)
+    #[arg(long)]
+    pub to: String,
+
+    /// Assets whose markets are indexed
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "btc,eth,sol,xrp")]
+    pub assets: Vec<SupportedAsset>,
+
+    /// Market durations that are indexed
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "5m,15m,1h")]
+    pub durations: Vec<SupportedDuration>,
+
+    /// Re-hash catalogued files and download again any that changed
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Rebuild the market index of hours already catalogued
+    #[arg(long)]
+    pub reindex: bool,
+
+    /// Show verbose output
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct DataStatusArgs {
+    /// Data directory holding PMXT hours and the catalog
+    #[arg(long, default_value = "data/pmxt")]
+    pub data_dir: String,
+
+    /// Only hours from this time (ISO format: 2026-03-02T00:00:00Z)
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// Only hours before this time
+    #[arg(long)]
+    pub to: Option<String>,
+}
+
+/// Archive file name of the hour starting at `hour_ts`
+fn hour_file_name(pattern: &str, hour_ts: i64) -> String {
+    let hour = DateTime::from_timestamp(hour_ts, 0).unwrap_or_default();
+    hour.format(pattern).to_string()
+}
+
+fn parse_time(value: &str, flag: &str) -> Result<i64> {
+    let time: DateTime<Utc> = value.parse().with_context(|| format!("Invalid {} time format", flag))?;
+    Ok(time.timestamp())
+}
+
+/// Hour starts covering `[from, to)`
+fn hours_in_range(from: i64, to: i64) -> Vec<i64> {
+    let first = from.div_euclid(3600) * 3600;
+    (first..to).step_by(3600).collect()
+}
+
+fn hour_label(hour_ts: i64) -> String {
+    DateTime::from_timestamp(hour_ts, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:00").to_string())
+        .unwrap_or_else(|| format!("ts={}", hour_ts))
+}
+
+/// Keccak-256 of a file, streamed
+fn file_checksum(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = alloy::primitives::Keccak256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().to_string())
+}
+
+#[derive(Debug, Clone)]
+struct CatalogHour {
+    hour_ts: i64,
+    file_name: String,
+    rows: i64,
+    bytes: i64,
+    checksum: String,
+}
+
+#[derive(Debug, Clone)]
+struct CatalogMarket {
+    asset: SupportedAsset,
+    duration: SupportedDuration,
+    market: DiscoveredMarket,
+}
+
+pub struct PmxtCatalog {
+    conn: duckdb::Connection,
+    data_dir: PathBuf,
+}
+
+impl PmxtCatalog {
+    pub fn open(data_dir: &str) -> Result<Self> {
+        std::fs::create_dir_all(data_dir).with_context(|| format!("Failed to create {}", data_dir))?;
+        let data_dir = PathBuf::from(data_dir);
+        let path = data_dir.join(CATALOG_FILE);
+        let conn = duckdb::Connection::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS pmxt_hours (
+                hour_ts BIGINT PRIMARY KEY,
+                file_name VARCHAR NOT NULL,
+                rows BIGINT NOT NULL,
+                bytes BIGINT NOT NULL,
+                checksum VARCHAR NOT NULL,
+                synced_at BIGINT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS pmxt_markets (
+                hour_ts BIGINT NOT NULL,
+                condition_id VARCHAR NOT NULL,
+                slug VARCHAR NOT NULL,
+                asset VARCHAR NOT NULL,
+                duration VARCHAR NOT NULL,
+                start_ts BIGINT NOT NULL,
+                end_ts BIGINT NOT NULL,
+                ticks BIGINT NOT NULL,
+                min_ts DOUBLE NOT NULL,
+                max_ts DOUBLE NOT NULL,
+                PRIMARY KEY (hour_ts, condition_id)
+            );",
+        )?;
+        Ok(Self { conn, data_dir })
+    }
+
+    fn path(&self, file_name: &str) -> PathBuf {
+        self.data_dir.join(file_name)
+    }
+
+    fn hours(&self, from: i64, to: i64) -> Result<Vec<CatalogHour>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT hour_ts, file_name, rows, bytes, checksum FROM pmxt_hours
+             WHERE hour_ts >= ? AND hour_ts < ? ORDER BY hour_ts",
+        )?;
+        let rows = stmt.query_map(duckdb::params![from, to], |row| {
+            Ok(CatalogHour {
+                hour_ts: row.get(0)?,
+                file_name: row.get(1)?,
+                rows: row.get(2)?,
+                bytes: row.get(3)?,
+                checksum: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<duckdb::Result<Vec<_>>>()?)
+    }
+
+    /// Replace an hour and its market index
+    fn record_hour(&self, hour: &CatalogHour, markets: &[CatalogMarket]) -> Result<()> {
+        self.conn.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<()> {
+            self.conn.execute("DELETE FROM pmxt_markets WHERE hour_ts = ?", [hour.hour_ts])?;
+            self.conn.execute(
+                "INSERT OR REPLACE INTO pmxt_hours VALUES (?, ?, ?, ?, ?, ?)",
+                duckdb::params![hour.hour_ts, hour.file_name, hour.rows, hour.bytes, hour.checksum, Utc::now().timestamp()],
+            )?;
+            let mut stmt = self.conn.prepare("INSERT INTO pmxt_markets VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")?;
+            for entry in markets {
+                let m = &entry.market;
+                stmt.execute(duckdb::params![
+                    hour.hour_ts,
+                    m.condition_id,
+                    m.slug,
+                    entry.asset.as_str(),
+                    entry.duration.as_str(),
+                    m.start_ts,
+                    m.end_ts,
+                    m.ticks,
+                    m.min_ts,
+                    m.max_ts
+                ])?;
+            }
+            Ok(())
+        })();
+        self.conn.execute_batch(if result.is_ok() { "COMMIT" } else { "ROLLBACK" })?;
+        result
+    }
+
+    /// Download one archive hour into the data directory. `Ok(None)` when
+    /// the archive does not have the hour (yet).
+    async fn download(&self, client: &reqwest::Client, args: &DataSyncArgs, hour_ts: i64) -> Result<Option<CatalogHour>> {
+        let file_name = hour_file_name(&args.file_pattern, hour_ts);
+        let url = format!("{}/{}", args.base_url.trim_end_matches('/'), file_name);
+        let response = client.get(&url).send().await.with_context(|| format!("GET {}", url))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let mut response = response.error_for_status().with_context(|| format!("GET {}", url))?;
+
+        let path = self.path(&file_name);
+        let partial = path.with_extension("parquet.part");
+        let mut file = std::fs::File::create(&partial).with_context(|| format!("Failed to create {}", partial.display()))?;
+        let mut hasher = alloy::primitives::Keccak256::new();
+        let mut bytes = 0i64;
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            file.write_all(&chunk)?;
+            bytes += chunk.len() as i64;
+        }
+        file.flush()?;
+        drop(file);
+        std::fs::rename(&partial, &path)?;
+        Ok(Some(CatalogHour { hour_ts, file_name, rows: 0, bytes, checksum: hasher.finalize().to_string() }))
+    }
+
+    /// Row count and markets per asset and duration of a local hour file
+    async fn index(&self, hour: &mut CatalogHour, args: &DataSyncArgs) -> Result<Vec<CatalogMarket>> {
+        self.count_rows(hour)?;
+        let mut markets = Vec::new();
+        for asset in &args.assets {
+            let hour_markets = resolve_hour_markets(hour.hour_ts, *asset, &args.durations, args.verbose).await?;
+            markets.extend(self.market_index(hour, *asset, hour_markets)?);
+        }
+        Ok(markets)
+    }
+
+    fn count_rows(&self, hour: &mut CatalogHour) -> Result<()> {
+        let path = self.path(&hour.file_name).to_string_lossy().to_string();
+        hour.rows = self
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM read_parquet({})", sql_literal(&path)), [], |row| row.get(0))?;
+        Ok(())
+    }
+
+    /// Index entries of the resolved markets of one asset that trade in the hour file
+    fn market_index(&self, hour: &CatalogHour, asset: SupportedAsset, hour_markets: Vec<HourMarket>) -> Result<Vec<CatalogMarket>> {
+        let path = self.path(&hour.file_name).to_string_lossy().to_string();
+        let ids: Vec<String> = hour_markets.iter().map(|m| m.condition_id.clone()).collect();
+        let stats: HashMap<String, _> = load_parquet_market_stats_for_ids(&self.conn, &path, &ids)?
+            .into_iter()
+            .map(|s| (s.condition_id.clone(), s))
+            .collect();
+        let mut markets = Vec::new();
+        for market in hour_markets {
+            let (Some(stat), Some(duration)) = (stats.get(&market.condition_id), SupportedDuration::from_slug(&market.slug)) else {
+                continue;
+            };
+            markets.push(CatalogMarket {
+                asset,
+                duration,
+                market: DiscoveredMarket {
+                    condition_id: market.condition_id,
+                    question: market.slug.clone(),
+                    slug: market.slug,
+                    start_ts: market.start_ts,
+                    end_ts: market.end_ts,
+                    ticks: stat.ticks,
+                    min_ts: stat.min_ts,
+                    max_ts: stat.max_ts,
+                },
+            });
+        }
+        Ok(markets)
+    }
+}
+
+/// Catalogued hour files of a `--catalog` backtest in `[--from, --to)`, in
+/// the `(path, hour, is_csv)` form of `pmxt_input_files`
+pub(super) fn catalog_input_files(args: &BacktestPmxtArgs, data_dir: &str) -> Result<Vec<(String, i64, bool)>> {
+    let from = args.from.as_deref().map(|v| parse_time(v, "--from")).transpose()?.unwrap_or(i64::MIN);
+    let to = args.to.as_deref().map(|v| parse_time(v, "--to")).transpose()?.unwrap_or(i64::MAX);
+    let path = Path::new(data_dir).join(CATALOG_FILE);
+    if !path.is_file() {
+        anyhow::bail!("No catalog at {}; run `bot data sync` first", path.display());
+    }
+    let config = duckdb::Config::default().access_mode(duckdb::AccessMode::ReadOnly)?;
+    let conn = duckdb::Connection::open_with_flags(&path, config)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut stmt = conn.prepare(
+        "SELECT file_name, hour_ts FROM pmxt_hours WHERE hour_ts >= ? AND hour_ts < ? ORDER BY hour_ts",
+    )?;
+    let rows = stmt.query_map(duckdb::params![from, to], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+    let mut files = Vec::new();
+    for row in rows {
+        let (file_name, hour_ts) = row?;
+        let file_path = Path::new(data_dir).join(&file_name);
+        if file_path.is_file() {
+            files.push((file_path.to_string_lossy().to_string(), hour_ts, false));
+        } else {
+            eprintln!("  [WARN] Catalogued hour {} is missing {}", hour_label(hour_ts), file_path.display());
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("No catalogued hours in {} for the requested range", data_dir);
+    }
+    Ok(files)
+}
+
+/// Attach the catalog of `data_dir` to a DuckDB connection as `catalog`
+pub(super) fn attach_catalog(conn: &duckdb::Connection, data_dir: &str) -> Result<()> {
+    let path = Path::new(data_dir).join(CATALOG_FILE);
+    conn.execute_batch(&format!("ATTACH {} AS catalog (READ_ONLY);", sql_literal(&path.to_string_lossy())))
+        .with_context(|| format!("Failed to attach {}", path.display()))
+}
+
+/// Indexed markets of the catalogued hour stored as `file_path` matching an
+/// asset, durations (all when empty), slug filter and minimum ticks, in
+/// start order
+pub(super) fn catalog_markets(
+    conn: &duckdb::Connection,
+    file_path: &str,
+    crypto: CryptoAsset,
+    durations: &[SupportedDuration],
+    slug_filter: &str,
+    min_ticks: usize,
+) -> Result<Vec<DiscoveredMarket>> {
+    let file_name = Path::new(file_path).file_name().and_then(|name| name.to_str()).unwrap_or(file_path);
+    let asset = match crypto {
+        CryptoAsset::Btc => "btc",
+        CryptoAsset::Eth => "eth",
+        CryptoAsset::Sol => "sol",
+        CryptoAsset::Xrp => "xrp",
+        CryptoAsset::All => "%",
+    };
+    let mut stmt = conn.prepare(
+        "SELECT m.condition_id, m.slug, m.start_ts, m.end_ts, m.ticks, m.min_ts, m.max_ts, m.duration
+         FROM catalog.pmxt_markets m JOIN catalog.pmxt_hours h ON h.hour_ts = m.hour_ts
+         WHERE h.file_name = ? AND m.asset LIKE ? AND lower(m.slug) LIKE ? AND m.ticks >= ?
+         ORDER BY m.start_ts, m.end_ts, m.condition_id",
+    )?;
+    let slug_pattern = format!("%{}%", slug_filter.trim().to_ascii_lowercase());
+    let rows = stmt.query_map(duckdb::params![file_name, asset, slug_pattern, min_ticks as i64], |row| {
+        let slug: String = row.get(1)?;
+        let market = DiscoveredMarket {
+            condition_id: row.get(0)?,
+            question: slug.clone(),
+            slug,
+            start_ts: row.get(2)?,
+            end_ts: row.get(3)?,
+            ticks: row.get(4)?,
+            min_ts: row.get(5)?,
+            max_ts: row.get(6)?,
+        };
+        Ok((row.get::<_, String>(7)?, market))
+    })?;
+    let mut markets = Vec::new();
+    for row in rows {
+        let (duration, market) = row?;
+        if durations.is_empty() || durations.iter().any(|d| d.as_str() == duration) {
+            markets.push(market);
+        }
+    }
+    Ok(markets)
+}
+
+pub async fn run_data(args: DataArgs) -> Result<()> {
+    match args.command {
+        DataCommand::Sync(sync_args) => run_data_sync(sync_args).await,
+        DataCommand::Status(status_args) => run_data_status(status_args),
+    }
+}
+
+async fn run_data_sync(args: DataSyncArgs) -> Result<()> {
+    let from = parse_time(&args.from, "--from")?;
+    let to = parse_time(&args.to, "--to")?;
+    let hours = hours_in_range(from, to);
+    if hours.is_empty() {
+        anyhow::bail!("--to must be after --from");
+    }
+    let catalog = PmxtCatalog::open(&args.data_dir)?;
+    let known: HashMap<i64, CatalogHour> = catalog.hours(from, to)?.into_iter().map(|h| (h.hour_ts, h)).collect();
+    println!("[DATA-SYNC] {} hours from {} to {} | {} already catalogued in {}",
+        hours.len(), hour_label(hours[0]), hour_label(*hours.last().unwrap()), known.len(), args.data_dir);
+
+    let client = reqwest::Client::builder()
+        .user_agent("polymarket-cli")
+        .connect_timeout(std::time::Duration::from_secs(15))
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    let (mut fetched, mut indexed, mut skipped, mut missing, mut failed) = (0, 0, 0, 0, 0);
+    for hour_ts in hours {
+        let label = hour_label(hour_ts);
+        let file_name = hour_file_name(&args.file_pattern, hour_ts);
+        let local = catalog.path(&file_name);
+        let current = known.get(&hour_ts).filter(|_| local.is_file());
+        let verified = match (current, args.verify) {
+            (Some(hour), true) => {
+                let matches = file_checksum(&local)? == hour.checksum;
+                if !matches {
+                    eprintln!("  [WARN] {} checksum mismatch, downloading again", label);
+                }
+                matches
+            }
+            (Some(_), false) => true,
+            (None, _) => false,
+        };
+        if verified && !args.reindex {
+            skipped += 1;
+            if args.verbose {
+                println!("  {} | up to date", label);
+            }
+            continue;
+        }
+
+        let mut hour = if verified {
+            current.cloned().expect("verified hours are catalogued")
+        } else if local.is_file() && current.is_none() {
+            // Downloaded by hand or by an interrupted sync
+            let bytes = std::fs::metadata(&local)?.len() as i64;
+            CatalogHour { hour_ts, file_name, rows: 0, bytes, checksum: file_checksum(&local)? }
+        } else {
+            match catalog.download(&client, &args, hour_ts).await {
+                Ok(Some(hour)) => {
+                    fetched += 1;
+                    hour
+                }
+                Ok(None) => {
+                    missing += 1;
+                    eprintln!("  [WARN] {} is not in the archive", label);
+                    continue;
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  [WARN] {} download failed: {}", label, e);
+                    continue;
+                }
+            }
+        };
+
+        let markets = match catalog.index(&mut hour, &args).await {
+            Ok(markets) => markets,
+            Err(e) => {
+                failed += 1;
+                eprintln!("  [WARN] {} indexing failed: {}", label, e);
+                continue;
+            }
+        };
+        catalog.record_hour(&hour, &markets)?;
+        indexed += 1;
+        println!("  {} | {:>9} rows | {:>6.1} MB | {} markets", label, hour.rows, hour.bytes as f64 / 1e6, markets.len());
+    }
+
+    println!("\n[DATA-SYNC] Downloaded {} | indexed {} | up to date {} | not in archive {} | failed {}",
+        fetched, indexed, skipped, missing, failed);
+    Ok(())
+}
+
+fn run_data_status(args: DataStatusArgs) -> Result<()> {
+    let catalog = PmxtCatalog::open(&args.data_dir)?;
+    let from = args.from.as_deref().map(|v| parse_time(v, "--from")).transpose()?.unwrap_or(i64::MIN);
+    let to = args.to.as_deref().map(|v| parse_time(v, "--to")).transpose()?.unwrap_or(i64::MAX);
+    let hours = catalog.hours(from, to)?;
+    let (Some(first), Some(last)) = (hours.first(), hours.last()) else {
+        println!("[DATA] No catalogued hours in {}", args.data_dir);
+        return Ok(());
+    };
+
+    let rows: i64 = hours.iter().map(|h| h.rows).sum();
+    let bytes: i64 = hours.iter().map(|h| h.bytes).sum();
+    let gaps: Vec<i64> = hours_in_range(first.hour_ts, last.hour_ts + 3600)
+        .into_iter()
+        .filter(|h| hours.binary_search_by_key(h, |c| c.hour_ts).is_err())
+        .collect();
+    println!("[DATA] {} hours from {} to {} | {} rows | {:.1} MB",
+        hours.len(), hour_label(first.hour_ts), hour_label(last.hour_ts), rows, bytes as f64 / 1e6);
+    if gaps.is_empty() {
+        println!("[DATA] No gaps");
+    } else {
+        println!("[DATA] {} missing hours, first {}", gaps.len(), hour_label(gaps[0]));
+    }
+
+    let mut stmt = catalog.conn.prepare(
+        "SELECT asset, duration, COUNT(DISTINCT condition_id), SUM(ticks) FROM pmxt_markets
+         WHERE hour_ts >= ? AND hour_ts < ? GROUP BY asset, duration ORDER BY asset, duration",
+    )?;
+    let breakdown = stmt.query_map(duckdb::params![from, to], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))
+    })?;
+    println!("\n{:<6} {:<8} {:>8} {:>12}", "Asset", "Duration", "Markets", "Ticks");
+    for row in breakdown {
+        let (asset, duration, markets, ticks) = row?;
+        println!("{:<6} {:<8} {:>8} {:>12}", asset, duration, markets, ticks);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hour_files_round_trip_and_ranges_cover_partial_hours() {
+        let hour = 1_772_409_600; // 2026-03-02 00:00 UTC
+        assert_eq!(hour_file_name(PMXT_FILE_PATTERN, hour), "polymarket_orderbook_2026-03-02T00.parquet");
+        assert_eq!(super::super::extract_hour_from_filename(&hour_file_name(PMXT_FILE_PATTERN, hour + 7200)), Some(hour + 7200));
+        assert_eq!(hour_file_name("%Y/%m/%d/%H.parquet", hour), "2026/03/02/00.parquet");
+
+        assert_eq!(hours_in_range(hour + 1800, hour + 7200), vec![hour, hour + 3600]);
+        assert!(hours_in_range(hour, hour).is_empty());
+        assert_eq!(parse_time("2026-03-02T00:00:00Z", "--from").unwrap(), hour);
+    }
+
+    fn hour_market(condition_id: &str, duration: SupportedDuration, start_ts: i64) -> HourMarket {
+        HourMarket {
+            condition_id: condition_id.to_string(),
+            slug: format!("btc-updown-{}-{}", duration.as_str(), start_ts),
+            timeframe: crate::bot::temporal_arb::graph_timeframe(duration),
+            start_ts,
+            end_ts: start_ts + duration.seconds(),
+            up_won: None,
+        }
+    }
+
+    #[test]
+    fn catalog_built_from_an_hour_file_answers_market_queries() {
+        let tmp = crate::bot::test_util::tempdir();
+        // A quote in the path has to survive the SQL the catalog runs
+        let data_dir = format!("{tmp}/o'hare");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let hour = 1_772_409_600;
+        let file_name = hour_file_name(PMXT_FILE_PATTERN, hour);
+        let file_path = format!("{data_dir}/{file_name}");
+        let row = |market: &str, ts: i64| {
+            format!("('{market}', '{{\"timestamp\": {ts}, \"side\": \"YES\", \"best_bid\": \"0.49\", \"best_ask\": \"0.51\"}}')")
+        };
+        let rows = [row("0x5m", hour + 10), row("0x5m", hour + 20), row("0x15m", hour + 30), row("0xother", hour + 40)];
+        duckdb::Connection::open_in_memory()
+            .unwrap()
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES {}) AS t(market_id, data)) TO {} (FORMAT PARQUET)",
+                rows.join(", "),
+                sql_literal(&file_path)
+            ))
+            .unwrap();
+
+        {
+            let catalog = PmxtCatalog::open(&data_dir).unwrap();
+            let mut entry = CatalogHour { hour_ts: hour, file_name, rows: 0, bytes: 0, checksum: String::new() };
+            catalog.count_rows(&mut entry).unwrap();
+            assert_eq!(entry.rows, 4);
+            let resolved = vec![
+                hour_market("0x5m", SupportedDuration::M5, hour),
+                hour_market("0x15m", SupportedDuration::M15, hour),
+                hour_market("0xmissing", SupportedDuration::H1, hour),
+            ];
+            let markets = catalog.market_index(&entry, SupportedAsset::Btc, resolved).unwrap();
+            assert_eq!(markets.len(), 2);
+            catalog.record_hour(&entry, &markets).unwrap();
+        }
+
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        attach_catalog(&conn, &data_dir).unwrap();
+        let ids = |markets: Vec<DiscoveredMarket>| markets.into_iter().map(|m| m.condition_id).collect::<Vec<_>>();
+        assert_eq!(ids(catalog_markets(&conn, &file_path, CryptoAsset::Btc, &[], "", 1).unwrap()), ["0x5m", "0x15m"]);
+        assert_eq!(ids(catalog_markets(&conn, &file_path, CryptoAsset::All, &[SupportedDuration::M15], "", 1).unwrap()), ["0x15m"]);
+        assert_eq!(ids(catalog_markets(&conn, &file_path, CryptoAsset::Btc, &[], "updown-5m", 2).unwrap()), ["0x5m"]);
+        assert!(catalog_markets(&conn, &file_path, CryptoAsset::Eth, &[], "", 1).unwrap().is_empty());
+        assert!(catalog_markets(&conn, "elsewhere.parquet", CryptoAsset::Btc, &[], "", 1).unwrap().is_empty());
+
+        let window = catalog_markets(&conn, &file_path, CryptoAsset::Btc, &[SupportedDuration::M5], "", 1).unwrap();
+        assert_eq!((window[0].start_ts, window[0].end_ts, window[0].ticks), (hour, hour + 300, 2));
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! Hawkes Calibration
+//!
+//! Fits the bivariate buy/sell Hawkes kernel per asset and market duration
+//! from PMXT history and writes the parameter file `--hawkes-params` loads.
+//! PMXT carries quotes but no trade prints, so flow events are inferred from
+//! YES midpoint moves exactly as the engine does without a trade stream.
+
+use anyhow::Result;
+use clap::Args;
+use std::collections::HashMap;
+
+use super::temporal::{hourly_parquet_files, load_hour_ticks, resolve_hour_markets};
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+use crate::bot::strategy::hawkes_calibration::{
+    beta_grid, fit, quote_flow_events, CalibratedKernel, EventSequence, HawkesCalibration, BUY, SELL,
+    ENGINE_MARK_UNIT,
+};
+
+#[derive(Args, Clone)]
+pub struct CalibrateHawkesArgs {
+    /// Directory containing hourly PMXT parquet files
+    #[arg(long)]
+    pub input_dir: String,
+
+    /// Assets to calibrate
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "btc")]
+    pub assets: Vec<SupportedAsset>,
+
+    /// Market durations to calibrate
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "5m")]
+    pub durations: Vec<SupportedDuration>,
+
+    /// Skip markets with fewer inferred flow events
+    #[arg(long, default_value = "10")]
+    pub min_events: usize,
+
+    /// Parameter file to write
+    #[arg(long, default_value = "hawkes_params.json")]
+    pub output: String,
+
+    /// Show verbose output
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+/// Flow events of one market over its window. `mids` are `(ts, midpoint)`
+/// with seconds or millisecond timestamps.
+fn market_sequence(start_ts: i64, end_ts: i64, mut mids: Vec<(f64, f64)>) -> EventSequence {
+    for (ts, _) in &mut mids {
+        if *ts > 1e11 {
+            *ts /= 1000.0;
+        }
+    }
+    mids.retain(|(ts, _)| *ts >= start_ts as f64 && *ts <= end_ts as f64);
+    mids.sort_by(|a, b| a.0.total_cmp(&b.0));
+    EventSequence {
+        start: start_ts as f64,
+        end: end_ts as f64,
+        events: quote_flow_events(&mids),
+    }
+}
+
+pub async fn run_calibrate_hawkes(args: CalibrateHawkesArgs) -> Result<()> {
+    let input_files = hourly_parquet_files(&args.input_dir)?;
+    println!("[CALIBRATE-HAWKES] Found {} hourly files", input_files.len());
+
+    let conn = duckdb::Connection::open_in_memory()?;
+    conn.execute_batch("INSTALL httpfs; LOAD httpfs; PRAGMA threads=4;")?;
+
+    let mut sequences: HashMap<(SupportedAsset, SupportedDuration), Vec<EventSequence>> = HashMap::new();
+    for (file_num, (file_path, hour_ts)) in input_files.iter().enumerate() {
+        println!("[FILE {}/{}] {}", file_num + 1, input_files.len(), file_path);
+        for asset in &args.assets {
+            let markets = match resolve_hour_markets(*hour_ts, *asset, &args.durations, args.verbose).await {
+                Ok(markets) if !markets.is_empty() => markets,
+                Ok(_) => continue,
+                Err(e) => {
+                    eprintln!("  [WARN] Market lookup failed for {}: {}", file_path, e);
+                    continue;
+                }
+            };
+            let ticks = match load_hour_ticks(&conn, file_path, &markets) {
+                Ok(ticks) => ticks,
+                Err(e) => {
+                    eprintln!("  [WARN] Query failed for {}: {}", file_path, e);
+                    continue;
+                }
+            };
+
+            let mut mids: Vec<Vec<(f64, f64)>> = vec![Vec::new(); markets.len()];
+            for (idx, row) in ticks {
+                if row.side == "YES" && row.bid > 0.0 && row.ask >= row.bid {
+                    mids[idx].push((row.ts, (row.bid + row.ask) / 2.0));
+                }
+            }
+            for (market, mids) in markets.iter().zip(mids) {
+                let Some(duration) = SupportedDuration::from_slug(&market.slug) else {
+                    continue;
+                };
+                let sequence = market_sequence(market.start_ts, market.end_ts, mids);
+                if args.verbose {
+                    println!("  {} | {} flow events", market.slug, sequence.events.len());
+                }
+                if sequence.events.len() >= args.min_events {
+                    sequences.entry((*asset, duration)).or_default().push(sequence);
+                }
+            }
+        }
+    }
+
+    let mut kernels = Vec::new();
+    let betas = beta_grid();
+    println!("\n[CALIBRATE-HAWKES] Fitted kernels (rates per second)");
+    for asset in &args.assets {
+        for duration in &args.durations {
+            let key = format!("{}-{}", asset.as_str(), duration.as_str());
+            let Some(group) = sequences.get(&(*asset, *duration)) else {
+                println!("  {:<8} no markets with enough flow events", key);
+                continue;
+            };
+            let Some(result) = fit(group, &betas) else {
+                println!("  {:<8} fit failed", key);
+                continue;
+            };
+            let p = &result.params;
+            println!(
+                "  {:<8} {:>4} markets {:>7} events | mu=({:.4}, {:.4}) alpha=[[{:.3}, {:.3}], [{:.3}, {:.3}]] beta={:.3} | branching {:.3} | LL {:.1}",
+                key,
+                group.len(),
+                result.events,
+                p.mu[BUY],
+                p.mu[SELL],
+                p.alpha[BUY][BUY],
+                p.alpha[BUY][SELL],
+                p.alpha[SELL][BUY],
+                p.alpha[SELL][SELL],
+                p.beta,
+                result.branching_ratio,
+                result.log_likelihood
+            );
+            if result.branching_ratio >= 1.0 {
+                eprintln!("  [WARN] {} kernel is explosive (branching ratio {:.3} >= 1)", key, result.branching_ratio);
+            }
+            kernels.push(CalibratedKernel {
+                asset: *asset,
+                duration: *duration,
+                params: result.params,
+                branching_ratio: result.branching_ratio,
+                log_likelihood: result.log_likelihood,
+                events: result.events,
+                markets: group.len(),
+                mark_unit: ENGINE_MARK_UNIT,
+            });
+        }
+    }
+
+    if kernels.is_empty() {
+        anyhow::bail!("No kernels fitted from {}", args.input_dir);
+    }
+    let count = kernels.len();
+    HawkesCalibration { kernels }.save(&args.output)?;
+    println!("\n[CALIBRATE-HAWKES] Wrote {} kernels to {}", count, args.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn market_sequence_clips_to_window_and_normalizes_ms() {
+        let sequence = market_sequence(
+            1_700_000_000,
+            1_700_000_300,
+            vec![
+                (1_699_999_990_000.0, 0.40),
+                (1_700_000_010_000.0, 0.50),
+                (1_700_000_005.0, 0.48),
+                (1_700_000_020_000.0, 0.47),
+                (1_700_000_400_000.0, 0.90),
+            ],
+        );
+        // 0.48 -> 0.50 -> 0.47 after sorting; the out-of-window ticks are dropped
+        assert_eq!(sequence.events.len(), 2);
+        assert_eq!(sequence.events[0].stream, BUY);
+        assert_eq!(sequence.events[1].stream, SELL);
+        assert_eq!(sequence.end - sequence.start, 300.0);
+    }
+}
@@ -1,27 +1,47 @@
-use crate::bot::candles::CandleEngine;
 use crate::bot::feed::{DualSnapshot, MarketSnapshot, ReplayMode, ReplaySnapshotSource, StrategyInputSource};
-use crate::bot::indicators::{IndicatorEngine, IndicatorState};
 use crate::bot::logging::{EngineEvent, EngineEventLoggers};
-use crate::bot::risk::{best_ask_price, best_bid_price, decimal_to_f64, midpoint_price, GatekeeperState};
-use crate::bot::shadow::{ShadowPosition, ShadowStepResult, TokenSide};
-use crate::bot::signal::{EntrySignal, SignalEngine};
-use crate::bot::strategy::{HawkesFlowConfig, HawkesFlowEngine, StrategyEngine, StrategyDecision, Direction, Observation};
-use crate::bot::strategy_runner::run_shadow_strategy_step;
+use crate::bot::pricing::PredictionLog;
+use crate::bot::research::SupportedDuration;
+use crate::bot::resolution::ResolutionArgs;
+use crate::bot::risk::{decimal_to_f64, GatekeeperState};
+use crate::bot::shadow::{ShadowPosition, ShadowStepResult, TokenSide, TouchFills};
+use crate::bot::sizing::{PositionSizer, SizingArgs};
+use crate::bot::strategy::{StrategyArgs, StrategyConfig};
+use crate::bot::strategy_runner::{run_driver_shadow_step, StrategyDriver};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc, Timelike};
 use clap::{Args, ValueEnum};
 use polymarket_client_sdk::types::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use crate::bot::backtest::{BacktestConfig, BacktestEngine, BeckerParser, PmxtFetcher};
+use crate::bot::backtest::{
+    BacktestConfig, BacktestEngine, BeckerParser, BookFills, BookReplay, BookUpdate, FillArgs, FillModel,
+    FillModelKind, FillStats, PmxtFetcher, TradeResult,
+};
+use crate::bot::feed_base::{BookChangeSide, OutcomeSide};
 use crate::bot::backtest::data::load_mock_data;
 use crate::bot::backtest::metrics::ParameterSweepResult;
-use crate::bot::monte_carlo::{MonteCarloSimulator, SimulationConfig};
+use crate::bot::monte_carlo::{load_trades, BlockBootstrap, MonteCarloSimulator, SimulationConfig};
 use polymarket_client_sdk::{clob, gamma};
 use polymarket_client_sdk::gamma::types::request::MarketBySlugRequest;
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 use polymarket_client_sdk::gamma::types::response::Market;
 
+mod catalog;
+mod hawkes;
+mod optimize;
+mod recordings;
+mod temporal;
+mod walk_forward;
+
+use recordings::read_csv_recordings;
+pub use catalog::{run_data, DataArgs};
+pub use hawkes::{run_calibrate_hawkes, CalibrateHawkesArgs};
+pub use optimize::{run_optimize, OptimizeArgs};
+pub use recordings::{run_backtest_recording, BacktestRecordingArgs};
+pub use temporal::{run_backtest_temporal, BacktestTemporalArgs};
+pub use walk_forward::{run_walk_forward, WalkForwardArgs};
+
 // ── CLI Arg Structs ────────────────────────────────────────────────────────────
 
 #[derive(Args, Clone)]
@@ -62,13 +82,11 @@ pub struct BacktestPipelineArgs {
     #[arg(long, default_value = "1")]
     pub size: f64,
 
-    /// Entry band low
-    #[arg(long, default_value = "0.35")]
-    pub band_low: f64,
+    #[command(flatten)]
+    pub sizing: SizingArgs,
 
-    /// Entry band high
-    #[arg(long, default_value = "0.65")]
-    pub band_high: f64,
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
 
     /// Export results to JSON
     #[arg(long)]
@@ -116,6 +134,9 @@ pub struct BacktestArgs {
     /// Export results to JSON file
     #[arg(long)]
     pub export: Option<String>,
+
+    #[command(flatten)]
+    pub fills: FillArgs,
 }
 
 #[derive(Args, Clone)]
@@ -140,6 +161,23 @@ pub struct MonteCarloArgs {
     #[arg(short, long)]
     pub input: Option<String>,
 
+    /// Block-bootstrap the trades of a backtest export (backtest, backtest-pmxt
+    /// or a bare trade list) instead of sampling win/loss distributions
+    #[arg(long, conflicts_with = "input")]
+    pub trades_file: Option<String>,
+
+    /// Trades per bootstrap block (default: cube root of the trade count)
+    #[arg(long)]
+    pub block_len: Option<usize>,
+
+    /// Draw bootstrap blocks conditioned on calm/volatile trade regimes
+    #[arg(long)]
+    pub regimes: bool,
+
+    /// Rolling window in trades used to label regimes
+    #[arg(long, default_value = "10")]
+    pub regime_window: usize,
+
     /// Export results to JSON file
     #[arg(long)]
     pub export: Option<String>,
@@ -240,28 +278,31 @@ pub struct ListMarketsArgs {
     pub verbose: bool,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-pub enum BtStrategy {
-    /// Scalper: EMA crossover + RSI + Bollinger Bands
-    Scalper,
-    /// Late window: high-prob sniping in final minutes
-    LateWindow,
-    /// Fair value: Logit jump-diffusion model for edge trading
-    FairValue,
-    /// Hawkes flow: order flow self/cross-excitation dynamics with VPIN toxicity gate
-    HawkesFlow,
-}
-
 #[derive(Args, Clone)]
 pub struct BacktestPmxtArgs {
     /// Directory containing PMXT parquet files
-    #[arg(long)]
-    pub input_dir: String,
+    #[arg(long, required_unless_present = "catalog")]
+    pub input_dir: Option<String>,
+
+    /// Data directory synced by `bot data sync`; replays its catalogued hours
+    /// and indexed markets instead of scanning --input-dir
+    #[arg(long, conflicts_with = "input_dir")]
+    pub catalog: Option<String>,
+
+    /// First catalogued hour to replay (ISO format: 2026-03-02T00:00:00Z)
+    #[arg(long, requires = "catalog")]
+    pub from: Option<String>,
 
-    /// Strategy mode
-    #[arg(long, value_enum, default_value_t = BtStrategy::Scalper)]
-    pub strategy: BtStrategy,
+    /// End of the catalogued range, exclusive
+    #[arg(long, requires = "catalog")]
+    pub to: Option<String>,
+
+    /// Catalogued market durations to replay, comma separated (default: all)
+    #[arg(long, value_enum, value_delimiter = ',', requires = "catalog")]
+    pub durations: Vec<SupportedDuration>,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
 
     /// Starting capital in USD
     #[arg(long, default_value = "5")]
@@ -271,18 +312,6 @@ pub struct BacktestPmxtArgs {
     #[arg(long, default_value = "1")]
     pub size: f64,
 
-    /// Entry band low (only for scalper)
-    #[arg(long, default_value = "0.35")]
-    pub band_low: f64,
-
-    /// Entry band high (only for scalper)
-    #[arg(long, default_value = "0.65")]
-    pub band_high: f64,
-
-    /// Minimum edge for late-window strategy
-    #[arg(long, default_value = "0.05")]
-    pub min_edge: f64,
-
     /// Filter pattern for market slug
     #[arg(long, default_value = "btc-updown-5m")]
     pub filter: String,
@@ -299,9 +328,22 @@ pub struct BacktestPmxtArgs {
     #[arg(long)]
     pub export: Option<String>,
 
+    /// Append each entry's fair value prediction to this JSON-lines file
+    #[arg(long)]
+    pub predictions_out: Option<String>,
+
     /// Show verbose output
     #[arg(short, long)]
     pub verbose: bool,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    #[command(flatten)]
+    pub fills: FillArgs,
+
+    #[command(flatten)]
+    pub resolution: ResolutionArgs,
 }
 
 // ── Enums and Constants ─────────────────────────────────────────────────────────
@@ -455,11 +497,60 @@ struct PipelineTickExport {
 }
 
 #[derive(Debug, Clone)]
-struct ReplayRow {
-    ts: f64,
-    side: String,
-    bid: f64,
-    ask: f64,
+pub(crate) struct ReplayRow {
+    pub(crate) ts: f64,
+    pub(crate) side: String,
+    pub(crate) bid: f64,
+    pub(crate) ask: f64,
+    /// Level changes carried by the event, when the source recorded them
+    pub(crate) depth: Option<BookUpdate>,
+}
+
+impl ReplayRow {
+    pub(crate) fn outcome_side(&self) -> Option<OutcomeSide> {
+        match self.side.as_str() {
+            "YES" => Some(OutcomeSide::Yes),
+            "NO" => Some(OutcomeSide::No),
+            _ => None,
+        }
+    }
+}
+
+/// Depth columns of a PMXT event: full `bids`/`asks` on book snapshots, a
+/// single level change on price changes
+fn pmxt_depth_update(
+    bids: Option<String>,
+    asks: Option<String>,
+    change_price: Option<f64>,
+    change_size: Option<f64>,
+    change_side: Option<String>,
+) -> Option<BookUpdate> {
+    if bids.is_some() || asks.is_some() {
+        let parse = |levels: Option<String>| {
+            levels.as_deref().and_then(BookUpdate::parse_levels).unwrap_or_default()
+        };
+        return Some(BookUpdate::Snapshot { bids: parse(bids), asks: parse(asks) });
+    }
+    let side = match change_side?.as_str() {
+        "BUY" => BookChangeSide::Buy,
+        "SELL" => BookChangeSide::Sell,
+        _ => return None,
+    };
+    Some(BookUpdate::Change { side, price: change_price?, size: change_size? })
+}
+
+pub(crate) fn build_book_replay(rows: &[ReplayRow]) -> BookReplay {
+    let mut book = BookReplay::default();
+    for row in rows {
+        let Some(side) = row.outcome_side() else {
+            continue;
+        };
+        if let Some(update) = &row.depth {
+            book.push(row.ts, side, update.clone());
+        }
+        book.push(row.ts, side, BookUpdate::Quote { bid: row.bid, ask: row.ask });
+    }
+    book
 }
 
 fn build_dual_snapshot(yes_bid: f64, yes_ask: f64, no_bid: f64, no_ask: f64, ts: f64) -> DualSnapshot {
@@ -838,7 +929,12 @@ pub fn market_matches_exact_filter(
     true
 }
 
-fn load_parquet_market_stats(
+/// SQL string literal for a file path or other text spliced into DuckDB SQL
+pub(crate) fn sql_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub(crate) fn load_parquet_market_stats(
     conn: &duckdb::Connection,
     input: &str,
     min_ticks: usize,
@@ -850,12 +946,12 @@ fn load_parquet_market_stats(
             COUNT(*) AS ticks,
             MIN(CAST(data ->> '$.timestamp' AS DOUBLE)) AS min_ts,
             MAX(CAST(data ->> '$.timestamp' AS DOUBLE)) AS max_ts
-        FROM read_parquet('{}')
+        FROM read_parquet({})
         GROUP BY market_id
         HAVING COUNT(*) >= {}
         ORDER BY min_ts ASC
         "#,
-        input, min_ticks
+        sql_literal(input), min_ticks
     );
 
     let mut stmt = conn.prepare(&sql).context("Failed to prepare market stats query")?;
@@ -948,12 +1044,12 @@ fn load_parquet_market_stats_for_ids(
             COUNT(*) AS ticks,
             MIN(CAST(data ->> '$.timestamp' AS DOUBLE)) AS min_ts,
             MAX(CAST(data ->> '$.timestamp' AS DOUBLE)) AS max_ts
-        FROM read_parquet('{}')
+        FROM read_parquet({})
         WHERE market_id IN ({})
         GROUP BY market_id
         ORDER BY min_ts ASC
         "#,
-        input, quoted_ids
+        sql_literal(input), quoted_ids
     );
 
     let mut stmt = conn.prepare(&sql).context("Failed to prepare filtered market stats query")?;
@@ -1080,16 +1176,11 @@ pub fn process_pipeline_snapshot(
     market: &DiscoveredMarket,
     dual_snapshot: &DualSnapshot,
     epoch_seconds: u64,
-    candle_engine: &mut CandleEngine,
-    ind_1m: &mut IndicatorEngine,
-    ind_5s: &mut IndicatorEngine,
-    signal_engine: &mut SignalEngine,
-    state_1m: &mut IndicatorState,
-    state_5s: &mut IndicatorState,
+    driver: &mut StrategyDriver,
+    sizer: &PositionSizer,
     shadow: &mut ShadowPosition,
     gatekeeper: &mut GatekeeperState,
     metrics: &mut PipelineMetrics,
-    position_size_usd: f64,
     verbose: bool,
     event_loggers: Option<&EngineEventLoggers>,
 ) -> Option<ShadowStepResult> {
@@ -1107,22 +1198,18 @@ pub fn process_pipeline_snapshot(
         });
     }
 
-    if let Some(step) = run_shadow_strategy_step(
+    if let Some(step) = run_driver_shadow_step(
+        driver,
         dual_snapshot,
         &market.slug,
         &market.slug,
         market.start_ts,
         market.end_ts,
         epoch_seconds,
-        position_size_usd,
-        candle_engine,
-        ind_1m,
-        ind_5s,
-        signal_engine,
-        state_1m,
-        state_5s,
+        sizer,
         shadow,
         gatekeeper,
+        &mut TouchFills,
         event_loggers,
     ) {
         if step.entry_blocked && verbose {
@@ -1142,338 +1229,6 @@ pub fn process_pipeline_snapshot(
     None
 }
 
-use crate::bot::pricing::{
-    CalibratedFairValue, FairValueModel, JumpCalibrator, KalmanFilter,
-    LogitJumpDiffusion, LogitObservation, sigmoid, prob_to_logit, risk_neutral_drift,
-};
-
-/// Process a single snapshot using the full FairValue pipeline:
-/// 1. Logit observation from market midpoint
-/// 2. Kalman filter for noise reduction
-/// 3. Jump calibrator (EM) for jump parameter estimation
-/// 4. Horizon classification for parameter adaptation
-/// 5. Fair probability via risk-neutral model
-/// 6. Calibrated edge with spread/book adjustment
-/// 7. Momentum gate (velocity + EMA alignment)
-/// 8. Entry/exit decisions
-pub fn process_fairvalue_snapshot(
-    logit_model: &mut LogitJumpDiffusion,
-    kalman: &mut Option<KalmanFilter>,
-    jump_calibrator: &mut Option<JumpCalibrator>,
-    calibrated_fv: &mut CalibratedFairValue,
-    snapshot: &DualSnapshot,
-    epoch_seconds: u64,
-    market_start_ts: i64,
-    market_end_ts: i64,
-    shadow: &mut ShadowPosition,
-    metrics: &mut PipelineMetrics,
-    position_size_usd: f64,
-    min_edge: f64,
-    verbose: bool,
-    cumulative_wins: &mut Vec<f64>,
-    cumulative_losses: &mut Vec<f64>,
-) {
-    let yes_mid = midpoint_price(&snapshot.yes).unwrap_or(0.5);
-    let no_mid = 1.0 - yes_mid;
-    let time_remaining = (market_end_ts - epoch_seconds as i64).max(0);
-
-    if time_remaining <= 0 {
-        return;
-    }
-
-    let yes_bid = best_bid_price(&snapshot.yes).unwrap_or(0.0);
-    let yes_ask = best_ask_price(&snapshot.yes).unwrap_or(1.0);
-    let no_bid = best_bid_price(&snapshot.no).unwrap_or(0.0);
-    let no_ask = best_ask_price(&snapshot.no).unwrap_or(1.0);
-    let spread = (yes_ask - yes_bid).max(0.001);
-    let book_sum = yes_ask + no_ask;
-
-    // Step 1: Create logit observation
-    let clamped_prob = yes_mid.clamp(0.01, 0.99);
-    let raw_logit = prob_to_logit(clamped_prob);
-
-    let obs = LogitObservation {
-        timestamp: epoch_seconds as i64,
-        prob: clamped_prob,
-        logit: raw_logit,
-        spread,
-        volume: 0.0,
-    };
-
-    // Step 2: Update logit model (exponential filter)
-    let filtered = logit_model.update(&obs);
-
-    // Step 3: Kalman filter for noise reduction
-    let kalman_logit = if let Some(kf) = kalman {
-        kf.set_measurement_noise(spread);
-        let dt = 1.0 / (365.25 * 24.0 * 3600.0); // ~1 second in years
-        let drift = risk_neutral_drift(filtered.logit, filtered.vol, 0.0);
-        kf.predict(dt, drift, filtered.vol);
-        kf.update(raw_logit, spread);
-        kf.state()
-    } else {
-        filtered.logit
-    };
-
-    // Step 4: Update jump calibrator (EM estimation)
-    if let Some(jc) = jump_calibrator {
-        jc.update(&obs);
-    }
-
-    // Step 5: Horizon classification
-    let horizon = crate::bot::market_classifier::classify_market(time_remaining);
-    let edge_multiplier = horizon.edge_multiplier();
-
-    // Step 6: Compute fair probability via risk-neutral model
-    let fair = logit_model.fair_prob(time_remaining);
-    let fair_prob = fair.expected;
-
-    // Step 7: Edge calculation (use raw fair probability + spread adjustment)
-    let spread_cost = (yes_ask - yes_bid) * 0.5;  // half spread as cost
-    let book_inefficiency = 1.0 - book_sum;  // book_sum < 1.0 means cheap
-    let adjusted_fair = (fair_prob - spread_cost + book_inefficiency * 0.1).clamp(0.01, 0.99);
-
-    let edge_yes = (adjusted_fair - yes_ask) * edge_multiplier;
-    let edge_no = ((1.0 - adjusted_fair) - no_ask) * edge_multiplier;
-
-    // Step 8: Momentum gate (EMA alignment from indicators)
-    // Use the filtered logit converted back to probability for EMA comparison
-    let filtered_prob = sigmoid(kalman_logit);
-    let ema_fast = filtered_prob;  // Use filtered probability as "fast EMA"
-    let ema_slow = clamped_prob;   // Raw market midpoint as "slow EMA"
-    let velocity = filtered_prob - clamped_prob;  // Direction of movement
-
-    let momentum_allows_yes = velocity > -0.002 && ema_fast >= (ema_slow - 0.002);
-    let momentum_allows_no = velocity < 0.002 && ema_fast <= (ema_slow + 0.002);
-
-    // Step 9: Jump detection
-    let is_jump = if let Some(jc) = jump_calibrator {
-        let dt = 1.0 / (365.25 * 24.0 * 3600.0);
-        jc.is_jump(kalman_logit, dt, 2.0)
-    } else {
-        false
-    };
-
-    // Trading cost
-    let trading_cost = 0.025;
-
-    // ── Entry Logic ──
-    if !shadow.is_active() {
-        let effective_min_edge = min_edge * edge_multiplier;
-
-        // YES entry: calibrated edge + momentum + no extreme price + no jump
-        if edge_yes > effective_min_edge
-            && momentum_allows_yes
-            && !is_jump
-            && yes_ask < 0.95
-            && yes_ask > 0.05
-            && time_remaining > 30
-        {
-            shadow.token_side = Some(TokenSide::Yes);
-            shadow.active_entry = Some(EntrySignal::Long);
-            shadow.entry_price = yes_ask;
-            shadow.size = 1.0;
-            shadow.position_size_usd = position_size_usd;
-            shadow.bankroll_usd -= position_size_usd;
-            shadow.entry_timestamp = epoch_seconds;
-            if verbose {
-                println!("[FV ENTRY] YES @ {:.4} | Fair: {:.4} | Adj: {:.4} | Edge: {:.4}x{:.1} | Horizon: {} | Jump: {} | Bankroll: ${:.2}",
-                    yes_ask, fair_prob, adjusted_fair, edge_yes, edge_multiplier, horizon.name(), is_jump, shadow.bankroll_usd);
-            }
-        }
-        // NO entry
-        else if edge_no > effective_min_edge
-            && momentum_allows_no
-            && !is_jump
-            && no_ask < 0.95
-            && no_ask > 0.05
-            && time_remaining > 30
-        {
-            shadow.token_side = Some(TokenSide::No);
-            shadow.active_entry = Some(EntrySignal::Short);
-            shadow.entry_price = no_ask;
-            shadow.size = 1.0;
-            shadow.position_size_usd = position_size_usd;
-            shadow.bankroll_usd -= position_size_usd;
-            shadow.entry_timestamp = epoch_seconds;
-            if verbose {
-                println!("[FV ENTRY] NO @ {:.4} | Fair(YES): {:.4} | Adj: {:.4} | Edge: {:.4}x{:.1} | Horizon: {} | Jump: {} | Bankroll: ${:.2}",
-                    no_ask, fair_prob, adjusted_fair, edge_no, edge_multiplier, horizon.name(), is_jump, shadow.bankroll_usd);
-            }
-        }
-    }
-
-    // ── Exit Logic ──
-    if shadow.is_active() {
-        let exit_price = match shadow.token_side {
-            Some(TokenSide::Yes) => best_bid_price(&snapshot.yes),
-            Some(TokenSide::No) => best_bid_price(&snapshot.no),
-            _ => None,
-        };
-
-        let should_exit = if let Some(price) = exit_price {
-            let current_edge = match shadow.token_side {
-                Some(TokenSide::Yes) => adjusted_fair - price - trading_cost,
-                Some(TokenSide::No) => (1.0 - adjusted_fair) - price - trading_cost,
-                _ => 0.0,
-            };
-
-            // Exit conditions:
-            // 1. Edge reversed (model now disagrees with position)
-            let edge_reversed = current_edge < 0.0;
-            // 2. Extreme price (near resolution)
-            let extreme_price = price > 0.95 || price < 0.05;
-            // 3. Time almost up
-            let time_up = time_remaining < 30;
-            // 4. Jump detected while holding (adverse event)
-            let jump_exit = is_jump && current_edge < 0.02;
-
-            edge_reversed || extreme_price || time_up || jump_exit
-        } else {
-            false
-        };
-
-        if should_exit {
-            if let Some(price) = exit_price {
-                let pnl = shadow.pnl(price);
-                let dollar_pnl = pnl * shadow.position_size_usd;
-                shadow.bankroll_usd += shadow.position_size_usd + dollar_pnl;
-                metrics.trades_taken += 1;
-                if dollar_pnl >= 0.0 {
-                    metrics.wins += 1;
-                    cumulative_wins.push(dollar_pnl);
-                } else {
-                    metrics.losses += 1;
-                    cumulative_losses.push(dollar_pnl);
-                }
-                if verbose {
-                    let exit_edge = match shadow.token_side {
-                        Some(TokenSide::Yes) => adjusted_fair - price - trading_cost,
-                        Some(TokenSide::No) => (1.0 - adjusted_fair) - price - trading_cost,
-                        _ => 0.0,
-                    };
-                    println!("[FV EXIT] {:.4}% | ${:.4} | Edge: {:.4} | Bankroll: ${:.2}",
-                        pnl * 100.0, dollar_pnl, exit_edge, shadow.bankroll_usd);
-                }
-                shadow.reset(epoch_seconds);
-            }
-        }
-    }
-}
-
-/// Process a single snapshot using the Hawkes Flow Excitation strategy:
-/// 1. Build Observation from snapshot data
-/// 2. Feed to HawkesFlowEngine.decide()
-/// 3. Handle entry/exit based on StrategyDecision
-pub fn process_hawkesflow_snapshot(
-    engine: &mut HawkesFlowEngine,
-    snapshot: &DualSnapshot,
-    epoch_seconds: u64,
-    market_start_ts: i64,
-    market_end_ts: i64,
-    shadow: &mut ShadowPosition,
-    metrics: &mut PipelineMetrics,
-    position_size_usd: f64,
-    verbose: bool,
-    cumulative_wins: &mut Vec<f64>,
-    cumulative_losses: &mut Vec<f64>,
-) {
-    let yes_mid = midpoint_price(&snapshot.yes).unwrap_or(0.5);
-    let time_remaining = (market_end_ts - epoch_seconds as i64).max(0);
-
-    if time_remaining <= 0 {
-        return;
-    }
-
-    let yes_bid = best_bid_price(&snapshot.yes).unwrap_or(0.0);
-    let yes_ask = best_ask_price(&snapshot.yes).unwrap_or(1.0);
-    let no_bid = best_bid_price(&snapshot.no).unwrap_or(0.0);
-    let no_ask = best_ask_price(&snapshot.no).unwrap_or(1.0);
-    let book_sum = yes_ask + no_ask;
-
-    let obs = Observation {
-        ts: epoch_seconds as i64,
-        condition_id: String::new(),
-        market_slug: String::new(),
-        yes_bid,
-        yes_ask,
-        no_bid,
-        no_ask,
-        yes_mid,
-        no_mid: 1.0 - yes_mid,
-        book_sum,
-        time_remaining_s: time_remaining,
-        indicator_5s: IndicatorState {
-            bb_width: Some(yes_ask - yes_bid), // use spread as vol proxy
-            ..Default::default()
-        },
-        indicator_1m: IndicatorState::default(),
-        fair_value_prob: None,
-        qlib_score: None,
-    };
-
-    let decision = engine.decide(&obs);
-
-    // Debug: show entry/exit decisions
-    match &decision {
-        StrategyDecision::Enter { direction, .. } => {
-            println!("[HAWKES] ENTER {:?} | mid={:.4}", direction, yes_mid);
-        }
-        StrategyDecision::Exit { .. } => {
-            println!("[HAWKES] EXIT | mid={:.4}", yes_mid);
-        }
-        _ => {}
-    }
-
-    match decision {
-        StrategyDecision::Enter { direction, reason } => {
-            if !shadow.is_active() {
-                let (side, entry_price, entry_signal) = match direction {
-                    Direction::Yes => (TokenSide::Yes, yes_ask, EntrySignal::Long),
-                    Direction::No => (TokenSide::No, no_ask, EntrySignal::Short),
-                };
-                shadow.token_side = Some(side);
-                shadow.active_entry = Some(entry_signal);
-                shadow.entry_price = entry_price;
-                shadow.size = 1.0;
-                shadow.position_size_usd = position_size_usd;
-                shadow.bankroll_usd -= position_size_usd;
-                shadow.entry_timestamp = epoch_seconds;
-                if verbose {
-                    println!("[HAWKES ENTRY] {:?} @ {:.4} | {} | Bankroll: ${:.2}",
-                        direction, entry_price, reason.detail, shadow.bankroll_usd);
-                }
-            }
-        }
-        StrategyDecision::Exit { reason, .. } => {
-            if shadow.is_active() {
-                let exit_price = match shadow.token_side {
-                    Some(TokenSide::Yes) => best_bid_price(&snapshot.yes).unwrap_or(0.0),
-                    Some(TokenSide::No) => best_bid_price(&snapshot.no).unwrap_or(0.0),
-                    _ => 0.0,
-                };
-                let pnl = shadow.pnl(exit_price);
-                let dollar_pnl = pnl * shadow.position_size_usd;
-                shadow.bankroll_usd += shadow.position_size_usd + dollar_pnl;
-                metrics.trades_taken += 1;
-                if dollar_pnl >= 0.0 {
-                    metrics.wins += 1;
-                    cumulative_wins.push(dollar_pnl);
-                } else {
-                    metrics.losses += 1;
-                    cumulative_losses.push(dollar_pnl);
-                }
-                if verbose {
-                    println!("[HAWKES EXIT] {:.4}% | ${:.4} | Bankroll: ${:.2}",
-                        pnl * 100.0, dollar_pnl, shadow.bankroll_usd);
-                }
-                shadow.reset(epoch_seconds);
-            }
-        }
-        _ => {}
-    }
-}
-
 pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
     let start: DateTime<Utc> = args.start.parse().context("Invalid start time format")?;
     let end: DateTime<Utc> = args.end.parse().context("Invalid end time format")?;
@@ -1484,7 +1239,12 @@ pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
         .transpose()
         .context("Failed to create pipeline event logs")?;
 
+    let strategy = args.strategy.config()?.deterministic();
+    let mut driver = StrategyDriver::new(&strategy);
+    let sizer = PositionSizer::new(args.size, &args.sizing);
+
     println!("[PIPELINE] Backtest Pipeline: {} to {}", start, end);
+    println!("[PIPELINE] Strategy: {} ({}) | Size: {}", strategy.kind(), strategy.describe(), sizer.describe());
     let inputs = resolve_pipeline_inputs(args.input.as_deref(), start, end);
     
     let conn = duckdb::Connection::open_in_memory()?;
@@ -1513,18 +1273,13 @@ pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
             if !processed_markets.insert(market.condition_id.clone()) { continue; }
 
             metrics.total_markets += 1;
-            let mut candle_engine = CandleEngine::new();
-            let mut ind_1m = IndicatorEngine::new();
-            let mut ind_5s = IndicatorEngine::new();
-            let mut signal_engine = SignalEngine::new_with_band(args.band_low, args.band_high);
-            let mut state_1m = IndicatorState::default();
-            let mut state_5s = IndicatorState::default();
+            driver.reset();
             let mut gatekeeper = GatekeeperState::new(args.size * 3.0, 15);
             shadow.full_reset();
 
             let tick_sql = format!(
-                "SELECT COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, COALESCE(UPPER(data->>'$.side'), '') as side, COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask FROM read_parquet('{}') WHERE market_id = '{}' ORDER BY ts",
-                input, market.condition_id
+                "SELECT COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, COALESCE(UPPER(data->>'$.side'), '') as side, COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask FROM read_parquet({}) WHERE market_id = '{}' ORDER BY ts",
+                sql_literal(input), market.condition_id
             );
             let mut stmt = conn.prepare(&tick_sql)?;
             let rows = stmt.query_map([], |row| {
@@ -1533,6 +1288,7 @@ pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
                     side: row.get::<_, String>(1)?,
                     bid: row.get::<_, f64>(2)?,
                     ask: row.get::<_, f64>(3)?,
+                    depth: None,
                 })
             })?;
 
@@ -1565,16 +1321,11 @@ pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
                     &market,
                     &snapshot,
                     epoch_seconds,
-                    &mut candle_engine,
-                    &mut ind_1m,
-                    &mut ind_5s,
-                    &mut signal_engine,
-                    &mut state_1m,
-                    &mut state_5s,
+                    &mut driver,
+                    &sizer,
                     &mut shadow,
                     &mut gatekeeper,
                     &mut metrics,
-                    args.size,
                     args.verbose,
                     event_loggers.as_ref(),
                 );
@@ -1617,57 +1368,396 @@ pub async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
     Ok(())
 }
 
-/// Read a CSV recording file and group rows by market_slug.
-/// Returns a map of market_slug -> Vec<ReplayRow>.
-fn read_csv_recordings(path: &str) -> Result<Vec<(String, Vec<ReplayRow>)>> {
-    use std::collections::HashMap;
-
-    let mut reader = csv::Reader::from_path(path)?;
-    let mut markets: HashMap<String, Vec<ReplayRow>> = HashMap::new();
-
-    for result in reader.records() {
-        let record = result?;
-        // Columns: timestamp, market_slug, yes_bid, yes_ask, no_bid, no_ask, time_remaining
-        let timestamp: f64 = record.get(0).unwrap_or("0").parse().unwrap_or(0.0);
-        let market_slug = record.get(1).unwrap_or("unknown").to_string();
-        let yes_bid: f64 = record.get(2).unwrap_or("0").parse().unwrap_or(0.0);
-        let yes_ask: f64 = record.get(3).unwrap_or("0").parse().unwrap_or(0.0);
-        let no_bid: f64 = record.get(4).unwrap_or("0").parse().unwrap_or(0.0);
-        let no_ask: f64 = record.get(5).unwrap_or("0").parse().unwrap_or(0.0);
-
-        // Add YES side tick
-        markets.entry(market_slug.clone()).or_default().push(ReplayRow {
-            ts: timestamp,
-            side: "YES".to_string(),
-            bid: yes_bid,
-            ask: yes_ask,
-        });
-        // Add NO side tick
-        markets.entry(market_slug).or_default().push(ReplayRow {
-            ts: timestamp,
-            side: "NO".to_string(),
-            bid: no_bid,
-            ask: no_ask,
-        });
+/// Bankroll, metrics and trade PnL carried across the markets of one backtest
+/// run, shared by the PMXT and recording entry points.
+pub struct BacktestSession {
+    strategy: StrategyConfig,
+    sizer: PositionSizer,
+    verbose: bool,
+    pub metrics: PipelineMetrics,
+    shadow: ShadowPosition,
+    cumulative_wins: Vec<f64>,
+    cumulative_losses: Vec<f64>,
+    bankroll_history: Vec<(String, f64)>, // (slug, bankroll after market)
+    fill_model: FillModel,
+    fill_stats: FillStats,
+    /// Shares an exit could not sell, redeemed at resolution: (side, shares, entry price)
+    unsold: Vec<(TokenSide, f64, f64)>,
+    /// Model probability of the entered side against whether it resolved in the money
+    forecasts: Vec<(f64, f64)>,
+    /// Every closed trade in order, for trade-level analysis of the export
+    trades: Vec<TradeResult>,
+    /// Fair value predictions of entries, for `bot calibration-report`
+    predictions: Option<PredictionLog>,
+}
+
+/// Headline numbers of a finished session, for comparing runs
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SessionSummary {
+    pub markets: usize,
+    pub trades: usize,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub total_pnl_pct: f64,
+    pub max_drawdown: f64,
+    /// Mean over standard deviation of per-market returns, not annualized
+    pub sharpe: f64,
+    /// Brier score of the entered side's calibrated probability; `None`
+    /// without entries priced by a model
+    pub brier: Option<f64>,
+}
+
+fn side_label(side: TokenSide) -> &'static str {
+    match side {
+        TokenSide::Yes => "YES",
+        TokenSide::No => "NO",
+    }
+}
+
+impl BacktestSession {
+    pub fn new(
+        strategy: StrategyConfig,
+        capital: f64,
+        sizer: PositionSizer,
+        fill_model: FillModel,
+        verbose: bool,
+    ) -> Self {
+        let mut shadow = ShadowPosition::default();
+        shadow.bankroll_usd = capital;
+        Self {
+            strategy: strategy.deterministic(),
+            sizer,
+            verbose,
+            metrics: PipelineMetrics::new(capital),
+            shadow,
+            cumulative_wins: Vec::new(),
+            cumulative_losses: Vec::new(),
+            bankroll_history: Vec::new(),
+            fill_model,
+            fill_stats: FillStats::default(),
+            unsold: Vec::new(),
+            forecasts: Vec::new(),
+            trades: Vec::new(),
+            predictions: None,
+        }
     }
 
-    // Sort each market's ticks by timestamp
-    for rows in markets.values_mut() {
-        rows.sort_by(|a, b| a.ts.partial_cmp(&b.ts).unwrap_or(std::cmp::Ordering::Equal));
+    /// Append the fair value prediction of every entry to `log`
+    pub fn with_predictions(mut self, log: PredictionLog) -> Self {
+        self.predictions = Some(log);
+        self
     }
 
-    let result: Vec<(String, Vec<ReplayRow>)> = markets.into_iter().collect();
-    Ok(result)
+    /// Replay one market with fresh indicator/signal state and settle any
+    /// position still open at the end. `winner` is the resolved outcome when
+    /// known; otherwise the held side wins if its last bid is above 0.5.
+    async fn run_market(
+        &mut self,
+        market: &DiscoveredMarket,
+        rows: &[ReplayRow],
+        winner: Option<TokenSide>,
+    ) -> Result<()> {
+        self.metrics.total_markets += 1;
+        let snapshots = build_replay_snapshots(rows, ReplayMode::EventByEvent);
+        let mut book = (!self.fill_model.is_frictionless()).then(|| build_book_replay(rows));
+
+        let mut driver = StrategyDriver::new(&self.strategy);
+        let mut gatekeeper = GatekeeperState::new(self.sizer.max_stake(self.shadow.bankroll_usd) * 3.0, 15);
+        self.shadow.full_reset();
+
+        let mut replay_source = ReplaySnapshotSource::new(snapshots);
+        let mut last_yes_bid = 0.0;
+        let mut last_no_bid = 0.0;
+        let mut entries: Vec<(TokenSide, f64)> = Vec::new();
+        let mut predictions = Vec::new();
+
+        while let Some(snapshot) = replay_source.next_snapshot().await? {
+            last_yes_bid = snapshot.yes.best_bid.map(decimal_to_f64).unwrap_or(last_yes_bid);
+            last_no_bid = snapshot.no.best_bid.map(decimal_to_f64).unwrap_or(last_no_bid);
+            let epoch_seconds = replay_source
+                .current_time()
+                .unwrap_or_else(|| snapshot.ts_exchange.floor() as u64);
+            let open_before = self.shadow.token_side;
+
+            self.metrics.total_ticks += 1;
+            let mut fills = BookFills::new(&self.fill_model, book.as_mut(), snapshot.ts_exchange, &mut self.fill_stats);
+            if let Some(step) = run_driver_shadow_step(
+                &mut driver,
+                &snapshot,
+                &market.slug,
+                &market.slug,
+                market.start_ts,
+                market.end_ts,
+                epoch_seconds,
+                &self.sizer,
+                &mut self.shadow,
+                &mut gatekeeper,
+                &mut fills,
+                None,
+            ) {
+                if step.entry_blocked && self.verbose {
+                    println!("[PIPELINE] blocked entry {}", market.condition_id);
+                }
+                if let Some(exit_trade) = step.exit_trade {
+                    if let Some(held) = open_before.filter(|_| exit_trade.unsold_shares > 1e-9) {
+                        self.unsold.push((held, exit_trade.unsold_shares, exit_trade.entry_price));
+                    }
+                    self.metrics.trades_taken += 1;
+                    self.record_trade(TradeResult {
+                        market_slug: market.slug.clone(),
+                        side: exit_trade.side,
+                        entry_price: exit_trade.entry_price,
+                        exit_price: exit_trade.exit_price,
+                        pnl_percent: exit_trade.pnl_pct,
+                        pnl_usd: exit_trade.pnl_usd,
+                        duration_seconds: exit_trade.duration,
+                        entry_timestamp: epoch_seconds as i64 - exit_trade.duration,
+                        exit_timestamp: epoch_seconds as i64,
+                    });
+                    update_pipeline_capital_metrics(&mut self.metrics, self.shadow.bankroll_usd);
+                }
+            }
+
+            let prediction = driver.take_prediction();
+            if let (None, Some(side)) = (open_before, self.shadow.token_side) {
+                entries.extend(driver.entry_quote().and_then(|quote| quote.fair_prob).map(|p| (side, p)));
+                predictions.extend(prediction);
+            }
+        }
+
+        let settlement = |held: TokenSide| match winner {
+            Some(side) => if side == held { 1.0 } else { 0.0 },
+            None => {
+                let side_bid = if held == TokenSide::Yes { last_yes_bid } else { last_no_bid };
+                // Binary resolution: if the held side's last bid > 0.5, it won (1.0), else lost (0.0)
+                if side_bid > 0.5 { 1.0 } else { 0.0 }
+            }
+        };
+
+        // Settle any open position at end of market
+        if let Some(held) = self.shadow.token_side {
+            let stake = self.shadow.position_size_usd;
+            let gross = self.shadow.pnl(settlement(held)) * stake;
+            self.shadow.bankroll_usd += stake + gross;
+            self.metrics.trades_taken += 1;
+            let pnl = gross - self.shadow.entry_fee;
+            self.record_trade(TradeResult {
+                market_slug: market.slug.clone(),
+                side: side_label(held).to_string(),
+                entry_price: self.shadow.entry_price,
+                exit_price: settlement(held),
+                pnl_percent: pnl / stake.max(1e-9),
+                pnl_usd: pnl,
+                duration_seconds: market.end_ts - self.shadow.entry_timestamp as i64,
+                entry_timestamp: self.shadow.entry_timestamp as i64,
+                exit_timestamp: market.end_ts,
+            });
+            self.shadow.reset(market.end_ts as u64);
+        }
+        self.forecasts.extend(entries.into_iter().map(|(side, fair_prob)| (fair_prob, settlement(side))));
+        if let Some(log) = &mut self.predictions {
+            for prediction in predictions {
+                let mut prediction = prediction.with_market_duration(market.end_ts - market.start_ts);
+                prediction.condition_id = Some(market.condition_id.clone());
+                // Unknown winners stay open for the report to resolve
+                prediction.outcome = winner.map(|side| side == TokenSide::Yes);
+                if let Err(err) = log.append(&prediction) {
+                    eprintln!("[warn] Failed to write prediction: {err:#}");
+                }
+            }
+        }
+        for (side, shares, entry_price) in std::mem::take(&mut self.unsold) {
+            let value = shares * settlement(side);
+            self.shadow.bankroll_usd += value;
+            self.metrics.trades_taken += 1;
+            let pnl = value - shares * entry_price;
+            self.record_trade(TradeResult {
+                market_slug: market.slug.clone(),
+                side: side_label(side).to_string(),
+                entry_price,
+                exit_price: settlement(side),
+                pnl_percent: pnl / (shares * entry_price).max(1e-9),
+                pnl_usd: pnl,
+                exit_timestamp: market.end_ts,
+                ..Default::default()
+            });
+        }
+
+        // Track bankroll
+        self.bankroll_history.push((market.slug.clone(), self.shadow.bankroll_usd));
+
+        // Update peak/drawdown
+        if self.shadow.bankroll_usd > self.metrics.peak_capital {
+            self.metrics.peak_capital = self.shadow.bankroll_usd;
+        }
+        let drawdown = (self.metrics.peak_capital - self.shadow.bankroll_usd) / self.metrics.peak_capital;
+        if drawdown > self.metrics.max_drawdown {
+            self.metrics.max_drawdown = drawdown;
+        }
+
+        if self.verbose {
+            println!("  {} | Bankroll: ${:.2} | Ticks: {}", market.slug, self.shadow.bankroll_usd, market.ticks);
+        }
+        Ok(())
+    }
+
+    fn record_trade(&mut self, trade: TradeResult) {
+        let pnl = trade.pnl_usd;
+        if pnl >= 0.0 {
+            self.metrics.wins += 1;
+            self.cumulative_wins.push(pnl);
+        } else {
+            self.metrics.losses += 1;
+            self.cumulative_losses.push(pnl);
+        }
+        self.trades.push(trade);
+    }
+
+    pub fn summary(&self) -> SessionSummary {
+        let starting = self.metrics.starting_capital;
+        let mut previous = starting;
+        let returns: Vec<f64> = self
+            .bankroll_history
+            .iter()
+            .map(|(_, bankroll)| {
+                let r = if previous > 0.0 { bankroll / previous - 1.0 } else { 0.0 };
+                previous = *bankroll;
+                r
+            })
+            .collect();
+        let n = returns.len() as f64;
+        let sharpe = if returns.len() > 1 {
+            let mean = returns.iter().sum::<f64>() / n;
+            let std = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            if std > 1e-12 { mean / std } else { 0.0 }
+        } else {
+            0.0
+        };
+        let brier = (!self.forecasts.is_empty()).then(|| {
+            self.forecasts.iter().map(|(p, o)| (p - o).powi(2)).sum::<f64>() / self.forecasts.len() as f64
+        });
+        let total_pnl = self.shadow.bankroll_usd - starting;
+        SessionSummary {
+            markets: self.metrics.total_markets,
+            trades: self.metrics.trades_taken,
+            win_rate: if self.metrics.trades_taken > 0 {
+                self.metrics.wins as f64 / self.metrics.trades_taken as f64
+            } else {
+                0.0
+            },
+            total_pnl,
+            total_pnl_pct: if starting > 0.0 { total_pnl / starting * 100.0 } else { 0.0 },
+            max_drawdown: self.metrics.max_drawdown,
+            sharpe,
+            brier,
+        }
+    }
+
+    /// Print the summary and optionally export it as JSON
+    pub fn finish(mut self, title: &str, files_processed: usize, export: Option<&str>) -> Result<()> {
+        let metrics = &mut self.metrics;
+        metrics.ending_capital = self.shadow.bankroll_usd;
+        metrics.total_pnl = metrics.ending_capital - metrics.starting_capital;
+        metrics.total_pnl_pct = (metrics.total_pnl / metrics.starting_capital) * 100.0;
+
+        // Print enhanced summary
+        println!("\n================ {} SUMMARY ================", title);
+        println!("Files Processed:    {}", files_processed);
+        println!("Markets Processed:  {}", metrics.total_markets);
+        println!("Total Ticks:        {}", metrics.total_ticks);
+        println!("Trades Taken:       {}", metrics.trades_taken);
+        let win_rate = if metrics.trades_taken > 0 {
+            metrics.wins as f64 / metrics.trades_taken as f64 * 100.0
+        } else { 0.0 };
+        println!("Wins/Losses:        {} / {} ({:.1}%)", metrics.wins, metrics.losses, win_rate);
+
+        let avg_win = if !self.cumulative_wins.is_empty() {
+            self.cumulative_wins.iter().sum::<f64>() / self.cumulative_wins.len() as f64
+        } else { 0.0 };
+        let avg_loss = if !self.cumulative_losses.is_empty() {
+            self.cumulative_losses.iter().sum::<f64>() / self.cumulative_losses.len() as f64
+        } else { 0.0 };
+        let profit_factor = if avg_loss.abs() > 0.0001 {
+            avg_win / avg_loss.abs()
+        } else if avg_win > 0.0 { f64::INFINITY } else { 0.0 };
+
+        println!("Avg Win:            ${:.4}", avg_win);
+        println!("Avg Loss:           ${:.4}", avg_loss);
+        println!("Profit Factor:      {:.2}", profit_factor);
+        println!("------------------------------------------------------");
+        println!("Starting Capital:   ${:.2}", metrics.starting_capital);
+        println!("Ending Capital:     ${:.2}", metrics.ending_capital);
+        println!("Total PnL:          ${:.2} ({:.2}%)", metrics.total_pnl, metrics.total_pnl_pct);
+        println!("Max Drawdown:       {:.2}%", metrics.max_drawdown * 100.0);
+        if !self.fill_model.is_frictionless() {
+            println!("------------------------------------------------------");
+            println!("Fill Model:         {:?} (+{}ms latency)", self.fill_model.kind, self.fill_model.latency_ms);
+            println!("Fees Paid:          ${:.4}", self.fill_stats.fees_paid);
+            println!("Partial Fills:      {}", self.fill_stats.partial_fills);
+            println!("Missed Entries:     {}", self.fill_stats.missed_entries);
+        }
+        println!("======================================================");
+
+        if let Some(path) = export {
+            #[derive(Serialize)]
+            struct ExportResult {
+                files_processed: usize,
+                markets_processed: usize,
+                total_ticks: usize,
+                trades_taken: usize,
+                wins: usize,
+                losses: usize,
+                win_rate: f64,
+                avg_win: f64,
+                avg_loss: f64,
+                profit_factor: f64,
+                starting_capital: f64,
+                ending_capital: f64,
+                total_pnl: f64,
+                total_pnl_pct: f64,
+                max_drawdown: f64,
+                bankroll_history: Vec<(String, f64)>,
+                fills: FillStats,
+                trades: Vec<TradeResult>,
+            }
+            let result = ExportResult {
+                files_processed,
+                markets_processed: metrics.total_markets,
+                total_ticks: metrics.total_ticks,
+                trades_taken: metrics.trades_taken,
+                wins: metrics.wins,
+                losses: metrics.losses,
+                win_rate,
+                avg_win,
+                avg_loss,
+                profit_factor,
+                starting_capital: metrics.starting_capital,
+                ending_capital: metrics.ending_capital,
+                total_pnl: metrics.total_pnl,
+                total_pnl_pct: metrics.total_pnl_pct,
+                max_drawdown: metrics.max_drawdown,
+                bankroll_history: self.bankroll_history,
+                fills: self.fill_stats,
+                trades: self.trades,
+            };
+            let file = std::fs::File::create(path)?;
+            serde_json::to_writer_pretty(file, &result)?;
+            println!("[EXPORT] Results written to {}", path);
+        }
+
+        Ok(())
+    }
 }
 
-pub async fn run_backtest_pmxt(args: BacktestPmxtArgs) -> Result<()> {
+/// PMXT parquet hours and recorded CSV sessions in `input_dir` as
+/// (path, sort key, is_csv), oldest first
+fn pmxt_input_files(input_dir: &str) -> Result<Vec<(String, i64, bool)>> {
     use std::path::Path;
-    use std::collections::HashSet;
 
     // Collect all parquet files from directory
-    let dir = Path::new(&args.input_dir);
+    let dir = Path::new(input_dir);
     if !dir.is_dir() {
-        anyhow::bail!("Not a directory: {}", args.input_dir);
+        anyhow::bail!("Not a directory: {}", input_dir);
     }
 
     let mut input_files: Vec<(String, i64, bool)> = Vec::new(); // (path, sort_key, is_csv)
@@ -1695,473 +1785,241 @@ pub async fn run_backtest_pmxt(args: BacktestPmxtArgs) -> Result<()> {
     }
 
     if input_files.is_empty() {
-        anyhow::bail!("No parquet or CSV files found in {}", args.input_dir);
+        anyhow::bail!("No parquet or CSV files found in {}", input_dir);
     }
 
     // Sort by timestamp
     input_files.sort_by_key(|(_, ts, _)| *ts);
-    let parquet_count = input_files.iter().filter(|(_, _, csv)| !csv).count();
-    let csv_count = input_files.iter().filter(|(_, _, csv)| *csv).count();
-    println!("[BACKTEST-PMXT] Found {} files ({} parquet, {} CSV) (sorted chronologically)",
-        input_files.len(), parquet_count, csv_count);
-    println!("[BACKTEST-PMXT] Strategy: {:?}", args.strategy);
-    println!("[BACKTEST-PMXT] Starting capital: ${:.2}", args.capital);
+    Ok(input_files)
+}
 
-    // Strategy config
-    let (band_low, band_high) = match args.strategy {
-        BtStrategy::Scalper => (args.band_low, args.band_high),
-        BtStrategy::LateWindow => (0.85, 0.98),
-        BtStrategy::FairValue => (0.05, 0.95), // wide band, model filters instead
-        BtStrategy::HawkesFlow => (0.05, 0.95), // wide band, flow model filters instead
-    };
+/// Input files of a PMXT backtest: the catalogued hours in range with
+/// --catalog, otherwise everything in --input-dir
+fn pmxt_inputs(args: &BacktestPmxtArgs) -> Result<Vec<(String, i64, bool)>> {
+    match (&args.catalog, &args.input_dir) {
+        (Some(data_dir), _) => catalog::catalog_input_files(args, data_dir),
+        (None, Some(input_dir)) => pmxt_input_files(input_dir),
+        (None, None) => anyhow::bail!("Pass --input-dir or --catalog"),
+    }
+}
 
-    // Setup DuckDB
+/// Markets of one PMXT file with their real slugs and windows: from the
+/// catalog index of `catalog` when given, else from market metadata. Only
+/// markets of `durations` are kept unless it is empty.
+pub async fn resolve_pmxt_markets(
+    input: &str,
+    catalog: Option<&str>,
+    crypto: CryptoAsset,
+    durations: &[SupportedDuration],
+    verbose: bool,
+) -> Result<Vec<DiscoveredMarket>> {
     let conn = duckdb::Connection::open_in_memory()?;
-    conn.execute_batch("INSTALL httpfs; LOAD httpfs; PRAGMA threads=4;")?;
-
-    // Global state (persists across files)
-    let mut metrics = PipelineMetrics::new(args.capital);
-    let mut shadow = ShadowPosition::default();
-    shadow.bankroll_usd = args.capital;
-    let mut processed_markets: HashSet<String> = HashSet::new();
-    let mut file_num = 0;
-    let mut cumulative_wins: Vec<f64> = Vec::new();
-    let mut cumulative_losses: Vec<f64> = Vec::new();
-    let mut bankroll_history: Vec<(String, f64)> = Vec::new(); // (slug, bankroll after market)
-
-    for (file_path, file_ts, is_csv) in &input_files {
-        file_num += 1;
-        let label = if *is_csv {
-            std::path::Path::new(file_path)
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| file_path.clone())
-        } else {
-            chrono::DateTime::from_timestamp(*file_ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
-                .unwrap_or_else(|| format!("ts={}", file_ts))
-        };
-
-        println!("\n[FILE {}/{}] {} — {}", file_num, input_files.len(), label, file_path);
-
-        if *is_csv {
-            // CSV recording file: read directly, group by market
-            let csv_markets = match read_csv_recordings(file_path) {
-                Ok(m) => m,
-                Err(e) => {
-                    eprintln!("  [WARN] Failed to read CSV {}: {}", file_path, e);
-                    continue;
-                }
-            };
+    match catalog {
+        Some(data_dir) => {
+            catalog::attach_catalog(&conn, data_dir)?;
+            catalog::catalog_markets(&conn, input, crypto, durations, "", 1)
+        }
+        None => {
+            let mut markets = discover_markets_for_input(&conn, input, 1, crypto, None, verbose).await?;
+            markets.retain(|m| {
+                durations.is_empty()
+                    || SupportedDuration::from_slug(&m.slug).is_some_and(|d| durations.contains(&d))
+            });
+            Ok(markets)
+        }
+    }
+}
 
-            for (market_slug, replay_rows) in csv_markets {
-                if !processed_markets.insert(market_slug.clone()) {
-                    continue;
-                }
+/// In-memory DuckDB for reading PMXT inputs, with the catalog attached when
+/// the backtest reads from one
+fn pmxt_connection(args: &BacktestPmxtArgs) -> Result<duckdb::Connection> {
+    let conn = duckdb::Connection::open_in_memory()?;
+    conn.execute_batch("INSTALL httpfs; LOAD httpfs; PRAGMA threads=4;")?;
+    if let Some(data_dir) = &args.catalog {
+        catalog::attach_catalog(&conn, data_dir)?;
+    }
+    Ok(conn)
+}
 
-                metrics.total_markets += 1;
+/// Ticks of one market in a PMXT file, in time order. Level data is only
+/// read `with_depth`, since it is what makes the query slow.
+pub(crate) fn load_market_rows(
+    conn: &duckdb::Connection,
+    file_path: &str,
+    condition_id: &str,
+    with_depth: bool,
+) -> Result<Vec<ReplayRow>> {
+    let depth_columns = if with_depth {
+        "data->>'$.bids', data->>'$.asks', \
+         TRY_CAST(data->>'$.change_price' AS DOUBLE), TRY_CAST(data->>'$.change_size' AS DOUBLE), \
+         UPPER(data->>'$.change_side')"
+    } else {
+        "NULL, NULL, NULL::DOUBLE, NULL::DOUBLE, NULL"
+    };
+    let tick_sql = format!(
+        "SELECT COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, \
+                COALESCE(UPPER(data->>'$.side'), '') as side, \
+                COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, \
+                COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask, \
+                {} \
+         FROM read_parquet({}) WHERE market_id = '{}' ORDER BY ts",
+        depth_columns, sql_literal(file_path), condition_id
+    );
 
-                // Estimate start/end times from the data (CSV timestamps are in milliseconds)
-                let min_ts = replay_rows.iter().map(|r| r.ts).fold(f64::INFINITY, f64::min);
-                let max_ts = replay_rows.iter().map(|r| r.ts).fold(f64::NEG_INFINITY, f64::max);
-                let min_ts_s = min_ts / 1000.0; // ms to s
-                let start_ts = (min_ts_s.floor() as i64) - ((min_ts_s.floor() as i64) % 300);
-                let end_ts = start_ts + 300;
+    let mut stmt = conn.prepare(&tick_sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ReplayRow {
+            ts: row.get::<_, f64>(0)?,
+            side: row.get::<_, String>(1)?,
+            bid: row.get::<_, f64>(2)?,
+            ask: row.get::<_, f64>(3)?,
+            depth: pmxt_depth_update(row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?),
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
 
-                let discovered = DiscoveredMarket {
-                    condition_id: market_slug.clone(),
-                    slug: market_slug.clone(),
-                    question: format!("Recorded: {}", market_slug),
-                    start_ts,
-                    end_ts,
-                    ticks: replay_rows.len() as i64,
-                    min_ts,
-                    max_ts,
-                };
+/// Markets of one PMXT input with their ticks, in start order. Failures are
+/// reported and skip the market (or the file) rather than the run.
+async fn load_pmxt_input(
+    conn: &duckdb::Connection,
+    file_path: &str,
+    is_csv: bool,
+    args: &BacktestPmxtArgs,
+    with_depth: bool,
+) -> Vec<(DiscoveredMarket, Vec<ReplayRow>)> {
+    if is_csv {
+        // CSV recording file: read directly, group by market
+        return match read_csv_recordings(file_path) {
+            Ok(markets) => markets.into_iter().map(|m| (m.discovered(), m.rows)).collect(),
+            Err(e) => {
+                eprintln!("  [WARN] Failed to read CSV {}: {}", file_path, e);
+                Vec::new()
+            }
+        };
+    }
 
-                // Fresh state per market
-                let mut candle_engine = CandleEngine::new();
-                let mut ind_1m = IndicatorEngine::new();
-                let mut ind_5s = IndicatorEngine::new();
-                let mut signal_engine = SignalEngine::new_with_band(band_low, band_high);
-                let mut state_1m = IndicatorState::default();
-                let mut state_5s = IndicatorState::default();
-                let mut gatekeeper = GatekeeperState::new(args.size * 3.0, 15);
-                shadow.full_reset();
-                let mut fv_model = LogitJumpDiffusion::new();
-                let mut fv_kalman = Some(KalmanFilter::new(0.0));
-                let mut fv_jump_calibrator = Some(JumpCalibrator::with_defaults());
-                let mut fv_calibrated = {
-                    let base_model = FairValueModel::default();
-                    CalibratedFairValue::with_defaults(base_model)
-                };
-                let mut hawkesflow_engine = HawkesFlowEngine::with_config(HawkesFlowConfig::default());
-
-                let snapshots = build_replay_snapshots(&replay_rows, ReplayMode::EventByEvent);
-                let mut replay_source = ReplaySnapshotSource::new(snapshots);
-
-                let mut last_yes_bid = 0.0;
-                let mut last_no_bid = 0.0;
-
-                while let Some(snapshot) = replay_source.next_snapshot().await? {
-                    last_yes_bid = snapshot.yes.best_bid.map(decimal_to_f64).unwrap_or(last_yes_bid);
-                    last_no_bid = snapshot.no.best_bid.map(decimal_to_f64).unwrap_or(last_no_bid);
-                    let epoch_seconds = (replay_source
-                        .current_time()
-                        .unwrap_or_else(|| snapshot.ts_exchange.floor() as u64)) / 1000; // ms to s
-
-                    if args.strategy == BtStrategy::FairValue {
-                        process_fairvalue_snapshot(
-                            &mut fv_model,
-                            &mut fv_kalman,
-                            &mut fv_jump_calibrator,
-                            &mut fv_calibrated,
-                            &snapshot,
-                            epoch_seconds,
-                            discovered.start_ts,
-                            discovered.end_ts,
-                            &mut shadow,
-                            &mut metrics,
-                            args.size,
-                            args.min_edge,
-                            args.verbose,
-                            &mut cumulative_wins,
-                            &mut cumulative_losses,
-                        );
-                    } else if args.strategy == BtStrategy::HawkesFlow {
-                        process_hawkesflow_snapshot(
-                            &mut hawkesflow_engine,
-                            &snapshot,
-                            epoch_seconds,
-                            discovered.start_ts,
-                            discovered.end_ts,
-                            &mut shadow,
-                            &mut metrics,
-                            args.size,
-                            args.verbose,
-                            &mut cumulative_wins,
-                            &mut cumulative_losses,
-                        );
-                    } else {
-                        if let Some(step) = process_pipeline_snapshot(
-                            &discovered, &snapshot, epoch_seconds,
-                            &mut candle_engine, &mut ind_1m, &mut ind_5s,
-                            &mut signal_engine, &mut state_1m, &mut state_5s,
-                            &mut shadow, &mut gatekeeper, &mut metrics,
-                            args.size, args.verbose, None,
-                        ) {
-                            if let Some(exit_trade) = step.exit_trade {
-                                if exit_trade.pnl_usd >= 0.0 { cumulative_wins.push(exit_trade.pnl_usd); }
-                                else { cumulative_losses.push(exit_trade.pnl_usd); }
-                            }
-                        }
-                    }
-                }
+    // Parquet file: markets from the catalog index, or DuckDB + Gamma API discovery
+    let discovered = match &args.catalog {
+        Some(_) => catalog::catalog_markets(conn, file_path, args.crypto, &args.durations, &args.filter, args.min_ticks),
+        None => discover_markets_for_input(conn, file_path, args.min_ticks, args.crypto, Some(&args.filter), args.verbose).await,
+    };
+    let discovered = match discovered {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("  [WARN] Discovery failed for {}: {}", file_path, e);
+            return Vec::new();
+        }
+    };
 
-                // Settle open position
-                if shadow.is_active() {
-                    let side_bid = if shadow.token_side == Some(TokenSide::Yes) { last_yes_bid } else { last_no_bid };
-                    let settlement = if side_bid > 0.5 { 1.0 } else { 0.0 };
-                    let pnl = shadow.pnl(settlement) * shadow.position_size_usd;
-                    shadow.bankroll_usd += shadow.position_size_usd + pnl;
-                    metrics.trades_taken += 1;
-                    if pnl >= 0.0 { metrics.wins += 1; cumulative_wins.push(pnl); }
-                    else { metrics.losses += 1; cumulative_losses.push(pnl); }
-                    shadow.reset(end_ts as u64);
-                }
+    if discovered.is_empty() {
+        println!("  No markets found in this file");
+        return Vec::new();
+    }
 
-                bankroll_history.push((market_slug.clone(), shadow.bankroll_usd));
-                if shadow.bankroll_usd > metrics.peak_capital { metrics.peak_capital = shadow.bankroll_usd; }
-                let dd = (metrics.peak_capital - shadow.bankroll_usd) / metrics.peak_capital;
-                if dd > metrics.max_drawdown { metrics.max_drawdown = dd; }
+    // Sort markets by start time
+    let mut sorted_markets = discovered;
+    sorted_markets.sort_by_key(|m| m.start_ts);
 
-                if args.verbose {
-                    println!("  {} | Bankroll: ${:.2} | Ticks: {}", market_slug, shadow.bankroll_usd, replay_rows.len());
-                }
-            }
-        } else {
-            // Parquet file: use DuckDB + Gamma API discovery
-            let discovered = match discover_markets_for_input(&conn, file_path, args.min_ticks, args.crypto, Some(&args.filter), args.verbose).await {
-            Ok(d) => d,
+    let mut loaded = Vec::new();
+    for market in sorted_markets {
+        let replay_rows = match load_market_rows(conn, file_path, &market.condition_id, with_depth) {
+            Ok(rows) => rows,
             Err(e) => {
-                eprintln!("  [WARN] Discovery failed for {}: {}", file_path, e);
+                eprintln!("  [WARN] Tick query failed for {}: {}", market.slug, e);
                 continue;
             }
         };
 
-        if discovered.is_empty() {
-            println!("  No markets found in this file");
-            continue;
+        if !replay_rows.is_empty() {
+            loaded.push((market, replay_rows));
         }
+    }
+    loaded
+}
 
-        // Sort markets by start time
-        let mut sorted_markets = discovered;
-        sorted_markets.sort_by_key(|m| m.start_ts);
-
-        for market in sorted_markets {
-            if !processed_markets.insert(market.condition_id.clone()) {
-                continue;
-            }
+fn pmxt_file_label(file_path: &str, sort_key: i64, is_csv: bool) -> String {
+    if is_csv {
+        std::path::Path::new(file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string())
+    } else {
+        chrono::DateTime::from_timestamp(sort_key, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| format!("ts={}", sort_key))
+    }
+}
 
-            metrics.total_markets += 1;
+pub async fn run_backtest_pmxt(args: BacktestPmxtArgs) -> Result<()> {
+    use std::collections::HashSet;
 
-            // Fresh indicator/signal state per market
-            let mut candle_engine = CandleEngine::new();
-            let mut ind_1m = IndicatorEngine::new();
-            let mut ind_5s = IndicatorEngine::new();
-            let mut signal_engine = SignalEngine::new_with_band(band_low, band_high);
-            let mut state_1m = IndicatorState::default();
-            let mut state_5s = IndicatorState::default();
-            let mut gatekeeper = GatekeeperState::new(args.size * 3.0, 15);
-            shadow.full_reset();
-            let mut fv_model = LogitJumpDiffusion::new();
-            let mut fv_kalman = Some(KalmanFilter::new(0.0));
-            let mut fv_jump_calibrator = Some(JumpCalibrator::with_defaults());
-            let mut fv_calibrated = {
-                let base_model = FairValueModel::default();
-                CalibratedFairValue::with_defaults(base_model)
-            };
-            let mut hawkesflow_engine = HawkesFlowEngine::with_config(HawkesFlowConfig::default());
+    let input_files = pmxt_inputs(&args)?;
+    let parquet_count = input_files.iter().filter(|(_, _, csv)| !csv).count();
+    let csv_count = input_files.iter().filter(|(_, _, csv)| *csv).count();
+    println!("[BACKTEST-PMXT] Found {} files ({} parquet, {} CSV) (sorted chronologically)",
+        input_files.len(), parquet_count, csv_count);
+    let strategy = args.strategy.config()?;
+    println!("[BACKTEST-PMXT] Strategy: {} ({})", strategy.kind(), strategy.describe());
+    println!("[BACKTEST-PMXT] Starting capital: ${:.2}", args.capital);
 
-            // Query ticks for this market
-            let tick_sql = format!(
-                "SELECT COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, \
-                        COALESCE(UPPER(data->>'$.side'), '') as side, \
-                        COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, \
-                        COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask \
-                 FROM read_parquet('{}') WHERE market_id = '{}' ORDER BY ts",
-                file_path, market.condition_id
-            );
+    // Setup DuckDB
+    let conn = pmxt_connection(&args)?;
 
-            let mut stmt = match conn.prepare(&tick_sql) {
-                Ok(s) => s,
-                Err(e) => {
-                    eprintln!("  [WARN] SQL failed for {}: {}", market.slug, e);
-                    continue;
-                }
-            };
+    // Global state (persists across files)
+    let fill_model = args.fills.model();
+    let with_depth = fill_model.kind == FillModelKind::Depth;
+    let sizer = PositionSizer::new(args.size, &args.sizing);
+    println!("[BACKTEST-PMXT] Sizing: {}", sizer.describe());
+    let mut session = BacktestSession::new(
+        strategy,
+        args.capital,
+        sizer,
+        fill_model,
+        args.verbose,
+    );
+    if let Some(path) = &args.predictions_out {
+        let label = format!("backtest-pmxt-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
+        session = session.with_predictions(PredictionLog::create(path, label)?);
+        println!("[BACKTEST-PMXT] Predictions: {}", path);
+    }
+    let mut processed_markets: HashSet<String> = HashSet::new();
+    let mut resolver = args.resolution.resolver()?;
+    if let Some(resolver) = &resolver {
+        println!("[BACKTEST-PMXT] Official outcomes: {} ({} cached)", args.resolution.resolutions_db, resolver.resolutions().len());
+    }
+    let (mut resolved, mut unresolved) = (0, 0);
 
-            let rows = match stmt.query_map([], |row| {
-                Ok(ReplayRow {
-                    ts: row.get::<_, f64>(0)?,
-                    side: row.get::<_, String>(1)?,
-                    bid: row.get::<_, f64>(2)?,
-                    ask: row.get::<_, f64>(3)?,
-                })
-            }) {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("  [WARN] Query failed for {}: {}", market.slug, e);
-                    continue;
-                }
-            };
+    for (file_num, (file_path, file_ts, is_csv)) in input_files.iter().enumerate() {
+        let label = pmxt_file_label(file_path, *file_ts, *is_csv);
+        println!("\n[FILE {}/{}] {} — {}", file_num + 1, input_files.len(), label, file_path);
 
-            let mut replay_rows = Vec::new();
-            for row in rows {
-                replay_rows.push(row?);
+        let markets = load_pmxt_input(&conn, file_path, *is_csv, &args, with_depth).await;
+        if let Some(resolver) = resolver.as_mut() {
+            let fetched = resolver.resolve(markets.iter().map(|(market, _)| market.condition_id.as_str())).await?;
+            if fetched > 0 && args.verbose {
+                println!("  Resolved {} markets", fetched);
             }
-
-            if replay_rows.is_empty() {
+        }
+        for (market, rows) in markets {
+            if !processed_markets.insert(market.condition_id.clone()) {
                 continue;
             }
-
-            let snapshots = build_replay_snapshots(&replay_rows, ReplayMode::EventByEvent);
-            let mut replay_source = ReplaySnapshotSource::new(snapshots);
-
-            let mut last_yes_bid = 0.0;
-            let mut last_no_bid = 0.0;
-
-            while let Some(snapshot) = replay_source.next_snapshot().await? {
-                last_yes_bid = snapshot.yes.best_bid.map(decimal_to_f64).unwrap_or(last_yes_bid);
-                last_no_bid = snapshot.no.best_bid.map(decimal_to_f64).unwrap_or(last_no_bid);
-                let epoch_seconds = replay_source
-                    .current_time()
-                    .unwrap_or_else(|| snapshot.ts_exchange.floor() as u64);
-
-                if args.strategy == BtStrategy::FairValue {
-                    // Fair value strategy uses full logit pipeline
-                    process_fairvalue_snapshot(
-                        &mut fv_model,
-                        &mut fv_kalman,
-                        &mut fv_jump_calibrator,
-                        &mut fv_calibrated,
-                        &snapshot,
-                        epoch_seconds,
-                        market.start_ts,
-                        market.end_ts,
-                        &mut shadow,
-                        &mut metrics,
-                        args.size,
-                        args.min_edge,
-                        args.verbose,
-                        &mut cumulative_wins,
-                        &mut cumulative_losses,
-                    );
-                } else if args.strategy == BtStrategy::HawkesFlow {
-                    process_hawkesflow_snapshot(
-                        &mut hawkesflow_engine,
-                        &snapshot,
-                        epoch_seconds,
-                        market.start_ts,
-                        market.end_ts,
-                        &mut shadow,
-                        &mut metrics,
-                        args.size,
-                        args.verbose,
-                        &mut cumulative_wins,
-                        &mut cumulative_losses,
-                    );
-                } else {
-                    // Standard strategy (scalper/late-window)
-                    if let Some(step) = process_pipeline_snapshot(
-                    &market,
-                    &snapshot,
-                    epoch_seconds,
-                    &mut candle_engine,
-                    &mut ind_1m,
-                    &mut ind_5s,
-                    &mut signal_engine,
-                    &mut state_1m,
-                    &mut state_5s,
-                    &mut shadow,
-                    &mut gatekeeper,
-                    &mut metrics,
-                    args.size,
-                    args.verbose,
-                    None,
-                ) {
-                    if let Some(exit_trade) = step.exit_trade {
-                        if exit_trade.pnl_usd >= 0.0 {
-                            cumulative_wins.push(exit_trade.pnl_usd);
-                        } else {
-                            cumulative_losses.push(exit_trade.pnl_usd);
-                        }
-                    }
-                }
-                } // end else (non-fairvalue strategy)
-            }
-
-            // Settle any open position at end of market
-            if shadow.is_active() {
-                let side_bid = if shadow.token_side == Some(TokenSide::Yes) {
-                    last_yes_bid
-                } else {
-                    last_no_bid
-                };
-                // Binary resolution: if the held side's last bid > 0.5, it won (1.0), else lost (0.0)
-                let settlement = if side_bid > 0.5 { 1.0 } else { 0.0 };
-                let pnl = shadow.pnl(settlement) * shadow.position_size_usd;
-                shadow.bankroll_usd += shadow.position_size_usd + pnl;
-                metrics.trades_taken += 1;
-                if pnl >= 0.0 {
-                    metrics.wins += 1;
-                    cumulative_wins.push(pnl);
-                } else {
-                    metrics.losses += 1;
-                    cumulative_losses.push(pnl);
-                }
-                shadow.reset(market.end_ts as u64);
-            }
-
-            // Track bankroll
-            bankroll_history.push((market.slug.clone(), shadow.bankroll_usd));
-
-            // Update peak/drawdown
-            if shadow.bankroll_usd > metrics.peak_capital {
-                metrics.peak_capital = shadow.bankroll_usd;
-            }
-            let drawdown = (metrics.peak_capital - shadow.bankroll_usd) / metrics.peak_capital;
-            if drawdown > metrics.max_drawdown {
-                metrics.max_drawdown = drawdown;
-            }
-
-            if args.verbose {
-                println!("  {} | Bankroll: ${:.2} | Ticks: {}", market.slug, shadow.bankroll_usd, market.ticks);
+            let winner = resolver.as_ref().and_then(|r| r.resolutions().winner(&market.condition_id));
+            if winner.is_some() {
+                resolved += 1;
+            } else {
+                unresolved += 1;
             }
+            session.run_market(&market, &rows, winner).await?;
         }
-        } // end else (parquet processing)
-    }
-
-    // Final metrics
-    metrics.ending_capital = shadow.bankroll_usd;
-    metrics.total_pnl = metrics.ending_capital - metrics.starting_capital;
-    metrics.total_pnl_pct = (metrics.total_pnl / metrics.starting_capital) * 100.0;
-
-    // Print enhanced summary
-    println!("\n================ BACKTEST-PMXT SUMMARY ================");
-    println!("Files Processed:    {}", input_files.len());
-    println!("Markets Processed:  {}", metrics.total_markets);
-    println!("Total Ticks:        {}", metrics.total_ticks);
-    println!("Trades Taken:       {}", metrics.trades_taken);
-    let win_rate = if metrics.trades_taken > 0 {
-        metrics.wins as f64 / metrics.trades_taken as f64 * 100.0
-    } else { 0.0 };
-    println!("Wins/Losses:        {} / {} ({:.1}%)", metrics.wins, metrics.losses, win_rate);
-
-    let avg_win = if !cumulative_wins.is_empty() {
-        cumulative_wins.iter().sum::<f64>() / cumulative_wins.len() as f64
-    } else { 0.0 };
-    let avg_loss = if !cumulative_losses.is_empty() {
-        cumulative_losses.iter().sum::<f64>() / cumulative_losses.len() as f64
-    } else { 0.0 };
-    let profit_factor = if avg_loss.abs() > 0.0001 {
-        avg_win / avg_loss.abs()
-    } else if avg_win > 0.0 { f64::INFINITY } else { 0.0 };
-
-    println!("Avg Win:            ${:.4}", avg_win);
-    println!("Avg Loss:           ${:.4}", avg_loss);
-    println!("Profit Factor:      {:.2}", profit_factor);
-    println!("------------------------------------------------------");
-    println!("Starting Capital:   ${:.2}", metrics.starting_capital);
-    println!("Ending Capital:     ${:.2}", metrics.ending_capital);
-    println!("Total PnL:          ${:.2} ({:.2}%)", metrics.total_pnl, metrics.total_pnl_pct);
-    println!("Max Drawdown:       {:.2}%", metrics.max_drawdown * 100.0);
-    println!("======================================================");
-
-    if let Some(path) = &args.export {
-        #[derive(Serialize)]
-        struct ExportResult {
-            files_processed: usize,
-            markets_processed: usize,
-            total_ticks: usize,
-            trades_taken: usize,
-            wins: usize,
-            losses: usize,
-            win_rate: f64,
-            avg_win: f64,
-            avg_loss: f64,
-            profit_factor: f64,
-            starting_capital: f64,
-            ending_capital: f64,
-            total_pnl: f64,
-            total_pnl_pct: f64,
-            max_drawdown: f64,
-            bankroll_history: Vec<(String, f64)>,
-        }
-        let result = ExportResult {
-            files_processed: input_files.len(),
-            markets_processed: metrics.total_markets,
-            total_ticks: metrics.total_ticks,
-            trades_taken: metrics.trades_taken,
-            wins: metrics.wins,
-            losses: metrics.losses,
-            win_rate,
-            avg_win,
-            avg_loss,
-            profit_factor,
-            starting_capital: metrics.starting_capital,
-            ending_capital: metrics.ending_capital,
-            total_pnl: metrics.total_pnl,
-            total_pnl_pct: metrics.total_pnl_pct,
-            max_drawdown: metrics.max_drawdown,
-            bankroll_history,
-        };
-        let file = std::fs::File::create(path)?;
-        serde_json::to_writer_pretty(file, &result)?;
-        println!("[EXPORT] Results written to {}", path);
     }
 
-    Ok(())
+    println!(
+        "\n[BACKTEST-PMXT] {} markets settled on official outcomes, {} on the final book",
+        resolved, unresolved
+    );
+    session.finish("BACKTEST-PMXT", input_files.len(), args.export.as_deref())
 }
 
 pub async fn run_backtest(args: BacktestArgs) -> Result<()> {
@@ -2179,6 +2037,7 @@ pub async fn run_backtest(args: BacktestArgs) -> Result<()> {
         entry_band_high: args.band_high,
         position_size_usd: args.size,
         starting_capital: args.capital,
+        fill_model: args.fills.model(),
         ..Default::default()
     }).run_all(&markets);
     metrics.print_summary();
@@ -2187,7 +2046,20 @@ pub async fn run_backtest(args: BacktestArgs) -> Result<()> {
 }
 
 pub fn run_monte_carlo(args: MonteCarloArgs) -> Result<()> {
-    let config = if let Some(path) = args.input {
+    let mut bootstrap = None;
+    let config = if let Some(path) = &args.trades_file {
+        let trades = load_trades(path)?;
+        let mut resampler = BlockBootstrap::from_trades(&trades, args.block_len)?;
+        if args.regimes {
+            resampler = resampler.with_regimes(args.regime_window);
+        }
+        bootstrap = Some(resampler);
+        let mut cfg = SimulationConfig::from_trades(&trades);
+        cfg.num_simulations = args.simulations;
+        cfg.num_trades_per_sim = args.trades;
+        cfg.position_size_pct = args.kelly;
+        cfg
+    } else if let Some(path) = args.input {
         let file = std::fs::File::open(path)?;
         let m: crate::bot::backtest::metrics::BacktestMetrics = serde_json::from_reader(file)?;
         let mut cfg = SimulationConfig::from_metrics(&m);
@@ -2203,7 +2075,19 @@ pub fn run_monte_carlo(args: MonteCarloArgs) -> Result<()> {
             ..Default::default()
         }
     };
-    MonteCarloSimulator::new(config).run().print_summary();
+    let mut simulator = match args.seed {
+        Some(seed) => MonteCarloSimulator::seeded(config, seed),
+        None => MonteCarloSimulator::new(config),
+    };
+    if let Some(bootstrap) = bootstrap {
+        simulator = simulator.with_bootstrap(bootstrap);
+    }
+    let result = simulator.run();
+    result.print_summary();
+    if let Some(path) = args.export {
+        result.export_json(&path)?;
+        println!("[EXPORT] Results written to {}", path);
+    }
     Ok(())
 }
 
@@ -2251,7 +2135,7 @@ pub async fn run_extract_midpoints(args: ExtractMidpointsArgs) -> Result<()> {
     let conn = duckdb::Connection::open_in_memory()?;
     conn.execute_batch("INSTALL httpfs; LOAD httpfs;")?;
     let id_list = ids.iter().map(|(id, _)| format!("'{}'", id)).collect::<Vec<_>>().join(",");
-    let sql = format!("SELECT market_id, COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, (COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0)+COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0))/2.0 as mid, COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask FROM read_parquet('{}') WHERE market_id IN ({}) ORDER BY ts", args.input, id_list);
+    let sql = format!("SELECT market_id, COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, (COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0)+COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0))/2.0 as mid, COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask FROM read_parquet({}) WHERE market_id IN ({}) ORDER BY ts", sql_literal(&args.input), id_list);
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map([], |row| Ok(MidpointRow {
         market_id: row.get(0)?,
@@ -2272,7 +2156,7 @@ pub async fn run_extract_midpoints(args: ExtractMidpointsArgs) -> Result<()> {
 pub fn run_inspect_parquet(args: InspectParquetArgs) -> Result<()> {
     let conn = duckdb::Connection::open_in_memory()?;
     conn.execute_batch("INSTALL httpfs; LOAD httpfs;")?;
-    let sql = format!("SELECT * FROM read_parquet('{}') LIMIT {}", args.input, args.sample);
+    let sql = format!("SELECT * FROM read_parquet({}) LIMIT {}", sql_literal(&args.input), args.sample);
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map([], |row| Ok(row.get::<_, Option<String>>(0).unwrap_or_default()))?;
     for (i, r) in rows.enumerate() { println!("{}: {:?}", i, r?); }
@@ -2324,10 +2208,10 @@ mod tests {
     fn live_parity_replay_emits_one_snapshot_per_second_boundary() {
         let snapshots = build_replay_snapshots(
             &[
-                ReplayRow { ts: 1000.1, side: "YES".to_string(), bid: 0.40, ask: 0.42 },
-                ReplayRow { ts: 1000.2, side: "NO".to_string(), bid: 0.58, ask: 0.60 },
-                ReplayRow { ts: 1001.1, side: "YES".to_string(), bid: 0.43, ask: 0.45 },
-                ReplayRow { ts: 1002.2, side: "NO".to_string(), bid: 0.55, ask: 0.57 },
+                ReplayRow { ts: 1000.1, side: "YES".to_string(), bid: 0.40, ask: 0.42, depth: None },
+                ReplayRow { ts: 1000.2, side: "NO".to_string(), bid: 0.58, ask: 0.60, depth: None },
+                ReplayRow { ts: 1001.1, side: "YES".to_string(), bid: 0.43, ask: 0.45, depth: None },
+                ReplayRow { ts: 1002.2, side: "NO".to_string(), bid: 0.55, ask: 0.57, depth: None },
             ],
             ReplayMode::LiveParity1s,
         );
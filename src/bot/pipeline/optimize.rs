@@ -0,0 +1,404 @@
+//! Strategy Optimizer
+//!
+//! Searches any registered strategy's config over PMXT history: the markets
+//! are loaded once, every trial replays them through a fresh
+//! `BacktestSession`, and trials run in parallel across cores. Results go to
+//! an `optimizer_trials` DuckDB table (optionally exported to parquet) so runs
+//! can be compared across methods and objectives.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{
+    load_pmxt_input, pmxt_connection, pmxt_inputs, sql_literal, BacktestPmxtArgs, BacktestSession, DiscoveredMarket,
+    ReplayRow, SessionSummary,
+};
+use crate::bot::backtest::optimizer::{random_point, ParamSpec, SearchMethod, SearchSpace, Tpe};
+use crate::bot::backtest::FillModelKind;
+use crate::bot::shadow::TokenSide;
+use crate::bot::sizing::PositionSizer;
+use crate::bot::strategy::StrategyConfig;
+
+/// Search space, method and objective shared by `optimize` and `walk-forward`
+#[derive(Args, Clone)]
+pub struct SearchArgs {
+    /// Searched config field: path=low:high[:step] or path=a,b,c (repeatable).
+    /// Paths are dotted into the strategy's config, e.g. min_heai,
+    /// calibration.spread_adjustment or fusion.qlib_weight
+    #[arg(long = "param")]
+    pub params: Vec<String>,
+
+    /// Search method
+    #[arg(long, value_enum, default_value_t = SearchMethod::Tpe)]
+    pub method: SearchMethod,
+
+    /// Trials for random and TPE search
+    #[arg(long, default_value = "50")]
+    pub trials: usize,
+
+    /// Values per numeric range without a step in grid search
+    #[arg(long, default_value = "5")]
+    pub grid_points: usize,
+
+    /// Quantity to optimize
+    #[arg(long, value_enum, default_value_t = Objective::Sharpe)]
+    pub objective: Objective,
+
+    /// Parallel backtests (default: available cores)
+    #[arg(long)]
+    pub jobs: Option<usize>,
+
+    /// Seed for random and TPE sampling
+    #[arg(long, default_value = "42")]
+    pub seed: u64,
+}
+
+impl SearchArgs {
+    pub(super) fn jobs(&self) -> usize {
+        self.jobs
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+            .max(1)
+    }
+
+    pub(super) fn space(&self, base: &Value) -> Result<SearchSpace> {
+        let specs = self.params.iter().map(|p| p.parse::<ParamSpec>()).collect::<Result<Vec<_>>>()?;
+        SearchSpace::resolve(specs, base)
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct OptimizeArgs {
+    #[command(flatten)]
+    pub backtest: BacktestPmxtArgs,
+
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    /// DuckDB file the trials are appended to
+    #[arg(long, default_value = "optimizer.duckdb")]
+    pub db: String,
+
+    /// Also write this run's trials to a parquet file
+    #[arg(long)]
+    pub parquet: Option<String>,
+
+    /// Ranked trials to print
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Objective {
+    /// Per-market return Sharpe ratio (maximized)
+    Sharpe,
+    /// Total PnL in USD (maximized)
+    Pnl,
+    /// Brier score of the entered side's calibrated probability (minimized)
+    Brier,
+}
+
+impl Objective {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sharpe => "sharpe",
+            Self::Pnl => "pnl",
+            Self::Brier => "brier",
+        }
+    }
+
+    /// Higher is better; a Brier objective without entries scores worst
+    pub fn score(&self, summary: &SessionSummary) -> f64 {
+        match self {
+            Self::Sharpe => summary.sharpe,
+            Self::Pnl => summary.total_pnl,
+            Self::Brier => summary.brier.map_or(f64::NEG_INFINITY, |b| -b),
+        }
+    }
+
+    /// Out-of-sample over in-sample performance, 1.0 meaning no degradation.
+    /// PnL is compared per market so windows of different length line up;
+    /// `None` when the in-sample value leaves nothing to degrade from.
+    pub fn efficiency(&self, in_sample: &SessionSummary, out_of_sample: &SessionSummary) -> Option<f64> {
+        match self {
+            Self::Sharpe => (in_sample.sharpe > 0.0).then(|| out_of_sample.sharpe / in_sample.sharpe),
+            Self::Pnl => {
+                let per_market = |s: &SessionSummary| s.total_pnl / s.markets.max(1) as f64;
+                (per_market(in_sample) > 0.0).then(|| per_market(out_of_sample) / per_market(in_sample))
+            }
+            Self::Brier => match (in_sample.brier, out_of_sample.brier) {
+                (Some(is), Some(oos)) if oos > 0.0 => Some(is / oos),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct Trial {
+    pub trial: usize,
+    pub params: Vec<Value>,
+    /// Unit coordinates the point was sampled at; `None` for grid points
+    #[serde(skip)]
+    pub unit: Option<Vec<f64>>,
+    #[serde(skip)]
+    pub config: StrategyConfig,
+    pub score: f64,
+    pub summary: SessionSummary,
+}
+
+/// A PMXT market's rows with its official winner, when resolved
+pub(super) type ReplayMarket = (DiscoveredMarket, Vec<ReplayRow>, Option<TokenSide>);
+
+/// Markets of each input file, in file order, each market kept only in the
+/// first file it appears in
+pub(super) async fn load_markets_by_file(
+    args: &BacktestPmxtArgs,
+    files: &[(String, i64, bool)],
+) -> Result<Vec<Vec<ReplayMarket>>> {
+    let conn = pmxt_connection(args)?;
+    let with_depth = args.fills.model().kind == FillModelKind::Depth;
+    let mut resolver = args.resolution.resolver()?;
+    let mut seen = HashSet::new();
+    let mut by_file = Vec::with_capacity(files.len());
+    for (file_path, _, is_csv) in files {
+        let markets: Vec<_> = load_pmxt_input(&conn, file_path, *is_csv, args, with_depth)
+            .await
+            .into_iter()
+            .filter(|(market, _)| seen.insert(market.condition_id.clone()))
+            .collect();
+        if let Some(resolver) = resolver.as_mut() {
+            resolver.resolve(markets.iter().map(|(market, _)| market.condition_id.as_str())).await?;
+        }
+        by_file.push(
+            markets
+                .into_iter()
+                .map(|(market, rows)| {
+                    let winner = resolver.as_ref().and_then(|r| r.resolutions().winner(&market.condition_id));
+                    (market, rows, winner)
+                })
+                .collect(),
+        );
+    }
+    Ok(by_file)
+}
+
+/// Replay every market with one strategy config
+async fn replay(
+    strategy: StrategyConfig,
+    args: &BacktestPmxtArgs,
+    markets: &[ReplayMarket],
+) -> Result<SessionSummary> {
+    let mut session = BacktestSession::new(strategy, args.capital, PositionSizer::new(args.size, &args.sizing), args.fills.model(), false);
+    for (market, rows, winner) in markets {
+        session.run_market(market, rows, *winner).await?;
+    }
+    Ok(session.summary())
+}
+
+/// Backtest each config on `jobs` worker threads, results in input order
+pub(super) fn evaluate(
+    configs: Vec<StrategyConfig>,
+    args: &BacktestPmxtArgs,
+    markets: &[ReplayMarket],
+    jobs: usize,
+) -> Result<Vec<SessionSummary>> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<SessionSummary>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(configs.len()).max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+                    let mut done = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(config) = configs.get(idx) else { break };
+                        done.push((idx, runtime.block_on(replay(config.clone(), args, markets))));
+                    }
+                    anyhow::Ok(done)
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().map_err(|_| anyhow::anyhow!("Optimizer worker panicked"))?)
+            .collect::<Result<Vec<_>>>()
+            .map(|done| done.into_iter().flatten().collect())
+    })?;
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+fn open_store(path: &str) -> Result<duckdb::Connection> {
+    let conn = duckdb::Connection::open(path).with_context(|| format!("Failed to open {}", path))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS optimizer_trials (
+            run_id VARCHAR,
+            started_at TIMESTAMP,
+            strategy VARCHAR,
+            method VARCHAR,
+            objective VARCHAR,
+            trial INTEGER,
+            params VARCHAR,
+            config VARCHAR,
+            score DOUBLE,
+            markets INTEGER,
+            trades INTEGER,
+            win_rate DOUBLE,
+            total_pnl DOUBLE,
+            total_pnl_pct DOUBLE,
+            max_drawdown DOUBLE,
+            sharpe DOUBLE,
+            brier DOUBLE
+        )",
+    )?;
+    Ok(conn)
+}
+
+/// Run one search over `markets`, calling `on_trial` as each trial finishes
+pub(super) fn search(
+    space: &SearchSpace,
+    base: &Value,
+    search: &SearchArgs,
+    backtest: &BacktestPmxtArgs,
+    markets: &[ReplayMarket],
+    mut on_trial: impl FnMut(&Trial, usize) -> Result<()>,
+) -> Result<Vec<Trial>> {
+    let jobs = search.jobs();
+    let grid = (search.method == SearchMethod::Grid).then(|| space.grid(search.grid_points));
+    let total = grid.as_ref().map_or(search.trials, Vec::len);
+    let tpe = Tpe { startup: (search.trials / 5).clamp(5, 20), ..Tpe::default() };
+    let mut rng = StdRng::seed_from_u64(search.seed);
+    let mut trials: Vec<Trial> = Vec::with_capacity(total);
+
+    while trials.len() < total {
+        let batch = jobs.min(total - trials.len());
+        let proposals: Vec<(Vec<Value>, Option<Vec<f64>>)> = match (&grid, search.method) {
+            (Some(points), _) => points[trials.len()..trials.len() + batch].iter().map(|p| (p.clone(), None)).collect(),
+            (None, SearchMethod::Tpe) => {
+                let history: Vec<(Vec<f64>, f64)> =
+                    trials.iter().filter_map(|t| Some((t.unit.clone()?, t.score))).collect();
+                (0..batch)
+                    .map(|_| {
+                        let unit = tpe.suggest(&history, space.dims(), &mut rng);
+                        (space.decode(&unit), Some(unit))
+                    })
+                    .collect()
+            }
+            (None, _) => (0..batch)
+                .map(|_| {
+                    let unit = random_point(space.dims(), &mut rng);
+                    (space.decode(&unit), Some(unit))
+                })
+                .collect(),
+        };
+
+        let configs = proposals
+            .iter()
+            .map(|(point, _)| {
+                serde_json::from_value::<StrategyConfig>(space.apply(base, point))
+                    .with_context(|| format!("Invalid config for {}", space.describe(point)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let summaries = tokio::task::block_in_place(|| evaluate(configs.clone(), backtest, markets, jobs))?;
+
+        for ((params, unit), (config, summary)) in proposals.into_iter().zip(configs.into_iter().zip(summaries)) {
+            let trial = Trial {
+                trial: trials.len() + 1,
+                score: search.objective.score(&summary),
+                params,
+                unit,
+                config,
+                summary,
+            };
+            on_trial(&trial, total)?;
+            trials.push(trial);
+        }
+    }
+    Ok(trials)
+}
+
+pub async fn run_optimize(args: OptimizeArgs) -> Result<()> {
+    let search_args = &args.search;
+    let base_config = args.backtest.strategy.config()?;
+    let base = serde_json::to_value(&base_config)?;
+    let space = search_args.space(&base)?;
+
+    let store = open_store(&args.db)?;
+    let started = Utc::now();
+    let run_id = format!("{}-{}", base_config.kind(), started.format("%Y%m%dT%H%M%S"));
+    println!("[OPTIMIZE] Run {}: {} search over {} params, objective {}, {} jobs",
+        run_id, search_args.method.as_str(), space.dims(), search_args.objective.as_str(), search_args.jobs());
+
+    // Load every market once; trials share the replay rows
+    let input_files = pmxt_inputs(&args.backtest)?;
+    let markets: Vec<_> = load_markets_by_file(&args.backtest, &input_files).await?.into_iter().flatten().collect();
+    if markets.is_empty() {
+        anyhow::bail!("No markets loaded from {} files", input_files.len());
+    }
+    println!("[OPTIMIZE] Loaded {} markets from {} files", markets.len(), input_files.len());
+
+    let trials = search(&space, &base, search_args, &args.backtest, &markets, |trial, total| {
+        let summary = &trial.summary;
+        println!("[TRIAL {}/{}] score {:>8.4} | {} trades | PnL ${:>8.2} | {}",
+            trial.trial, total, trial.score, summary.trades, summary.total_pnl, space.describe(&trial.params));
+        store.execute(
+            "INSERT INTO optimizer_trials VALUES (?, CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            duckdb::params![
+                run_id,
+                started.format("%Y-%m-%d %H:%M:%S").to_string(),
+                base_config.kind().to_string(),
+                search_args.method.as_str(),
+                search_args.objective.as_str(),
+                trial.trial as i64,
+                serde_json::to_string(&space.describe_json(&trial.params))?,
+                serde_json::to_string(&trial.config)?,
+                trial.score.is_finite().then_some(trial.score),
+                summary.markets as i64,
+                summary.trades as i64,
+                summary.win_rate,
+                summary.total_pnl,
+                summary.total_pnl_pct,
+                summary.max_drawdown,
+                summary.sharpe,
+                summary.brier,
+            ],
+        )?;
+        Ok(())
+    })?;
+
+    let mut ranked: Vec<&Trial> = trials.iter().collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    println!("\n================ OPTIMIZE SUMMARY ({}) ================", search_args.objective.as_str());
+    println!("{:<5} {:<6} {:>9} {:>7} {:>7} {:>10} {:>7} {:>7} {:>7}  Params",
+        "Rank", "Trial", "Score", "Trades", "Win%", "PnL", "MaxDD", "Sharpe", "Brier");
+    for (rank, trial) in ranked.iter().take(args.top).enumerate() {
+        let s = &trial.summary;
+        println!("{:<5} {:<6} {:>9.4} {:>7} {:>6.1}% {:>10.2} {:>6.1}% {:>7.3} {:>7}  {}",
+            rank + 1, trial.trial, trial.score, s.trades, s.win_rate * 100.0, s.total_pnl,
+            s.max_drawdown * 100.0, s.sharpe, s.brier.map_or("-".to_string(), |b| format!("{:.4}", b)),
+            space.describe(&trial.params));
+    }
+    println!("\n[OPTIMIZE] {} trials stored in {} (run_id = '{}')", trials.len(), args.db, run_id);
+
+    if let Some(path) = &args.parquet {
+        store.execute_batch(&format!(
+            "COPY (SELECT * FROM optimizer_trials WHERE run_id = {} ORDER BY trial) TO {} (FORMAT PARQUET)",
+            sql_literal(&run_id),
+            sql_literal(path)
+        ))?;
+        println!("[OPTIMIZE] Exported trials to {}", path);
+    }
+    if let Some(path) = &args.backtest.export {
+        std::fs::write(path, serde_json::to_string_pretty(&ranked)?)?;
+        println!("[OPTIMIZE] Exported ranked trials to {}", path);
+    }
+    Ok(())
+}
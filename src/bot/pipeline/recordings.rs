@@ -0,0 +1,331 @@
+//! Recorded Sessions
+//!
+//! Loads `TickRecorder` session CSVs and backtests them through the same
+//! `BacktestSession` as the PMXT archive path.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use super::{parse_slug_timestamp, BacktestSession, DiscoveredMarket, ReplayRow};
+use crate::bot::backtest::FillArgs;
+use crate::bot::pricing::PredictionLog;
+use crate::bot::research::SupportedDuration;
+use crate::bot::shadow::TokenSide;
+use crate::bot::sizing::{PositionSizer, SizingArgs};
+use crate::bot::strategy::StrategyArgs;
+
+#[derive(Args, Clone)]
+pub struct BacktestRecordingArgs {
+    /// Session CSV files or directories of them (recordings/session_*.csv)
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+
+    /// Starting capital in USD
+    #[arg(long, default_value = "5")]
+    pub capital: f64,
+
+    /// Position size in USD per trade
+    #[arg(long, default_value = "1")]
+    pub size: f64,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    /// Only backtest markets whose slug contains this text
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Minimum recorded ticks per market
+    #[arg(long, default_value = "1")]
+    pub min_ticks: usize,
+
+    /// Treat the final book as resolved if it was recorded within this many seconds of expiry
+    #[arg(long, default_value = "15")]
+    pub resolve_window: i64,
+
+    /// Skip markets whose recording stops before a resolved final book
+    #[arg(long)]
+    pub skip_unresolved: bool,
+
+    /// Export results to JSON
+    #[arg(long)]
+    pub export: Option<String>,
+
+    /// Append each entry's fair value prediction to this JSON-lines file
+    #[arg(long)]
+    pub predictions_out: Option<String>,
+
+    #[command(flatten)]
+    pub fills: FillArgs,
+
+    /// Show verbose output
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+/// Ticks of one market, possibly gathered from several session files
+#[derive(Debug, Clone)]
+pub(super) struct RecordedMarket {
+    pub(super) slug: String,
+    /// YES and NO rows per tick, timestamps in seconds
+    pub(super) rows: Vec<ReplayRow>,
+    ticks: usize,
+    /// Seconds to expiry at the latest tick, as recorded
+    last_time_remaining: i64,
+    latest_ts: f64,
+}
+
+impl RecordedMarket {
+    fn first_ts(&self) -> f64 {
+        self.rows.first().map_or(0.0, |r| r.ts)
+    }
+
+    fn last_ts(&self) -> f64 {
+        self.rows.last().map_or(0.0, |r| r.ts)
+    }
+
+    /// Market window from the slug timestamp, else from `time_remaining`
+    fn window(&self) -> (i64, i64) {
+        let duration = SupportedDuration::from_slug(&self.slug).map_or(300, |d| d.seconds());
+        match parse_slug_timestamp(&self.slug) {
+            Some(start) => (start, start + duration),
+            None => {
+                let end = self.last_ts() as i64 + self.last_time_remaining.max(0);
+                (end - duration, end)
+            }
+        }
+    }
+
+    pub(super) fn discovered(&self) -> DiscoveredMarket {
+        let (start_ts, end_ts) = self.window();
+        DiscoveredMarket {
+            condition_id: self.slug.clone(),
+            slug: self.slug.clone(),
+            question: format!("Recorded: {}", self.slug),
+            start_ts,
+            end_ts,
+            ticks: self.ticks as i64,
+            min_ts: self.first_ts(),
+            max_ts: self.last_ts(),
+        }
+    }
+
+    /// Winner implied by the final book, trusted only when it was recorded
+    /// close to expiry and one side had converged towards 1.
+    fn resolved_winner(&self, resolve_window: i64) -> Option<TokenSide> {
+        if self.last_time_remaining > resolve_window {
+            return None;
+        }
+        let last_bid = |side: &str| {
+            self.rows.iter().rev().find(|r| r.side == side).map_or(0.0, |r| r.bid)
+        };
+        let (yes_bid, no_bid) = (last_bid("YES"), last_bid("NO"));
+        if yes_bid >= 0.9 && yes_bid > no_bid {
+            Some(TokenSide::Yes)
+        } else if no_bid >= 0.9 && no_bid > yes_bid {
+            Some(TokenSide::No)
+        } else {
+            None
+        }
+    }
+
+    fn merge(&mut self, other: RecordedMarket) {
+        self.ticks += other.ticks;
+        if other.latest_ts >= self.latest_ts {
+            self.latest_ts = other.latest_ts;
+            self.last_time_remaining = other.last_time_remaining;
+        }
+        self.rows.extend(other.rows);
+        self.rows.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+    }
+}
+
+/// Parse a `TickRecorder` session CSV into per-market ticks, ordered by the
+/// first tick of each market. Live websocket recordings carry millisecond
+/// timestamps; they are normalized to seconds.
+pub(super) fn parse_recording_csv(reader: impl Read) -> Result<Vec<RecordedMarket>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut markets: HashMap<String, RecordedMarket> = HashMap::new();
+
+    for result in reader.records() {
+        let record = result?;
+        // Columns: timestamp, market_slug, yes_bid, yes_ask, no_bid, no_ask, time_remaining
+        let field = |i: usize| record.get(i).unwrap_or("0").parse::<f64>().unwrap_or(0.0);
+        let mut timestamp = field(0);
+        if timestamp > 1e11 {
+            timestamp /= 1000.0;
+        }
+        let market_slug = record.get(1).unwrap_or("unknown").to_string();
+        let time_remaining = record.get(6).and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+
+        let market = markets.entry(market_slug.clone()).or_insert_with(|| RecordedMarket {
+            slug: market_slug,
+            rows: Vec::new(),
+            ticks: 0,
+            last_time_remaining: time_remaining,
+            latest_ts: timestamp,
+        });
+        if timestamp >= market.latest_ts {
+            market.latest_ts = timestamp;
+            market.last_time_remaining = time_remaining;
+        }
+        market.ticks += 1;
+        market.rows.push(ReplayRow { ts: timestamp, side: "YES".to_string(), bid: field(2), ask: field(3), depth: None });
+        market.rows.push(ReplayRow { ts: timestamp, side: "NO".to_string(), bid: field(4), ask: field(5), depth: None });
+    }
+
+    let mut markets: Vec<RecordedMarket> = markets.into_values().collect();
+    for market in &mut markets {
+        market.rows.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+    }
+    markets.sort_by(|a, b| a.first_ts().total_cmp(&b.first_ts()));
+    Ok(markets)
+}
+
+pub(super) fn read_csv_recordings(path: &str) -> Result<Vec<RecordedMarket>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    parse_recording_csv(file)
+}
+
+/// Expand directories to the CSV files inside them, sorted by name
+fn collect_recording_files(inputs: &[String]) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            let mut dir_files = Vec::new();
+            for entry in std::fs::read_dir(path)? {
+                let entry_path = entry?.path();
+                if entry_path.extension().and_then(|e| e.to_str()) == Some("csv") {
+                    dir_files.push(entry_path.to_string_lossy().to_string());
+                }
+            }
+            dir_files.sort();
+            files.extend(dir_files);
+        } else if path.is_file() {
+            files.push(input.clone());
+        } else {
+            anyhow::bail!("No such recording file or directory: {}", input);
+        }
+    }
+    Ok(files)
+}
+
+pub async fn run_backtest_recording(args: BacktestRecordingArgs) -> Result<()> {
+    let files = collect_recording_files(&args.inputs)?;
+    if files.is_empty() {
+        anyhow::bail!("No CSV recordings found in {}", args.inputs.join(", "));
+    }
+    println!("[BACKTEST-RECORDING] Found {} session files", files.len());
+    let strategy = args.strategy.config()?;
+    println!("[BACKTEST-RECORDING] Strategy: {} ({})", strategy.kind(), strategy.describe());
+    println!("[BACKTEST-RECORDING] Starting capital: ${:.2}", args.capital);
+
+    // A market can span sessions if the recorder was restarted mid-window
+    let mut markets: Vec<RecordedMarket> = Vec::new();
+    for file in &files {
+        let recorded = match read_csv_recordings(file) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("  [WARN] Failed to read CSV {}: {}", file, e);
+                continue;
+            }
+        };
+        for market in recorded {
+            match markets.iter_mut().find(|m| m.slug == market.slug) {
+                Some(existing) => existing.merge(market),
+                None => markets.push(market),
+            }
+        }
+    }
+    markets.retain(|m| {
+        m.ticks >= args.min_ticks && args.filter.as_deref().is_none_or(|f| m.slug.contains(f))
+    });
+    markets.sort_by(|a, b| a.first_ts().total_cmp(&b.first_ts()));
+
+    let mut session = BacktestSession::new(
+        strategy,
+        args.capital,
+        PositionSizer::new(args.size, &args.sizing),
+        args.fills.model(),
+        args.verbose,
+    );
+    if let Some(path) = &args.predictions_out {
+        let label = format!("backtest-recording-{}", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+        session = session.with_predictions(PredictionLog::create(path, label)?);
+        println!("[BACKTEST-RECORDING] Predictions: {}", path);
+    }
+    let (mut resolved, mut unresolved) = (0, 0);
+    for market in &markets {
+        let winner = market.resolved_winner(args.resolve_window);
+        if winner.is_some() {
+            resolved += 1;
+        } else {
+            unresolved += 1;
+            if args.skip_unresolved {
+                if args.verbose {
+                    println!("  {} | skipped: recording ends {}s before expiry", market.slug, market.last_time_remaining);
+                }
+                continue;
+            }
+        }
+        session.run_market(&market.discovered(), &market.rows, winner).await?;
+    }
+
+    println!(
+        "\n[BACKTEST-RECORDING] {} markets resolved from the final book, {} unresolved{}",
+        resolved,
+        unresolved,
+        if args.skip_unresolved { " (skipped)" } else { " (settled on last bid)" }
+    );
+    session.finish("BACKTEST-RECORDING", files.len(), args.export.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "\
+timestamp,market_slug,yes_bid,yes_ask,no_bid,no_ask,time_remaining
+1700000290000.0,btc-updown-5m-1700000000,0.950000,0.970000,0.030000,0.050000,10
+1700000000500.0,btc-updown-5m-1700000000,0.480000,0.500000,0.500000,0.520000,299
+1700000301000.0,btc-updown-5m-1700000300,0.500000,0.520000,0.480000,0.500000,299
+";
+
+    #[test]
+    fn splits_session_by_market_and_normalizes_timestamps() {
+        let markets = parse_recording_csv(SESSION.as_bytes()).unwrap();
+        assert_eq!(markets.len(), 2);
+
+        let first = &markets[0];
+        assert_eq!(first.slug, "btc-updown-5m-1700000000");
+        assert_eq!(first.ticks, 2);
+        assert_eq!(first.rows.len(), 4);
+        assert!((first.first_ts() - 1_700_000_000.5).abs() < 1e-6);
+        assert_eq!(first.discovered().end_ts, 1_700_000_300);
+        assert_eq!(first.resolved_winner(15), Some(TokenSide::Yes));
+
+        // Recording stopped with the whole window still ahead
+        assert_eq!(markets[1].resolved_winner(15), None);
+    }
+
+    #[test]
+    fn markets_merge_across_sessions() {
+        let mut markets = parse_recording_csv(SESSION.as_bytes()).unwrap();
+        let later = parse_recording_csv(
+            "timestamp,market_slug,yes_bid,yes_ask,no_bid,no_ask,time_remaining\n\
+             1700000598.0,btc-updown-5m-1700000300,0.020000,0.040000,0.960000,0.980000,2\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        markets[1].merge(later.into_iter().next().unwrap());
+        assert_eq!(markets[1].ticks, 2);
+        assert_eq!(markets[1].resolved_winner(15), Some(TokenSide::No));
+    }
+}
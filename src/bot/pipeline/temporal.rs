@@ -0,0 +1,854 @@
+//! Temporal Arbitrage Backtest
+//!
+//! Replays the nested BTC Up/Down markets of each PMXT hour (5m, 15m and 1h
+//! by default) through the same `TemporalGraph` and `ConstraintEngine` as
+//! `bot temporal-arb`. Markets join the graph when their window opens, with
+//! the spot at the open as strike, and become resolved legs at expiry.
+//! Entries fill through the shared `FillModel` against the replayed touch and
+//! are held to the official outcome from the `ResolutionResolver` cache;
+//! results are reported overall and per `ViolationType`.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use super::{extract_hour_from_filename, gamma_market_condition_id_hex, sql_literal, ReplayRow};
+use crate::bot::backtest::{
+    BacktestMetrics, BookFills, BookReplay, BookUpdate, FillArgs, FillModel, FillStats, TradeResult,
+};
+use crate::bot::discovery::MarketTarget;
+use crate::bot::feed_base::OutcomeSide;
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+use crate::bot::resolution::ResolutionArgs;
+use crate::bot::shadow::{ShadowFills, TokenSide};
+use crate::bot::strategy::constraint_engine::ConstraintEngine;
+use crate::bot::strategy::graph_builder::TemporalGraphBuilder;
+use crate::bot::strategy::probability_engine::{ArbitrageAction, ViolationType};
+use crate::bot::strategy::temporal_arbitrage::{
+    TemporalArbitrageConfig, TemporalNode, Timeframe, VolatilityEstimator,
+};
+use crate::bot::strategy::{Direction, TemporalGraph};
+use crate::bot::temporal_arb::{graph_timeframe, TemporalArbParams};
+use polymarket_client_sdk::gamma;
+use polymarket_client_sdk::gamma::types::request::MarketBySlugRequest;
+
+const VIOLATION_TYPES: [ViolationType; 3] = [
+    ViolationType::ChainedConditional,
+    ViolationType::LatePhaseAnomaly,
+    ViolationType::PriceConsistency,
+];
+
+#[derive(Args, Clone)]
+pub struct BacktestTemporalArgs {
+    /// Directory containing hourly PMXT parquet files
+    #[arg(long)]
+    pub input_dir: String,
+
+    /// BTC/USD spot CSV with `timestamp,price` rows (seconds or milliseconds)
+    #[arg(long)]
+    pub spot: String,
+
+    #[command(flatten)]
+    pub params: TemporalArbParams,
+
+    #[command(flatten)]
+    pub fills: FillArgs,
+
+    #[command(flatten)]
+    pub resolution: ResolutionArgs,
+
+    /// Starting capital in USD
+    #[arg(long, default_value = "5")]
+    pub capital: f64,
+
+    /// Export results to JSON
+    #[arg(long)]
+    pub export: Option<String>,
+
+    /// Show verbose output
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+/// BTC/USD spot samples as (timestamp, price), sorted by time
+#[derive(Debug, Default)]
+pub(super) struct SpotSeries {
+    points: Vec<(f64, f64)>,
+}
+
+impl SpotSeries {
+    pub(super) fn parse(reader: impl Read) -> Result<Self> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let mut points = Vec::new();
+        for result in reader.records() {
+            let record = result?;
+            let field = |i: usize| record.get(i).and_then(|v| v.trim().parse::<f64>().ok());
+            let (Some(mut ts), Some(price)) = (field(0), field(1)) else {
+                continue;
+            };
+            if ts > 1e11 {
+                ts /= 1000.0;
+            }
+            if price > 0.0 {
+                points.push((ts, price));
+            }
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    fn load(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        Self::parse(file)
+    }
+
+    /// Latest price at or before `ts`, if it is at most `max_age` seconds old
+    pub(super) fn price_at(&self, ts: f64, max_age: f64) -> Option<f64> {
+        let idx = self.points.partition_point(|(t, _)| *t <= ts);
+        let (t, price) = *self.points.get(idx.checked_sub(1)?)?;
+        (ts - t <= max_age).then_some(price)
+    }
+}
+
+/// One market of the hour as listed on Gamma
+#[derive(Debug, Clone)]
+pub(super) struct HourMarket {
+    pub(super) condition_id: String,
+    pub(super) slug: String,
+    pub(super) timeframe: Timeframe,
+    pub(super) start_ts: i64,
+    pub(super) end_ts: i64,
+    /// Official outcome once resolved: true when Up won
+    pub(super) up_won: Option<bool>,
+}
+
+/// Look up every window of `durations` that opens inside the hour
+pub(super) async fn resolve_hour_markets(
+    hour_start_ts: i64,
+    asset: SupportedAsset,
+    durations: &[SupportedDuration],
+    verbose: bool,
+) -> Result<Vec<HourMarket>> {
+    let gamma_client = gamma::Client::default();
+    let mut markets = Vec::new();
+
+    for duration in durations {
+        let target = MarketTarget::new(asset, *duration);
+        let mut start_ts = hour_start_ts;
+        while start_ts < hour_start_ts + 3600 {
+            let slug = format!("{}{}", target.slug_prefix(), start_ts);
+            let req = MarketBySlugRequest::builder().slug(slug.clone()).build();
+            match gamma_client.market_by_slug(&req).await {
+                Ok(market) => {
+                    if let Some(condition_id) = gamma_market_condition_id_hex(&market) {
+                        markets.push(HourMarket {
+                            condition_id,
+                            slug,
+                            timeframe: graph_timeframe(*duration),
+                            start_ts,
+                            end_ts: start_ts + duration.seconds(),
+                            up_won: None,
+                        });
+                    }
+                }
+                Err(err) => {
+                    if verbose {
+                        eprintln!("  [WARN] Slug lookup failed for {}: {}", slug, err);
+                    }
+                }
+            }
+            start_ts += duration.seconds();
+        }
+    }
+    markets.sort_by_key(|m| (m.start_ts, m.end_ts));
+    Ok(markets)
+}
+
+/// Top-of-book ticks of the hour's markets, tagged with the market index
+pub(super) fn load_hour_ticks(
+    conn: &duckdb::Connection,
+    file_path: &str,
+    markets: &[HourMarket],
+) -> Result<Vec<(usize, ReplayRow)>> {
+    let index: HashMap<&str, usize> = markets
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.condition_id.as_str(), i))
+        .collect();
+    let quoted_ids = markets
+        .iter()
+        .map(|m| format!("'{}'", m.condition_id))
+        .collect::<Vec<_>>()
+        .join(",");
+    let sql = format!(
+        "SELECT market_id, \
+                COALESCE(TRY_CAST(data->>'$.timestamp' AS DOUBLE), 0.0) as ts, \
+                COALESCE(UPPER(data->>'$.side'), '') as side, \
+                COALESCE(TRY_CAST(data->>'$.best_bid' AS DOUBLE), 0.0) as bid, \
+                COALESCE(TRY_CAST(data->>'$.best_ask' AS DOUBLE), 0.0) as ask \
+         FROM read_parquet({}) WHERE market_id IN ({}) ORDER BY ts",
+        sql_literal(file_path), quoted_ids
+    );
+
+    let mut stmt = conn.prepare(&sql).context("Failed to prepare temporal tick query")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ReplayRow {
+                    ts: row.get::<_, f64>(1)?,
+                    side: row.get::<_, String>(2)?,
+                    bid: row.get::<_, f64>(3)?,
+                    ask: row.get::<_, f64>(4)?,
+                    depth: None,
+                },
+            ))
+        })
+        .context("Failed to execute temporal tick query")?;
+
+    let mut ticks = Vec::new();
+    for row in rows {
+        let (market_id, row) = row?;
+        if let Some(&idx) = index.get(market_id.as_str()) {
+            ticks.push((idx, row));
+        }
+    }
+    Ok(ticks)
+}
+
+/// Where a market is in its lifecycle during the replay
+#[derive(Debug, Clone, Copy)]
+enum Leg {
+    Pending,
+    Open { strike: f64 },
+    /// `close` is missing when the outcome is official but the spot at
+    /// expiry was not recorded
+    Resolved { strike: f64, up: bool, close: Option<f64> },
+    /// No spot at the window open, so the market cannot be priced
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OpenArb {
+    violation_type: ViolationType,
+    direction: Direction,
+    expected_edge: f64,
+    /// Average fill price, before fees
+    entry_price: f64,
+    shares: f64,
+    /// Notional plus the taker fee
+    cost: f64,
+    entry_ts: i64,
+}
+
+#[derive(Debug, Clone)]
+struct TemporalTrade {
+    violation_type: ViolationType,
+    expected_edge: f64,
+    result: TradeResult,
+}
+
+impl TemporalTrade {
+    /// Payout minus entry price, in probability units like the expected edge
+    fn realized_edge(&self) -> f64 {
+        self.result.exit_price - self.result.entry_price
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ViolationReport {
+    violation_type: ViolationType,
+    signals: usize,
+    avg_expected_edge: f64,
+    avg_realized_edge: f64,
+    metrics: BacktestMetrics,
+}
+
+/// Constraint settings and results carried across the hours of a run
+pub(super) struct TemporalReplay {
+    constraints: ConstraintEngine,
+    config: TemporalArbitrageConfig,
+    fill_model: FillModel,
+    fill_stats: FillStats,
+    max_spot_age: i64,
+    size: f64,
+    max_positions: usize,
+    verbose: bool,
+    trades: Vec<TemporalTrade>,
+    /// Distinct (market, type, direction) violations seen
+    signals: HashMap<ViolationType, usize>,
+    /// Positions dropped because their market could not be resolved
+    unsettled: usize,
+}
+
+impl TemporalReplay {
+    pub(super) fn new(params: &TemporalArbParams, fill_model: FillModel, verbose: bool) -> Self {
+        Self {
+            constraints: params.constraints(),
+            config: params.config(),
+            fill_model,
+            fill_stats: FillStats::default(),
+            max_spot_age: params.max_spot_age,
+            size: params.size,
+            max_positions: params.max_positions,
+            verbose,
+            trades: Vec::new(),
+            signals: HashMap::new(),
+            unsettled: 0,
+        }
+    }
+
+    fn node(market: &HourMarket, leg: Leg, book: &[(f64, f64); 2]) -> Option<TemporalNode> {
+        let (strike_price, resolved) = match leg {
+            Leg::Open { strike } => (strike, None),
+            Leg::Resolved { strike, up, close } => (strike, Some((up, close))),
+            Leg::Pending | Leg::Skipped => return None,
+        };
+        let mids = book_mids(book);
+        Some(TemporalNode {
+            condition_id: market.condition_id.clone(),
+            timeframe: market.timeframe,
+            strike_price,
+            start_time: market.start_ts,
+            end_time: market.end_ts,
+            parent: None,
+            children: Vec::new(),
+            yes_price: mids.map(|(yes, _)| yes),
+            no_price: mids.map(|(_, no)| no),
+            resolved_outcome: resolved.map(|(up, _)| up),
+            close_price: resolved.and_then(|(_, close)| close),
+        })
+    }
+
+    /// Replay one hour of ticks over its markets
+    pub(super) fn run_hour(
+        &mut self,
+        markets: &[HourMarket],
+        ticks: &[(usize, ReplayRow)],
+        spot: &SpotSeries,
+    ) {
+        let Some((_, first)) = ticks.first() else {
+            return;
+        };
+        let max_age = self.max_spot_age as f64;
+        let index: HashMap<&str, usize> = markets
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.condition_id.as_str(), i))
+            .collect();
+
+        let mut builder = TemporalGraphBuilder::new();
+        let mut graph = TemporalGraph {
+            vol_estimator: VolatilityEstimator::new(self.config.base_volatility_5m, 0.94),
+            ..TemporalGraph::new()
+        };
+        let mut legs = vec![Leg::Pending; markets.len()];
+        // (bid, ask) for YES and NO
+        let mut books = vec![[(0.0, 0.0); 2]; markets.len()];
+        let mut replays: Vec<BookReplay> = (0..markets.len()).map(|_| BookReplay::default()).collect();
+        for (idx, row) in ticks {
+            if let Some(side) = row.outcome_side() {
+                replays[*idx].push(row.ts, side, BookUpdate::Quote { bid: row.bid, ask: row.ask });
+            }
+        }
+        let mut positions: Vec<Option<OpenArb>> = vec![None; markets.len()];
+        let mut seen: HashSet<(usize, ViolationType, bool)> = HashSet::new();
+        let mut cursor = spot.points.partition_point(|(t, _)| *t < first.ts - max_age);
+
+        for (idx, row) in ticks {
+            let now = row.ts.floor() as i64;
+            while let Some(&(t, price)) = spot.points.get(cursor) {
+                if t > row.ts {
+                    break;
+                }
+                graph.update_price_state(price, t.floor() as i64);
+                cursor += 1;
+            }
+            match row.outcome_side() {
+                Some(OutcomeSide::Yes) => books[*idx][0] = (row.bid, row.ask),
+                Some(OutcomeSide::No) => books[*idx][1] = (row.bid, row.ask),
+                None => {}
+            }
+
+            // Open markets whose window started, resolve those that ended
+            let mut rebuild = false;
+            for (i, market) in markets.iter().enumerate() {
+                match legs[i] {
+                    Leg::Pending if market.start_ts <= now && now < market.end_ts => {
+                        legs[i] = match spot.price_at(market.start_ts as f64, max_age) {
+                            Some(strike) => {
+                                rebuild = true;
+                                Leg::Open { strike }
+                            }
+                            None => Leg::Skipped,
+                        };
+                    }
+                    Leg::Open { strike } if now >= market.end_ts => {
+                        legs[i] = self.resolve(market, strike, positions[i].take(), spot);
+                        if let Leg::Resolved { up, close: Some(close), .. } = legs[i] {
+                            graph.mark_resolved(&market.condition_id, up, close);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if rebuild {
+                let nodes = markets
+                    .iter()
+                    .zip(&legs)
+                    .zip(&books)
+                    .filter_map(|((market, leg), book)| Self::node(market, *leg, book))
+                    .collect();
+                let price = graph.price_state.clone();
+                let vol = graph.vol_estimator.clone();
+                graph = builder.build_from_nodes(nodes, price.current_price, price.last_update);
+                graph.price_state = price;
+                graph.vol_estimator = vol;
+            }
+
+            if let (Leg::Open { .. }, Some((yes_mid, no_mid))) = (legs[*idx], book_mids(&books[*idx])) {
+                graph.update_node_price(&markets[*idx].condition_id, yes_mid, no_mid);
+            }
+            if graph.price_state.current_price <= 0.0
+                || graph.price_state.is_stale(now, self.max_spot_age)
+            {
+                continue;
+            }
+
+            let actions = self.constraints.check_all(
+                &graph,
+                now,
+                self.config.enable_chained_conditional,
+                self.config.enable_late_phase,
+                self.config.enable_price_consistency,
+            );
+            for action in actions {
+                let confidence = action.confidence();
+                let ArbitrageAction::EnterSingle { condition_id, direction, edge, reason, .. } = action else {
+                    continue;
+                };
+                if confidence < self.config.min_confidence {
+                    continue;
+                }
+                let Some(&i) = index.get(condition_id.as_str()) else {
+                    continue;
+                };
+                let violation_type = reason.violation_type();
+                if seen.insert((i, violation_type, direction == Direction::Yes)) {
+                    *self.signals.entry(violation_type).or_default() += 1;
+                }
+
+                let open = positions.iter().filter(|p| p.is_some()).count();
+                if positions[i].is_some() || open >= self.max_positions {
+                    continue;
+                }
+                let (side, ask) = match direction {
+                    Direction::Yes => (TokenSide::Yes, books[i][0].1),
+                    Direction::No => (TokenSide::No, books[i][1].1),
+                };
+                if !(ask > 0.0 && ask < 1.0) {
+                    continue;
+                }
+                let mut fills = BookFills::new(&self.fill_model, Some(&mut replays[i]), row.ts, &mut self.fill_stats);
+                let Some(fill) = fills.buy(side, ask, self.size) else {
+                    continue;
+                };
+                positions[i] = Some(OpenArb {
+                    violation_type,
+                    direction,
+                    expected_edge: edge,
+                    entry_price: fill.avg_price,
+                    shares: fill.shares,
+                    cost: fill.notional + fill.fee,
+                    entry_ts: now,
+                });
+                if self.verbose {
+                    println!(
+                        "  [ENTRY] {} | {:?} @ {:.4} | {} edge {:.3}",
+                        markets[i].slug, direction, fill.avg_price, violation_type, edge
+                    );
+                }
+            }
+        }
+
+        // Markets still open when the ticks ran out resolve from the spot at expiry
+        for (i, market) in markets.iter().enumerate() {
+            if let Leg::Open { strike } = legs[i] {
+                self.resolve(market, strike, positions[i].take(), spot);
+            }
+        }
+    }
+
+    /// Resolve a market from its official outcome, else from the spot at
+    /// expiry against the strike, and settle any position held in it
+    fn resolve(
+        &mut self,
+        market: &HourMarket,
+        strike: f64,
+        position: Option<OpenArb>,
+        spot: &SpotSeries,
+    ) -> Leg {
+        let close = spot.price_at(market.end_ts as f64, self.max_spot_age as f64);
+        let Some(up) = market.up_won.or(close.map(|c| c >= strike)) else {
+            if position.is_some() {
+                self.unsettled += 1;
+                eprintln!("[warn] {} has no outcome or closing spot; position dropped", market.slug);
+            }
+            return Leg::Skipped;
+        };
+
+        if let Some(position) = position {
+            let won = (position.direction == Direction::Yes) == up;
+            let exit_price = if won { 1.0 } else { 0.0 };
+            let pnl_usd = position.shares * exit_price - position.cost;
+            if self.verbose {
+                println!(
+                    "  [SETTLE] {} | {:?} {} | PnL ${:.2}",
+                    market.slug,
+                    position.direction,
+                    if won { "won" } else { "lost" },
+                    pnl_usd
+                );
+            }
+            self.trades.push(TemporalTrade {
+                violation_type: position.violation_type,
+                expected_edge: position.expected_edge,
+                result: TradeResult {
+                    market_slug: market.slug.clone(),
+                    side: match position.direction {
+                        Direction::Yes => "YES".to_string(),
+                        Direction::No => "NO".to_string(),
+                    },
+                    entry_price: position.entry_price,
+                    exit_price,
+                    pnl_percent: pnl_usd / position.cost,
+                    pnl_usd,
+                    duration_seconds: market.end_ts - position.entry_ts,
+                    entry_timestamp: position.entry_ts,
+                    exit_timestamp: market.end_ts,
+                },
+            });
+        }
+        Leg::Resolved { strike, up, close }
+    }
+
+    fn reports(&self, capital: f64) -> Vec<ViolationReport> {
+        VIOLATION_TYPES
+            .iter()
+            .map(|violation_type| {
+                let trades: Vec<&TemporalTrade> = self
+                    .trades
+                    .iter()
+                    .filter(|t| t.violation_type == *violation_type)
+                    .collect();
+                let mean = |f: fn(&TemporalTrade) -> f64| {
+                    if trades.is_empty() {
+                        0.0
+                    } else {
+                        trades.iter().map(|t| f(t)).sum::<f64>() / trades.len() as f64
+                    }
+                };
+                ViolationReport {
+                    violation_type: *violation_type,
+                    signals: self.signals.get(violation_type).copied().unwrap_or(0),
+                    avg_expected_edge: mean(|t| t.expected_edge),
+                    avg_realized_edge: mean(TemporalTrade::realized_edge),
+                    metrics: BacktestMetrics::from_trades(
+                        trades.iter().map(|t| t.result.clone()).collect(),
+                        capital,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    fn finish(mut self, capital: f64, hours: usize, export: Option<&str>) -> Result<()> {
+        self.trades.sort_by_key(|t| t.result.exit_timestamp);
+        let overall = BacktestMetrics::from_trades(
+            self.trades.iter().map(|t| t.result.clone()).collect(),
+            capital,
+        );
+        let reports = self.reports(capital);
+
+        overall.print_summary();
+        println!("\n============ EDGE BY VIOLATION TYPE ============");
+        println!(
+            "{:<20} {:>7} {:>6} {:>7} {:>9} {:>9} {:>9}",
+            "Type", "Signals", "Trades", "Win %", "Exp Edge", "Real Edge", "PnL $"
+        );
+        for report in &reports {
+            let metrics = &report.metrics;
+            println!(
+                "{:<20} {:>7} {:>6} {:>6.1}% {:>9.4} {:>9.4} {:>9.2}",
+                report.violation_type.to_string(),
+                report.signals,
+                metrics.total_trades,
+                metrics.win_rate,
+                report.avg_expected_edge,
+                report.avg_realized_edge,
+                metrics.ending_capital - metrics.starting_capital
+            );
+        }
+        if self.unsettled > 0 {
+            println!("Unsettled positions (no outcome): {}", self.unsettled);
+        }
+        if !self.fill_model.is_frictionless() {
+            println!("------------------------------------------------");
+            println!("Fill Model:  {:?} (+{}ms latency)", self.fill_model.kind, self.fill_model.latency_ms);
+            println!("Fees Paid:   ${:.4}", self.fill_stats.fees_paid);
+            println!("Missed:      {}", self.fill_stats.missed_entries);
+        }
+        println!("================================================");
+
+        if let Some(path) = export {
+            #[derive(Serialize)]
+            struct ExportResult {
+                hours_processed: usize,
+                unsettled: usize,
+                fills: FillStats,
+                overall: BacktestMetrics,
+                by_violation: Vec<ViolationReport>,
+            }
+            let result = ExportResult {
+                hours_processed: hours,
+                unsettled: self.unsettled,
+                fills: self.fill_stats,
+                overall,
+                by_violation: reports,
+            };
+            let file = std::fs::File::create(path)?;
+            serde_json::to_writer_pretty(file, &result)?;
+            println!("[EXPORT] Results written to {}", path);
+        }
+        Ok(())
+    }
+}
+
+/// YES and NO midpoints once both sides have a two-sided book
+fn book_mids(book: &[(f64, f64); 2]) -> Option<(f64, f64)> {
+    let mid = |(bid, ask): (f64, f64)| (bid > 0.0 && ask > 0.0).then(|| (bid + ask) / 2.0);
+    Some((mid(book[0])?, mid(book[1])?))
+}
+
+/// Hourly PMXT parquet files in `input_dir` with their hour, oldest first
+pub(super) fn hourly_parquet_files(input_dir: &str) -> Result<Vec<(String, i64)>> {
+    let dir = Path::new(input_dir);
+    if !dir.is_dir() {
+        anyhow::bail!("Not a directory: {}", input_dir);
+    }
+
+    let mut input_files: Vec<(String, i64)> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("parquet") {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        if let Some(hour_ts) = extract_hour_from_filename(&path_str) {
+            input_files.push((path_str, hour_ts));
+        }
+    }
+    if input_files.is_empty() {
+        anyhow::bail!("No hourly parquet files found in {}", input_dir);
+    }
+    input_files.sort_by_key(|(_, ts)| *ts);
+    Ok(input_files)
+}
+
+pub async fn run_backtest_temporal(args: BacktestTemporalArgs) -> Result<()> {
+    let durations = args.params.nested_durations()?;
+    let input_files = hourly_parquet_files(&args.input_dir)?;
+
+    let spot = SpotSeries::load(&args.spot)?;
+    if spot.points.is_empty() {
+        anyhow::bail!("No spot prices found in {}", args.spot);
+    }
+    let config = args.params.config();
+    println!("[BACKTEST-TEMPORAL] Found {} hourly files", input_files.len());
+    println!(
+        "[BACKTEST-TEMPORAL] Windows: {} | min edge {:.3} | max spread {:.3} | base vol ${:.0}/5m",
+        durations.iter().map(|d| d.as_str()).collect::<Vec<_>>().join(","),
+        config.min_edge,
+        config.max_spread,
+        config.base_volatility_5m
+    );
+    println!("[BACKTEST-TEMPORAL] Spot samples: {}", spot.points.len());
+
+    let conn = duckdb::Connection::open_in_memory()?;
+    conn.execute_batch("INSTALL httpfs; LOAD httpfs; PRAGMA threads=4;")?;
+
+    let mut resolver = args.resolution.resolver()?;
+    if let Some(resolver) = &resolver {
+        println!(
+            "[BACKTEST-TEMPORAL] Official outcomes: {} ({} cached)",
+            args.resolution.resolutions_db,
+            resolver.resolutions().len()
+        );
+    }
+
+    let mut replay = TemporalReplay::new(&args.params, args.fills.model(), args.verbose);
+    for (file_num, (file_path, hour_ts)) in input_files.iter().enumerate() {
+        let label = chrono::DateTime::from_timestamp(*hour_ts, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| format!("ts={}", hour_ts));
+        println!("\n[FILE {}/{}] {} — {}", file_num + 1, input_files.len(), label, file_path);
+
+        let mut markets = match resolve_hour_markets(*hour_ts, SupportedAsset::Btc, &durations, args.verbose).await {
+            Ok(markets) => markets,
+            Err(e) => {
+                eprintln!("  [WARN] Market lookup failed for {}: {}", file_path, e);
+                continue;
+            }
+        };
+        if markets.len() < 2 {
+            println!("  Fewer than two markets found for this hour");
+            continue;
+        }
+        if let Some(resolver) = resolver.as_mut() {
+            resolver.resolve(markets.iter().map(|m| m.condition_id.as_str())).await?;
+            for market in &mut markets {
+                market.up_won = resolver.resolutions().yes_won(&market.condition_id);
+            }
+        }
+        let ticks = match load_hour_ticks(&conn, file_path, &markets) {
+            Ok(ticks) => ticks,
+            Err(e) => {
+                eprintln!("  [WARN] Query failed for {}: {}", file_path, e);
+                continue;
+            }
+        };
+        let trades_before = replay.trades.len();
+        replay.run_hour(&markets, &ticks, &spot);
+        println!(
+            "  {} markets | {} ticks | {} trades",
+            markets.len(),
+            ticks.len(),
+            replay.trades.len() - trades_before
+        );
+    }
+
+    replay.finish(args.capital, input_files.len(), args.export.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> TemporalArbParams {
+        TemporalArbParams {
+            durations: vec![SupportedDuration::M5, SupportedDuration::M15],
+            min_edge: 0.05,
+            max_spread: 0.03,
+            min_confidence: 0.5,
+            base_vol_5m: 250.0,
+            no_chained: false,
+            no_late_phase: false,
+            no_consistency: false,
+            max_spot_age: 10,
+            size: 1.0,
+            max_positions: 3,
+        }
+    }
+
+    fn market(id: &str, timeframe: Timeframe, start_ts: i64, up_won: Option<bool>) -> HourMarket {
+        HourMarket {
+            condition_id: id.to_string(),
+            slug: format!("btc-updown-{}-{}", timeframe, start_ts),
+            timeframe,
+            start_ts,
+            end_ts: start_ts + timeframe.duration_secs(),
+            up_won,
+        }
+    }
+
+    fn tick(idx: usize, ts: f64, side: &str, bid: f64, ask: f64) -> (usize, ReplayRow) {
+        (idx, ReplayRow { ts, side: side.to_string(), bid, ask, depth: None })
+    }
+
+    #[test]
+    fn spot_lookup_normalizes_and_respects_age() {
+        let spot = SpotSeries::parse(
+            "timestamp,price\n1700000001000,71010\n1700000000,71000\n1700000005,bad\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(spot.points.len(), 2);
+        assert_eq!(spot.price_at(1_700_000_000.5, 10.0), Some(71_000.0));
+        assert_eq!(spot.price_at(1_700_000_008.0, 10.0), Some(71_010.0));
+        assert_eq!(spot.price_at(1_700_000_030.0, 10.0), None);
+        assert_eq!(spot.price_at(1_699_999_999.0, 10.0), None);
+    }
+
+    #[test]
+    fn official_outcome_settles_without_closing_spot() {
+        let start = 1_700_000_100;
+        let spot = SpotSeries::parse(format!("timestamp,price\n{},71000\n", start).as_bytes()).unwrap();
+        let mut replay = TemporalReplay::new(&params(), FillModel::default(), false);
+        let position = OpenArb {
+            violation_type: ViolationType::LatePhaseAnomaly,
+            direction: Direction::No,
+            expected_edge: 0.1,
+            entry_price: 0.4,
+            shares: 2.5,
+            cost: 1.0,
+            entry_ts: start,
+        };
+
+        let m5 = market("m5", Timeframe::M5, start, Some(false));
+        let leg = replay.resolve(&m5, 71_000.0, Some(position), &spot);
+        assert!(matches!(leg, Leg::Resolved { up: false, close: None, .. }));
+        assert_eq!(replay.trades.len(), 1);
+        assert!((replay.trades[0].result.pnl_usd - 1.5).abs() < 1e-9);
+
+        let unknown = market("m15", Timeframe::M15, start, None);
+        assert!(matches!(replay.resolve(&unknown, 71_000.0, Some(position), &spot), Leg::Skipped));
+        assert_eq!(replay.unsettled, 1);
+    }
+
+    #[test]
+    fn mispriced_market_is_traded_and_settled_by_type() {
+        // Spot runs $600 above both strikes while the 15m market sits at 0.50
+        let start = 1_700_000_100;
+        let markets = vec![
+            market("m15", Timeframe::M15, start, Some(true)),
+            market("m5", Timeframe::M5, start, Some(true)),
+        ];
+        let mut csv = String::from("timestamp,price\n");
+        for t in 0..=900 {
+            let price = if t < 60 { 71_000.0 } else { 71_600.0 };
+            csv.push_str(&format!("{},{}\n", start + t, price));
+        }
+        let spot = SpotSeries::parse(csv.as_bytes()).unwrap();
+
+        let mut ticks = Vec::new();
+        for t in (0..900).step_by(5) {
+            let ts = (start + t) as f64;
+            ticks.push(tick(0, ts, "YES", 0.49, 0.51));
+            ticks.push(tick(0, ts, "NO", 0.49, 0.51));
+            if t < 300 {
+                ticks.push(tick(1, ts, "YES", 0.49, 0.51));
+                ticks.push(tick(1, ts, "NO", 0.49, 0.51));
+            }
+        }
+        ticks.push(tick(0, (start + 901) as f64, "YES", 0.99, 1.0));
+
+        let mut replay = TemporalReplay::new(&params(), FillModel::default(), false);
+        replay.run_hour(&markets, &ticks, &spot);
+
+        assert_eq!(replay.trades.len(), 2);
+        assert_eq!(replay.unsettled, 0);
+        for trade in &replay.trades {
+            assert_eq!(trade.violation_type, ViolationType::PriceConsistency);
+            assert_eq!(trade.result.side, "YES");
+            assert!((trade.realized_edge() - 0.49).abs() < 1e-9);
+        }
+        let reports = replay.reports(5.0);
+        let consistency = &reports[2];
+        assert_eq!(consistency.metrics.total_trades, 2);
+        assert!(consistency.signals >= 2);
+        assert!(consistency.avg_expected_edge > 0.05);
+        assert_eq!(reports[0].metrics.total_trades, 0);
+    }
+}
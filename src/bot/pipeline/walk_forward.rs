@@ -0,0 +1,258 @@
+//! Walk-Forward Validation
+//!
+//! Splits the PMXT directory by time into rolling (or anchored) train/test
+//! windows. Each fold searches the strategy config on its train window,
+//! replays the best config on the following test window, and reports the
+//! out-of-sample metrics next to the in-sample ones.
+
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use serde_json::Value;
+use std::ops::Range;
+
+use super::optimize::{evaluate, load_markets_by_file, search, SearchArgs};
+use super::{pmxt_inputs, BacktestPmxtArgs, SessionSummary};
+
+#[derive(Args, Clone)]
+pub struct WalkForwardArgs {
+    #[command(flatten)]
+    pub backtest: BacktestPmxtArgs,
+
+    /// Config search run on each train window; without --param the base
+    /// config is evaluated as is
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    /// Train window length in hours
+    #[arg(long, default_value = "24")]
+    pub train_hours: i64,
+
+    /// Test window length in hours
+    #[arg(long, default_value = "6")]
+    pub test_hours: i64,
+
+    /// Hours between fold starts (default: test window)
+    #[arg(long)]
+    pub step_hours: Option<i64>,
+
+    /// Keep every train window starting at the first file (expanding window)
+    #[arg(long)]
+    pub anchored: bool,
+}
+
+/// Input file index ranges of one fold
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fold {
+    train_start: i64,
+    test_start: i64,
+    test_end: i64,
+    train: Range<usize>,
+    test: Range<usize>,
+}
+
+/// Folds over sorted file timestamps; folds with an empty train or test
+/// window are skipped and the last test window may be partial
+fn walk_forward_folds(timestamps: &[i64], train_secs: i64, test_secs: i64, step_secs: i64, anchored: bool) -> Vec<Fold> {
+    let (Some(&first), Some(&last)) = (timestamps.first(), timestamps.last()) else {
+        return Vec::new();
+    };
+    let index = |t: i64| timestamps.partition_point(|ts| *ts < t);
+    let mut folds = Vec::new();
+    for k in 0.. {
+        let offset = first + k * step_secs.max(1);
+        let train_start = if anchored { first } else { offset };
+        let test_start = offset + train_secs;
+        if test_start > last {
+            break;
+        }
+        let test_end = test_start + test_secs;
+        let fold = Fold {
+            train_start,
+            test_start,
+            test_end,
+            train: index(train_start)..index(test_start),
+            test: index(test_start)..index(test_end),
+        };
+        if !fold.train.is_empty() && !fold.test.is_empty() {
+            folds.push(fold);
+        }
+    }
+    folds
+}
+
+#[derive(Debug, Serialize)]
+struct FoldResult {
+    fold: usize,
+    train_start: i64,
+    test_start: i64,
+    test_end: i64,
+    params: Value,
+    in_sample_score: f64,
+    out_of_sample_score: f64,
+    efficiency: Option<f64>,
+    in_sample: SessionSummary,
+    out_of_sample: SessionSummary,
+}
+
+fn hour_label(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| format!("ts={}", ts))
+}
+
+pub async fn run_walk_forward(args: WalkForwardArgs) -> Result<()> {
+    let base_config = args.backtest.strategy.config()?;
+    let base = serde_json::to_value(&base_config)?;
+    let space = (!args.search.params.is_empty()).then(|| args.search.space(&base)).transpose()?;
+    let objective = args.search.objective;
+
+    let input_files = pmxt_inputs(&args.backtest)?;
+    let timestamps: Vec<i64> = input_files.iter().map(|(_, ts, _)| *ts).collect();
+    let folds = walk_forward_folds(
+        &timestamps,
+        args.train_hours * 3600,
+        args.test_hours * 3600,
+        args.step_hours.unwrap_or(args.test_hours) * 3600,
+        args.anchored,
+    );
+    if folds.is_empty() {
+        anyhow::bail!(
+            "{} files do not span a {}h train + test window",
+            input_files.len(),
+            args.train_hours
+        );
+    }
+    println!("[WALK-FORWARD] Strategy: {} ({})", base_config.kind(), base_config.describe());
+    println!("[WALK-FORWARD] {} folds: {}h train / {}h test{}, objective {}, {}",
+        folds.len(), args.train_hours, args.test_hours, if args.anchored { " (anchored)" } else { "" },
+        objective.as_str(),
+        match &space {
+            Some(space) => format!("{} search over {} params", args.search.method.as_str(), space.dims()),
+            None => "no search".to_string(),
+        });
+
+    // Markets stay in file order, so a fold's window is one contiguous slice
+    let mut markets = Vec::new();
+    let mut offsets = vec![0];
+    for file_markets in load_markets_by_file(&args.backtest, &input_files).await? {
+        markets.extend(file_markets);
+        offsets.push(markets.len());
+    }
+    let jobs = args.search.jobs();
+
+    let mut results = Vec::with_capacity(folds.len());
+    for (n, fold) in folds.iter().enumerate() {
+        let train = &markets[offsets[fold.train.start]..offsets[fold.train.end]];
+        let test = &markets[offsets[fold.test.start]..offsets[fold.test.end]];
+        println!("\n[FOLD {}/{}] train {} → {} ({} markets) | test → {} ({} markets)",
+            n + 1, folds.len(), hour_label(fold.train_start), hour_label(fold.test_start), train.len(),
+            hour_label(fold.test_end), test.len());
+        if train.is_empty() || test.is_empty() {
+            eprintln!("  [WARN] Skipping fold without markets");
+            continue;
+        }
+
+        let (config, params, in_sample) = match &space {
+            Some(space) => {
+                let trials = search(space, &base, &args.search, &args.backtest, train, |trial, total| {
+                    if args.backtest.verbose {
+                        println!("  [TRIAL {}/{}] score {:>8.4} | {}",
+                            trial.trial, total, trial.score, space.describe(&trial.params));
+                    }
+                    Ok(())
+                })?;
+                let Some(best) = trials.into_iter().max_by(|a, b| a.score.total_cmp(&b.score)) else {
+                    continue;
+                };
+                (best.config, space.describe_json(&best.params), best.summary)
+            }
+            None => {
+                let summary = tokio::task::block_in_place(|| evaluate(vec![base_config.clone()], &args.backtest, train, jobs))?;
+                (base_config.clone(), Value::Null, summary[0])
+            }
+        };
+        let out_of_sample = tokio::task::block_in_place(|| evaluate(vec![config], &args.backtest, test, jobs))?[0];
+
+        let result = FoldResult {
+            fold: n + 1,
+            train_start: fold.train_start,
+            test_start: fold.test_start,
+            test_end: fold.test_end,
+            in_sample_score: objective.score(&in_sample),
+            out_of_sample_score: objective.score(&out_of_sample),
+            efficiency: objective.efficiency(&in_sample, &out_of_sample),
+            params,
+            in_sample,
+            out_of_sample,
+        };
+        println!("  IS  score {:>8.4} | {:>4} trades | PnL ${:>8.2} | Sharpe {:>6.3}",
+            result.in_sample_score, in_sample.trades, in_sample.total_pnl, in_sample.sharpe);
+        println!("  OOS score {:>8.4} | {:>4} trades | PnL ${:>8.2} | Sharpe {:>6.3} | efficiency {}",
+            result.out_of_sample_score, out_of_sample.trades, out_of_sample.total_pnl, out_of_sample.sharpe,
+            result.efficiency.map_or("-".to_string(), |e| format!("{:.2}", e)));
+        if !result.params.is_null() {
+            println!("  params {}", result.params);
+        }
+        results.push(result);
+    }
+
+    if results.is_empty() {
+        anyhow::bail!("No fold produced a result");
+    }
+    let folds_run = results.len() as f64;
+    let oos_pnl: f64 = results.iter().map(|r| r.out_of_sample.total_pnl).sum();
+    let is_pnl: f64 = results.iter().map(|r| r.in_sample.total_pnl).sum();
+    let oos_trades: usize = results.iter().map(|r| r.out_of_sample.trades).sum();
+    let oos_wins: f64 = results.iter().map(|r| r.out_of_sample.win_rate * r.out_of_sample.trades as f64).sum();
+    let profitable = results.iter().filter(|r| r.out_of_sample.total_pnl > 0.0).count();
+    let finite_mean = |values: Vec<f64>| {
+        let finite: Vec<f64> = values.into_iter().filter(|v| v.is_finite()).collect();
+        (!finite.is_empty()).then(|| finite.iter().sum::<f64>() / finite.len() as f64)
+    };
+    let mean_is = finite_mean(results.iter().map(|r| r.in_sample_score).collect());
+    let mean_oos = finite_mean(results.iter().map(|r| r.out_of_sample_score).collect());
+    let mean_efficiency = finite_mean(results.iter().filter_map(|r| r.efficiency).collect());
+    let fmt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.4}", v));
+
+    println!("\n================ WALK-FORWARD SUMMARY ({}) ================", objective.as_str());
+    println!("Folds:                 {}", results.len());
+    println!("Mean IS Score:         {}", fmt(mean_is));
+    println!("Mean OOS Score:        {}", fmt(mean_oos));
+    println!("Mean Efficiency:       {}", fmt(mean_efficiency));
+    println!("IS PnL (sum):          ${:.2}", is_pnl);
+    println!("OOS PnL (sum):         ${:.2}", oos_pnl);
+    println!("OOS Trades:            {}", oos_trades);
+    println!("OOS Win Rate:          {:.1}%", if oos_trades > 0 { oos_wins / oos_trades as f64 * 100.0 } else { 0.0 });
+    println!("Profitable OOS Folds:  {} / {} ({:.0}%)", profitable, results.len(), profitable as f64 / folds_run * 100.0);
+    println!("===========================================================");
+
+    if let Some(path) = &args.backtest.export {
+        std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+        println!("[WALK-FORWARD] Exported folds to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_and_anchored_folds() {
+        let hours: Vec<i64> = (0..10).map(|h| h * 3600).collect();
+
+        let rolling = walk_forward_folds(&hours, 4 * 3600, 2 * 3600, 2 * 3600, false);
+        let ranges: Vec<_> = rolling.iter().map(|f| (f.train.clone(), f.test.clone())).collect();
+        assert_eq!(ranges, vec![(0..4, 4..6), (2..6, 6..8), (4..8, 8..10)]);
+
+        let anchored = walk_forward_folds(&hours, 4 * 3600, 2 * 3600, 2 * 3600, true);
+        let trains: Vec<_> = anchored.iter().map(|f| f.train.clone()).collect();
+        assert_eq!(trains, vec![0..4, 0..6, 0..8]);
+
+        // A partial final test window still counts
+        let partial = walk_forward_folds(&hours, 6 * 3600, 3 * 3600, 3 * 3600, false);
+        assert_eq!(partial.last().unwrap().test, 9..10);
+        assert!(walk_forward_folds(&hours, 12 * 3600, 3600, 3600, false).is_empty());
+    }
+}
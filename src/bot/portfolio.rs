@@ -0,0 +1,468 @@
+//! Portfolio Runner
+//!
+//! Drives several Up/Down markets concurrently from one process. Each market
+//! slot runs its own `StrategyDriver` for the `--strategy` chosen and rolls over on its own
+//! schedule, while all slots share one `GatekeeperState` and `ExposureBudget`
+//! so daily loss limits, total exposure and per-asset caps hold across the
+//! whole book.
+
+use crate::auth;
+use crate::bot::discovery::{discover_market_loop, MarketTarget, WatchedMarket};
+use crate::bot::execution::{
+    apply_settlement_event, get_usdc_balance, handle_live_signals, open_state_store,
+    recover_positions, try_settle_pending, LivePosition, PendingSettlement,
+};
+use crate::bot::feed::multi_market_feed::Timeframe;
+use crate::bot::feed::{DualSnapshot, MarketSubscription, MultiMarketWebsocketFeed, UserWebsocketFeed};
+use crate::bot::logging::{EngineEvent, EngineEventLoggers};
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+use crate::bot::risk::{best_ask_price, best_bid_price, ExposureBudget, GatekeeperState};
+use crate::bot::signal::EntrySignal;
+use crate::bot::sizing::{PositionSizer, SizingArgs};
+use crate::bot::strategy::{StrategyArgs, StrategyConfig};
+use crate::bot::strategy_runner::StrategyDriver;
+use crate::persistence::StateStore;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Args;
+use polymarket_client_sdk::clob;
+use polymarket_client_sdk::gamma;
+use std::collections::HashMap;
+use tokio::task::JoinSet;
+use tokio::time::{Duration, MissedTickBehavior, interval};
+
+#[derive(Args, Clone)]
+pub struct PortfolioArgs {
+    /// Market families to trade as ASSET:DURATION, comma separated (e.g. btc:5m,eth:15m)
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub markets: Vec<MarketTarget>,
+
+    /// Position size in USDC per trade
+    #[arg(long, default_value = "1.0")]
+    pub size: f64,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+
+    /// Dry run mode - show signals but don't place orders
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Optional structured event log path or directory
+    #[arg(long)]
+    pub event_log: Option<String>,
+
+    /// Daily realized loss limit in USD across all markets
+    #[arg(long, default_value = "5.0")]
+    pub daily_loss_limit: f64,
+
+    /// Cooldown duration after a losing trade
+    #[arg(long, default_value = "15")]
+    pub cooldown_seconds: u64,
+
+    /// Start the engine in an emergency-halted state
+    #[arg(long)]
+    pub emergency_halt: bool,
+
+    /// Maximum open exposure in USDC across all markets (0 = unlimited)
+    #[arg(long, default_value = "5.0")]
+    pub max_total_exposure: f64,
+
+    /// Maximum open exposure in USDC per underlying asset (0 = unlimited)
+    #[arg(long, default_value = "2.0")]
+    pub max_asset_exposure: f64,
+
+    /// SQLite database for crash-safe position and trade persistence
+    #[arg(long, default_value = "bot_state.db")]
+    pub state_db: String,
+}
+
+/// Per-market strategy state
+struct MarketSlot {
+    target: MarketTarget,
+    watched: WatchedMarket,
+    position: LivePosition,
+    driver: StrategyDriver,
+    latest: Option<DualSnapshot>,
+    /// Market ended and discovery of the next one is in flight
+    rolling: bool,
+}
+
+impl MarketSlot {
+    fn new(target: MarketTarget, watched: WatchedMarket, strategy: &StrategyConfig) -> Self {
+        let mut driver = StrategyDriver::new(strategy);
+        if let Some(spec) = watched.research_spec() {
+            driver.begin_market(spec);
+        }
+        Self {
+            target,
+            watched,
+            position: LivePosition::default(),
+            driver,
+            latest: None,
+            rolling: false,
+        }
+    }
+
+    /// Key the multi-market feed reports this market's events under
+    fn feed_key(&self) -> String {
+        self.watched
+            .condition_id
+            .clone()
+            .unwrap_or_else(|| self.watched.slug.clone())
+    }
+
+    fn subscription(&self) -> MarketSubscription {
+        MarketSubscription {
+            condition_id: self.feed_key(),
+            yes_token_id: self.watched.yes_token_id.to_string(),
+            no_token_id: self.watched.no_token_id.to_string(),
+            timeframe: match self.target.duration {
+                SupportedDuration::M5 => Timeframe::M5,
+                SupportedDuration::M15 => Timeframe::M15,
+                SupportedDuration::H1 => Timeframe::H1,
+            },
+            start_time: self.watched.start_ts(),
+            end_time: self.watched.end_time.timestamp(),
+            strike_price: 0.0,
+        }
+    }
+
+    fn roll_over(&mut self, watched: WatchedMarket) {
+        self.watched = watched;
+        self.position.full_reset();
+        self.driver.reset();
+        if let Some(spec) = self.watched.research_spec() {
+            self.driver.begin_market(spec);
+        }
+        self.latest = None;
+        self.rolling = false;
+    }
+}
+
+async fn connect_feed(
+    slots: &[MarketSlot],
+) -> Result<(MultiMarketWebsocketFeed, HashMap<String, usize>)> {
+    let subscriptions = slots.iter().map(MarketSlot::subscription).collect();
+    let index = slots
+        .iter()
+        .enumerate()
+        .map(|(i, slot)| (slot.feed_key(), i))
+        .collect();
+    let feed = MultiMarketWebsocketFeed::connect(subscriptions, None)
+        .await
+        .context("Failed to connect multi-market feed")?;
+    Ok((feed, index))
+}
+
+pub async fn run_portfolio(args: PortfolioArgs) -> Result<()> {
+    let mut targets: Vec<MarketTarget> = Vec::new();
+    for target in &args.markets {
+        if !targets.contains(target) {
+            targets.push(*target);
+        }
+    }
+    if targets.is_empty() {
+        anyhow::bail!("--markets must name at least one ASSET:DURATION pair");
+    }
+
+    let signer = auth::resolve_signer(None)?;
+    let clob_client = auth::authenticate_with_signer(&signer, None).await?;
+    let gamma_client = gamma::Client::default();
+    let read_client = clob::Client::default();
+    let event_loggers = args
+        .event_log
+        .as_deref()
+        .map(EngineEventLoggers::new)
+        .transpose()
+        .context("Failed to create structured event logs")?;
+
+    let balance = get_usdc_balance(&clob_client).await?;
+    println!("[PORTFOLIO] USDC Balance: ${:.2}", balance);
+    if balance < args.size {
+        anyhow::bail!("Insufficient USDC balance: ${:.2} < ${:.2}", balance, args.size);
+    }
+
+    let strategy = args.strategy.config()?;
+    let mut slots = Vec::with_capacity(targets.len());
+    for target in targets {
+        let watched = discover_market_loop(&gamma_client, target).await;
+        slots.push(MarketSlot::new(target, watched, &strategy));
+    }
+    let (mut feed, mut feed_index) = connect_feed(&slots).await?;
+    let mut discoveries: JoinSet<(usize, WatchedMarket)> = JoinSet::new();
+
+    let mut gatekeeper = GatekeeperState::new(args.daily_loss_limit, args.cooldown_seconds);
+    if args.emergency_halt {
+        gatekeeper.halt();
+    }
+    let mut budget = ExposureBudget::new(args.max_total_exposure, args.max_asset_exposure);
+    let sizer = PositionSizer::new(args.size, &args.sizing);
+    let mut pending_settlements: Vec<PendingSettlement> = Vec::new();
+
+    // Dry runs never hold real tokens, so they skip persistence entirely
+    let state_store = if args.dry_run {
+        None
+    } else {
+        let now = Utc::now();
+        let store = open_state_store(&args.state_db, balance, &mut gatekeeper, now)?;
+
+        let mut markets: Vec<(&WatchedMarket, &mut LivePosition)> = slots
+            .iter_mut()
+            .map(|slot| (&slot.watched, &mut slot.position))
+            .collect();
+        let recovered = recover_positions(
+            &store,
+            &clob_client,
+            &mut markets,
+            &mut pending_settlements,
+            now,
+        )
+        .await
+        .context("Failed to reconcile persisted positions")?;
+        if recovered > 0 {
+            println!("[PORTFOLIO] Recovered {} persisted position(s)", recovered);
+        }
+        Some(store)
+    };
+    let state_store_ref = state_store.as_ref().map(|s| s as &dyn StateStore);
+    // Settlement sells are confirmed by the fills the user channel pushes
+    let mut user_feed = if args.dry_run {
+        None
+    } else {
+        Some(
+            UserWebsocketFeed::connect(clob_client.credentials(), Vec::new(), None)
+                .await
+                .context("Failed to subscribe to the user channel")?,
+        )
+    };
+
+    // Recovered positions count against the budget until they are sold
+    for slot in &slots {
+        if slot.position.is_active() {
+            budget.open(
+                &slot.watched.slug,
+                slot.target.asset,
+                slot.position.shares * slot.position.entry_price,
+            );
+        }
+    }
+    for p in &pending_settlements {
+        if let Some(asset) = SupportedAsset::from_slug(&p.market_slug) {
+            budget.open(&p.market_slug, asset, p.shares * p.entry_price);
+        }
+    }
+
+    let mut ticker = interval(Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    if args.dry_run {
+        println!("[PORTFOLIO *** DRY RUN ***] No orders will be placed");
+    }
+    println!("[PORTFOLIO] Strategy: {} ({})", strategy.kind(), strategy.describe());
+    for slot in &slots {
+        println!("[PORTFOLIO] {} | {}", slot.target, slot.watched.slug);
+    }
+    println!(
+        "[PORTFOLIO] Size: {} | Max exposure: ${:.2} total, ${:.2} per asset | Daily loss limit: ${:.2}",
+        sizer.describe(), args.max_total_exposure, args.max_asset_exposure, args.daily_loss_limit
+    );
+    println!("========================================");
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n[PORTFOLIO] Stopping bot...");
+                for slot in &slots {
+                    if slot.position.is_active() {
+                        println!("[PORTFOLIO] WARNING: {} position still open! Manual exit required.", slot.watched.slug);
+                    }
+                }
+                if !pending_settlements.is_empty() {
+                    println!("[PORTFOLIO] WARNING: {} pending settlements require manual resolution!", pending_settlements.len());
+                    for p in &pending_settlements {
+                        println!("  - {} | {:?} | {} shares", p.market_slug, p.token_side, p.shares);
+                    }
+                }
+                if state_store.is_some() && budget.total_exposure() > 0.0 {
+                    println!("[PORTFOLIO] Open positions are persisted in {} and will be recovered on restart.", args.state_db);
+                }
+                break;
+            }
+            Some(event) = feed.recv() => {
+                if !event.is_valid() {
+                    continue;
+                }
+                if let Some(&idx) = feed_index.get(&event.condition_id) {
+                    slots[idx].latest = Some(event.to_dual_snapshot());
+                }
+            }
+            Some(discovered) = discoveries.join_next() => {
+                let (idx, watched) = discovered.context("Market discovery task failed")?;
+                let slot = &mut slots[idx];
+                slot.roll_over(watched);
+                feed.add_subscription(slot.subscription()).await?;
+                feed_index.insert(slot.feed_key(), idx);
+                println!("[MARKET RESET] {} | {}", slot.target, slot.watched.slug);
+                println!("========================================");
+            }
+            _ = ticker.tick() => {
+                let now = Utc::now();
+                let epoch_seconds = now.timestamp() as u64;
+
+                if let Some(feed) = user_feed.as_mut() {
+                    while let Some(event) = feed.try_recv() {
+                        apply_settlement_event(&mut pending_settlements, &event);
+                    }
+                }
+                if !pending_settlements.is_empty() && !args.dry_run {
+                    try_settle_pending(
+                        &mut pending_settlements,
+                        &read_client,
+                        &clob_client,
+                        &signer,
+                        &mut gatekeeper,
+                        event_loggers.as_ref(),
+                        state_store_ref,
+                        user_feed.is_some(),
+                        now,
+                    ).await;
+                    budget.retain(|slug| {
+                        pending_settlements.iter().any(|p| p.market_slug == slug)
+                            || slots.iter().any(|s| s.position.is_active() && s.watched.slug == slug)
+                    });
+                }
+
+                // Roll over every market that reached its end time; the others keep trading
+                for (idx, slot) in slots.iter_mut().enumerate() {
+                    if slot.rolling || now < slot.watched.end_time {
+                        continue;
+                    }
+                    // Dry-run positions hold no tokens, so there is nothing to settle
+                    let pending = PendingSettlement::from_position(&slot.watched, &slot.position, now)
+                        .filter(|_| !args.dry_run);
+                    if let Some(pending) = pending {
+                        println!(
+                            "[PENDING] {} | {:?} | {:.4} shares @ {:.4} | Auto-sell queued",
+                            pending.market_slug, pending.token_side, pending.shares, pending.entry_price
+                        );
+                        pending_settlements.push(pending);
+                    } else {
+                        budget.close(&slot.watched.slug);
+                    }
+
+                    println!("[PORTFOLIO] Market {} ended. Looking for next {} market...", slot.watched.slug, slot.target);
+                    let feed_key = slot.feed_key();
+                    feed.remove_subscription(&feed_key).await?;
+                    feed_index.remove(&feed_key);
+                    slot.latest = None;
+                    slot.rolling = true;
+                    let (client, target) = (gamma_client.clone(), slot.target);
+                    discoveries.spawn(async move { (idx, discover_market_loop(&client, target).await) });
+                    if !pending_settlements.is_empty() {
+                        println!("[PENDING] {} positions awaiting settlement", pending_settlements.len());
+                    }
+                }
+
+                for slot in slots.iter_mut() {
+                    let Some(dual_snapshot) = slot.latest.clone() else {
+                        continue;
+                    };
+                    if let Some(loggers) = &event_loggers {
+                        loggers.log_market(EngineEvent::BookUpdate {
+                            ts: epoch_seconds,
+                            market_slug: slot.watched.slug.clone(),
+                            source: "multi_market_websocket".to_string(),
+                            yes_bid: best_bid_price(&dual_snapshot.yes).unwrap_or(0.0),
+                            yes_ask: best_ask_price(&dual_snapshot.yes).unwrap_or(0.0),
+                            no_bid: best_bid_price(&dual_snapshot.no).unwrap_or(0.0),
+                            no_ask: best_ask_price(&dual_snapshot.no).unwrap_or(0.0),
+                        });
+                    }
+
+                    let Some(mut signal) = slot.driver.step(
+                        &dual_snapshot,
+                        &slot.watched.slug,
+                        slot.watched.end_time.timestamp(),
+                        epoch_seconds,
+                        event_loggers.as_ref(),
+                    ) else {
+                        continue;
+                    };
+                    let entry_sent = signal.entry != EntrySignal::None;
+                    let open_before = slot.position.token_side;
+
+                    // Entries are staked against the persisted bankroll when there is one
+                    let entry_size = if entry_sent && !slot.position.is_active() {
+                        let bankroll = state_store_ref
+                            .and_then(|store| store.get_bankroll().ok())
+                            .unwrap_or(balance);
+                        sizer.size_signal(&mut signal, slot.driver.entry_quote(), bankroll)
+                    } else {
+                        args.size
+                    };
+
+                    // Portfolio-wide exposure caps are checked before the shared gatekeeper
+                    if signal.entry != EntrySignal::None
+                        && !slot.position.is_active()
+                        && let Err(reason) = budget.check(slot.target.asset, entry_size)
+                    {
+                        println!(
+                            "[FILTER BLOCKED] {} | {:?} | Reason: {:?} (exposure ${:.2})",
+                            slot.watched.label, signal.entry, reason, budget.total_exposure()
+                        );
+                        if let Some(loggers) = &event_loggers {
+                            loggers.log_strategy(EngineEvent::GateDecision {
+                                ts: epoch_seconds,
+                                market_slug: slot.watched.slug.clone(),
+                                decision: "blocked".to_string(),
+                                reason: format!("{reason:?}"),
+                            });
+                        }
+                        signal.entry = EntrySignal::None;
+                    }
+
+                    handle_live_signals(
+                        &signal,
+                        &dual_snapshot,
+                        &mut slot.position,
+                        &mut gatekeeper,
+                        event_loggers.as_ref(),
+                        state_store_ref,
+                        &slot.watched,
+                        epoch_seconds,
+                        entry_size,
+                        args.dry_run,
+                        &clob_client,
+                        &signer,
+                    ).await;
+                    slot.driver.sync_position(
+                        entry_sent,
+                        open_before,
+                        slot.position.token_side.map(|side| (side, slot.position.entry_price)),
+                    );
+                    match (open_before.is_some(), slot.position.is_active()) {
+                        (false, true) => budget.open(&slot.watched.slug, slot.target.asset, entry_size),
+                        (true, false) => budget.close(&slot.watched.slug),
+                        _ => {}
+                    }
+                }
+
+                if epoch_seconds.is_multiple_of(30) {
+                    println!(
+                        "[PORTFOLIO] exposure ${:.2} | daily PnL ${:.2} | pending {}{}",
+                        budget.total_exposure(),
+                        gatekeeper.daily_pnl,
+                        pending_settlements.len(),
+                        if gatekeeper.emergency_halt { " | HALTED" } else { "" }
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
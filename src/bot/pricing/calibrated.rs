@@ -6,7 +6,7 @@ use super::FairValueModel;
 use serde::{Deserialize, Serialize};
 
 /// Calibration configuration for fair value adjustments
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct CalibrationConfig {
     /// Spread adjustment factor (0.0 = ignore, 0.5 = half spread, 1.0 = full spread)
     pub spread_adjustment: f64,
@@ -38,10 +38,79 @@ impl Default for CalibrationConfig {
     }
 }
 
+impl CalibrationConfig {
+    /// Adjust a model's YES probability for spread cost, book imbalance and
+    /// historical bias, and price the edge on both sides
+    pub fn calibrate(
+        &self,
+        fair_prob_base: f64,
+        yes_ask: f64,
+        no_ask: f64,
+        yes_spread: f64,
+        book_sum: f64,
+        min_edge_threshold: f64,
+    ) -> CalibratedProb {
+        // Clamp to valid range
+        let fair_prob_base = fair_prob_base.clamp(self.min_prob, self.max_prob);
+
+        // Adjust for spread cost
+        // If we buy at ask, we pay spread - reduce fair prob by half spread
+        let spread_cost = yes_spread * self.spread_adjustment;
+        let fair_prob_adjusted = fair_prob_base - spread_cost;
+
+        // Adjust for book imbalance
+        // book_sum < 1.0 means YES is undervalued (both YES and NO are cheap)
+        // book_sum > 1.0 means YES is overvalued
+        let book_inefficiency = 1.0 - book_sum;
+        let book_adjustment = book_inefficiency * self.book_imbalance_weight;
+
+        // Apply historical bias (learned from past outcomes)
+        let fair_prob_calibrated =
+            (fair_prob_adjusted + book_adjustment + self.historical_bias)
+                .clamp(self.min_prob, self.max_prob);
+
+        // Calculate edges
+        let edge_yes = fair_prob_calibrated - yes_ask;
+        let edge_no = (1.0 - fair_prob_calibrated) - no_ask;
+
+        // Determine if tradeable
+        let tradeable = edge_yes.abs() >= min_edge_threshold || edge_no.abs() >= min_edge_threshold;
+
+        // Determine direction based on which edge is larger
+        let direction = if tradeable {
+            if edge_yes.abs() >= edge_no.abs() {
+                if edge_yes > 0.0 {
+                    Some(CalibratedDirection::Yes)
+                } else {
+                    Some(CalibratedDirection::No)
+                }
+            } else {
+                if edge_no > 0.0 {
+                    Some(CalibratedDirection::No)
+                } else {
+                    Some(CalibratedDirection::Yes)
+                }
+            }
+        } else {
+            None
+        };
+
+        CalibratedProb {
+            fair_prob_base,
+            fair_prob_adjusted,
+            fair_prob_calibrated,
+            edge_yes,
+            edge_no,
+            tradeable,
+            direction,
+        }
+    }
+}
+
 /// Calibrated probability output
 #[derive(Debug, Clone, Serialize)]
 pub struct CalibratedProb {
-    /// Base fair probability from Black-Scholes
+    /// Base fair probability from the pricing model
     pub fair_prob_base: f64,
     /// Fair probability after spread adjustment
     pub fair_prob_adjusted: f64,
@@ -123,60 +192,7 @@ impl CalibratedFairValue {
             self.base_model
                 .fair_prob_updown(spot, spot_at_open, time_remaining_s, realized_vol);
 
-        // Clamp to valid range
-        let fair_prob_base = fair_prob_base.clamp(self.config.min_prob, self.config.max_prob);
-
-        // Adjust for spread cost
-        // If we buy at ask, we pay spread - reduce fair prob by half spread
-        let spread_cost = yes_spread * self.config.spread_adjustment;
-        let fair_prob_adjusted = fair_prob_base - spread_cost;
-
-        // Adjust for book imbalance
-        // book_sum < 1.0 means YES is undervalued (both YES and NO are cheap)
-        // book_sum > 1.0 means YES is overvalued
-        let book_inefficiency = 1.0 - book_sum;
-        let book_adjustment = book_inefficiency * self.config.book_imbalance_weight;
-
-        // Apply historical bias (learned from past outcomes)
-        let fair_prob_calibrated =
-            (fair_prob_adjusted + book_adjustment + self.config.historical_bias)
-                .clamp(self.config.min_prob, self.config.max_prob);
-
-        // Calculate edges
-        let edge_yes = fair_prob_calibrated - yes_ask;
-        let edge_no = (1.0 - fair_prob_calibrated) - no_ask;
-
-        // Determine if tradeable
-        let tradeable = edge_yes.abs() >= min_edge_threshold || edge_no.abs() >= min_edge_threshold;
-
-        // Determine direction based on which edge is larger
-        let direction = if tradeable {
-            if edge_yes.abs() >= edge_no.abs() {
-                if edge_yes > 0.0 {
-                    Some(CalibratedDirection::Yes)
-                } else {
-                    Some(CalibratedDirection::No)
-                }
-            } else {
-                if edge_no > 0.0 {
-                    Some(CalibratedDirection::No)
-                } else {
-                    Some(CalibratedDirection::Yes)
-                }
-            }
-        } else {
-            None
-        };
-
-        CalibratedProb {
-            fair_prob_base,
-            fair_prob_adjusted,
-            fair_prob_calibrated,
-            edge_yes,
-            edge_no,
-            tradeable,
-            direction,
-        }
+        self.config.calibrate(fair_prob_base, yes_ask, no_ask, yes_spread, book_sum, min_edge_threshold)
     }
 
     /// Calculate confidence from edge
@@ -0,0 +1,325 @@
+//! Calibration Report
+//!
+//! Brier decomposition, log loss and reliability curves over persisted fair
+//! value predictions, overall and per time-remaining bucket and market horizon.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use super::monitor::FairValuePrediction;
+use crate::bot::market_classifier::MarketHorizon;
+
+/// Exclusive upper bounds of the time-remaining buckets, in seconds
+pub const TIME_REMAINING_BUCKETS: [i64; 7] = [30, 60, 120, 180, 300, 900, 3600];
+
+/// Probabilities are clamped this far from 0 and 1 for log loss
+const LOG_LOSS_EPS: f64 = 1e-6;
+
+const HORIZONS: [MarketHorizon; 4] =
+    [MarketHorizon::UltraShort, MarketHorizon::Short, MarketHorizon::Medium, MarketHorizon::Long];
+
+/// One non-empty probability bin of a calibration curve
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_prob: f64,
+    pub observed_rate: f64,
+}
+
+/// Scores of a set of resolved predictions.
+///
+/// Murphy decomposition over the bins: `brier ≈ reliability - resolution +
+/// uncertainty`, exact up to the spread of probabilities within each bin.
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationStats {
+    pub count: usize,
+    /// Fraction of predictions whose market resolved YES
+    pub base_rate: f64,
+    pub brier: f64,
+    /// Weighted squared gap between mean probability and observed rate (lower is better)
+    pub reliability: f64,
+    /// Weighted squared gap between bin rates and the base rate (higher is better)
+    pub resolution: f64,
+    /// Brier score of always predicting the base rate
+    pub uncertainty: f64,
+    pub log_loss: f64,
+    pub curve: Vec<CalibrationBin>,
+}
+
+impl CalibrationStats {
+    /// Score `(YES probability, YES won)` pairs over `bins` equal-width
+    /// bins; `None` without any
+    pub fn compute(points: &[(f64, bool)], bins: usize) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let bins = bins.max(1);
+        let n = points.len() as f64;
+        let target = |won: bool| if won { 1.0 } else { 0.0 };
+
+        let base_rate = points.iter().map(|&(_, won)| target(won)).sum::<f64>() / n;
+        let brier = points.iter().map(|&(p, won)| (p - target(won)).powi(2)).sum::<f64>() / n;
+        let log_loss = points
+            .iter()
+            .map(|&(p, won)| {
+                let p = p.clamp(LOG_LOSS_EPS, 1.0 - LOG_LOSS_EPS);
+                if won { -p.ln() } else { -(1.0 - p).ln() }
+            })
+            .sum::<f64>()
+            / n;
+
+        // (count, probability sum, YES count) per bin
+        let mut totals = vec![(0usize, 0.0, 0.0); bins];
+        for &(p, won) in points {
+            let idx = ((p.clamp(0.0, 1.0) * bins as f64) as usize).min(bins - 1);
+            totals[idx].0 += 1;
+            totals[idx].1 += p;
+            totals[idx].2 += target(won);
+        }
+
+        let mut reliability = 0.0;
+        let mut resolution = 0.0;
+        let mut curve = Vec::new();
+        for (idx, &(count, prob_sum, yes)) in totals.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let weight = count as f64 / n;
+            let mean_prob = prob_sum / count as f64;
+            let observed_rate = yes / count as f64;
+            reliability += weight * (mean_prob - observed_rate).powi(2);
+            resolution += weight * (observed_rate - base_rate).powi(2);
+            curve.push(CalibrationBin {
+                lower: idx as f64 / bins as f64,
+                upper: (idx + 1) as f64 / bins as f64,
+                count,
+                mean_prob,
+                observed_rate,
+            });
+        }
+
+        Some(Self {
+            count: points.len(),
+            base_rate,
+            brier,
+            reliability,
+            resolution,
+            uncertainty: base_rate * (1.0 - base_rate),
+            log_loss,
+            curve,
+        })
+    }
+}
+
+/// Stats of one slice of the predictions
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationGroup {
+    pub group: String,
+    #[serde(flatten)]
+    pub stats: CalibrationStats,
+}
+
+/// Calibration of predictions pooled from any number of sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationReport {
+    pub predictions: usize,
+    pub resolved: usize,
+    pub sessions: Vec<String>,
+    pub bins: usize,
+    pub overall: Option<CalibrationStats>,
+    pub by_time_remaining: Vec<CalibrationGroup>,
+    pub by_horizon: Vec<CalibrationGroup>,
+}
+
+/// Row of the calibration curve CSV
+#[derive(Serialize)]
+struct CurveRow<'a> {
+    slice: &'a str,
+    group: &'a str,
+    bin_lower: f64,
+    bin_upper: f64,
+    count: usize,
+    mean_prob: f64,
+    observed_rate: f64,
+}
+
+/// Label of the time-remaining bucket `seconds` falls in
+pub fn time_remaining_bucket(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds else {
+        return "unknown".to_string();
+    };
+    let mut lower = 0;
+    for upper in TIME_REMAINING_BUCKETS {
+        if seconds < upper {
+            return format!("{lower}-{upper}s");
+        }
+        lower = upper;
+    }
+    format!("{lower}s+")
+}
+
+impl CalibrationReport {
+    /// Score every resolved prediction; unresolved ones are only counted
+    pub fn build(predictions: &[FairValuePrediction], bins: usize) -> Self {
+        let resolved: Vec<_> = predictions.iter().filter(|p| p.outcome.is_some()).collect();
+        let points = |filter: &dyn Fn(&FairValuePrediction) -> bool| -> Vec<(f64, bool)> {
+            resolved
+                .iter()
+                .filter(|p| filter(p))
+                .filter_map(|p| Some((p.fair_prob_calibrated, p.outcome?)))
+                .collect()
+        };
+        let group = |name: String, points: Vec<(f64, bool)>| {
+            CalibrationStats::compute(&points, bins).map(|stats| CalibrationGroup { group: name, stats })
+        };
+
+        let mut time_labels: Vec<String> = (0..=TIME_REMAINING_BUCKETS.len())
+            .map(|i| time_remaining_bucket(Some(if i == 0 { 0 } else { TIME_REMAINING_BUCKETS[i - 1] })))
+            .collect();
+        time_labels.push(time_remaining_bucket(None));
+        let by_time_remaining = time_labels
+            .into_iter()
+            .filter_map(|label| {
+                let points = points(&|p| time_remaining_bucket(p.time_remaining_s) == label);
+                group(label, points)
+            })
+            .collect();
+
+        let mut by_horizon: Vec<_> = HORIZONS
+            .iter()
+            .filter_map(|&horizon| group(horizon.name().to_string(), points(&|p| p.horizon() == Some(horizon))))
+            .collect();
+        by_horizon.extend(group("unknown".to_string(), points(&|p| p.horizon().is_none())));
+
+        let mut sessions: Vec<String> = predictions.iter().filter_map(|p| p.session.clone()).collect();
+        sessions.sort();
+        sessions.dedup();
+
+        Self {
+            predictions: predictions.len(),
+            resolved: resolved.len(),
+            sessions,
+            bins,
+            overall: CalibrationStats::compute(&points(&|_| true), bins),
+            by_time_remaining,
+            by_horizon,
+        }
+    }
+
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Write every calibration curve as one long CSV, one row per bin
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = csv::Writer::from_path(path)?;
+        let overall = self.overall.iter().map(|stats| ("overall", "all", stats));
+        let time = self.by_time_remaining.iter().map(|g| ("time_remaining", g.group.as_str(), &g.stats));
+        let horizon = self.by_horizon.iter().map(|g| ("horizon", g.group.as_str(), &g.stats));
+        for (slice, group, stats) in overall.chain(time).chain(horizon) {
+            for bin in &stats.curve {
+                writer.serialize(CurveRow {
+                    slice,
+                    group,
+                    bin_lower: bin.lower,
+                    bin_upper: bin.upper,
+                    count: bin.count,
+                    mean_prob: bin.mean_prob,
+                    observed_rate: bin.observed_rate,
+                })?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn write_stats_line(f: &mut std::fmt::Formatter<'_>, label: &str, stats: &CalibrationStats) -> std::fmt::Result {
+    writeln!(
+        f,
+        "  {:<14} n={:<6} brier={:.4} rel={:.4} res={:.4} unc={:.4} logloss={:.4}",
+        label, stats.count, stats.brier, stats.reliability, stats.resolution, stats.uncertainty, stats.log_loss
+    )
+}
+
+impl std::fmt::Display for CalibrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "=== Calibration Report ===")?;
+        writeln!(f, "Predictions: {} ({} resolved) from {} sessions", self.predictions, self.resolved, self.sessions.len())?;
+        let Some(overall) = &self.overall else {
+            return writeln!(f, "No resolved predictions");
+        };
+        write_stats_line(f, "overall", overall)?;
+
+        writeln!(f, "\nBy time remaining:")?;
+        for group in &self.by_time_remaining {
+            write_stats_line(f, &group.group, &group.stats)?;
+        }
+        writeln!(f, "\nBy market horizon:")?;
+        for group in &self.by_horizon {
+            write_stats_line(f, &group.group, &group.stats)?;
+        }
+
+        writeln!(f, "\nCalibration curve ({} bins):", self.bins)?;
+        for bin in &overall.curve {
+            writeln!(
+                f,
+                "  [{:.2}, {:.2}) n={:<6} predicted={:.3} observed={:.3}",
+                bin.lower, bin.upper, bin.count, bin.mean_prob, bin.observed_rate
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decomposition_matches_brier_for_binned_probabilities() {
+        // Every probability sits at its bin's mean, so the decomposition is exact
+        let points = [(0.2, false), (0.2, false), (0.2, true), (0.8, true), (0.8, true), (0.8, false)];
+        let stats = CalibrationStats::compute(&points, 10).unwrap();
+
+        assert_eq!(stats.curve.len(), 2);
+        assert!((stats.base_rate - 0.5).abs() < 1e-12);
+        assert!((stats.uncertainty - 0.25).abs() < 1e-12);
+        let expected_reliability = (0.2_f64 - 1.0 / 3.0).powi(2);
+        assert!((stats.reliability - expected_reliability).abs() < 1e-12);
+        assert!((stats.resolution - (1.0_f64 / 6.0).powi(2)).abs() < 1e-12);
+        assert!((stats.brier - (stats.reliability - stats.resolution + stats.uncertainty)).abs() < 1e-12);
+        let expected_log_loss = -(2.0 * 0.8_f64.ln() + 0.2_f64.ln()) / 3.0;
+        assert!((stats.log_loss - expected_log_loss).abs() < 1e-12);
+        assert!(CalibrationStats::compute(&[], 10).is_none());
+    }
+
+    #[test]
+    fn report_slices_by_time_remaining_and_horizon() {
+        let predictions = vec![
+            FairValuePrediction::new(1, Some("a".into()), 0.9, 0.8, 0.1)
+                .with_outcome(true)
+                .with_time_remaining(45)
+                .with_market_duration(300),
+            FairValuePrediction::new(2, Some("b".into()), 0.3, 0.4, -0.1)
+                .with_outcome(false)
+                .with_time_remaining(200)
+                .with_market_duration(3600),
+            FairValuePrediction::new(3, Some("c".into()), 0.6, 0.5, 0.1).with_outcome(true),
+            FairValuePrediction::new(4, Some("d".into()), 0.6, 0.5, 0.1),
+        ];
+        let report = CalibrationReport::build(&predictions, 10);
+
+        assert_eq!((report.predictions, report.resolved), (4, 3));
+        assert_eq!(report.overall.as_ref().unwrap().count, 3);
+        let groups = |groups: &[CalibrationGroup]| groups.iter().map(|g| g.group.clone()).collect::<Vec<_>>();
+        assert_eq!(groups(&report.by_time_remaining), vec!["30-60s", "180-300s", "unknown"]);
+        assert_eq!(groups(&report.by_horizon), vec!["ULTRA-SHORT", "MEDIUM", "unknown"]);
+        assert_eq!(time_remaining_bucket(Some(7200)), "3600s+");
+    }
+}
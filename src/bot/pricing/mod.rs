@@ -3,6 +3,7 @@
 //! Fair value models and volatility calculations.
 
 mod calibrated;
+mod calibration;
 mod em_estimator;
 mod fair_value;
 mod jump_calibrator;
@@ -13,6 +14,7 @@ mod spot_feed;
 mod volatility;
 
 pub use calibrated::{CalibratedFairValue, CalibratedProb, CalibrationConfig};
+pub use calibration::CalibrationReport;
 pub use em_estimator::EMState;
 pub use fair_value::{FairValueConfig, FairValueModel};
 pub use jump_calibrator::JumpCalibrator;
@@ -21,7 +23,7 @@ pub use logit_model::{
     sigmoid, prob_to_logit, logit_to_prob, risk_neutral_drift, jump_compensator,
     LogitJumpDiffusion, LogitObservation, FilteredState, FairProbability,
 };
-pub use monitor::{FairValueMonitor, FairValuePrediction, MonitorSummary};
+pub use monitor::{load_predictions, FairValueMonitor, FairValuePrediction, MonitorSummary, PredictionLog};
 pub use spot_feed::{
     SpotFeed, ChainlinkFeed, ChainlinkConfig, DerivedSpotFeed, CompositeSpotFeed,
     PolymarketRtdsFeed, SharedSpotFeed, start_rtds_poller,
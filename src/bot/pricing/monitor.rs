@@ -2,8 +2,13 @@
 //!
 //! Tracks fair value predictions and outcomes for model validation.
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::bot::market_classifier::{classify_market, MarketHorizon};
+use crate::bot::logging::{read_json_records, JsonlAppender};
 
 /// A single fair value prediction with optional outcome
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +30,15 @@ pub struct FairValuePrediction {
     pub outcome: Option<bool>,
     /// Market slug for reference
     pub market_slug: Option<String>,
+    /// Seconds until market end at prediction time
+    #[serde(default)]
+    pub time_remaining_s: Option<i64>,
+    /// Full market window in seconds, start to end
+    #[serde(default)]
+    pub market_duration_s: Option<i64>,
+    /// Session or backtest run that made the prediction
+    #[serde(default)]
+    pub session: Option<String>,
 }
 
 impl FairValuePrediction {
@@ -45,6 +59,9 @@ impl FairValuePrediction {
             direction: None,
             outcome: None,
             market_slug: None,
+            time_remaining_s: None,
+            market_duration_s: None,
+            session: None,
         }
     }
 
@@ -66,6 +83,24 @@ impl FairValuePrediction {
         self
     }
 
+    /// Set the seconds remaining until market end
+    pub fn with_time_remaining(mut self, seconds: i64) -> Self {
+        self.time_remaining_s = Some(seconds);
+        self
+    }
+
+    /// Set the full market window
+    pub fn with_market_duration(mut self, seconds: i64) -> Self {
+        self.market_duration_s = Some(seconds);
+        self
+    }
+
+    /// Horizon of the market window, or of the time remaining when the
+    /// window is unknown
+    pub fn horizon(&self) -> Option<MarketHorizon> {
+        self.market_duration_s.or(self.time_remaining_s).map(classify_market)
+    }
+
     /// Calculate squared error for this prediction (if resolved)
     pub fn squared_error(&self) -> Option<f64> {
         self.outcome.map(|o| {
@@ -322,6 +357,33 @@ impl FairValueMonitor {
     }
 }
 
+/// Appends predictions as JSON lines, stamped with one session label, so
+/// many runs can share a file for `bot calibration-report`
+pub struct PredictionLog {
+    log: JsonlAppender,
+    session: String,
+}
+
+impl PredictionLog {
+    pub fn create<P: AsRef<Path>>(path: P, session: impl Into<String>) -> Result<Self> {
+        Ok(Self { log: JsonlAppender::create(path)?, session: session.into() })
+    }
+
+    pub fn append(&mut self, prediction: &FairValuePrediction) -> Result<()> {
+        if prediction.session.is_some() {
+            self.log.append(prediction)
+        } else {
+            self.log.append(&FairValuePrediction { session: Some(self.session.clone()), ..prediction.clone() })
+        }
+    }
+}
+
+/// Load predictions from a monitor export (JSON array) or a prediction log
+/// (JSON lines)
+pub fn load_predictions<P: AsRef<Path>>(path: P) -> Result<Vec<FairValuePrediction>> {
+    read_json_records(path)
+}
+
 /// Summary statistics for the fair value monitor
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitorSummary {
@@ -450,6 +512,36 @@ mod tests {
         assert!(pred_no_outcome.squared_error().is_none());
     }
 
+    #[test]
+    fn prediction_log_round_trips_with_session() {
+        let dir = crate::bot::test_util::tempdir();
+        let path = format!("{dir}/predictions.jsonl");
+
+        let mut log = PredictionLog::create(&path, "run-a").unwrap();
+        log.append(&FairValuePrediction::new(1000, Some("0xaa".to_string()), 0.7, 0.6, 0.1)
+            .with_time_remaining(120)
+            .with_market_duration(300))
+            .unwrap();
+        drop(log);
+        let mut log = PredictionLog::create(&path, "run-b").unwrap();
+        log.append(&FairValuePrediction::new(2000, None, 0.4, 0.45, -0.05)).unwrap();
+        drop(log);
+
+        let loaded = load_predictions(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].session.as_deref(), Some("run-a"));
+        assert_eq!(loaded[0].horizon(), Some(MarketHorizon::UltraShort));
+        assert_eq!(loaded[1].session.as_deref(), Some("run-b"));
+        assert_eq!(loaded[1].horizon(), None);
+
+        // Older exports without the new fields still load
+        let old = r#"[{"ts":1,"condition_id":null,"fair_prob_calibrated":0.5,"market_yes_ask":0.5,
+            "edge_predicted":0.0,"direction":null,"outcome":true,"market_slug":null}]"#;
+        let old: Vec<FairValuePrediction> = serde_json::from_str(old).unwrap();
+        assert_eq!(old[0].time_remaining_s, None);
+    }
+
     #[test]
     fn was_correct_check() {
         let pred_correct = FairValuePrediction::new(1000, Some("test-1".to_string()), 0.7, 0.5, 0.2).with_outcome(true);
@@ -0,0 +1,448 @@
+//! Replay Log
+//!
+//! Event-sourced replay of the JSONL logs written by `EngineEventLoggers`.
+//! Books are rebuilt from `BookUpdate` events, the `--strategy` chosen is re-run
+//! over them through a `StrategyDriver` and its decisions are diffed against
+//! the ones recorded live.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::bot::feed::{DualSnapshot, MarketSnapshot, ReplaySnapshotSource, StrategyInputSource};
+use crate::bot::logging::{split_log_paths, EngineEvent};
+use crate::bot::pipeline::parse_slug_timestamp;
+use crate::bot::research::SupportedDuration;
+use crate::bot::risk::GatekeeperState;
+use crate::bot::shadow::{ShadowPosition, TokenSide, TouchFills};
+use crate::bot::signal::SignalState;
+use crate::bot::sizing::{PositionSizer, SizingArgs};
+use crate::bot::strategy::StrategyArgs;
+use crate::bot::strategy_runner::{run_driver_shadow_step, StrategyDriver};
+
+#[derive(Args, Clone)]
+pub struct ReplayLogArgs {
+    /// Event log path as passed to --event-log (a .jsonl stem or a directory)
+    pub path: String,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    /// Replay the watch-btc shadow step too, diffing its gated entries and exits
+    #[arg(long)]
+    pub shadow: bool,
+
+    /// Only replay markets whose slug contains this text
+    #[arg(long)]
+    pub market: Option<String>,
+
+    /// Shadow mode: starting bankroll in USD
+    #[arg(long, default_value = "5.0")]
+    pub bankroll: f64,
+
+    /// Shadow mode: position size in USD
+    #[arg(long, default_value = "1.0")]
+    pub size: f64,
+
+    /// Shadow mode: daily realized loss limit in USD
+    #[arg(long, default_value = "2.0")]
+    pub daily_loss_limit: f64,
+
+    /// Shadow mode: cooldown after a losing trade
+    #[arg(long, default_value = "15")]
+    pub cooldown_seconds: u64,
+
+    /// Export the full diff report to JSON
+    #[arg(long)]
+    pub export: Option<String>,
+
+    /// Exit with an error if any decision differs
+    #[arg(long)]
+    pub fail_on_diff: bool,
+
+    /// Print every differing decision instead of the first few
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionKind {
+    Signal,
+    Entry,
+    Exit,
+}
+
+/// Decisions keyed by market, time and kind, with a comparable value
+pub type DecisionLog = BTreeMap<(String, u64, DecisionKind), String>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionDiff {
+    pub market_slug: String,
+    pub ts: u64,
+    pub kind: DecisionKind,
+    pub recorded: Option<String>,
+    pub replayed: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReplayReport {
+    pub markets: usize,
+    pub book_updates: usize,
+    pub skipped_lines: usize,
+    pub recorded_decisions: usize,
+    pub replayed_decisions: usize,
+    pub matched: usize,
+    pub diffs: Vec<DecisionDiff>,
+}
+
+/// Book updates of one market in log order
+#[derive(Debug, Default)]
+struct RecordedMarket {
+    slug: String,
+    snapshots: Vec<DualSnapshot>,
+}
+
+#[derive(Debug, Default)]
+struct RecordedLog {
+    markets: Vec<RecordedMarket>,
+    decisions: DecisionLog,
+    book_updates: usize,
+    skipped_lines: usize,
+}
+
+impl RecordedLog {
+    fn push(&mut self, event: EngineEvent, shadow: bool) {
+        match event {
+            EngineEvent::BookUpdate { ts, market_slug, yes_bid, yes_ask, no_bid, no_ask, .. } => {
+                self.book_updates += 1;
+                // Depth is not logged; the signal engines only read prices
+                let snapshot = DualSnapshot {
+                    yes: MarketSnapshot::from_state(yes_bid, yes_ask, 0.0, 0.0),
+                    no: MarketSnapshot::from_state(no_bid, no_ask, 0.0, 0.0),
+                    ts_exchange: ts as f64,
+                };
+                // Portfolio logs interleave markets; keep first-seen order
+                match self.markets.iter_mut().rev().find(|m| m.slug == market_slug) {
+                    Some(market) => market.snapshots.push(snapshot),
+                    None => self.markets.push(RecordedMarket {
+                        slug: market_slug,
+                        snapshots: vec![snapshot],
+                    }),
+                }
+            }
+            EngineEvent::StrategySignal { ts, market_slug, entry, exit, .. } => {
+                self.decisions
+                    .insert((market_slug, ts, DecisionKind::Signal), signal_value(&entry, &exit));
+            }
+            EngineEvent::ShadowEntry { ts, market_slug, side, .. } if shadow => {
+                self.decisions.insert((market_slug, ts, DecisionKind::Entry), side);
+            }
+            EngineEvent::ShadowExit { ts, market_slug, side, .. } if shadow => {
+                self.decisions.insert((market_slug, ts, DecisionKind::Exit), side);
+            }
+            _ => {}
+        }
+    }
+
+    fn read_file(&mut self, path: &Path, shadow: bool) -> Result<()> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<EngineEvent>(&line) {
+                Ok(event) => self.push(event, shadow),
+                Err(_) => self.skipped_lines += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn signal_value(entry: &str, exit: &str) -> String {
+    format!("{entry}/{exit}")
+}
+
+/// Read the market and strategy (and for shadow replays, execution) logs
+/// behind an `--event-log` path. A plain file holding every event also works.
+fn load_recorded_log(path: &Path, shadow: bool) -> Result<RecordedLog> {
+    let (market_path, strategy_path, execution_path) = split_log_paths(path);
+    let mut log = RecordedLog::default();
+
+    if market_path.exists() {
+        log.read_file(&market_path, shadow)?;
+        if strategy_path.exists() {
+            log.read_file(&strategy_path, shadow)?;
+        }
+        if shadow && execution_path.exists() {
+            log.read_file(&execution_path, shadow)?;
+        }
+    } else if path.is_file() {
+        log.read_file(path, shadow)?;
+    } else {
+        anyhow::bail!("No event log found at {} or {}", path.display(), market_path.display());
+    }
+    Ok(log)
+}
+
+/// Slug-encoded market window, falling back to the span of recorded books
+fn market_window(market: &RecordedMarket) -> (i64, i64) {
+    let first = market.snapshots.first().map_or(0, |s| s.ts_exchange as i64);
+    let last = market.snapshots.last().map_or(0, |s| s.ts_exchange as i64);
+    match parse_slug_timestamp(&market.slug) {
+        Some(start) => {
+            let duration = SupportedDuration::from_slug(&market.slug).map_or(300, |d| d.seconds());
+            (start, start + duration)
+        }
+        None => (first, last + 1),
+    }
+}
+
+/// Re-run the strategy behind `driver` over the recorded markets in log
+/// order. Every market starts from a reset driver, as in the live loops.
+async fn replay_decisions(
+    log: &RecordedLog,
+    driver: &mut StrategyDriver,
+    args: &ReplayLogArgs,
+) -> Result<DecisionLog> {
+    let mut decisions = DecisionLog::new();
+    let sizer = PositionSizer::new(args.size, &args.sizing);
+    let mut gatekeeper = GatekeeperState::new(args.daily_loss_limit, args.cooldown_seconds);
+    let mut shadow = ShadowPosition { bankroll_usd: args.bankroll, ..Default::default() };
+
+    for market in &log.markets {
+        driver.reset();
+        shadow.full_reset();
+        let (start_ts, end_ts) = market_window(market);
+
+        let mut source = ReplaySnapshotSource::new(market.snapshots.clone());
+        while let Some(snapshot) = source.next_snapshot().await? {
+            let ts = source.current_time().unwrap_or(snapshot.ts_exchange as u64);
+            let mut record = |kind: DecisionKind, value: String| {
+                decisions.insert((market.slug.clone(), ts, kind), value);
+            };
+
+            if !args.shadow {
+                if let Some(signal) = driver.step(&snapshot, &market.slug, end_ts, ts, None) {
+                    record(DecisionKind::Signal, format_signal(&signal));
+                }
+                continue;
+            }
+            let Some(step) = run_driver_shadow_step(
+                driver,
+                &snapshot,
+                &market.slug,
+                &market.slug,
+                start_ts,
+                end_ts,
+                ts,
+                &sizer,
+                &mut shadow,
+                &mut gatekeeper,
+                &mut TouchFills,
+                None,
+            ) else {
+                continue;
+            };
+            record(DecisionKind::Signal, format_signal(&step.signal));
+            if step.entry_taken {
+                let side = match shadow.token_side {
+                    Some(TokenSide::Yes) => "YES",
+                    Some(TokenSide::No) => "NO",
+                    None => "N/A",
+                };
+                record(DecisionKind::Entry, side.to_string());
+            }
+            if let Some(exit) = &step.exit_trade {
+                record(DecisionKind::Exit, exit.side.clone());
+            }
+        }
+    }
+    Ok(decisions)
+}
+
+fn format_signal(signal: &SignalState) -> String {
+    signal_value(&format!("{:?}", signal.entry), &format!("{:?}", signal.exit))
+}
+
+/// Decisions that differ, or exist on only one side, between two logs
+pub fn diff_decisions(recorded: &DecisionLog, replayed: &DecisionLog) -> (usize, Vec<DecisionDiff>) {
+    let mut matched = 0;
+    let mut diffs = Vec::new();
+    let mut keys: Vec<_> = recorded.keys().chain(replayed.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let before = recorded.get(key);
+        let after = replayed.get(key);
+        if before == after {
+            matched += 1;
+            continue;
+        }
+        let (market_slug, ts, kind) = key;
+        diffs.push(DecisionDiff {
+            market_slug: market_slug.clone(),
+            ts: *ts,
+            kind: *kind,
+            recorded: before.cloned(),
+            replayed: after.cloned(),
+        });
+    }
+    (matched, diffs)
+}
+
+pub async fn run_replay_log(args: ReplayLogArgs) -> Result<()> {
+    let strategy = args.strategy.config()?.deterministic();
+    let mut log = load_recorded_log(Path::new(&args.path), args.shadow)?;
+    if let Some(filter) = &args.market {
+        log.markets.retain(|m| m.slug.contains(filter.as_str()));
+        log.decisions.retain(|(slug, _, _), _| slug.contains(filter.as_str()));
+    }
+    if log.markets.is_empty() {
+        anyhow::bail!("No BookUpdate events to replay in {}", args.path);
+    }
+
+    println!(
+        "[REPLAY] {} | {} ({}){} | {} markets | {} book updates",
+        args.path,
+        strategy.kind(),
+        strategy.describe(),
+        if args.shadow { " shadow" } else { "" },
+        log.markets.len(),
+        log.book_updates
+    );
+
+    let mut driver = StrategyDriver::new(&strategy);
+    let replayed = replay_decisions(&log, &mut driver, &args).await?;
+    let (matched, diffs) = diff_decisions(&log.decisions, &replayed);
+    let report = ReplayReport {
+        markets: log.markets.len(),
+        book_updates: log.book_updates,
+        skipped_lines: log.skipped_lines,
+        recorded_decisions: log.decisions.len(),
+        replayed_decisions: replayed.len(),
+        matched,
+        diffs,
+    };
+
+    println!("\n========================================");
+    println!("REPLAY DIFF");
+    println!("========================================");
+    println!("Recorded decisions: {}", report.recorded_decisions);
+    println!("Replayed decisions: {}", report.replayed_decisions);
+    println!("Matched:            {}", report.matched);
+    println!("Differing:          {}", report.diffs.len());
+    if report.skipped_lines > 0 {
+        println!("Skipped lines:      {}", report.skipped_lines);
+    }
+
+    let shown = if args.verbose { report.diffs.len() } else { report.diffs.len().min(20) };
+    for diff in &report.diffs[..shown] {
+        println!(
+            "  {} @ {} {:?}: recorded={} replayed={}",
+            diff.market_slug,
+            diff.ts,
+            diff.kind,
+            diff.recorded.as_deref().unwrap_or("-"),
+            diff.replayed.as_deref().unwrap_or("-"),
+        );
+    }
+    if shown < report.diffs.len() {
+        println!("  ... {} more (use --verbose or --export)", report.diffs.len() - shown);
+    }
+
+    if let Some(path) = &args.export {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!("\nExported diff report to {}", path);
+    }
+
+    if args.fail_on_diff && !report.diffs.is_empty() {
+        anyhow::bail!("{} decisions differ from the recorded log", report.diffs.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(ts: u64, slug: &str) -> EngineEvent {
+        EngineEvent::BookUpdate {
+            ts,
+            market_slug: slug.to_string(),
+            source: "Websocket".to_string(),
+            yes_bid: 0.49,
+            yes_ask: 0.51,
+            no_bid: 0.49,
+            no_ask: 0.51,
+        }
+    }
+
+    #[test]
+    fn book_updates_group_into_markets() {
+        let mut log = RecordedLog::default();
+        let line = serde_json::to_string(&book(100, "btc-updown-5m-1700000000")).unwrap();
+        log.push(serde_json::from_str(&line).unwrap(), false);
+        log.push(book(101, "btc-updown-5m-1700000000"), false);
+        log.push(book(400, "btc-updown-5m-1700000300"), false);
+        log.push(
+            EngineEvent::ShadowEntry {
+                ts: 101,
+                market_slug: "btc-updown-5m-1700000000".to_string(),
+                side: "YES".to_string(),
+                price: 0.51,
+                size_usd: 1.0,
+                bankroll_after: 4.0,
+            },
+            false,
+        );
+
+        assert_eq!(log.markets.len(), 2);
+        assert_eq!(log.markets[0].snapshots.len(), 2);
+        assert_eq!(market_window(&log.markets[1]), (1_700_000_300, 1_700_000_600));
+        // Shadow fills are only compared when replaying the shadow strategy
+        assert!(log.decisions.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_changed_and_one_sided_decisions() {
+        let key = |ts| ("m".to_string(), ts, DecisionKind::Signal);
+        let recorded: DecisionLog = [
+            (key(5), "None/None".to_string()),
+            (key(10), "Long/None".to_string()),
+            (key(15), "None/FullExit".to_string()),
+        ]
+        .into();
+        let replayed: DecisionLog = [
+            (key(5), "None/None".to_string()),
+            (key(10), "Short/None".to_string()),
+            (key(20), "None/None".to_string()),
+        ]
+        .into();
+
+        let (matched, diffs) = diff_decisions(&recorded, &replayed);
+        assert_eq!(matched, 1);
+        let summary: Vec<_> = diffs
+            .iter()
+            .map(|d| (d.ts, d.recorded.as_deref(), d.replayed.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (10, Some("Long/None"), Some("Short/None")),
+                (15, Some("None/FullExit"), None),
+                (20, None, Some("None/None")),
+            ]
+        );
+    }
+}
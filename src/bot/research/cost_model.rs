@@ -45,6 +45,12 @@ impl CostModel {
         gross_edge - total_fees - slippage
     }
 
+    /// Net edge of a taker entry held to settlement at `payout` (0 or 1):
+    /// settlement pays no exit fee
+    pub fn settlement_edge(&self, entry_price: f64, payout: f64) -> f64 {
+        payout - entry_price - entry_price * (self.taker_fee + self.slippage_buffer)
+    }
+
     /// Check if trade is profitable after fees
     pub fn is_profitable_after_fees(&self, entry_price: f64, exit_price: f64) -> bool {
         self.net_edge(entry_price, exit_price, true) > 0.0
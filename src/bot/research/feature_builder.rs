@@ -0,0 +1,558 @@
+//! Feature Builder
+//!
+//! Incremental `FeatureRow` computation for one market: each book tick is
+//! pushed in time order and the row for that tick comes back, with returns,
+//! spread EMAs, book-gap z-scores and realized vols computed over a rolling
+//! history of recent ticks. `export-features` and the live loops both build
+//! rows here, so training and live features cannot drift apart. Ticks that
+//! carry level depth also get the `features_v2` book-shape columns.
+
+use super::{CryptoBinaryMarketSpec, FeatureRow};
+use crate::bot::feed::{BookDepth, DualSnapshot, TokenDepth, DEPTH_LEVELS};
+use crate::bot::risk::{best_ask_price, best_bid_price};
+use std::collections::VecDeque;
+
+/// Longest lookback any feature needs, in seconds
+const MAX_LOOKBACK_S: i64 = 60;
+
+/// Entries stop this many seconds before market end
+const EXIT_ONLY_S: i64 = 45;
+
+/// Window of the depth-change rates, in seconds
+const DEPTH_CHANGE_S: i64 = 5;
+
+/// Top-of-book state at one observation time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BookTick {
+    pub ts: i64,
+    pub yes_bid: f64,
+    pub yes_ask: f64,
+    pub no_bid: f64,
+    pub no_ask: f64,
+    /// Level sizes, when the source recorded them
+    pub depth: Option<BookDepth>,
+    pub spot_price: Option<f64>,
+}
+
+impl BookTick {
+    /// Tick from a live snapshot; one-sided books fall back to 0 bid / 1 ask
+    pub fn from_snapshot(snapshot: &DualSnapshot, ts: i64) -> Self {
+        Self {
+            ts,
+            yes_bid: best_bid_price(&snapshot.yes).unwrap_or(0.0),
+            yes_ask: best_ask_price(&snapshot.yes).unwrap_or(1.0),
+            no_bid: best_bid_price(&snapshot.no).unwrap_or(0.0),
+            no_ask: best_ask_price(&snapshot.no).unwrap_or(1.0),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    ts: i64,
+    yes_mid: f64,
+    no_mid: f64,
+    book_gap: f64,
+    spot: Option<f64>,
+    /// Top-5 depth as yes bid, yes ask, no bid, no ask
+    depth_5: Option<[f64; 4]>,
+}
+
+/// Book-shape columns of one token
+#[derive(Debug, Clone, Copy)]
+struct TokenShape {
+    /// Cumulative bid/ask size through each level
+    bid_depth: [f64; DEPTH_LEVELS],
+    ask_depth: [f64; DEPTH_LEVELS],
+    imbalance_1: f64,
+    imbalance_5: f64,
+    microprice: f64,
+    queue_mid: f64,
+}
+
+/// `(bid - ask) / (bid + ask)`, 0 on an empty book
+fn imbalance(bid_size: f64, ask_size: f64) -> f64 {
+    let total = bid_size + ask_size;
+    if total > 0.0 { (bid_size - ask_size) / total } else { 0.0 }
+}
+
+/// Quote mid with each side weighted by the opposite side's size, so the
+/// price leans towards the thinner side; the plain mid on an empty book
+fn weighted_mid(bid: f64, ask: f64, bid_size: f64, ask_size: f64) -> f64 {
+    let total = bid_size + ask_size;
+    if total > 0.0 { (bid * ask_size + ask * bid_size) / total } else { (bid + ask) / 2.0 }
+}
+
+impl TokenShape {
+    fn new(bid: f64, ask: f64, depth: &TokenDepth) -> Self {
+        let bid_depth: [f64; DEPTH_LEVELS] = std::array::from_fn(|k| depth.bid_depth(k + 1));
+        let ask_depth: [f64; DEPTH_LEVELS] = std::array::from_fn(|k| depth.ask_depth(k + 1));
+        let (top, all) = (0, DEPTH_LEVELS - 1);
+        Self {
+            bid_depth,
+            ask_depth,
+            imbalance_1: imbalance(bid_depth[top], ask_depth[top]),
+            imbalance_5: imbalance(bid_depth[all], ask_depth[all]),
+            microprice: weighted_mid(bid, ask, bid_depth[top], ask_depth[top]),
+            queue_mid: weighted_mid(bid, ask, bid_depth[all], ask_depth[all]),
+        }
+    }
+}
+
+/// Simple return from `past` to `now`, 0 without a usable base
+fn ret(now: f64, past: Option<f64>) -> f64 {
+    match past {
+        Some(past) if past > 0.0 => now / past - 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Time-aware EMA with a `span_s` second time constant
+#[derive(Debug, Clone, Default)]
+struct TimeEma {
+    value: Option<f64>,
+    last_ts: i64,
+}
+
+impl TimeEma {
+    fn update(&mut self, ts: i64, x: f64, span_s: f64) -> f64 {
+        let value = match self.value {
+            Some(prev) => {
+                let alpha = 1.0 - (-((ts - self.last_ts).max(0) as f64) / span_s).exp();
+                prev + alpha * (x - prev)
+            }
+            None => x,
+        };
+        self.value = Some(value);
+        self.last_ts = ts;
+        value
+    }
+}
+
+/// Builds feature rows tick by tick for one market
+#[derive(Debug, Clone)]
+pub struct FeatureBuilder {
+    /// Identity columns shared by every row of the market
+    identity: FeatureRow,
+    history: VecDeque<Sample>,
+    yes_spread_ema: TimeEma,
+    no_spread_ema: TimeEma,
+}
+
+impl FeatureBuilder {
+    pub fn new(spec: CryptoBinaryMarketSpec) -> Self {
+        Self::with_identity(FeatureRow {
+            condition_id: spec.condition_id.clone(),
+            market_slug: spec.slug,
+            instrument: spec.condition_id,
+            asset: spec.asset.as_str().to_string(),
+            duration: spec.duration.as_str().to_string(),
+            market_family: spec.family.as_str().to_string(),
+            market_start_ts: spec.start_ts,
+            market_end_ts: spec.end_ts,
+            ..Default::default()
+        })
+    }
+
+    /// Market whose slug names no supported asset
+    pub fn unclassified(condition_id: &str, slug: &str, start_ts: i64, end_ts: i64) -> Self {
+        Self::with_identity(FeatureRow {
+            condition_id: condition_id.to_string(),
+            market_slug: slug.to_string(),
+            instrument: condition_id.to_string(),
+            asset: "unknown".to_string(),
+            duration: "5m".to_string(),
+            market_family: "updown_open_close".to_string(),
+            market_start_ts: start_ts,
+            market_end_ts: end_ts,
+            ..Default::default()
+        })
+    }
+
+    fn with_identity(identity: FeatureRow) -> Self {
+        Self {
+            identity: FeatureRow { schema_version: FeatureRow::schema_version().to_string(), ..identity },
+            history: VecDeque::new(),
+            yes_spread_ema: TimeEma::default(),
+            no_spread_ema: TimeEma::default(),
+        }
+    }
+
+    /// Latest sample at or before `ts`
+    fn at_or_before(&self, ts: i64) -> Option<&Sample> {
+        self.history.iter().rev().find(|s| s.ts <= ts)
+    }
+
+    /// Samples strictly after `since`, oldest first
+    fn since(&self, since: i64) -> impl Iterator<Item = &Sample> {
+        self.history.iter().filter(move |s| s.ts > since)
+    }
+
+    /// Square root of summed squared tick-to-tick returns over the window
+    fn realized_vol(&self, now: i64, window_s: i64, value: impl Fn(&Sample) -> Option<f64>) -> Option<f64> {
+        let values: Vec<f64> = self.since(now - window_s).filter_map(&value).collect();
+        if values.is_empty() {
+            return None;
+        }
+        Some(values.windows(2).map(|w| ret(w[1], Some(w[0])).powi(2)).sum::<f64>().sqrt())
+    }
+
+    fn book_gap_z(&self, now: i64, gap: f64) -> f64 {
+        let gaps: Vec<f64> = self.since(now - 30).map(|s| s.book_gap).collect();
+        let n = gaps.len() as f64;
+        let mean = gaps.iter().sum::<f64>() / n;
+        let std = (gaps.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / n).sqrt();
+        if std > 1e-12 { (gap - mean) / std } else { 0.0 }
+    }
+
+    /// Push the next tick (ticks must arrive in time order) and return its row
+    pub fn update(&mut self, tick: &BookTick) -> FeatureRow {
+        let ts = tick.ts;
+        let yes_mid = (tick.yes_bid + tick.yes_ask) / 2.0;
+        let no_mid = (tick.no_bid + tick.no_ask) / 2.0;
+        let yes_spread = tick.yes_ask - tick.yes_bid;
+        let no_spread = tick.no_ask - tick.no_bid;
+        let book_sum = tick.yes_ask + tick.no_ask;
+        let book_gap = book_sum - 1.0;
+        let shapes = tick.depth.map(|depth| {
+            (TokenShape::new(tick.yes_bid, tick.yes_ask, &depth.yes), TokenShape::new(tick.no_bid, tick.no_ask, &depth.no))
+        });
+        let depth_5 = shapes.map(|(yes, no)| {
+            let all = DEPTH_LEVELS - 1;
+            [yes.bid_depth[all], yes.ask_depth[all], no.bid_depth[all], no.ask_depth[all]]
+        });
+
+        // A repeated timestamp replaces the earlier tick
+        if self.history.back().is_some_and(|s| s.ts >= ts) {
+            self.history.pop_back();
+        }
+        self.history.push_back(Sample { ts, yes_mid, no_mid, book_gap, spot: tick.spot_price, depth_5 });
+        // Keep one sample older than the longest lookback as the return base
+        while self.history.len() > 2 && self.history[1].ts <= ts - MAX_LOOKBACK_S {
+            self.history.pop_front();
+        }
+
+        let yes_spread_ema_15s = self.yes_spread_ema.update(ts, yes_spread, 15.0);
+        let no_spread_ema_15s = self.no_spread_ema.update(ts, no_spread, 15.0);
+        let yes_ret = |h: i64| ret(yes_mid, self.at_or_before(ts - h).map(|s| s.yes_mid));
+        let no_ret = |h: i64| ret(no_mid, self.at_or_before(ts - h).map(|s| s.no_mid));
+        let spot_ret = |h: i64| {
+            let spot = tick.spot_price?;
+            Some(ret(spot, self.at_or_before(ts - h).and_then(|s| s.spot)))
+        };
+        let yes_vol = |w: i64| self.realized_vol(ts, w, |s| Some(s.yes_mid)).unwrap_or(0.0);
+        // Change in top-5 depth per second since the depth-change base, 0
+        // without one
+        let depth_chg = |i: usize| {
+            let now = depth_5?[i];
+            let rate = match self.at_or_before(ts - DEPTH_CHANGE_S) {
+                Some(Sample { ts: base_ts, depth_5: Some(base), .. }) => (now - base[i]) / (ts - base_ts) as f64,
+                _ => 0.0,
+            };
+            Some(rate)
+        };
+        let yes = |f: fn(&TokenShape) -> f64| shapes.map(|(yes, _)| f(&yes));
+        let no = |f: fn(&TokenShape) -> f64| shapes.map(|(_, no)| f(&no));
+
+        let age_s = ts - self.identity.market_start_ts;
+        let time_remaining_s = self.identity.market_end_ts - ts;
+        let in_exit_only_window = time_remaining_s < EXIT_ONLY_S;
+
+        FeatureRow {
+            ts,
+            yes_bid: tick.yes_bid,
+            yes_ask: tick.yes_ask,
+            no_bid: tick.no_bid,
+            no_ask: tick.no_ask,
+            yes_mid,
+            no_mid,
+            yes_spread,
+            no_spread,
+            book_sum,
+            book_gap,
+            yes_bid_depth_1: yes(|s| s.bid_depth[0]),
+            yes_bid_depth_2: yes(|s| s.bid_depth[1]),
+            yes_bid_depth_3: yes(|s| s.bid_depth[2]),
+            yes_bid_depth_4: yes(|s| s.bid_depth[3]),
+            yes_bid_depth_5: yes(|s| s.bid_depth[4]),
+            yes_ask_depth_1: yes(|s| s.ask_depth[0]),
+            yes_ask_depth_2: yes(|s| s.ask_depth[1]),
+            yes_ask_depth_3: yes(|s| s.ask_depth[2]),
+            yes_ask_depth_4: yes(|s| s.ask_depth[3]),
+            yes_ask_depth_5: yes(|s| s.ask_depth[4]),
+            no_bid_depth_1: no(|s| s.bid_depth[0]),
+            no_bid_depth_2: no(|s| s.bid_depth[1]),
+            no_bid_depth_3: no(|s| s.bid_depth[2]),
+            no_bid_depth_4: no(|s| s.bid_depth[3]),
+            no_bid_depth_5: no(|s| s.bid_depth[4]),
+            no_ask_depth_1: no(|s| s.ask_depth[0]),
+            no_ask_depth_2: no(|s| s.ask_depth[1]),
+            no_ask_depth_3: no(|s| s.ask_depth[2]),
+            no_ask_depth_4: no(|s| s.ask_depth[3]),
+            no_ask_depth_5: no(|s| s.ask_depth[4]),
+            yes_imbalance_1: yes(|s| s.imbalance_1),
+            yes_imbalance_5: yes(|s| s.imbalance_5),
+            no_imbalance_1: no(|s| s.imbalance_1),
+            no_imbalance_5: no(|s| s.imbalance_5),
+            yes_microprice: yes(|s| s.microprice),
+            no_microprice: no(|s| s.microprice),
+            yes_queue_mid: yes(|s| s.queue_mid),
+            no_queue_mid: no(|s| s.queue_mid),
+            yes_bid_depth_chg_5s: depth_chg(0),
+            yes_ask_depth_chg_5s: depth_chg(1),
+            no_bid_depth_chg_5s: depth_chg(2),
+            no_ask_depth_chg_5s: depth_chg(3),
+            age_s: age_s as i32,
+            time_remaining_s: time_remaining_s as i32,
+            in_entry_window: age_s >= 0 && !in_exit_only_window,
+            in_exit_only_window,
+            yes_mid_ret_1s: yes_ret(1),
+            yes_mid_ret_5s: yes_ret(5),
+            yes_mid_ret_15s: yes_ret(15),
+            yes_mid_ret_30s: yes_ret(30),
+            no_mid_ret_1s: no_ret(1),
+            no_mid_ret_5s: no_ret(5),
+            no_mid_ret_15s: no_ret(15),
+            no_mid_ret_30s: no_ret(30),
+            book_gap_z_30s: self.book_gap_z(ts, book_gap),
+            yes_spread_ema_15s,
+            no_spread_ema_15s,
+            realized_vol_15s: yes_vol(15),
+            realized_vol_30s: yes_vol(30),
+            realized_vol_60s: yes_vol(60),
+            spot_price: tick.spot_price,
+            spot_ret_1s: spot_ret(1),
+            spot_ret_5s: spot_ret(5),
+            spot_ret_15s: spot_ret(15),
+            spot_realized_vol_30s: tick.spot_price.and_then(|_| self.realized_vol(ts, 30, |s| s.spot)),
+            ..self.identity.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(ts: i64, yes_bid: f64, yes_ask: f64) -> BookTick {
+        BookTick { ts, yes_bid, yes_ask, no_bid: 1.0 - yes_ask, no_ask: 1.0 - yes_bid, ..Default::default() }
+    }
+
+    #[test]
+    fn returns_and_windows_follow_the_tick_history() {
+        let spec = CryptoBinaryMarketSpec::from_slug("btc-updown-5m-1000", "0xabc", 1000, 1300).unwrap();
+        let mut builder = FeatureBuilder::new(spec);
+
+        let first = builder.update(&tick(1000, 0.49, 0.51));
+        assert_eq!(first.yes_mid_ret_1s, 0.0);
+        assert_eq!(first.realized_vol_30s, 0.0);
+        assert_eq!(first.yes_spread_ema_15s, first.yes_spread);
+        assert!(first.in_entry_window);
+
+        for ts in 1001..1010 {
+            builder.update(&tick(ts, 0.49, 0.51));
+        }
+        let row = builder.update(&tick(1010, 0.59, 0.61));
+        assert!((row.yes_mid_ret_5s - 0.2).abs() < 1e-12);
+        assert!((row.no_mid_ret_1s + 0.2).abs() < 1e-12);
+        assert!((row.realized_vol_15s - 0.2).abs() < 1e-12);
+        assert!(row.book_gap_z_30s.abs() < 1e-12);
+        assert_eq!(row.age_s, 10);
+
+        let late = builder.update(&tick(1280, 0.59, 0.61));
+        assert!(late.in_exit_only_window && !late.in_entry_window);
+        // The 60s window has rolled past every earlier tick
+        assert_eq!(late.realized_vol_60s, 0.0);
+    }
+
+    #[test]
+    fn level_depth_gives_book_shape_columns() {
+        let spec = CryptoBinaryMarketSpec::from_slug("btc-updown-5m-1000", "0xabc", 1000, 1300).unwrap();
+        let mut builder = FeatureBuilder::new(spec);
+        let depth = |yes_bid_1: f64| BookDepth {
+            yes: TokenDepth { bid_sizes: [yes_bid_1, 20.0, 0.0, 0.0, 0.0], ask_sizes: [10.0, 10.0, 10.0, 0.0, 0.0] },
+            no: TokenDepth::default(),
+        };
+
+        let without = builder.update(&tick(1000, 0.40, 0.50));
+        assert!(!without.has_depth() && without.yes_microprice.is_none());
+
+        builder.update(&BookTick { depth: Some(depth(30.0)), ..tick(1001, 0.40, 0.50) });
+        let row = builder.update(&BookTick { depth: Some(depth(10.0)), ..tick(1006, 0.40, 0.50) });
+        assert_eq!(row.schema_version, "features_v2");
+        assert_eq!((row.yes_bid_depth_1, row.yes_bid_depth_2, row.yes_bid_depth_5), (Some(10.0), Some(30.0), Some(30.0)));
+        assert_eq!(row.yes_ask_depth_3, Some(30.0));
+        assert_eq!(row.yes_imbalance_1, Some(0.0));
+        assert_eq!(row.yes_imbalance_5, Some(0.0));
+        // Equal top sizes: the microprice is the mid
+        assert!((row.yes_microprice.unwrap() - 0.45).abs() < 1e-12);
+        // An empty NO book falls back to the mid
+        assert_eq!(row.no_imbalance_5, Some(0.0));
+        assert!((row.no_queue_mid.unwrap() - row.no_mid).abs() < 1e-12);
+        // Top-5 bid depth fell from 50 to 30 over 5s
+        assert!((row.yes_bid_depth_chg_5s.unwrap() + 4.0).abs() < 1e-12);
+        assert_eq!(row.yes_ask_depth_chg_5s, Some(0.0));
+
+        let thin_ask = builder.update(&BookTick {
+            depth: Some(BookDepth {
+                yes: TokenDepth { bid_sizes: [30.0, 0.0, 0.0, 0.0, 0.0], ask_sizes: [10.0, 0.0, 0.0, 0.0, 0.0] },
+                no: TokenDepth::default(),
+            }),
+            ..tick(1007, 0.40, 0.50)
+        });
+        // Heavy bids lean the microprice towards the ask
+        assert!((thin_ask.yes_microprice.unwrap() - 0.475).abs() < 1e-12);
+        assert!((thin_ask.yes_imbalance_1.unwrap() - 0.5).abs() < 1e-12);
+    }
+
+    /// Each row recomputed from the market's whole tick history
+    fn batch_features(identity: &FeatureRow, ticks: &[BookTick]) -> Vec<FeatureRow> {
+        let mid = |t: &BookTick| ((t.yes_bid + t.yes_ask) / 2.0, (t.no_bid + t.no_ask) / 2.0);
+        (0..ticks.len())
+            .map(|i| {
+                let tick = &ticks[i];
+                let past = &ticks[..=i];
+                let (yes_mid, no_mid) = mid(tick);
+                let book_gap = tick.yes_ask + tick.no_ask - 1.0;
+                let base = |h: i64| past.iter().rev().find(|t| t.ts <= tick.ts - h);
+                let window = |w: i64| past.iter().filter(move |t| t.ts > tick.ts - w);
+                let vol = |w: i64, value: &dyn Fn(&BookTick) -> Option<f64>| {
+                    let values: Vec<f64> = window(w).filter_map(value).collect();
+                    values.windows(2).map(|v| ret(v[1], Some(v[0])).powi(2)).sum::<f64>().sqrt()
+                };
+                let ema = |spread: &dyn Fn(&BookTick) -> f64| {
+                    let mut ema = TimeEma::default();
+                    past.iter().fold(0.0, |_, t| ema.update(t.ts, spread(t), 15.0))
+                };
+                let gaps: Vec<f64> = window(30).map(|t| t.yes_ask + t.no_ask - 1.0).collect();
+                let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+                let std = (gaps.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / gaps.len() as f64).sqrt();
+                let time_remaining_s = identity.market_end_ts - tick.ts;
+                let age_s = tick.ts - identity.market_start_ts;
+
+                let mut row = FeatureRow {
+                    ts: tick.ts,
+                    yes_bid: tick.yes_bid,
+                    yes_ask: tick.yes_ask,
+                    no_bid: tick.no_bid,
+                    no_ask: tick.no_ask,
+                    yes_mid,
+                    no_mid,
+                    yes_spread: tick.yes_ask - tick.yes_bid,
+                    no_spread: tick.no_ask - tick.no_bid,
+                    book_sum: tick.yes_ask + tick.no_ask,
+                    book_gap,
+                    age_s: age_s as i32,
+                    time_remaining_s: time_remaining_s as i32,
+                    in_entry_window: age_s >= 0 && time_remaining_s >= EXIT_ONLY_S,
+                    in_exit_only_window: time_remaining_s < EXIT_ONLY_S,
+                    yes_mid_ret_1s: ret(yes_mid, base(1).map(|t| mid(t).0)),
+                    yes_mid_ret_5s: ret(yes_mid, base(5).map(|t| mid(t).0)),
+                    yes_mid_ret_15s: ret(yes_mid, base(15).map(|t| mid(t).0)),
+                    yes_mid_ret_30s: ret(yes_mid, base(30).map(|t| mid(t).0)),
+                    no_mid_ret_1s: ret(no_mid, base(1).map(|t| mid(t).1)),
+                    no_mid_ret_5s: ret(no_mid, base(5).map(|t| mid(t).1)),
+                    no_mid_ret_15s: ret(no_mid, base(15).map(|t| mid(t).1)),
+                    no_mid_ret_30s: ret(no_mid, base(30).map(|t| mid(t).1)),
+                    book_gap_z_30s: if std > 1e-12 { (book_gap - mean) / std } else { 0.0 },
+                    yes_spread_ema_15s: ema(&|t| t.yes_ask - t.yes_bid),
+                    no_spread_ema_15s: ema(&|t| t.no_ask - t.no_bid),
+                    realized_vol_15s: vol(15, &|t| Some(mid(t).0)),
+                    realized_vol_30s: vol(30, &|t| Some(mid(t).0)),
+                    realized_vol_60s: vol(60, &|t| Some(mid(t).0)),
+                    spot_price: tick.spot_price,
+                    spot_ret_1s: tick.spot_price.map(|spot| ret(spot, base(1).and_then(|t| t.spot_price))),
+                    spot_ret_5s: tick.spot_price.map(|spot| ret(spot, base(5).and_then(|t| t.spot_price))),
+                    spot_ret_15s: tick.spot_price.map(|spot| ret(spot, base(15).and_then(|t| t.spot_price))),
+                    spot_realized_vol_30s: tick.spot_price.map(|_| vol(30, &|t| t.spot_price)),
+                    ..identity.clone()
+                };
+                if let Some(depth) = tick.depth {
+                    let cum = |sizes: &[f64; DEPTH_LEVELS], k: usize| Some(sizes[..k].iter().sum::<f64>());
+                    let (yes, no) = (&depth.yes, &depth.no);
+                    row.yes_bid_depth_1 = cum(&yes.bid_sizes, 1);
+                    row.yes_bid_depth_2 = cum(&yes.bid_sizes, 2);
+                    row.yes_bid_depth_3 = cum(&yes.bid_sizes, 3);
+                    row.yes_bid_depth_4 = cum(&yes.bid_sizes, 4);
+                    row.yes_bid_depth_5 = cum(&yes.bid_sizes, 5);
+                    row.yes_ask_depth_1 = cum(&yes.ask_sizes, 1);
+                    row.yes_ask_depth_2 = cum(&yes.ask_sizes, 2);
+                    row.yes_ask_depth_3 = cum(&yes.ask_sizes, 3);
+                    row.yes_ask_depth_4 = cum(&yes.ask_sizes, 4);
+                    row.yes_ask_depth_5 = cum(&yes.ask_sizes, 5);
+                    row.no_bid_depth_1 = cum(&no.bid_sizes, 1);
+                    row.no_bid_depth_2 = cum(&no.bid_sizes, 2);
+                    row.no_bid_depth_3 = cum(&no.bid_sizes, 3);
+                    row.no_bid_depth_4 = cum(&no.bid_sizes, 4);
+                    row.no_bid_depth_5 = cum(&no.bid_sizes, 5);
+                    row.no_ask_depth_1 = cum(&no.ask_sizes, 1);
+                    row.no_ask_depth_2 = cum(&no.ask_sizes, 2);
+                    row.no_ask_depth_3 = cum(&no.ask_sizes, 3);
+                    row.no_ask_depth_4 = cum(&no.ask_sizes, 4);
+                    row.no_ask_depth_5 = cum(&no.ask_sizes, 5);
+                    row.yes_imbalance_1 = Some(imbalance(yes.bid_sizes[0], yes.ask_sizes[0]));
+                    row.yes_imbalance_5 = Some(imbalance(yes.bid_depth(5), yes.ask_depth(5)));
+                    row.no_imbalance_1 = Some(imbalance(no.bid_sizes[0], no.ask_sizes[0]));
+                    row.no_imbalance_5 = Some(imbalance(no.bid_depth(5), no.ask_depth(5)));
+                    row.yes_microprice = Some(weighted_mid(tick.yes_bid, tick.yes_ask, yes.bid_sizes[0], yes.ask_sizes[0]));
+                    row.no_microprice = Some(weighted_mid(tick.no_bid, tick.no_ask, no.bid_sizes[0], no.ask_sizes[0]));
+                    row.yes_queue_mid = Some(weighted_mid(tick.yes_bid, tick.yes_ask, yes.bid_depth(5), yes.ask_depth(5)));
+                    row.no_queue_mid = Some(weighted_mid(tick.no_bid, tick.no_ask, no.bid_depth(5), no.ask_depth(5)));
+                    let depth_5 = |d: &BookDepth| [d.yes.bid_depth(5), d.yes.ask_depth(5), d.no.bid_depth(5), d.no.ask_depth(5)];
+                    let now = depth_5(&depth);
+                    let chg = |i: usize| {
+                        Some(match base(DEPTH_CHANGE_S) {
+                            Some(t) if t.depth.is_some() => {
+                                (now[i] - depth_5(&t.depth.unwrap())[i]) / (tick.ts - t.ts) as f64
+                            }
+                            _ => 0.0,
+                        })
+                    };
+                    row.yes_bid_depth_chg_5s = chg(0);
+                    row.yes_ask_depth_chg_5s = chg(1);
+                    row.no_bid_depth_chg_5s = chg(2);
+                    row.no_ask_depth_chg_5s = chg(3);
+                }
+                row
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streaming_rows_match_the_batch_computation() {
+        let spec = CryptoBinaryMarketSpec::from_slug("eth-updown-15m-5000", "0xdef", 5000, 5900).unwrap();
+        // Irregular ticks with gaps longer than the 60s lookback and a spot feed
+        // that starts late
+        let mut ts = 5000;
+        let ticks: Vec<BookTick> = (0..400)
+            .map(|i| {
+                ts += [1, 1, 2, 3, 1, 7, 1, 90][i % 8];
+                let yes_bid = 0.30 + 0.4 * ((i as f64) * 0.37).sin().abs();
+                let spread = 0.01 + 0.01 * (i % 3) as f64;
+                BookTick {
+                    ts,
+                    yes_bid,
+                    yes_ask: yes_bid + spread,
+                    no_bid: 1.0 - yes_bid - spread - 0.01 * (i % 2) as f64,
+                    no_ask: 1.0 - yes_bid + 0.01 * (i % 5) as f64,
+                    spot_price: (i > 40).then(|| 3000.0 + (i as f64 * 0.11).cos() * 25.0),
+                    // Depth drops out now and then, leaving some change rates without a base
+                    depth: (i % 11 != 0).then(|| {
+                        let sizes = |k: f64| std::array::from_fn(|l| (10.0 + k * (i as f64 * 0.7 + l as f64).cos()).max(0.0));
+                        BookDepth {
+                            yes: TokenDepth { bid_sizes: sizes(6.0), ask_sizes: sizes(8.0) },
+                            no: TokenDepth { bid_sizes: sizes(12.0), ask_sizes: sizes(3.0) },
+                        }
+                    }),
+                }
+            })
+            .collect();
+
+        let mut builder = FeatureBuilder::new(spec);
+        let expected = batch_features(&builder.identity.clone(), &ticks);
+        let streamed: Vec<FeatureRow> = ticks.iter().map(|t| builder.update(t)).collect();
+        assert!(builder.history.len() < 70, "history is pruned to the lookback");
+        assert_eq!(streamed, expected);
+    }
+}
@@ -1,11 +1,34 @@
 //! Feature Export
 //!
-//! Export PMXT archive data to feature parquet.
-
-use super::{CryptoBinaryMarketSpec, FeatureRow, LabelConfig, LabelRow, Labeler, ResearchConfig};
+//! Export PMXT archive data to feature parquet. Book snapshots (`bids`/`asks`)
+//! and level changes (`change_*`) are replayed per token into `features_v2`
+//! depth columns.
+
+use super::schema::{DEPTH_FEATURE_COLUMNS, FEATURE_SCHEMA_VERSIONS};
+use super::{
+    BookTick, CostModel, CryptoBinaryMarketSpec, FeatureBuilder, FeatureRow, LabelConfig, LabelRow, Labeler, Manifest,
+    ResearchConfig,
+};
+use crate::bot::feed::{BookDepth, TokenDepth};
+use crate::bot::feed_base::OutcomeSide;
+use crate::bot::logging::{read_json_records, JsonlAppender};
+use crate::bot::pipeline::{build_book_replay, load_market_rows, DiscoveredMarket, ReplayRow};
+use crate::bot::resolution::Resolutions;
 use anyhow::{anyhow, Result};
 use parquet::file::writer::SerializedFileWriter;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Model input columns, all computed by `FeatureBuilder`
+const FEATURE_COLUMNS: &[&str] = &[
+    "yes_bid", "yes_ask", "no_bid", "no_ask",
+    "yes_mid", "no_mid", "yes_spread", "no_spread",
+    "book_sum", "book_gap",
+    "yes_mid_ret_1s", "yes_mid_ret_5s", "yes_mid_ret_15s", "yes_mid_ret_30s",
+    "no_mid_ret_1s", "no_mid_ret_5s", "no_mid_ret_15s", "no_mid_ret_30s",
+    "book_gap_z_30s", "yes_spread_ema_15s", "no_spread_ema_15s",
+    "realized_vol_15s", "realized_vol_30s", "realized_vol_60s",
+    "age_s", "time_remaining_s",
+];
 
 /// Feature exporter
 pub struct FeatureExporter {
@@ -21,21 +44,36 @@ impl FeatureExporter {
         }
     }
 
-    /// Export features from PMXT archive
+    /// Compute the label families `config` selects, priced with `cost_model`
+    pub fn with_label_config(mut self, config: LabelConfig, cost_model: CostModel) -> Self {
+        self.labeler = self.labeler.with_config(config).with_cost_model(cost_model);
+        self
+    }
+
+    /// Label with official market outcomes
+    pub fn with_resolutions(mut self, resolutions: Resolutions) -> Self {
+        self.labeler = self.labeler.with_resolutions(resolutions);
+        self
+    }
+
+    /// Read feature rows of `markets` from a PMXT archive, each market over
+    /// its own window
+    pub fn read<P: AsRef<Path>>(&self, input_path: P, markets: &[DiscoveredMarket]) -> Result<Vec<FeatureRow>> {
+        self.read_pmxt_archive(input_path.as_ref(), markets)
+    }
+
+    /// Export features and labels read from a PMXT archive
     ///
     /// # Arguments
-    /// * `input_path` - Path to PMXT archive parquet
+    /// * `rows` - Feature rows from `read`
     /// * `output_path` - Output path for features parquet
     /// * `manifest_path` - Output path for manifest JSON
-    pub fn export<P1: AsRef<Path>, P2: AsRef<Path>, P3: AsRef<Path>>(
+    pub fn export<P1: AsRef<Path>, P2: AsRef<Path>>(
         &self,
-        input_path: P1,
-        output_path: P2,
-        manifest_path: P3,
+        rows: &[FeatureRow],
+        output_path: P1,
+        manifest_path: P2,
     ) -> Result<(usize, usize)> {
-        // Read PMXT archive
-        let rows = self.read_pmxt_archive(input_path.as_ref())?;
-
         if rows.len() < self.config.min_samples {
             return Err(anyhow!(
                 "Not enough samples: {} < {}",
@@ -45,117 +83,86 @@ impl FeatureExporter {
         }
 
         // Compute labels if configured
-        let labels = self.labeler.compute_labels(&rows);
+        let labels = self.labeler.compute_labels(rows);
 
         // Write features parquet
-        let feature_count = self.write_features_parquet(&rows, output_path.as_ref())?;
+        let features_path = Self::features_path(output_path.as_ref());
+        let feature_count = self.write_features_parquet(rows, &features_path)?;
+
+        // Labels sit next to the features, row for row
+        let labels_path = Self::labels_path(output_path.as_ref());
+        std::fs::write(&labels_path, serde_json::to_string_pretty(&labels)?)?;
 
         // Write manifest
-        self.write_manifest(&rows, &labels, manifest_path.as_ref())?;
+        self.write_manifest(rows, &labels, &features_path, &labels_path, manifest_path.as_ref())?;
 
         Ok((feature_count, labels.len()))
     }
 
-    fn read_pmxt_archive(&self, path: &Path) -> Result<Vec<FeatureRow>> {
-        use parquet::file::reader::{FileReader, SerializedFileReader};
-        use parquet::record::RowAccessor;
+    /// Where `export` writes the features for `output_path`
+    pub fn features_path(output_path: &Path) -> PathBuf {
+        output_path.with_extension("json")
+    }
+
+    /// Where `export` writes the labels for `output_path`
+    pub fn labels_path(output_path: &Path) -> PathBuf {
+        output_path.with_extension("labels.json")
+    }
 
-        let file = std::fs::File::open(path)?;
-        let reader = SerializedFileReader::new(file)?;
+    fn read_pmxt_archive(&self, path: &Path, markets: &[DiscoveredMarket]) -> Result<Vec<FeatureRow>> {
+        let input = path.to_string_lossy();
+        let conn = duckdb::Connection::open_in_memory()?;
 
         let mut rows = Vec::new();
-        for i in 0..reader.num_row_groups() {
-            let row_group = reader.get_row_group(i)?;
-            let iter = row_group.get_row_iter(None)?;
-            for row in iter {
-                let row = row?;
-                let condition_id = row.get_string(0).map(|s| s.to_string()).unwrap_or_default();
-                let ts: i64 = row.get_long(1).unwrap_or(0);
-
-                let yes_bid: f64 = row.get_double(2).unwrap_or(0.5);
-                let yes_ask: f64 = row.get_double(3).unwrap_or(0.5);
-                let no_bid: f64 = row.get_double(4).unwrap_or(0.5);
-                let no_ask: f64 = row.get_double(5).unwrap_or(0.5);
-
-                let yes_mid = (yes_bid + yes_ask) / 2.0;
-                let no_mid = (no_bid + no_ask) / 2.0;
-
-                // Parse market spec
-                let spec = CryptoBinaryMarketSpec::from_slug(
-                    &format!("{}-market", condition_id),
-                    &condition_id,
-                    ts - 100,
-                    ts + 200,
-                );
-
-                let (asset, duration, family) = match spec {
-                    Some(s) => (
-                        s.asset.as_str().to_string(),
-                        s.duration.as_str().to_string(),
-                        s.family.as_str().to_string(),
-                    ),
-                    None => (
-                        "unknown".to_string(),
-                        "5m".to_string(),
-                        "updown_open_close".to_string(),
-                    ),
-                };
-
-                rows.push(FeatureRow {
-                    schema_version: FeatureRow::schema_version().to_string(),
-                    market_slug: format!("{}-market", condition_id),
-                    instrument: condition_id.clone(),
-                    condition_id,
-                    asset,
-                    duration,
-                    market_family: family,
-                    market_start_ts: ts - 100,
-                    market_end_ts: ts + 200,
-                    ts,
-                    yes_bid,
-                    yes_ask,
-                    no_bid,
-                    no_ask,
-                    yes_mid,
-                    no_mid,
-                    yes_spread: yes_ask - yes_bid,
-                    no_spread: no_ask - no_bid,
-                    book_sum: yes_ask + no_ask,
-                    book_gap: yes_ask + no_ask - 1.0,
-                    yes_bid_depth_1: None,
-                    yes_ask_depth_1: None,
-                    no_bid_depth_1: None,
-                    no_ask_depth_1: None,
-                    age_s: 0,
-                    time_remaining_s: 200,
-                    in_entry_window: true,
-                    in_exit_only_window: false,
-                    yes_mid_ret_1s: 0.0,
-                    yes_mid_ret_5s: 0.0,
-                    yes_mid_ret_15s: 0.0,
-                    yes_mid_ret_30s: 0.0,
-                    no_mid_ret_1s: 0.0,
-                    no_mid_ret_5s: 0.0,
-                    no_mid_ret_15s: 0.0,
-                    no_mid_ret_30s: 0.0,
-                    book_gap_z_30s: 0.0,
-                    yes_spread_ema_15s: 0.0,
-                    no_spread_ema_15s: 0.0,
-                    realized_vol_15s: 0.0,
-                    realized_vol_30s: 0.0,
-                    realized_vol_60s: 0.0,
-                    spot_price: None,
-                    spot_ret_1s: None,
-                    spot_ret_5s: None,
-                    spot_ret_15s: None,
-                    spot_realized_vol_30s: None,
-                });
-            }
+        for market in markets {
+            let ticks = self.book_ticks(&load_market_rows(&conn, &input, &market.condition_id, true)?);
+            let (id, slug) = (&market.condition_id, &market.slug);
+            let mut builder = match CryptoBinaryMarketSpec::from_slug(slug, id, market.start_ts, market.end_ts) {
+                Some(spec) => FeatureBuilder::new(spec),
+                None => FeatureBuilder::unclassified(id, slug, market.start_ts, market.end_ts),
+            };
+            rows.extend(ticks.iter().map(|tick| builder.update(tick)));
         }
 
         Ok(rows)
     }
 
+    /// One tick per `resolution_seconds` bucket with the quotes after the
+    /// bucket's last event. Level depth is replayed from the PMXT book
+    /// snapshots and changes, so markets without them carry none.
+    fn book_ticks(&self, events: &[ReplayRow]) -> Vec<BookTick> {
+        let resolution = self.config.resolution_seconds.max(1);
+        let bucket = |event: &ReplayRow| (event.ts.floor() as i64).div_euclid(resolution) * resolution;
+        let with_depth = events.iter().any(|e| e.depth.is_some());
+        let mut book = build_book_replay(events);
+
+        let (mut yes, mut no) = (None, None);
+        let mut ticks = Vec::new();
+        for (i, event) in events.iter().enumerate() {
+            match event.outcome_side() {
+                Some(OutcomeSide::Yes) => yes = Some((event.bid, event.ask)),
+                Some(OutcomeSide::No) => no = Some((event.bid, event.ask)),
+                None => {}
+            }
+            if events.get(i + 1).is_some_and(|next| bucket(next) == bucket(event)) {
+                continue;
+            }
+            let (Some((yes_bid, yes_ask)), Some((no_bid, no_ask))) = (yes, no) else {
+                continue;
+            };
+            let depth = with_depth.then(|| {
+                let (yes_bids, yes_asks) = book.levels_at(event.ts, OutcomeSide::Yes);
+                let (no_bids, no_asks) = book.levels_at(event.ts, OutcomeSide::No);
+                BookDepth {
+                    yes: TokenDepth::from_levels(&yes_bids, &yes_asks),
+                    no: TokenDepth::from_levels(&no_bids, &no_asks),
+                }
+            });
+            ticks.push(BookTick { ts: bucket(event), yes_bid, yes_ask, no_bid, no_ask, depth, ..Default::default() });
+        }
+        ticks
+    }
+
     fn write_features_parquet(&self, rows: &[FeatureRow], path: &Path) -> Result<usize> {
         // Create directory if needed
         if let Some(parent) = path.parent() {
@@ -165,14 +172,19 @@ impl FeatureExporter {
         // Write as JSON for simplicity (parquet writing is complex)
         // In production, use proper parquet writer
         let json = serde_json::to_string_pretty(rows)?;
-        std::fs::write(path.with_extension("json"), json)?;
+        std::fs::write(path, json)?;
 
         Ok(rows.len())
     }
 
-    fn write_manifest(&self, rows: &[FeatureRow], labels: &[LabelRow], path: &Path) -> Result<()> {
-        use super::Manifest;
-
+    fn write_manifest(
+        &self,
+        rows: &[FeatureRow],
+        labels: &[LabelRow],
+        features_path: &Path,
+        labels_path: &Path,
+        path: &Path,
+    ) -> Result<()> {
         if rows.is_empty() {
             return Ok(());
         }
@@ -185,9 +197,35 @@ impl FeatureExporter {
         let train_end = min_ts + (total_duration as f64 * 0.7) as i64;
         let valid_end = min_ts + (total_duration as f64 * 0.85) as i64;
 
+        let mut label_columns = vec![
+            "label_reachable_yes_30s".to_string(),
+            "label_reachable_no_30s".to_string(),
+            "label_edge_yes_30s".to_string(),
+            "label_edge_no_30s".to_string(),
+        ];
+        if labels.iter().any(|l| l.label_resolution.is_some()) {
+            label_columns.push("label_resolution".to_string());
+        }
+        // Outcome labels exist only for resolved markets
+        let resolved = labels.iter().any(|l| l.label_outcome_yes.is_some());
+        label_columns.extend(
+            self.labeler
+                .config()
+                .label_columns()
+                .into_iter()
+                .filter(|c| resolved || !c.starts_with("label_outcome")),
+        );
+
+        let mut feature_columns: Vec<String> = FEATURE_COLUMNS.iter().map(|c| c.to_string()).collect();
+        if rows.iter().any(FeatureRow::has_depth) {
+            feature_columns.extend(DEPTH_FEATURE_COLUMNS.iter().map(|c| c.to_string()));
+        }
+
         let manifest = Manifest {
             schema_version: Manifest::schema_version().to_string(),
-            features_path: path.display().to_string(),
+            feature_schema_version: FeatureRow::schema_version().to_string(),
+            features_path: features_path.display().to_string(),
+            labels_path: Some(labels_path.display().to_string()),
             asset: rows[0].asset.clone(),
             duration: rows[0].duration.clone(),
             market_family: rows[0].market_family.clone(),
@@ -201,20 +239,8 @@ impl FeatureExporter {
             valid_end_ts: valid_end,
             test_start_ts: valid_end,
             test_end_ts: max_ts,
-            label_columns: vec![
-                "label_reachable_yes_30s".to_string(),
-                "label_reachable_no_30s".to_string(),
-                "label_edge_yes_30s".to_string(),
-                "label_edge_no_30s".to_string(),
-            ],
-            feature_columns: vec![
-                "yes_bid".to_string(),
-                "yes_ask".to_string(),
-                "no_bid".to_string(),
-                "no_ask".to_string(),
-                "book_sum".to_string(),
-                "book_gap".to_string(),
-            ],
+            label_columns,
+            feature_columns,
         };
 
         let json = serde_json::to_string_pretty(&manifest)?;
@@ -230,20 +256,235 @@ impl Default for FeatureExporter {
     }
 }
 
-/// Inspect features from a file
-pub fn inspect_features<P: AsRef<Path>>(path: P, sample_count: usize) -> Result<()> {
-    let content = std::fs::read_to_string(path)?;
-    let rows: Vec<FeatureRow> = serde_json::from_str(&content)?;
+/// Appends feature rows as JSON lines, e.g. from a live loop
+pub struct FeatureLog {
+    log: JsonlAppender,
+}
+
+impl FeatureLog {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self { log: JsonlAppender::create(path)? })
+    }
+
+    pub fn append(&mut self, row: &FeatureRow) -> Result<()> {
+        self.log.append(row)
+    }
+}
+
+/// Inspect features from an export (JSON array) or a live feature log
+/// (JSON lines), of any supported schema version, optionally checked
+/// against the manifest describing them
+pub fn inspect_features<P: AsRef<Path>>(path: P, sample_count: usize, manifest_path: Option<&str>) -> Result<()> {
+    let rows: Vec<FeatureRow> = read_json_records(path)?;
+
+    let mut versions: Vec<(&str, usize)> = Vec::new();
+    for row in &rows {
+        match versions.iter_mut().find(|(v, _)| *v == row.schema_version) {
+            Some((_, count)) => *count += 1,
+            None => versions.push((&row.schema_version, 1)),
+        }
+    }
+    if let Some((version, _)) = versions.iter().find(|(v, _)| !FEATURE_SCHEMA_VERSIONS.contains(v)) {
+        return Err(anyhow!("Unsupported feature schema version {}", version));
+    }
 
     println!("Total rows: {}", rows.len());
+    for (version, count) in &versions {
+        println!("  {}: {} rows", version, count);
+    }
+    println!("Rows with level depth: {}", rows.iter().filter(|r| r.has_depth()).count());
+
+    if let Some(manifest_path) = manifest_path {
+        let manifest = Manifest::load(manifest_path)?;
+        println!(
+            "Manifest: {} describing {} ({} feature columns)",
+            manifest.schema_version,
+            manifest.feature_schema_version,
+            manifest.feature_columns.len()
+        );
+        if versions.iter().any(|(v, _)| *v != manifest.feature_schema_version) {
+            eprintln!("[warn] Rows do not all match the manifest's {}", manifest.feature_schema_version);
+        }
+        let probe = FeatureRow::default();
+        let unknown: Vec<&String> = manifest.feature_columns.iter().filter(|c| probe.feature(c).is_none()).collect();
+        if !unknown.is_empty() {
+            eprintln!("[warn] Manifest lists unknown feature columns: {:?}", unknown);
+        }
+    }
+
     println!("\nFirst {} samples:\n", sample_count.min(rows.len()));
 
     for (i, row) in rows.iter().take(sample_count).enumerate() {
-        println!(
+        print!(
             "[{}] {} @ {} - yes_mid: {:.4}, book_gap: {:.4}",
             i, row.condition_id, row.ts, row.yes_mid, row.book_gap
         );
+        match (row.yes_microprice, row.yes_imbalance_5, row.yes_bid_depth_5) {
+            (Some(microprice), Some(imbalance), Some(depth)) => println!(
+                ", yes_microprice: {:.4}, yes_imbalance_5: {:.3}, yes_bid_depth_5: {:.1}",
+                microprice, imbalance, depth
+            ),
+            _ => println!(),
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::backtest::fills::BookUpdate;
+    use crate::bot::feed_base::{BookChangeSide, BookDeltaEvent, DualBookState};
+    use crate::bot::research::LabelKind;
+    use crate::bot::resolution::{Resolution, ResolutionSource};
+    use crate::bot::strategy::registry::{StrategyConfig, StrategyKind};
+    use crate::bot::strategy_runner::StrategyDriver;
+    use serde_json::{json, Value};
+
+    fn market() -> DiscoveredMarket {
+        DiscoveredMarket {
+            condition_id: "0xabc".to_string(),
+            slug: "btc-updown-5m-900".to_string(),
+            question: String::new(),
+            start_ts: 900,
+            end_ts: 1200,
+            ticks: 0,
+            min_ts: 0.0,
+            max_ts: 0.0,
+        }
+    }
+
+    fn book_events() -> Vec<Value> {
+        vec![
+            json!({"timestamp": 1000.1, "side": "YES", "best_bid": "0.48", "best_ask": "0.50",
+                   "bids": [["0.48", "10"], ["0.47", "5"]], "asks": [["0.50", "4"]]}),
+            json!({"timestamp": 1000.2, "side": "NO", "best_bid": "0.50", "best_ask": "0.52",
+                   "bids": [["0.50", "6"]], "asks": [["0.52", "8"]]}),
+            json!({"timestamp": 1001.4, "side": "YES", "best_bid": "0.48", "best_ask": "0.50",
+                   "change_price": "0.51", "change_size": "3", "change_side": "SELL"}),
+            json!({"timestamp": 1001.5, "side": "YES", "best_bid": "0.48", "best_ask": "0.51",
+                   "change_price": "0.50", "change_size": "0", "change_side": "SELL"}),
+            json!({"timestamp": 1003.2, "side": "NO", "best_bid": "0.49", "best_ask": "0.52",
+                   "change_price": "0.50", "change_size": "0", "change_side": "BUY"}),
+            json!({"timestamp": 1003.9, "side": "YES", "best_bid": "0.49", "best_ask": "0.51",
+                   "change_price": "0.49", "change_size": "7", "change_side": "BUY"}),
+        ]
+    }
+
+    /// PMXT archive of `events` for `market()`, in the archive's
+    /// `market_id` + JSON `data` layout
+    fn write_archive(dir: &str, events: &[Value]) -> String {
+        let archive = format!("{dir}/orderbook.parquet");
+        let values: Vec<String> = events.iter().map(|data| format!("('0xabc', '{}')", data)).collect();
+        duckdb::Connection::open_in_memory()
+            .unwrap()
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES {}) AS t(market_id, data)) TO '{}' (FORMAT PARQUET)",
+                values.join(", "),
+                archive
+            ))
+            .unwrap();
+        archive
+    }
+
+    /// The websocket delta carrying the same book event
+    fn book_delta(event: &Value) -> BookDeltaEvent {
+        let num = |key: &str| event[key].as_str().and_then(|v| v.parse().ok());
+        let levels = |key: &str| event.get(key).and_then(|v| BookUpdate::parse_levels(&v.to_string()));
+        BookDeltaEvent {
+            market_id: None,
+            token_id: String::new(),
+            side: if event["side"] == "YES" { OutcomeSide::Yes } else { OutcomeSide::No },
+            ts_exchange: event["timestamp"].as_f64().unwrap(),
+            best_bid: num("best_bid").unwrap(),
+            best_ask: num("best_ask").unwrap(),
+            change_price: num("change_price"),
+            change_size: num("change_size"),
+            change_side: match event["change_side"].as_str() {
+                Some("BUY") => Some(BookChangeSide::Buy),
+                Some("SELL") => Some(BookChangeSide::Sell),
+                _ => None,
+            },
+            top5_bid_depth: None,
+            top5_ask_depth: None,
+            bids: levels("bids"),
+            asks: levels("asks"),
+            source: "test",
+        }
+    }
+
+    #[test]
+    fn depth_is_replayed_from_pmxt_book_events() {
+        let dir = crate::bot::test_util::tempdir();
+        let archive = write_archive(&dir, &book_events());
+
+        let rows = FeatureExporter::default().read(&archive, &[market()]).unwrap();
+        assert_eq!(rows.iter().map(|r| r.ts).collect::<Vec<_>>(), vec![1000, 1001, 1003]);
+        assert_eq!((rows[0].yes_bid_depth_1, rows[0].yes_bid_depth_2), (Some(10.0), Some(15.0)));
+        assert_eq!((rows[0].yes_ask_depth_1, rows[0].no_bid_depth_1), (Some(4.0), Some(6.0)));
+        // The 0.50 ask was pulled, leaving the 0.51 level on top
+        assert_eq!((rows[1].yes_ask, rows[1].yes_ask_depth_1), (0.51, Some(3.0)));
+        assert_eq!(rows[1].schema_version, "features_v2");
+        // Rows span the market's own window, not its first and last tick
+        assert_eq!((rows[0].market_slug.as_str(), rows[0].market_end_ts), ("btc-updown-5m-900", 1200));
+        assert_eq!(rows[2].time_remaining_s, 197);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_matches_the_live_driver_on_the_same_events() {
+        let dir = crate::bot::test_util::tempdir();
+        let events = book_events();
+        let archive = write_archive(&dir, &events);
+        let exported = FeatureExporter::default().read(&archive, &[market()]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The live loop applies each delta and samples its book once a second
+        let market = market();
+        let spec = CryptoBinaryMarketSpec::from_slug(&market.slug, &market.condition_id, market.start_ts, market.end_ts);
+        let mut driver = StrategyDriver::new(&StrategyConfig::default_for(StrategyKind::Scalper));
+        driver.begin_market(spec.unwrap());
+        let mut book = DualBookState::default();
+        let mut streamed = Vec::new();
+        for (i, event) in events.iter().enumerate() {
+            let delta = book_delta(event);
+            book.apply(&delta);
+            let second = delta.ts_exchange.floor();
+            if events.get(i + 1).is_some_and(|next| next["timestamp"].as_f64().unwrap().floor() == second) {
+                continue;
+            }
+            driver.record_depth(book.depth());
+            driver.step(&book.snapshot().unwrap(), &market.slug, market.end_ts, second as u64, None);
+            streamed.extend(driver.take_features());
+        }
+
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(serde_json::to_value(&exported).unwrap(), serde_json::to_value(&streamed).unwrap());
+    }
+
+    #[test]
+    fn labels_are_written_beside_the_features_over_the_market_window() {
+        let dir = crate::bot::test_util::tempdir();
+        let archive = write_archive(&dir, &book_events());
+        let mut resolutions = Resolutions::default();
+        resolutions.insert("0xabc", Resolution { yes_payout: 1.0, source: ResolutionSource::Gamma });
+        let kinds = vec![LabelKind::TripleBarrier, LabelKind::ExpectedValue];
+        let exporter = FeatureExporter::new(ResearchConfig { min_samples: 1, ..Default::default() })
+            .with_label_config(LabelConfig { kinds, ..Default::default() }, CostModel::default())
+            .with_resolutions(resolutions);
+
+        let rows = exporter.read(&archive, &[market()]).unwrap();
+        let (out, manifest_path) = (format!("{dir}/export/features.parquet"), format!("{dir}/export/manifest.json"));
+        assert_eq!(exporter.export(&rows, &out, &manifest_path).unwrap(), (3, 3));
+
+        let manifest = Manifest::load(&manifest_path).unwrap();
+        assert_eq!(manifest.features_path, format!("{dir}/export/features.json"));
+        let labels: Vec<LabelRow> = read_json_records(manifest.labels_path.unwrap()).unwrap();
+        assert_eq!(labels.iter().map(|l| l.ts).collect::<Vec<_>>(), vec![1000, 1001, 1003]);
+        assert!(labels[0].label_ev_yes_15s.is_some() && labels[0].label_barrier_yes.is_some());
+        // The market runs to 1200: nothing after the last tick settles it
+        assert_eq!((labels[2].label_ev_yes_45s, labels[2].label_barrier_yes), (None, None));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -16,7 +16,7 @@ pub enum FusionMode {
 }
 
 /// Fusion configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FusionConfig {
     /// Minimum Qlib score threshold for entry
     pub score_threshold: f64,
@@ -0,0 +1,311 @@
+//! Model Inference
+//!
+//! Scores `FeatureRow`s in process with models trained in `research/qlib`
+//! and dumped to JSON by `export_model.py`: standardized logistic
+//! regressions and LightGBM binary tree ensembles. Each model predicts one
+//! `label_reachable_*` target; `ModelScorer` writes each prediction into the
+//! matching `ScoreRow` column and leaves the rest at the neutral defaults
+//! `export_scores.py` uses, so live and offline scores share one schema.
+
+use super::{FeatureRow, ScoreLoader, ScoreRow};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Score for targets no loaded model predicts
+const NEUTRAL_SCORE: f64 = 0.5;
+
+/// Risk score `export_scores.py` writes until risk models exist
+const DEFAULT_RISK: f64 = 0.1;
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+/// LightGBM `tree_structure` node; numerical splits only
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum TreeNode {
+    Split {
+        split_feature: usize,
+        threshold: f64,
+        #[serde(default)]
+        default_left: bool,
+        left_child: Box<TreeNode>,
+        right_child: Box<TreeNode>,
+    },
+    Leaf {
+        leaf_value: f64,
+    },
+}
+
+impl TreeNode {
+    fn eval(&self, x: &[f64]) -> f64 {
+        let mut node = self;
+        loop {
+            match node {
+                Self::Leaf { leaf_value } => return *leaf_value,
+                Self::Split { split_feature, threshold, default_left, left_child, right_child } => {
+                    let value = x[*split_feature];
+                    let left = if value.is_nan() { *default_left } else { value <= *threshold };
+                    node = if left { left_child } else { right_child };
+                }
+            }
+        }
+    }
+
+    fn max_feature(&self) -> Option<usize> {
+        match self {
+            Self::Leaf { .. } => None,
+            Self::Split { split_feature, left_child, right_child, .. } => [
+                Some(*split_feature),
+                left_child.max_feature(),
+                right_child.max_feature(),
+            ]
+            .into_iter()
+            .flatten()
+            .max(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ModelSpec {
+    /// `StandardScaler` + `LogisticRegression`; empty mean/scale skip scaling
+    Logistic {
+        intercept: f64,
+        coefficients: Vec<f64>,
+        #[serde(default)]
+        mean: Vec<f64>,
+        #[serde(default)]
+        scale: Vec<f64>,
+    },
+    /// Binary-objective boosted trees; the summed raw score goes through a sigmoid
+    Trees {
+        #[serde(default)]
+        base_score: f64,
+        trees: Vec<TreeNode>,
+    },
+}
+
+/// One trained model and the feature columns it reads, in training order
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoringModel {
+    pub model_version: String,
+    pub target: String,
+    pub feature_columns: Vec<String>,
+    model: ModelSpec,
+}
+
+impl ScoringModel {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let model: Self = serde_json::from_str(&content).with_context(|| format!("Invalid model file {}", path.display()))?;
+        model.validate().with_context(|| format!("Invalid model file {}", path.display()))?;
+        Ok(model)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let probe = FeatureRow::default();
+        if let Some(column) = self.feature_columns.iter().find(|c| probe.feature(c).is_none()) {
+            anyhow::bail!("Unknown feature column {}", column);
+        }
+        let n = self.feature_columns.len();
+        match &self.model {
+            ModelSpec::Logistic { coefficients, mean, scale, .. } => {
+                if coefficients.len() != n
+                    || (!mean.is_empty() && mean.len() != n)
+                    || (!scale.is_empty() && scale.len() != n)
+                {
+                    anyhow::bail!("Logistic weights do not match {} feature columns", n);
+                }
+            }
+            ModelSpec::Trees { trees, .. } => {
+                if let Some(feature) = trees.iter().filter_map(TreeNode::max_feature).max().filter(|f| *f >= n) {
+                    anyhow::bail!("Tree splits on feature {} of {}", feature, n);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Probability of the target label for one row
+    pub fn predict(&self, row: &FeatureRow) -> f64 {
+        let x: Vec<f64> = self.feature_columns.iter().map(|c| row.feature(c).unwrap_or(0.0)).collect();
+        match &self.model {
+            ModelSpec::Logistic { intercept, coefficients, mean, scale } => {
+                let z = x.iter().enumerate().fold(*intercept, |z, (i, value)| {
+                    let centered = value - mean.get(i).copied().unwrap_or(0.0);
+                    let scale = scale.get(i).copied().filter(|s| *s != 0.0).unwrap_or(1.0);
+                    z + coefficients[i] * centered / scale
+                });
+                sigmoid(z)
+            }
+            ModelSpec::Trees { base_score, trees } => {
+                sigmoid(trees.iter().fold(*base_score, |raw, tree| raw + tree.eval(&x)))
+            }
+        }
+    }
+}
+
+type ScoreSlot = fn(&mut ScoreRow) -> &mut f64;
+
+/// The `ScoreRow` column a target label is scored into
+fn score_slot(target: &str) -> Option<ScoreSlot> {
+    let slot: ScoreSlot = match target {
+        "label_reachable_yes_15s" => |r| &mut r.score_yes_15s,
+        "label_reachable_yes_30s" => |r| &mut r.score_yes_30s,
+        "label_reachable_yes_45s" => |r| &mut r.score_yes_45s,
+        "label_reachable_no_15s" => |r| &mut r.score_no_15s,
+        "label_reachable_no_30s" => |r| &mut r.score_no_30s,
+        "label_reachable_no_45s" => |r| &mut r.score_no_45s,
+        _ => return None,
+    };
+    Some(slot)
+}
+
+/// Scores rows with a set of models, at most one per target
+pub struct ModelScorer {
+    models: Vec<(ScoringModel, ScoreSlot)>,
+    freshness_ttl: i64,
+}
+
+impl ModelScorer {
+    pub fn new(models: Vec<ScoringModel>, freshness_ttl: i64) -> Result<Self> {
+        if models.is_empty() {
+            anyhow::bail!("No models to score with");
+        }
+        let mut slotted: Vec<(ScoringModel, ScoreSlot)> = Vec::with_capacity(models.len());
+        for model in models {
+            let slot = score_slot(&model.target)
+                .with_context(|| format!("{} predicts {}, which has no score column", model.model_version, model.target))?;
+            if slotted.iter().any(|(m, _)| m.target == model.target) {
+                anyhow::bail!("More than one model predicts {}", model.target);
+            }
+            slotted.push((model, slot));
+        }
+        Ok(Self { models: slotted, freshness_ttl })
+    }
+
+    pub fn load(paths: &[String], freshness_ttl: i64) -> Result<Self> {
+        let models = paths.iter().map(ScoringModel::load).collect::<Result<Vec<_>>>()?;
+        Self::new(models, freshness_ttl)
+    }
+
+    pub fn describe(&self) -> String {
+        self.models
+            .iter()
+            .map(|(m, _)| format!("{} -> {}", m.model_version, m.target))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Score one feature row under the `scores_v1` schema
+    pub fn infer(&self, row: &FeatureRow) -> ScoreRow {
+        let mut score = ScoreRow {
+            schema_version: ScoreRow::schema_version().to_string(),
+            condition_id: row.condition_id.clone(),
+            ts: row.ts,
+            model_version: self.models.iter().map(|(m, _)| m.model_version.as_str()).collect::<Vec<_>>().join("+"),
+            score_yes_15s: NEUTRAL_SCORE,
+            score_yes_30s: NEUTRAL_SCORE,
+            score_yes_45s: NEUTRAL_SCORE,
+            score_no_15s: NEUTRAL_SCORE,
+            score_no_30s: NEUTRAL_SCORE,
+            score_no_45s: NEUTRAL_SCORE,
+            risk_yes_30s: DEFAULT_RISK,
+            risk_no_30s: DEFAULT_RISK,
+            fresh_until_ts: row.ts + self.freshness_ttl,
+        };
+        for (model, slot) in &self.models {
+            *slot(&mut score) = model.predict(row);
+        }
+        score
+    }
+}
+
+/// Where a live loop gets the score for the row it just built
+pub trait ScoreSource {
+    fn score(&mut self, row: &FeatureRow) -> Option<ScoreRow>;
+}
+
+impl ScoreSource for ModelScorer {
+    fn score(&mut self, row: &FeatureRow) -> Option<ScoreRow> {
+        Some(self.infer(row))
+    }
+}
+
+/// Precomputed scores: the latest fresh one for the row's market
+impl ScoreSource for ScoreLoader {
+    fn score(&mut self, row: &FeatureRow) -> Option<ScoreRow> {
+        self.get_latest_score(&row.condition_id, row.ts)
+            .filter(|score| self.is_fresh(score, row.ts))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model(value: serde_json::Value) -> ScoringModel {
+        let model: ScoringModel = serde_json::from_value(value).unwrap();
+        model.validate().unwrap();
+        model
+    }
+
+    #[test]
+    fn logistic_and_tree_models_match_their_reference_outputs() {
+        let row = FeatureRow { yes_mid: 0.6, book_gap: 0.02, time_remaining_s: 120, ..Default::default() };
+
+        let logistic = model(json!({
+            "model_version": "logistic_yes_30s",
+            "target": "label_reachable_yes_30s",
+            "feature_columns": ["yes_mid", "book_gap"],
+            "model": {"kind": "logistic", "intercept": -0.5, "coefficients": [2.0, -1.0], "mean": [0.5, 0.0], "scale": [0.1, 0.02]}
+        }));
+        // z = -0.5 + 2 * 1 - 1 * 1 = 0.5
+        assert!((logistic.predict(&row) - sigmoid(0.5)).abs() < 1e-12);
+
+        let trees = model(json!({
+            "model_version": "lgbm_no_30s",
+            "target": "label_reachable_no_30s",
+            "feature_columns": ["yes_mid", "time_remaining_s"],
+            "model": {"kind": "trees", "trees": [
+                {"split_feature": 0, "threshold": 0.55, "default_left": true,
+                 "left_child": {"leaf_value": -1.0},
+                 "right_child": {"split_feature": 1, "threshold": 60.0,
+                                 "left_child": {"leaf_value": 0.2}, "right_child": {"leaf_value": 0.4}}},
+                {"leaf_value": 0.1}
+            ]}
+        }));
+        assert!((trees.predict(&row) - sigmoid(0.5)).abs() < 1e-12);
+
+        let scorer = ModelScorer::new(vec![logistic, trees], 300).unwrap();
+        let score = scorer.infer(&row);
+        assert_eq!(score.schema_version, ScoreRow::schema_version());
+        assert!((score.score_yes_30s - score.score_no_30s).abs() < 1e-12);
+        assert_eq!(score.score_yes_15s, NEUTRAL_SCORE);
+        assert_eq!(score.model_version, "logistic_yes_30s+lgbm_no_30s");
+        assert_eq!(score.fresh_until_ts, 300);
+    }
+
+    #[test]
+    fn rejects_unknown_columns_and_unscorable_targets() {
+        let bad_column: ScoringModel = serde_json::from_value(json!({
+            "model_version": "m", "target": "label_reachable_yes_30s", "feature_columns": ["condition_id"],
+            "model": {"kind": "logistic", "intercept": 0.0, "coefficients": [1.0]}
+        }))
+        .unwrap();
+        assert!(bad_column.validate().is_err());
+
+        let edge = model(json!({
+            "model_version": "m", "target": "label_edge_yes_30s", "feature_columns": ["yes_mid"],
+            "model": {"kind": "logistic", "intercept": 0.0, "coefficients": [1.0]}
+        }));
+        assert!(ModelScorer::new(vec![edge], 300).is_err());
+    }
+}
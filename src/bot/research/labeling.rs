@@ -1,11 +1,46 @@
 //! Label Generation
 //!
-//! Compute labels for ML training from feature data.
+//! Compute labels for ML training from feature data. The fixed-horizon
+//! reachable/edge labels are always computed; triple-barrier, outcome and
+//! expected-value labels are opt-in through `LabelConfig::kinds`.
 
 use super::{CostModel, FeatureRow, LabelRow};
+use crate::bot::resolution::Resolutions;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
+/// Optional label families
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LabelKind {
+    /// First of take-profit, stop-loss or time barrier after entry
+    TripleBarrier,
+    /// Entry held to the official resolution; needs resolutions
+    Outcome,
+    /// Net edge of exiting at each horizon, not its best point
+    ExpectedValue,
+}
+
+/// Triple-barrier parameters, as net edge per share
+#[derive(Debug, Clone, Deserialize)]
+pub struct BarrierConfig {
+    pub take_profit: f64,
+    pub stop_loss: f64,
+    /// Time barrier in seconds, cut short by market end
+    pub max_holding_s: i64,
+}
+
+impl Default for BarrierConfig {
+    fn default() -> Self {
+        Self {
+            take_profit: 0.05,
+            stop_loss: 0.05,
+            max_holding_s: 60,
+        }
+    }
+}
+
 /// Label configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct LabelConfig {
@@ -15,6 +50,11 @@ pub struct LabelConfig {
     pub fee_buffer: f64,
     /// Slippage buffer
     pub slippage_buffer: f64,
+    /// Optional label families to compute
+    #[serde(default)]
+    pub kinds: Vec<LabelKind>,
+    #[serde(default)]
+    pub barrier: BarrierConfig,
 }
 
 impl Default for LabelConfig {
@@ -23,14 +63,56 @@ impl Default for LabelConfig {
             horizons: vec![15, 30, 45],
             fee_buffer: 0.02,
             slippage_buffer: 0.005,
+            kinds: Vec::new(),
+            barrier: BarrierConfig::default(),
         }
     }
 }
 
+impl LabelConfig {
+    pub fn computes(&self, kind: LabelKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    /// `LabelRow` columns the selected optional kinds fill
+    pub fn label_columns(&self) -> Vec<String> {
+        let mut columns = Vec::new();
+        for kind in &self.kinds {
+            let names: &[&str] = match kind {
+                LabelKind::TripleBarrier => &[
+                    "label_barrier_yes", "label_barrier_no",
+                    "label_barrier_ret_yes", "label_barrier_ret_no",
+                    "label_barrier_hold_s_yes", "label_barrier_hold_s_no",
+                ],
+                LabelKind::Outcome => &[
+                    "label_outcome_yes", "label_outcome_no", "label_outcome_pnl_yes", "label_outcome_pnl_no",
+                ],
+                LabelKind::ExpectedValue => &[
+                    "label_ev_yes_15s", "label_ev_yes_30s", "label_ev_yes_45s",
+                    "label_ev_no_15s", "label_ev_no_30s", "label_ev_no_45s",
+                ],
+            };
+            columns.extend(names.iter().map(|c| c.to_string()));
+        }
+        columns
+    }
+}
+
+/// Where a triple-barrier trade exited
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BarrierExit {
+    /// 1 take-profit, -1 stop-loss, 0 time barrier
+    barrier: i8,
+    net_edge: f64,
+    hold_s: i64,
+}
+
 /// Label generator
 pub struct Labeler {
     config: LabelConfig,
     cost_model: CostModel,
+    /// Official outcomes for `label_resolution`
+    resolutions: Resolutions,
 }
 
 impl Labeler {
@@ -38,9 +120,32 @@ impl Labeler {
         Self {
             config,
             cost_model: CostModel::default(),
+            resolutions: Resolutions::default(),
         }
     }
 
+    /// Replace the label configuration, keeping costs and resolutions
+    pub fn with_config(mut self, config: LabelConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Price label edges with `cost_model` instead of the default fees
+    pub fn with_cost_model(mut self, cost_model: CostModel) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    pub fn config(&self) -> &LabelConfig {
+        &self.config
+    }
+
+    /// Label rows with their market's official outcome (1 = YES won)
+    pub fn with_resolutions(mut self, resolutions: Resolutions) -> Self {
+        self.resolutions = resolutions;
+        self
+    }
+
     /// Compute labels for a sequence of feature rows
     ///
     /// Rows must be sorted by (condition_id, ts)
@@ -96,7 +201,8 @@ impl Labeler {
             label_edge_no_45s: 0.0,
             label_adverse_yes_30s: 0.0,
             label_adverse_no_30s: 0.0,
-            label_resolution: None,
+            label_resolution: self.resolutions.yes_won(&row.condition_id).map(i8::from),
+            ..Default::default()
         };
 
         let entry_yes = row.yes_ask; // Enter YES by buying at ask
@@ -148,9 +254,97 @@ impl Labeler {
             }
         }
 
+        let market = &rows[idx..end];
+        if self.config.computes(LabelKind::TripleBarrier) {
+            let yes = self.triple_barrier(market, |r| r.yes_ask, |r| r.yes_bid);
+            let no = self.triple_barrier(market, |r| r.no_ask, |r| r.no_bid);
+            labels.label_barrier_yes = yes.map(|e| e.barrier);
+            labels.label_barrier_ret_yes = yes.map(|e| e.net_edge);
+            labels.label_barrier_hold_s_yes = yes.map(|e| e.hold_s);
+            labels.label_barrier_no = no.map(|e| e.barrier);
+            labels.label_barrier_ret_no = no.map(|e| e.net_edge);
+            labels.label_barrier_hold_s_no = no.map(|e| e.hold_s);
+        }
+        if self.config.computes(LabelKind::Outcome) {
+            if let Some(yes_payout) = self.resolutions.get(&row.condition_id).map(|r| r.yes_payout) {
+                let pnl_yes = self.cost_model.settlement_edge(entry_yes, yes_payout);
+                let pnl_no = self.cost_model.settlement_edge(entry_no, 1.0 - yes_payout);
+                labels.label_outcome_pnl_yes = Some(pnl_yes);
+                labels.label_outcome_pnl_no = Some(pnl_no);
+                labels.label_outcome_yes = Some(i8::from(pnl_yes > 0.0));
+                labels.label_outcome_no = Some(i8::from(pnl_no > 0.0));
+            }
+        }
+        if self.config.computes(LabelKind::ExpectedValue) {
+            for horizon in &self.config.horizons {
+                let yes = self.horizon_edge(market, *horizon as i64, entry_yes, |r| r.yes_bid, |p| p);
+                let no = self.horizon_edge(market, *horizon as i64, entry_no, |r| r.no_bid, |p| 1.0 - p);
+                match *horizon {
+                    15 => (labels.label_ev_yes_15s, labels.label_ev_no_15s) = (yes, no),
+                    30 => (labels.label_ev_yes_30s, labels.label_ev_no_30s) = (yes, no),
+                    45 => (labels.label_ev_yes_45s, labels.label_ev_no_45s) = (yes, no),
+                    _ => {}
+                }
+            }
+        }
+
         labels
     }
 
+    /// Buy at `ask` on `market[0]` and sell at the first later `bid` whose
+    /// net edge reaches the take-profit or stop-loss, else at the last bid
+    /// before the time barrier. `None` without a later row.
+    fn triple_barrier(
+        &self,
+        market: &[FeatureRow],
+        ask: impl Fn(&FeatureRow) -> f64,
+        bid: impl Fn(&FeatureRow) -> f64,
+    ) -> Option<BarrierExit> {
+        let entry = &market[0];
+        let entry_price = ask(entry);
+        let barrier_ts = (entry.ts + self.config.barrier.max_holding_s).min(entry.market_end_ts.max(entry.ts));
+        let mut last = None;
+        for row in market.iter().skip(1).take_while(|r| r.ts <= barrier_ts) {
+            let net_edge = self.cost_model.net_edge(entry_price, bid(row), true);
+            let hold_s = row.ts - entry.ts;
+            if net_edge >= self.config.barrier.take_profit {
+                return Some(BarrierExit { barrier: 1, net_edge, hold_s });
+            }
+            if net_edge <= -self.config.barrier.stop_loss {
+                return Some(BarrierExit { barrier: -1, net_edge, hold_s });
+            }
+            last = Some(BarrierExit { barrier: 0, net_edge, hold_s });
+        }
+        last
+    }
+
+    /// Net edge of buying at `entry_price` on `market[0]` and exiting
+    /// `horizon` seconds later: at the last bid by then, or at settlement if
+    /// the market has ended and resolved. `payout` maps the YES payout to
+    /// the token held.
+    fn horizon_edge(
+        &self,
+        market: &[FeatureRow],
+        horizon: i64,
+        entry_price: f64,
+        bid: impl Fn(&FeatureRow) -> f64,
+        payout: impl Fn(f64) -> f64,
+    ) -> Option<f64> {
+        let entry = &market[0];
+        let horizon_ts = entry.ts + horizon;
+        if horizon_ts >= entry.market_end_ts {
+            if let Some(resolution) = self.resolutions.get(&entry.condition_id) {
+                return Some(self.cost_model.settlement_edge(entry_price, payout(resolution.yes_payout)));
+            }
+        }
+        market
+            .iter()
+            .skip(1)
+            .take_while(|r| r.ts <= horizon_ts)
+            .last()
+            .map(|exit| self.cost_model.net_edge(entry_price, bid(exit), true))
+    }
+
     /// Compute reachable profit and max edge
     fn compute_reachable(
         &self,
@@ -263,6 +457,7 @@ mod tests {
             spot_ret_5s: None,
             spot_ret_15s: None,
             spot_realized_vol_30s: None,
+            ..Default::default()
         }
     }
 
@@ -303,4 +498,93 @@ mod tests {
         // Should not be reachable (spread + fees eat any edge)
         assert_eq!(labels[0].label_reachable_yes_30s, 0);
     }
+
+    #[test]
+    fn resolution_label_comes_from_official_outcome() {
+        use crate::bot::resolution::{Resolution, ResolutionSource};
+
+        let mut resolutions = Resolutions::default();
+        resolutions.insert("won-no", Resolution { yes_payout: 0.0, source: ResolutionSource::Ctf });
+        let labeler = Labeler::default().with_resolutions(resolutions);
+
+        // The final book favours YES, but the market resolved NO
+        let rows = vec![
+            make_row("won-no", 0, 0.80, 0.82),
+            make_row("won-no", 10, 0.95, 0.97),
+            make_row("open", 0, 0.50, 0.52),
+        ];
+        let labels = labeler.compute_labels(&rows);
+        assert_eq!(labels[0].label_resolution, Some(0));
+        assert_eq!(labels[1].label_resolution, Some(0));
+        assert_eq!(labels[2].label_resolution, None);
+    }
+
+    fn labeler(kinds: Vec<LabelKind>) -> Labeler {
+        // Frictionless costs keep the expected edges readable
+        Labeler::new(LabelConfig { kinds, ..Default::default() }).with_cost_model(CostModel::new(0.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn triple_barrier_takes_the_first_barrier_hit() {
+        let labeler = labeler(vec![LabelKind::TripleBarrier]);
+        let rows = vec![
+            make_row("tb", 0, 0.48, 0.50),
+            make_row("tb", 10, 0.53, 0.55),
+            make_row("tb", 20, 0.56, 0.58), // YES take-profit: 0.56 - 0.50
+            make_row("tb", 30, 0.40, 0.42),
+            make_row("tb", 100, 0.50, 0.52),
+        ];
+        let labels = labeler.compute_labels(&rows);
+
+        assert_eq!(labels[0].label_barrier_yes, Some(1));
+        assert!((labels[0].label_barrier_ret_yes.unwrap() - 0.06).abs() < 1e-12);
+        assert_eq!(labels[0].label_barrier_hold_s_yes, Some(20));
+        // NO bought at 0.52 hits its stop at the first tick (bid 0.45)
+        assert_eq!(labels[0].label_barrier_no, Some(-1));
+        assert_eq!(labels[0].label_barrier_hold_s_no, Some(10));
+        // Row 3 only reaches the 60s time barrier, through an empty window
+        assert_eq!(labels[3].label_barrier_yes, None);
+        // Unselected kinds stay empty
+        assert_eq!(labels[0].label_outcome_yes, None);
+        assert_eq!(labels[0].label_ev_yes_30s, None);
+
+        let mut config = LabelConfig { kinds: vec![LabelKind::TripleBarrier], ..Default::default() };
+        config.barrier.take_profit = 0.5;
+        config.barrier.stop_loss = 0.5;
+        let wide = Labeler::new(config).with_cost_model(CostModel::new(0.0, 0.0, 0.0)).compute_labels(&rows);
+        assert_eq!(wide[0].label_barrier_yes, Some(0));
+        assert_eq!(wide[0].label_barrier_hold_s_yes, Some(30));
+        assert!((wide[0].label_barrier_ret_yes.unwrap() + 0.10).abs() < 1e-12);
+    }
+
+    #[test]
+    fn outcome_and_expected_value_labels_are_net_of_costs() {
+        use crate::bot::resolution::{Resolution, ResolutionSource};
+
+        let mut resolutions = Resolutions::default();
+        resolutions.insert("ev", Resolution { yes_payout: 1.0, source: ResolutionSource::Gamma });
+        let labeler = Labeler::new(LabelConfig { kinds: vec![LabelKind::Outcome, LabelKind::ExpectedValue], ..Default::default() })
+            .with_resolutions(resolutions);
+        let cost = CostModel::default();
+
+        // Market ends at 200 (make_row puts the end 200s after the first row)
+        let rows = vec![
+            make_row("ev", 0, 0.48, 0.50),
+            make_row("ev", 20, 0.60, 0.62),
+            make_row("ev", 40, 0.45, 0.47),
+        ];
+        let labels = labeler.compute_labels(&rows);
+
+        assert_eq!(labels[0].label_outcome_yes, Some(1));
+        assert_eq!(labels[0].label_outcome_pnl_yes, Some(cost.settlement_edge(0.50, 1.0)));
+        assert_eq!(labels[0].label_outcome_no, Some(0));
+        assert!(labels[0].label_outcome_pnl_no.unwrap() < -0.5);
+
+        // EV exits at the horizon's last bid, not the best one
+        assert_eq!(labels[0].label_ev_yes_15s, None);
+        assert_eq!(labels[0].label_ev_yes_30s, Some(cost.net_edge(0.50, 0.60, true)));
+        assert_eq!(labels[0].label_ev_yes_45s, Some(cost.net_edge(0.50, 0.45, true)));
+        assert!(labels[0].label_edge_yes_45s > labels[0].label_ev_yes_45s.unwrap());
+        assert_eq!(labels[0].label_ev_no_45s, Some(cost.net_edge(0.52, 1.0 - 0.47, true)));
+    }
 }
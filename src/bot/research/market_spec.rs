@@ -2,11 +2,12 @@
 //!
 //! Types for market classification and filtering.
 
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 /// Supported assets
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
 pub enum SupportedAsset {
     Btc,
     Eth,
@@ -24,6 +25,16 @@ impl SupportedAsset {
         }
     }
 
+    /// Full asset names as they appear in market questions
+    pub fn question_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Btc => &["btc", "bitcoin"],
+            Self::Eth => &["eth", "ethereum"],
+            Self::Sol => &["sol", "solana"],
+            Self::Xrp => &["xrp", "ripple"],
+        }
+    }
+
     pub fn from_slug(slug: &str) -> Option<Self> {
         let lower = slug.to_lowercase();
         if lower.contains("btc") || lower.contains("bitcoin") {
@@ -41,10 +52,13 @@ impl SupportedAsset {
 }
 
 /// Supported durations
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
 pub enum SupportedDuration {
+    #[value(name = "5m")]
     M5,
+    #[value(name = "15m")]
     M15,
+    #[value(name = "1h")]
     H1,
 }
 
@@ -65,6 +79,15 @@ impl SupportedDuration {
         }
     }
 
+    /// Phrases used for this duration in market questions
+    pub fn question_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::M5 => &["5m", "5 min", "5-minute", "five minute"],
+            Self::M15 => &["15m", "15 min", "15-minute", "fifteen minute"],
+            Self::H1 => &["1h", "1 hour", "1-hour", "hourly"],
+        }
+    }
+
     pub fn from_slug(slug: &str) -> Option<Self> {
         let lower = slug.to_lowercase();
         if lower.contains("-5m") || lower.contains("5min") {
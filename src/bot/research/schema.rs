@@ -2,10 +2,12 @@
 //!
 //! Data structures matching docs/research_schema.md
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 /// Feature row for export
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FeatureRow {
     // Identity columns
     pub schema_version: String,
@@ -37,6 +39,67 @@ pub struct FeatureRow {
     pub no_bid_depth_1: Option<f64>,
     pub no_ask_depth_1: Option<f64>,
 
+    // Multi-level depth (features_v2, optional): cumulative size through
+    // the k best levels
+    #[serde(default)]
+    pub yes_bid_depth_2: Option<f64>,
+    #[serde(default)]
+    pub yes_bid_depth_3: Option<f64>,
+    #[serde(default)]
+    pub yes_bid_depth_4: Option<f64>,
+    #[serde(default)]
+    pub yes_bid_depth_5: Option<f64>,
+    #[serde(default)]
+    pub yes_ask_depth_2: Option<f64>,
+    #[serde(default)]
+    pub yes_ask_depth_3: Option<f64>,
+    #[serde(default)]
+    pub yes_ask_depth_4: Option<f64>,
+    #[serde(default)]
+    pub yes_ask_depth_5: Option<f64>,
+    #[serde(default)]
+    pub no_bid_depth_2: Option<f64>,
+    #[serde(default)]
+    pub no_bid_depth_3: Option<f64>,
+    #[serde(default)]
+    pub no_bid_depth_4: Option<f64>,
+    #[serde(default)]
+    pub no_bid_depth_5: Option<f64>,
+    #[serde(default)]
+    pub no_ask_depth_2: Option<f64>,
+    #[serde(default)]
+    pub no_ask_depth_3: Option<f64>,
+    #[serde(default)]
+    pub no_ask_depth_4: Option<f64>,
+    #[serde(default)]
+    pub no_ask_depth_5: Option<f64>,
+
+    // Book shape (features_v2, optional)
+    #[serde(default)]
+    pub yes_imbalance_1: Option<f64>,
+    #[serde(default)]
+    pub yes_imbalance_5: Option<f64>,
+    #[serde(default)]
+    pub no_imbalance_1: Option<f64>,
+    #[serde(default)]
+    pub no_imbalance_5: Option<f64>,
+    #[serde(default)]
+    pub yes_microprice: Option<f64>,
+    #[serde(default)]
+    pub no_microprice: Option<f64>,
+    #[serde(default)]
+    pub yes_queue_mid: Option<f64>,
+    #[serde(default)]
+    pub no_queue_mid: Option<f64>,
+    #[serde(default)]
+    pub yes_bid_depth_chg_5s: Option<f64>,
+    #[serde(default)]
+    pub yes_ask_depth_chg_5s: Option<f64>,
+    #[serde(default)]
+    pub no_bid_depth_chg_5s: Option<f64>,
+    #[serde(default)]
+    pub no_ask_depth_chg_5s: Option<f64>,
+
     // Market state
     pub age_s: i32,
     pub time_remaining_s: i32,
@@ -67,14 +130,109 @@ pub struct FeatureRow {
     pub spot_realized_vol_30s: Option<f64>,
 }
 
+/// Feature schema versions this build reads; v1 rows lack the level
+/// depth columns, which read as missing
+pub const FEATURE_SCHEMA_VERSIONS: &[&str] = &["features_v1", "features_v2"];
+
+/// Columns added by `features_v2`, all optional
+pub const DEPTH_FEATURE_COLUMNS: &[&str] = &[
+    "yes_bid_depth_1", "yes_bid_depth_2", "yes_bid_depth_3", "yes_bid_depth_4", "yes_bid_depth_5",
+    "yes_ask_depth_1", "yes_ask_depth_2", "yes_ask_depth_3", "yes_ask_depth_4", "yes_ask_depth_5",
+    "no_bid_depth_1", "no_bid_depth_2", "no_bid_depth_3", "no_bid_depth_4", "no_bid_depth_5",
+    "no_ask_depth_1", "no_ask_depth_2", "no_ask_depth_3", "no_ask_depth_4", "no_ask_depth_5",
+    "yes_imbalance_1", "yes_imbalance_5", "no_imbalance_1", "no_imbalance_5",
+    "yes_microprice", "no_microprice", "yes_queue_mid", "no_queue_mid",
+    "yes_bid_depth_chg_5s", "yes_ask_depth_chg_5s", "no_bid_depth_chg_5s", "no_ask_depth_chg_5s",
+];
+
 impl FeatureRow {
     pub fn schema_version() -> &'static str {
-        "features_v1"
+        "features_v2"
+    }
+
+    /// Whether the row carries order-book level depth
+    pub fn has_depth(&self) -> bool {
+        self.yes_bid_depth_5.is_some()
+    }
+
+    /// Numeric feature column by name, as a model reads it: booleans as
+    /// 0/1 and missing optionals as 0 (matching `nan_to_num` in training).
+    /// `None` for identity columns and unknown names.
+    pub fn feature(&self, column: &str) -> Option<f64> {
+        let value = match column {
+            "yes_bid" => self.yes_bid,
+            "yes_ask" => self.yes_ask,
+            "no_bid" => self.no_bid,
+            "no_ask" => self.no_ask,
+            "yes_mid" => self.yes_mid,
+            "no_mid" => self.no_mid,
+            "yes_spread" => self.yes_spread,
+            "no_spread" => self.no_spread,
+            "book_sum" => self.book_sum,
+            "book_gap" => self.book_gap,
+            "yes_bid_depth_1" => self.yes_bid_depth_1.unwrap_or(0.0),
+            "yes_ask_depth_1" => self.yes_ask_depth_1.unwrap_or(0.0),
+            "no_bid_depth_1" => self.no_bid_depth_1.unwrap_or(0.0),
+            "no_ask_depth_1" => self.no_ask_depth_1.unwrap_or(0.0),
+            "yes_bid_depth_2" => self.yes_bid_depth_2.unwrap_or(0.0),
+            "yes_bid_depth_3" => self.yes_bid_depth_3.unwrap_or(0.0),
+            "yes_bid_depth_4" => self.yes_bid_depth_4.unwrap_or(0.0),
+            "yes_bid_depth_5" => self.yes_bid_depth_5.unwrap_or(0.0),
+            "yes_ask_depth_2" => self.yes_ask_depth_2.unwrap_or(0.0),
+            "yes_ask_depth_3" => self.yes_ask_depth_3.unwrap_or(0.0),
+            "yes_ask_depth_4" => self.yes_ask_depth_4.unwrap_or(0.0),
+            "yes_ask_depth_5" => self.yes_ask_depth_5.unwrap_or(0.0),
+            "no_bid_depth_2" => self.no_bid_depth_2.unwrap_or(0.0),
+            "no_bid_depth_3" => self.no_bid_depth_3.unwrap_or(0.0),
+            "no_bid_depth_4" => self.no_bid_depth_4.unwrap_or(0.0),
+            "no_bid_depth_5" => self.no_bid_depth_5.unwrap_or(0.0),
+            "no_ask_depth_2" => self.no_ask_depth_2.unwrap_or(0.0),
+            "no_ask_depth_3" => self.no_ask_depth_3.unwrap_or(0.0),
+            "no_ask_depth_4" => self.no_ask_depth_4.unwrap_or(0.0),
+            "no_ask_depth_5" => self.no_ask_depth_5.unwrap_or(0.0),
+            "yes_imbalance_1" => self.yes_imbalance_1.unwrap_or(0.0),
+            "yes_imbalance_5" => self.yes_imbalance_5.unwrap_or(0.0),
+            "no_imbalance_1" => self.no_imbalance_1.unwrap_or(0.0),
+            "no_imbalance_5" => self.no_imbalance_5.unwrap_or(0.0),
+            "yes_microprice" => self.yes_microprice.unwrap_or(0.0),
+            "no_microprice" => self.no_microprice.unwrap_or(0.0),
+            "yes_queue_mid" => self.yes_queue_mid.unwrap_or(0.0),
+            "no_queue_mid" => self.no_queue_mid.unwrap_or(0.0),
+            "yes_bid_depth_chg_5s" => self.yes_bid_depth_chg_5s.unwrap_or(0.0),
+            "yes_ask_depth_chg_5s" => self.yes_ask_depth_chg_5s.unwrap_or(0.0),
+            "no_bid_depth_chg_5s" => self.no_bid_depth_chg_5s.unwrap_or(0.0),
+            "no_ask_depth_chg_5s" => self.no_ask_depth_chg_5s.unwrap_or(0.0),
+            "age_s" => self.age_s as f64,
+            "time_remaining_s" => self.time_remaining_s as f64,
+            "in_entry_window" => f64::from(u8::from(self.in_entry_window)),
+            "in_exit_only_window" => f64::from(u8::from(self.in_exit_only_window)),
+            "yes_mid_ret_1s" => self.yes_mid_ret_1s,
+            "yes_mid_ret_5s" => self.yes_mid_ret_5s,
+            "yes_mid_ret_15s" => self.yes_mid_ret_15s,
+            "yes_mid_ret_30s" => self.yes_mid_ret_30s,
+            "no_mid_ret_1s" => self.no_mid_ret_1s,
+            "no_mid_ret_5s" => self.no_mid_ret_5s,
+            "no_mid_ret_15s" => self.no_mid_ret_15s,
+            "no_mid_ret_30s" => self.no_mid_ret_30s,
+            "book_gap_z_30s" => self.book_gap_z_30s,
+            "yes_spread_ema_15s" => self.yes_spread_ema_15s,
+            "no_spread_ema_15s" => self.no_spread_ema_15s,
+            "realized_vol_15s" => self.realized_vol_15s,
+            "realized_vol_30s" => self.realized_vol_30s,
+            "realized_vol_60s" => self.realized_vol_60s,
+            "spot_price" => self.spot_price.unwrap_or(0.0),
+            "spot_ret_1s" => self.spot_ret_1s.unwrap_or(0.0),
+            "spot_ret_5s" => self.spot_ret_5s.unwrap_or(0.0),
+            "spot_ret_15s" => self.spot_ret_15s.unwrap_or(0.0),
+            "spot_realized_vol_30s" => self.spot_realized_vol_30s.unwrap_or(0.0),
+            _ => return None,
+        };
+        Some(if value.is_finite() { value } else { 0.0 })
     }
 }
 
 /// Label row for ML training
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LabelRow {
     pub condition_id: String,
     pub ts: i64,
@@ -101,6 +259,46 @@ pub struct LabelRow {
 
     // Resolution (optional)
     pub label_resolution: Option<i8>,
+
+    // Triple barrier (optional): 1 take-profit, -1 stop-loss, 0 time barrier
+    #[serde(default)]
+    pub label_barrier_yes: Option<i8>,
+    #[serde(default)]
+    pub label_barrier_no: Option<i8>,
+    /// Net edge at the barrier exit
+    #[serde(default)]
+    pub label_barrier_ret_yes: Option<f64>,
+    #[serde(default)]
+    pub label_barrier_ret_no: Option<f64>,
+    /// Seconds from entry to the barrier exit
+    #[serde(default)]
+    pub label_barrier_hold_s_yes: Option<i64>,
+    #[serde(default)]
+    pub label_barrier_hold_s_no: Option<i64>,
+
+    // Resolution outcome (optional): buying now and holding to settlement
+    #[serde(default)]
+    pub label_outcome_yes: Option<i8>,
+    #[serde(default)]
+    pub label_outcome_no: Option<i8>,
+    #[serde(default)]
+    pub label_outcome_pnl_yes: Option<f64>,
+    #[serde(default)]
+    pub label_outcome_pnl_no: Option<f64>,
+
+    // Expected value (optional): net edge of exiting at the horizon
+    #[serde(default)]
+    pub label_ev_yes_15s: Option<f64>,
+    #[serde(default)]
+    pub label_ev_yes_30s: Option<f64>,
+    #[serde(default)]
+    pub label_ev_yes_45s: Option<f64>,
+    #[serde(default)]
+    pub label_ev_no_15s: Option<f64>,
+    #[serde(default)]
+    pub label_ev_no_30s: Option<f64>,
+    #[serde(default)]
+    pub label_ev_no_45s: Option<f64>,
 }
 
 /// Score row from Qlib model
@@ -131,13 +329,27 @@ impl ScoreRow {
     pub fn schema_version() -> &'static str {
         "scores_v1"
     }
+
+    /// YES minus NO 30s score: positive favours YES, negative NO. This is
+    /// the signed `qlib_score` strategies read.
+    pub fn signed_score(&self) -> f64 {
+        self.score_yes_30s - self.score_no_30s
+    }
 }
 
 /// Manifest metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
     pub schema_version: String,
+    /// Schema of the features file; absent in `manifest_v1`, which only
+    /// described `features_v1` exports
+    #[serde(default = "Manifest::v1_feature_schema")]
+    pub feature_schema_version: String,
     pub features_path: String,
+    /// Label rows computed alongside the features; absent before labels
+    /// were written
+    #[serde(default)]
+    pub labels_path: Option<String>,
     pub asset: String,
     pub duration: String,
     pub market_family: String,
@@ -157,6 +369,82 @@ pub struct Manifest {
 
 impl Manifest {
     pub fn schema_version() -> &'static str {
-        "manifest_v1"
+        "manifest_v2"
+    }
+
+    fn v1_feature_schema() -> String {
+        "features_v1".to_string()
+    }
+
+    /// Read a `manifest_v1` or `manifest_v2` file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let manifest: Self =
+            serde_json::from_str(&content).with_context(|| format!("Invalid manifest {}", path.display()))?;
+        if !["manifest_v1", "manifest_v2"].contains(&manifest.schema_version.as_str()) {
+            anyhow::bail!("Unsupported manifest version {} in {}", manifest.schema_version, path.display());
+        }
+        if !FEATURE_SCHEMA_VERSIONS.contains(&manifest.feature_schema_version.as_str()) {
+            anyhow::bail!("Unsupported feature schema {} in {}", manifest.feature_schema_version, path.display());
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_rows_and_manifests_still_read() {
+        // A v1 row is a v2 row without the columns v2 added
+        let mut v1 = serde_json::to_value(FeatureRow {
+            schema_version: "features_v1".to_string(),
+            yes_bid_depth_1: Some(12.0),
+            ..Default::default()
+        })
+        .unwrap();
+        for column in DEPTH_FEATURE_COLUMNS.iter().filter(|c| !c.ends_with("_depth_1")) {
+            v1.as_object_mut().unwrap().remove(*column);
+        }
+        let row: FeatureRow = serde_json::from_value(v1).unwrap();
+        assert!(!row.has_depth());
+        assert_eq!(row.feature("yes_bid_depth_1"), Some(12.0));
+        assert_eq!(row.feature("yes_microprice"), Some(0.0));
+        assert!(DEPTH_FEATURE_COLUMNS.iter().all(|c| row.feature(c).is_some()));
+
+        let dir = crate::bot::test_util::tempdir();
+        let path = format!("{dir}/manifest.json");
+        let manifest = Manifest {
+            schema_version: "manifest_v1".to_string(),
+            feature_schema_version: String::new(),
+            features_path: "f.json".to_string(),
+            labels_path: None,
+            asset: "btc".to_string(),
+            duration: "5m".to_string(),
+            market_family: "updown_open_close".to_string(),
+            start_ts: 0,
+            end_ts: 300,
+            resolution: "1s".to_string(),
+            source_inputs: vec![],
+            train_start_ts: 0,
+            train_end_ts: 210,
+            valid_start_ts: 210,
+            valid_end_ts: 255,
+            test_start_ts: 255,
+            test_end_ts: 300,
+            label_columns: vec![],
+            feature_columns: vec!["yes_mid".to_string()],
+        };
+        let mut json = serde_json::to_value(&manifest).unwrap();
+        json.as_object_mut().unwrap().remove("feature_schema_version");
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert_eq!(Manifest::load(&path).unwrap().feature_schema_version, "features_v1");
+
+        json["schema_version"] = "manifest_v3".into();
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(Manifest::load(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,302 @@
+//! Market Resolution
+//!
+//! Official outcomes of binary markets by condition ID. Gamma's settled
+//! `outcomePrices` on closed markets are tried first; markets Gamma has not
+//! settled fall back to the CTF contract's payout numerators. Resolved
+//! outcomes are cached in a local DuckDB table, so each market is fetched
+//! once, and unresolved ones are retried on the next lookup.
+#![allow(clippy::exhaustive_enums, reason = "Generated by sol! macro")]
+#![allow(clippy::exhaustive_structs, reason = "Generated by sol! macro")]
+
+use alloy::primitives::{B256, U256};
+use alloy::sol;
+use anyhow::{Context, Result};
+use clap::Args;
+use polymarket_client_sdk::gamma::types::request::MarketsRequest;
+use polymarket_client_sdk::gamma::types::response::Market;
+use polymarket_client_sdk::{contract_config, gamma, POLYGON};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::bot::risk::decimal_to_f64;
+use crate::bot::shadow::TokenSide;
+
+sol! {
+    #[sol(rpc)]
+    interface IConditionalTokens {
+        function payoutNumerators(bytes32 conditionId, uint256 index) external view returns (uint256);
+        function payoutDenominator(bytes32 conditionId) external view returns (uint256);
+    }
+}
+
+/// Condition IDs per Gamma request
+const GAMMA_BATCH: usize = 50;
+
+#[derive(Args, Clone, Debug)]
+pub struct ResolutionArgs {
+    /// DuckDB cache of official market outcomes
+    #[arg(long, default_value = "resolutions.duckdb")]
+    pub resolutions_db: String,
+
+    /// Skip the official outcome lookup and settle on the final book
+    #[arg(long)]
+    pub no_resolve: bool,
+}
+
+impl ResolutionArgs {
+    pub fn resolver(&self) -> Result<Option<ResolutionResolver>> {
+        if self.no_resolve {
+            return Ok(None);
+        }
+        ResolutionResolver::open(&self.resolutions_db).map(Some)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolutionSource {
+    Gamma,
+    Ctf,
+}
+
+impl ResolutionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gamma => "gamma",
+            Self::Ctf => "ctf",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gamma" => Some(Self::Gamma),
+            "ctf" => Some(Self::Ctf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Resolution {
+    /// Payout per share of the first outcome (YES / Up), 0 to 1
+    pub yes_payout: f64,
+    pub source: ResolutionSource,
+}
+
+impl Resolution {
+    /// Whether YES won; `None` for a split payout
+    pub fn yes_won(&self) -> Option<bool> {
+        if self.yes_payout >= 0.99 {
+            Some(true)
+        } else if self.yes_payout <= 0.01 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn winner(&self) -> Option<TokenSide> {
+        self.yes_won().map(|yes| if yes { TokenSide::Yes } else { TokenSide::No })
+    }
+}
+
+/// Resolved outcomes keyed by lowercase condition ID
+#[derive(Debug, Clone, Default)]
+pub struct Resolutions(HashMap<String, Resolution>);
+
+impl Resolutions {
+    pub fn insert(&mut self, condition_id: &str, resolution: Resolution) {
+        self.0.insert(condition_id.to_ascii_lowercase(), resolution);
+    }
+
+    pub fn get(&self, condition_id: &str) -> Option<&Resolution> {
+        self.0.get(&condition_id.to_ascii_lowercase())
+    }
+
+    pub fn yes_won(&self, condition_id: &str) -> Option<bool> {
+        self.get(condition_id).and_then(Resolution::yes_won)
+    }
+
+    pub fn winner(&self, condition_id: &str) -> Option<TokenSide> {
+        self.get(condition_id).and_then(Resolution::winner)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// YES payout from a closed market's outcome prices, once they have
+/// settled to 0/1
+fn settled_yes_payout(prices: &[f64]) -> Option<f64> {
+    let [yes, no] = prices else {
+        return None;
+    };
+    let settled = |p: f64| p >= 0.99 || p <= 0.01;
+    (settled(*yes) && settled(*no) && (yes + no - 1.0).abs() < 0.02).then(|| yes.round())
+}
+
+fn gamma_resolution(market: &Market) -> Option<(String, Resolution)> {
+    if market.closed != Some(true) {
+        return None;
+    }
+    let condition_id = market.condition_id.as_ref()?.to_string();
+    let prices: Vec<f64> = market.outcome_prices.as_ref()?.iter().map(|p| decimal_to_f64(*p)).collect();
+    let yes_payout = settled_yes_payout(&prices)?;
+    Some((condition_id, Resolution { yes_payout, source: ResolutionSource::Gamma }))
+}
+
+/// Looks up official outcomes, caching resolved markets in DuckDB
+pub struct ResolutionResolver {
+    conn: duckdb::Connection,
+    resolutions: Resolutions,
+}
+
+impl ResolutionResolver {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = duckdb::Connection::open(path).with_context(|| format!("Failed to open {}", path))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: duckdb::Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS market_resolutions (
+                condition_id VARCHAR PRIMARY KEY,
+                yes_payout DOUBLE NOT NULL,
+                source VARCHAR NOT NULL,
+                fetched_at BIGINT NOT NULL
+            );",
+        )?;
+        let mut resolutions = Resolutions::default();
+        {
+            let mut stmt = conn.prepare("SELECT condition_id, yes_payout, source FROM market_resolutions")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
+            })?;
+            for row in rows {
+                let (condition_id, yes_payout, source) = row?;
+                if let Some(source) = ResolutionSource::parse(&source) {
+                    resolutions.insert(&condition_id, Resolution { yes_payout, source });
+                }
+            }
+        }
+        Ok(Self { conn, resolutions })
+    }
+
+    pub fn resolutions(&self) -> &Resolutions {
+        &self.resolutions
+    }
+
+    pub fn into_resolutions(self) -> Resolutions {
+        self.resolutions
+    }
+
+    fn store(&mut self, condition_id: &str, resolution: Resolution) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO market_resolutions VALUES (?, ?, ?, ?)",
+            duckdb::params![
+                condition_id.to_ascii_lowercase(),
+                resolution.yes_payout,
+                resolution.source.as_str(),
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        self.resolutions.insert(condition_id, resolution);
+        Ok(())
+    }
+
+    /// Fetch outcomes for the condition IDs not yet cached. IDs that are not
+    /// 32-byte hex are ignored. Returns how many markets were newly resolved.
+    pub async fn resolve<'a>(&mut self, condition_ids: impl IntoIterator<Item = &'a str>) -> Result<usize> {
+        let mut missing: Vec<B256> = condition_ids
+            .into_iter()
+            .filter(|id| self.resolutions.get(id).is_none())
+            .filter_map(|id| id.parse::<B256>().ok())
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        let before = self.resolutions.len();
+
+        let gamma_client = gamma::Client::default();
+        for batch in missing.chunks(GAMMA_BATCH) {
+            let request = MarketsRequest::builder()
+                .condition_ids(batch.to_vec())
+                .closed(true)
+                .limit(batch.len() as i32)
+                .build();
+            match gamma_client.markets(&request).await {
+                Ok(markets) => {
+                    for (condition_id, resolution) in markets.iter().filter_map(gamma_resolution) {
+                        self.store(&condition_id, resolution)?;
+                    }
+                }
+                Err(err) => eprintln!("[warn] Gamma resolution lookup failed: {}", err),
+            }
+        }
+
+        missing.retain(|id| self.resolutions.get(&id.to_string()).is_none());
+        if !missing.is_empty()
+            && let Err(err) = self.resolve_onchain(&missing).await
+        {
+            eprintln!("[warn] CTF payout lookup failed: {}", err);
+        }
+        Ok(self.resolutions.len() - before)
+    }
+
+    /// Payout numerators over the denominator, which stays 0 until the
+    /// oracle reports
+    async fn resolve_onchain(&mut self, condition_ids: &[B256]) -> Result<()> {
+        let provider = crate::auth::create_readonly_provider().await?;
+        let config = contract_config(POLYGON, false).context("No contract config for Polygon")?;
+        let ctf = IConditionalTokens::new(config.conditional_tokens, provider);
+        for condition_id in condition_ids {
+            let denominator = ctf.payoutDenominator(*condition_id).call().await?;
+            if denominator.is_zero() {
+                continue;
+            }
+            let numerator = ctf.payoutNumerators(*condition_id, U256::ZERO).call().await?;
+            let (Ok(numerator), Ok(denominator)) = (u64::try_from(numerator), u64::try_from(denominator)) else {
+                continue;
+            };
+            let resolution = Resolution {
+                yes_payout: numerator as f64 / denominator as f64,
+                source: ResolutionSource::Ctf,
+            };
+            self.store(&condition_id.to_string(), resolution)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_settled_prices_resolve() {
+        assert_eq!(settled_yes_payout(&[1.0, 0.0]), Some(1.0));
+        assert_eq!(settled_yes_payout(&[0.0005, 0.9995]), Some(0.0));
+        // Closed but still trading near expiry, or not binary
+        assert_eq!(settled_yes_payout(&[0.97, 0.03]), None);
+        assert_eq!(settled_yes_payout(&[1.0, 0.0, 0.0]), None);
+
+        let split = Resolution { yes_payout: 0.5, source: ResolutionSource::Ctf };
+        assert_eq!(split.winner(), None);
+    }
+
+    #[test]
+    fn cache_round_trips_and_ignores_id_case() {
+        let id = "0xABCDEF0000000000000000000000000000000000000000000000000000000001";
+        let mut resolver = ResolutionResolver::with_connection(duckdb::Connection::open_in_memory().unwrap()).unwrap();
+        resolver.store(id, Resolution { yes_payout: 0.0, source: ResolutionSource::Gamma }).unwrap();
+        assert_eq!(resolver.resolutions().winner(&id.to_ascii_lowercase()), Some(TokenSide::No));
+
+        let conn = resolver.conn.try_clone().unwrap();
+        let reloaded = ResolutionResolver::with_connection(conn).unwrap();
+        assert_eq!(reloaded.resolutions().yes_won(id), Some(false));
+        assert_eq!(reloaded.resolutions().get(id).unwrap().source, ResolutionSource::Gamma);
+    }
+}
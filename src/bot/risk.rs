@@ -1,7 +1,9 @@
 use crate::bot::feed::MarketSnapshot;
+use crate::bot::research::SupportedAsset;
 use chrono::{NaiveDate, TimeZone, Utc};
 use polymarket_client_sdk::types::Decimal;
 use serde::Serialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -16,6 +18,8 @@ pub enum FilterReason {
     EmergencyHalt,
     DirectionLocked,
     Bankroll,
+    TotalExposure,
+    AssetExposure,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -156,6 +160,17 @@ impl GatekeeperState {
         }
     }
 
+    /// Seed the daily PnL from persisted state after a restart so the daily
+    /// loss limit survives process crashes.
+    pub fn restore_daily_pnl(&mut self, timestamp: u64, daily_pnl: f64) {
+        self.sync_trading_day(timestamp);
+        self.daily_pnl = daily_pnl;
+
+        if self.daily_loss_limit > 0.0 && self.daily_pnl <= -self.daily_loss_limit {
+            self.emergency_halt = true;
+        }
+    }
+
     pub fn halt(&mut self) {
         self.emergency_halt = true;
     }
@@ -171,6 +186,69 @@ impl Default for GatekeeperState {
     }
 }
 
+/// Open exposure across every market a portfolio runner is trading.
+///
+/// Complements `GatekeeperState`, which only knows about realized PnL: the
+/// budget caps capital at risk in total and per underlying asset.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExposureBudget {
+    pub max_total_usd: f64,
+    pub max_per_asset_usd: f64,
+    positions: HashMap<String, (SupportedAsset, f64)>,
+}
+
+impl ExposureBudget {
+    #[must_use]
+    pub fn new(max_total_usd: f64, max_per_asset_usd: f64) -> Self {
+        Self {
+            max_total_usd,
+            max_per_asset_usd,
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn total_exposure(&self) -> f64 {
+        self.positions.values().map(|(_, usd)| usd).sum()
+    }
+
+    pub fn asset_exposure(&self, asset: SupportedAsset) -> f64 {
+        self.positions
+            .values()
+            .filter(|(a, _)| *a == asset)
+            .map(|(_, usd)| usd)
+            .sum()
+    }
+
+    pub fn check(&self, asset: SupportedAsset, size_usd: f64) -> Result<(), FilterReason> {
+        if self.max_total_usd > 0.0 && self.total_exposure() + size_usd > self.max_total_usd + f64::EPSILON {
+            return Err(FilterReason::TotalExposure);
+        }
+        if self.max_per_asset_usd > 0.0
+            && self.asset_exposure(asset) + size_usd > self.max_per_asset_usd + f64::EPSILON
+        {
+            return Err(FilterReason::AssetExposure);
+        }
+        Ok(())
+    }
+
+    pub fn open(&mut self, market_slug: &str, asset: SupportedAsset, size_usd: f64) {
+        self.positions.insert(market_slug.to_string(), (asset, size_usd));
+    }
+
+    pub fn close(&mut self, market_slug: &str) {
+        self.positions.remove(market_slug);
+    }
+
+    pub fn is_open(&self, market_slug: &str) -> bool {
+        self.positions.contains_key(market_slug)
+    }
+
+    /// Drop every position whose market no longer holds tokens
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.positions.retain(|slug, _| keep(slug));
+    }
+}
+
 pub fn decimal_to_f64(value: Decimal) -> f64 {
     value.to_string().parse::<f64>().unwrap_or_default()
 }
@@ -350,4 +428,42 @@ mod tests {
         let approved = gatekeeper.check_entry(&snapshot(0.47, 0.50), &entry_context(1_700_000_020));
         assert!(matches!(approved, GateDecision::Approved { .. }));
     }
+
+    #[test]
+    fn gatekeeper_restored_daily_pnl_enforces_limit() {
+        let mut gatekeeper = GatekeeperState::new(2.0, 15);
+        gatekeeper.restore_daily_pnl(1_700_000_000, -2.10);
+
+        let decision = gatekeeper.check_entry(&snapshot(0.47, 0.50), &entry_context(1_700_000_030));
+        assert!(matches!(
+            decision,
+            GateDecision::Blocked {
+                reason: FilterReason::EmergencyHalt
+            }
+        ));
+    }
+
+    #[test]
+    fn exposure_budget_enforces_total_and_asset_caps() {
+        let mut budget = ExposureBudget::new(3.0, 2.0);
+        assert!(budget.check(SupportedAsset::Btc, 1.0).is_ok());
+
+        budget.open("btc-updown-5m-1", SupportedAsset::Btc, 1.0);
+        budget.open("btc-updown-15m-1", SupportedAsset::Btc, 1.0);
+        assert_eq!(
+            budget.check(SupportedAsset::Btc, 1.0),
+            Err(FilterReason::AssetExposure)
+        );
+        assert!(budget.check(SupportedAsset::Eth, 1.0).is_ok());
+
+        budget.open("eth-updown-5m-1", SupportedAsset::Eth, 1.0);
+        assert_eq!(
+            budget.check(SupportedAsset::Sol, 1.0),
+            Err(FilterReason::TotalExposure)
+        );
+
+        budget.close("btc-updown-5m-1");
+        assert!(budget.check(SupportedAsset::Sol, 1.0).is_ok());
+        assert!((budget.total_exposure() - 2.0).abs() < f64::EPSILON);
+    }
 }
@@ -23,6 +23,51 @@ pub struct ShadowExitRecord {
     pub pnl_usd: f64,
     pub duration: i64,
     pub bankroll_after: f64,
+    /// Shares the exit could not sell, left to redeem at resolution
+    pub unsold_shares: f64,
+}
+
+/// One executed shadow order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowFill {
+    pub shares: f64,
+    pub avg_price: f64,
+    /// Shares times price, before fees
+    pub notional: f64,
+    pub fee: f64,
+}
+
+/// Prices shadow orders when they execute. Shadow mode takes the touch;
+/// backtests fill against the recorded book.
+pub trait ShadowFills {
+    /// Spend `stake_usd` buying `side`, whose best ask is `touch`
+    fn buy(&mut self, side: TokenSide, touch: f64, stake_usd: f64) -> Option<ShadowFill>;
+
+    /// Sell `shares` of `side`, whose best bid is `touch`
+    fn sell(&mut self, side: TokenSide, touch: f64, shares: f64) -> Option<ShadowFill>;
+}
+
+/// Unlimited size at the touch with no fees
+pub struct TouchFills;
+
+impl ShadowFills for TouchFills {
+    fn buy(&mut self, _side: TokenSide, touch: f64, stake_usd: f64) -> Option<ShadowFill> {
+        (touch > 0.0001).then(|| ShadowFill {
+            shares: stake_usd / touch,
+            avg_price: touch,
+            notional: stake_usd,
+            fee: 0.0,
+        })
+    }
+
+    fn sell(&mut self, _side: TokenSide, touch: f64, shares: f64) -> Option<ShadowFill> {
+        (touch > 0.0001).then(|| ShadowFill {
+            shares,
+            avg_price: touch,
+            notional: shares * touch,
+            fee: 0.0,
+        })
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -31,6 +76,9 @@ pub struct ShadowStepResult {
     pub entry_taken: bool,
     pub entry_blocked: bool,
     pub exit_trade: Option<ShadowExitRecord>,
+    /// Signal as the strategy emitted it, before gating
+    #[serde(skip)]
+    pub signal: SignalState,
 }
 
 pub struct ShadowPosition {
@@ -43,6 +91,8 @@ pub struct ShadowPosition {
     pub last_exit_timestamp: u64,
     pub entry_timestamp: u64,
     pub position_size_usd: f64,
+    /// Fee paid on the entry of the open position
+    pub entry_fee: f64,
     pub bankroll_usd: f64,
     pub realized_usd: f64,
     pub position_realized_usd: f64,
@@ -62,6 +112,7 @@ impl Default for ShadowPosition {
             last_exit_timestamp: 0,
             entry_timestamp: 0,
             position_size_usd: 0.0,
+            entry_fee: 0.0,
             bankroll_usd: 5.0,
             realized_usd: 0.0,
             position_realized_usd: 0.0,
@@ -92,6 +143,7 @@ impl ShadowPosition {
         self.position_realized_pnl = 0.0;
         self.last_exit_timestamp = timestamp;
         self.position_size_usd = 0.0;
+        self.entry_fee = 0.0;
         self.position_realized_usd = 0.0;
     }
 
@@ -104,6 +156,7 @@ impl ShadowPosition {
         self.last_exit_timestamp = 0;
         self.entry_timestamp = 0;
         self.position_size_usd = 0.0;
+        self.entry_fee = 0.0;
         self.position_realized_usd = 0.0;
         self.yes_blocked = false;
         self.no_blocked = false;
@@ -115,6 +168,11 @@ impl ShadowPosition {
         }
         (current_price - self.entry_price) / self.entry_price
     }
+
+    /// Shares held, from the stake and entry price
+    pub fn shares(&self) -> f64 {
+        self.position_size_usd / self.entry_price.max(0.0001)
+    }
 }
 
 pub fn handle_shadow_signals(
@@ -122,6 +180,7 @@ pub fn handle_shadow_signals(
     dual_snapshot: &DualSnapshot,
     shadow: &mut ShadowPosition,
     gatekeeper: &mut GatekeeperState,
+    fills: &mut dyn ShadowFills,
     event_loggers: Option<&EngineEventLoggers>,
     market_label: &str,
     market_slug: &str,
@@ -135,6 +194,7 @@ pub fn handle_shadow_signals(
         entry_taken: false,
         entry_blocked: false,
         exit_trade: None,
+        signal: signal.clone(),
     };
     let time_remaining = (market_end_ts - timestamp as i64).max(0);
     let contract_age = (timestamp as i64) - market_start_ts;
@@ -171,7 +231,7 @@ pub fn handle_shadow_signals(
                 timestamp,
                 time_remaining,
                 min_time_remaining: 30,
-                max_time_remaining: 280,
+                max_time_remaining: (market_end_ts - market_start_ts - 20).max(0),
                 contract_age,
                 yes_ask,
                 no_ask,
@@ -221,28 +281,31 @@ pub fn handle_shadow_signals(
             return result;
         }
 
-        shadow.token_side = match signal.entry {
-            EntrySignal::Long => Some(TokenSide::Yes),
-            EntrySignal::Short => Some(TokenSide::No),
-            EntrySignal::None => None,
+        let side = match signal.entry {
+            EntrySignal::Long => TokenSide::Yes,
+            EntrySignal::Short => TokenSide::No,
+            EntrySignal::None => return result,
         };
+        let fill = best_ask_price(snapshot_side).and_then(|ask| fills.buy(side, ask, position_size_usd));
 
-        match best_ask_price(snapshot_side) {
-            Some(price) if price > 0.0001 => {
+        match fill {
+            Some(fill) => {
+                let price = fill.avg_price;
+                shadow.token_side = Some(side);
                 shadow.active_entry = Some(signal.entry);
                 shadow.entry_price = price;
                 shadow.size = 1.0;
                 shadow.position_realized_pnl = 0.0;
                 shadow.entry_timestamp = timestamp;
-                shadow.position_size_usd = position_size_usd;
-                shadow.bankroll_usd -= position_size_usd;
+                shadow.position_size_usd = fill.notional;
+                shadow.entry_fee = fill.fee;
+                shadow.bankroll_usd -= fill.notional + fill.fee;
                 shadow.position_realized_usd = 0.0;
                 result.entry_taken = true;
 
-                let side_name = match shadow.token_side {
-                    Some(TokenSide::Yes) => "YES",
-                    Some(TokenSide::No) => "NO",
-                    None => "N/A",
+                let side_name = match side {
+                    TokenSide::Yes => "YES",
+                    TokenSide::No => "NO",
                 };
 
                 let yes_bid = best_bid_price(&dual_snapshot.yes).unwrap_or(0.0);
@@ -264,43 +327,45 @@ pub fn handle_shadow_signals(
                         market_slug: market_slug.to_string(),
                         side: side_name.to_string(),
                         price,
-                        size_usd: position_size_usd,
+                        size_usd: fill.notional,
                         bankroll_after: shadow.bankroll_usd,
                     });
                 }
             }
-            _ => {
-                println!("[NO LIQUIDITY] No best_ask for {:?}", shadow.token_side);
+            None => {
+                println!("[NO LIQUIDITY] No fill for {:?}", side);
             }
         }
 
         return result;
     }
 
-    let exit_price = match shadow.token_side {
+    let exit_bid = match shadow.token_side {
         Some(TokenSide::Yes) => best_bid_price(&dual_snapshot.yes),
         Some(TokenSide::No) => best_bid_price(&dual_snapshot.no),
         _ => None,
     };
 
-    if signal.exit == ExitSignal::FullExit && shadow.is_active() {
-        match exit_price {
-            Some(price) if price > 0.0001 => {
-                let pnl = shadow.pnl(price);
-                let realized = pnl * shadow.size;
-                shadow.realized_pnl += realized;
-                shadow.position_realized_pnl += realized;
-
-                let dollar_pnl = pnl * shadow.position_size_usd;
-                shadow.bankroll_usd += shadow.position_size_usd + dollar_pnl;
+    let exiting = shadow.token_side.filter(|_| signal.exit == ExitSignal::FullExit);
+    if let Some(side) = exiting {
+        let shares = shadow.shares();
+        match exit_bid.and_then(|bid| fills.sell(side, bid, shares)) {
+            Some(fill) => {
+                let price = fill.avg_price;
+                // Unsold shares keep their cost basis until resolution
+                let proceeds = fill.notional - fill.fee;
+                let dollar_pnl = proceeds - fill.shares * shadow.entry_price - shadow.entry_fee;
+                let pnl = dollar_pnl / shadow.position_size_usd.max(1e-9);
+                shadow.realized_pnl += pnl * shadow.size;
+                shadow.position_realized_pnl += pnl * shadow.size;
+                shadow.bankroll_usd += proceeds;
                 shadow.realized_usd += dollar_pnl;
                 shadow.position_realized_usd += dollar_pnl;
 
                 let duration = (timestamp - shadow.entry_timestamp) as i64;
-                let side_str = match shadow.token_side {
-                    Some(TokenSide::Yes) => "YES".to_string(),
-                    Some(TokenSide::No) => "NO".to_string(),
-                    None => "N/A".to_string(),
+                let side_str = match side {
+                    TokenSide::Yes => "YES".to_string(),
+                    TokenSide::No => "NO".to_string(),
                 };
                 result.exit_trade = Some(ShadowExitRecord {
                     side: side_str,
@@ -310,6 +375,7 @@ pub fn handle_shadow_signals(
                     pnl_usd: shadow.position_realized_usd,
                     duration,
                     bankroll_after: shadow.bankroll_usd,
+                    unsold_shares: (shares - fill.shares).max(0.0),
                 });
 
                 println!(
@@ -344,8 +410,8 @@ pub fn handle_shadow_signals(
                 }
                 shadow.reset(timestamp);
             }
-            _ => {
-                println!("[NO EXIT BID] {:?}", shadow.token_side);
+            None => {
+                println!("[NO EXIT BID] {:?}", side);
             }
         }
     }
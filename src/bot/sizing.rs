@@ -0,0 +1,188 @@
+//! Position Sizing
+//!
+//! Stakes entries by fractional Kelly on the model's fair probability for the
+//! side being bought, priced at the executable ask plus taker fee and
+//! slippage from `CostModel`, and capped as a fraction of bankroll. Shadow,
+//! backtest and live entries all size through `PositionSizer`; without
+//! `--kelly-fraction`, or for strategies that report no fair probability,
+//! entries keep the flat `--size`.
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::bot::pricing::{CalibratedProb, FairProbability};
+use crate::bot::research::CostModel;
+use crate::bot::signal::{EntrySignal, SignalState};
+use crate::bot::strategy::Direction;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct SizingArgs {
+    /// Stake this fraction of full Kelly on the model probability instead of
+    /// the flat --size (e.g. 0.25 for quarter Kelly)
+    #[arg(long)]
+    pub kelly_fraction: Option<f64>,
+
+    /// Largest Kelly stake as a fraction of bankroll
+    #[arg(long, default_value = "0.10")]
+    pub kelly_cap: f64,
+
+    /// Skip entries whose Kelly stake is below this many USD
+    #[arg(long, default_value = "1.0")]
+    pub min_stake: f64,
+}
+
+/// A model's probability that YES resolves in the money
+pub trait ModelProbability {
+    fn yes_probability(&self) -> f64;
+}
+
+impl ModelProbability for CalibratedProb {
+    fn yes_probability(&self) -> f64 {
+        self.fair_prob_calibrated
+    }
+}
+
+impl ModelProbability for FairProbability {
+    fn yes_probability(&self) -> f64 {
+        self.expected
+    }
+}
+
+impl ModelProbability for f64 {
+    fn yes_probability(&self) -> f64 {
+        *self
+    }
+}
+
+/// Fair probability of the side an entry buys and the ask it buys at
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SizingQuote {
+    pub fair_prob: Option<f64>,
+    pub ask: f64,
+}
+
+impl SizingQuote {
+    pub fn from_model(model: &impl ModelProbability, direction: Direction, ask: f64) -> Self {
+        let yes = model.yes_probability().clamp(0.0, 1.0);
+        let fair_prob = match direction {
+            Direction::Yes => yes,
+            Direction::No => 1.0 - yes,
+        };
+        Self { fair_prob: Some(fair_prob), ask }
+    }
+}
+
+/// Full-Kelly fraction of bankroll for buying a binary share at `ask` that
+/// pays 1 with probability `fair_prob`, after taker fee and slippage
+pub fn kelly_fraction(fair_prob: f64, ask: f64, costs: &CostModel) -> f64 {
+    let cost = ask * (1.0 + costs.taker_fee + costs.slippage_buffer);
+    if cost <= 0.0 || cost >= 1.0 {
+        return 0.0;
+    }
+    // Net odds b = 1/cost - 1, so f* = p - (1 - p) / b = (p - cost) / (1 - cost)
+    ((fair_prob - cost) / (1.0 - cost)).max(0.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionSizer {
+    flat_size: f64,
+    kelly: Option<SizingArgs>,
+    costs: CostModel,
+}
+
+impl PositionSizer {
+    pub fn new(flat_size: f64, args: &SizingArgs) -> Self {
+        Self {
+            flat_size,
+            kelly: args.kelly_fraction.filter(|f| *f > 0.0).map(|_| args.clone()),
+            costs: CostModel::default(),
+        }
+    }
+
+    /// Largest stake an entry can get at this bankroll
+    pub fn max_stake(&self, bankroll: f64) -> f64 {
+        match &self.kelly {
+            Some(args) => self.flat_size.max(bankroll.max(0.0) * args.kelly_cap),
+            None => self.flat_size,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.kelly {
+            Some(args) => format!(
+                "{:.2} Kelly, cap {:.0}% of bankroll, min ${:.2} (flat ${:.2} without a model probability)",
+                args.kelly_fraction.unwrap_or_default(),
+                args.kelly_cap * 100.0,
+                args.min_stake,
+                self.flat_size
+            ),
+            None => format!("flat ${:.2}", self.flat_size),
+        }
+    }
+
+    /// Stake in USD for an entry; 0 when Kelly finds no edge after costs or
+    /// the stake falls below the minimum
+    pub fn size(&self, quote: Option<SizingQuote>, bankroll: f64) -> f64 {
+        let (Some(args), Some(SizingQuote { fair_prob: Some(p), ask })) = (&self.kelly, quote) else {
+            return self.flat_size;
+        };
+        let fraction = (args.kelly_fraction.unwrap_or_default() * kelly_fraction(p, ask, &self.costs)).min(args.kelly_cap);
+        let stake = bankroll.max(0.0) * fraction;
+        if stake < args.min_stake { 0.0 } else { stake }
+    }
+
+    /// Size a fresh entry signal, dropping the entry when the stake is 0
+    pub fn size_signal(&self, signal: &mut SignalState, quote: Option<SizingQuote>, bankroll: f64) -> f64 {
+        let size = self.size(quote, bankroll);
+        if size <= 0.0 && signal.entry != EntrySignal::None {
+            signal.entry = EntrySignal::None;
+        }
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::strategy::constraint_engine::ConstraintEngine;
+
+    fn kelly(fraction: f64, cap: f64) -> PositionSizer {
+        PositionSizer::new(1.0, &SizingArgs { kelly_fraction: Some(fraction), kelly_cap: cap, min_stake: 1.0 })
+    }
+
+    #[test]
+    fn kelly_matches_the_frictionless_formula_and_shrinks_with_fees() {
+        let free = CostModel::new(0.0, 0.0, 0.0);
+        let f = kelly_fraction(0.60, 0.50, &free);
+        assert!((f - ConstraintEngine::kelly_fraction(0.60, 0.50)).abs() < 1e-12);
+        assert!((f - 0.2).abs() < 1e-12);
+
+        let with_fees = kelly_fraction(0.60, 0.50, &CostModel::default());
+        assert!(with_fees > 0.0 && with_fees < f);
+        assert_eq!(kelly_fraction(0.50, 0.50, &CostModel::default()), 0.0);
+        assert_eq!(kelly_fraction(0.99, 0.99, &free), 0.0);
+    }
+
+    #[test]
+    fn sizes_by_bankroll_with_cap_minimum_and_flat_fallback() {
+        let quote = |p: f64| Some(SizingQuote { fair_prob: Some(p), ask: 0.50 });
+
+        // Quarter Kelly of ~0.17 on $100, under the 10% cap
+        let stake = kelly(0.25, 0.10).size(quote(0.60), 100.0);
+        assert!(stake > 3.0 && stake < 5.0, "{stake}");
+        assert_eq!(kelly(1.0, 0.10).size(quote(0.90), 100.0), 10.0);
+        // No edge, or a stake under the $1 minimum, skips the entry
+        assert_eq!(kelly(0.25, 0.10).size(quote(0.50), 100.0), 0.0);
+        assert_eq!(kelly(0.25, 0.10).size(quote(0.60), 10.0), 0.0);
+
+        // Without a model probability, or without Kelly, the flat size stands
+        let no_model = Some(SizingQuote { fair_prob: None, ask: 0.5 });
+        assert_eq!(kelly(0.25, 0.10).size(no_model, 100.0), 1.0);
+        assert_eq!(PositionSizer::new(2.0, &SizingArgs::default()).size(quote(0.9), 100.0), 2.0);
+
+        let no = SizingQuote::from_model(&0.30, Direction::No, 0.50);
+        assert_eq!(no.fair_prob, Some(0.70));
+        // Probabilities outside [0, 1] are clamped before sizing
+        assert_eq!(SizingQuote::from_model(&1.2, Direction::No, 0.50).fair_prob, Some(0.0));
+    }
+}
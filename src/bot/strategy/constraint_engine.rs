@@ -13,7 +13,6 @@ use crate::bot::strategy::probability_engine::{
     ArbitrageAction, ArbitrageActionReason, ProbabilityEngine, ViolationType,
 };
 use crate::bot::strategy::temporal_arbitrage::TemporalArbitrageConfig;
-use serde::{Deserialize, Serialize};
 
 /// Constraint engine configuration
 #[derive(Debug, Clone)]
@@ -28,6 +27,8 @@ pub struct ConstraintConfig {
     pub late_phase_threshold_sec: i64,
     /// Maximum spread allowed
     pub max_spread: f64,
+    /// Spot price older than this (seconds) is too stale to price against
+    pub max_price_age_sec: i64,
 }
 
 impl Default for ConstraintConfig {
@@ -38,6 +39,7 @@ impl Default for ConstraintConfig {
             min_price_consistency_edge: 0.06,
             late_phase_threshold_sec: 120,
             max_spread: 0.03,
+            max_price_age_sec: 900,
         }
     }
 }
@@ -76,10 +78,17 @@ impl ConstraintEngine {
                 min_price_consistency_edge: config.min_edge,
                 late_phase_threshold_sec: 120,
                 max_spread: config.max_spread,
+                ..Default::default()
             },
         }
     }
 
+    /// Override how old the spot price may be before consistency checks stop
+    pub fn with_max_price_age(mut self, secs: i64) -> Self {
+        self.config.max_price_age_sec = secs;
+        self
+    }
+
     /// CORRECTED: Check chained conditional after some children resolve
     ///
     /// This is the PRIMARY EDGE source. After k of n children resolve,
@@ -177,6 +186,7 @@ impl ConstraintEngine {
             condition_id: parent_id.to_string(),
             direction,
             edge,
+            fair_prob,
             reason: ArbitrageActionReason::ChainedConditional(confidence),
         })
     }
@@ -207,10 +217,10 @@ impl ConstraintEngine {
                     continue;
                 };
 
-                // Check spread
+                // Skip overround books; an underround one is the anomaly itself
                 let spread = yes_price + no_price - 1.0;
-                if spread.abs() > self.config.max_spread {
-                    continue; // Skip if spread is too wide
+                if spread > self.config.max_spread {
+                    continue;
                 }
 
                 // Calculate fair probability
@@ -243,6 +253,7 @@ impl ConstraintEngine {
                         condition_id: id.clone(),
                         direction,
                         edge,
+                        fair_prob,
                         reason: ArbitrageActionReason::LatePhaseAnomaly(confidence),
                     });
                 }
@@ -266,7 +277,7 @@ impl ConstraintEngine {
         }
 
         // Check if price is stale
-        if graph.price_state.is_stale(now, 300) {
+        if graph.price_state.is_stale(now, self.config.max_price_age_sec) {
             return violations;
         }
 
@@ -316,6 +327,7 @@ impl ConstraintEngine {
                     condition_id: id.clone(),
                     direction,
                     edge,
+                    fair_prob,
                     reason: ArbitrageActionReason::PriceConsistency(confidence),
                 });
             }
@@ -367,10 +379,8 @@ impl ConstraintEngine {
     /// # Returns
     /// Expected value per dollar bet
     pub fn expected_value(fair_prob: f64, market_ask: f64, payout: f64) -> f64 {
-        let win_prob = fair_prob;
-        let lose_prob = 1.0 - fair_prob;
-
-        win_prob * payout / market_ask - lose_prob
+        // A dollar buys 1/ask shares, each paying `payout` on a win
+        fair_prob * payout / market_ask - 1.0
     }
 
     /// Calculate Kelly criterion position size
@@ -583,6 +593,32 @@ mod tests {
         assert!((ev - 0.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_expected_value_reference_values() {
+        // A dollar at ask 0.50 buys two shares
+        assert!((ConstraintEngine::expected_value(0.60, 0.50, 1.0) - 0.2).abs() < 1e-12);
+        assert!((ConstraintEngine::expected_value(0.40, 0.50, 1.0) + 0.2).abs() < 1e-12);
+        assert!((ConstraintEngine::expected_value(0.70, 0.55, 1.0) - 3.0 / 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_late_phase_skips_only_overround_books() {
+        let engine = ConstraintEngine::new(Default::default());
+        let mut graph = create_test_graph();
+        let now = 1800;
+
+        // Underround book: the cheap YES is the anomaly itself
+        let node = graph.nodes.get_mut("child3").unwrap();
+        node.yes_price = Some(0.40);
+        node.no_price = Some(0.50);
+        assert!(!engine.check_late_phase_anomalies(&graph, now).is_empty());
+
+        // Overround book: too wide to trade
+        let node = graph.nodes.get_mut("child3").unwrap();
+        node.no_price = Some(0.70);
+        assert!(engine.check_late_phase_anomalies(&graph, now).is_empty());
+    }
+
     #[test]
     fn test_kelly_fraction() {
         // Positive edge
@@ -19,7 +19,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 
 /// Fair value strategy signal configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct FairValueSignalConfig {
     /// Minimum edge to enter a position
     pub min_edge: f64,
@@ -491,6 +491,7 @@ mod tests {
             indicator_1m: IndicatorState::default(),
             fair_value_prob: None,
             qlib_score: None,
+            trades: Vec::new(),
         }
     }
 
@@ -7,11 +7,10 @@
 use crate::bot::strategy::temporal_arbitrage::{
     PriceState, TemporalNode, Timeframe, VolatilityEstimator,
 };
-use chrono::{Timelike, Utc};
+use chrono::Timelike;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fmt;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -204,15 +203,7 @@ impl TemporalGraphBuilder {
     pub fn parse_timeframe(title: &str) -> Option<Timeframe> {
         let normalized = title.to_ascii_lowercase();
 
-        // Try explicit patterns first
-        if normalized.contains("5m")
-            || normalized.contains("5 min")
-            || normalized.contains("5min")
-            || normalized.contains("5-minute")
-        {
-            return Some(Timeframe::M5);
-        }
-
+        // Longer patterns first: "15min" also contains "5min"
         if normalized.contains("15m")
             || normalized.contains("15 min")
             || normalized.contains("15min")
@@ -241,6 +232,14 @@ impl TemporalGraphBuilder {
             return Some(Timeframe::H4);
         }
 
+        if normalized.contains("5m")
+            || normalized.contains("5 min")
+            || normalized.contains("5min")
+            || normalized.contains("5-minute")
+        {
+            return Some(Timeframe::M5);
+        }
+
         None
     }
 
@@ -277,7 +276,7 @@ impl TemporalGraphBuilder {
         };
 
         let now = chrono::Utc::now();
-        let mut start_time = now
+        let start_time = now
             .with_hour(start_hour as u32)?
             .with_minute(start_min as u32)?
             .with_second(0)?
@@ -319,6 +318,21 @@ impl TemporalGraphBuilder {
     /// The "price to beat" is typically in the description or metadata.
     /// This is a best-effort extraction.
     pub fn extract_strike_price(description: &str) -> Option<f64> {
+        let parse_price = |caps: regex::Captures| -> Option<f64> {
+            let price = caps.get(1)?.as_str().replace(",", "").parse::<f64>().ok()?;
+            // Sanity check: BTC prices are typically 5-6 figures
+            (1000.0..=200000.0).contains(&price).then_some(price)
+        };
+
+        // "Price to beat: $71,500"
+        let labelled_re = Regex::new(
+            r"(?i)(?:strike|price to beat|beat|ref|target)[^\d$]{0,20}\$?(\d[\d,]*(?:\.\d+)?)",
+        )
+        .ok()?;
+        if let Some(price) = labelled_re.captures(description).and_then(parse_price) {
+            return Some(price);
+        }
+
         // Try to find a price pattern in the description
         let price_re =
             Regex::new(r"\$?([\d,]+(?:\.\d+)?)\s*(?:strike|price|beat|ref|target)").ok()?;
@@ -406,38 +420,23 @@ impl TemporalGraphBuilder {
 
         for id in &node_ids {
             let node = &graph.nodes[id];
-            let node_timeframe = node.timeframe;
-            let node_start = node.start_time;
-            let node_end = node.end_time;
             let node_strike = node.strike_price;
 
-            // Try to find a parent
-            for potential_parent in &node_ids {
-                if potential_parent == id {
-                    continue;
-                }
-
-                let parent = &graph.nodes[potential_parent];
-
-                // Parent must be longer timeframe
-                if parent.timeframe as i32 <= node_timeframe as i32 {
-                    continue;
-                }
+            // Nearest enclosing market: the shortest containing timeframe,
+            // so a 5m node hangs off its 15m parent rather than the 4h root
+            let parent = node_ids
+                .iter()
+                .filter(|candidate| *candidate != id)
+                .map(|candidate| &graph.nodes[candidate])
+                .filter(|parent| self.is_contained(node, parent))
+                .min_by_key(|parent| parent.timeframe as i32);
 
-                // FIX #1: Time containment + timeframe nesting is sufficient
-                // Child strikes reset, so we DON'T check strike equality
-                if node_start >= parent.start_time
-                    && node_end <= parent.end_time
-                    && Self::is_valid_child_by_timeframe(node_timeframe, parent.timeframe)
-                {
-                    relationships.push((id.clone(), potential_parent.clone()));
-
-                    // FIX #3: If parent has no strike, infer from first child
-                    if parent.strike_price == 0.0 && node_strike > 0.0 {
-                        strike_inferences.push((potential_parent.clone(), node_strike));
-                    }
+            if let Some(parent) = parent {
+                relationships.push((id.clone(), parent.condition_id.clone()));
 
-                    break; // Found a parent, stop looking
+                // FIX #3: If parent has no strike, infer from first child
+                if parent.strike_price == 0.0 && node_strike > 0.0 {
+                    strike_inferences.push((parent.condition_id.clone(), node_strike));
                 }
             }
         }
@@ -462,6 +461,14 @@ impl TemporalGraphBuilder {
         }
     }
 
+    /// FIX #1: Time containment + timeframe nesting is sufficient.
+    /// Child strikes reset, so we DON'T check strike equality.
+    pub fn is_contained(&self, child: &TemporalNode, parent: &TemporalNode) -> bool {
+        child.start_time >= parent.start_time
+            && child.end_time <= parent.end_time
+            && Self::is_valid_child_by_timeframe(child.timeframe, parent.timeframe)
+    }
+
     /// Check if this is a valid parent-child relationship by timeframe
     fn is_valid_child_by_timeframe(child: Timeframe, parent: Timeframe) -> bool {
         // Check if parent timeframe is exactly one level up
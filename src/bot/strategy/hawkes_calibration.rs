@@ -0,0 +1,442 @@
+//! Hawkes Kernel Calibration
+//!
+//! Maximum-likelihood fit of a bivariate (buy/sell) Hawkes process with an
+//! exponential kernel shared by both streams:
+//!
+//! ```text
+//! λ_i(t) = μ_i + Σ_j α_ij Σ_{t_k^j < t} m_k e^{-β (t - t_k^j)}
+//! ```
+//!
+//! For a fixed β the branching-structure EM has closed-form updates for μ
+//! and α (self-excitation on the diagonal, cross-excitation off it); β is
+//! picked from a log grid by likelihood. The branching ratio, the spectral
+//! radius of `α·E[m]/β`, is the share of flow triggered by earlier flow
+//! rather than arriving exogenously: values near 1 mark a reflexive regime.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+
+pub const BUY: usize = 0;
+pub const SELL: usize = 1;
+
+const EM_ITERATIONS: usize = 200;
+
+/// Kernel parameters; rates are per second
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HawkesParams {
+    /// Background rates `[buy, sell]`
+    pub mu: [f64; 2],
+    /// `alpha[i][j]`: excitation of stream `i` by an event of stream `j`
+    pub alpha: [[f64; 2]; 2],
+    /// Kernel decay
+    pub beta: f64,
+}
+
+impl HawkesParams {
+    /// Two self-exciting streams with no cross-excitation
+    pub fn independent(mu: f64, alpha: f64, beta: f64) -> Self {
+        Self {
+            mu: [mu, mu],
+            alpha: [[alpha, 0.0], [0.0, alpha]],
+            beta,
+        }
+    }
+
+    /// Spectral radius of the mean offspring matrix `α_ij·E[m_j]/β`
+    pub fn branching_ratio(&self, mean_marks: [f64; 2]) -> f64 {
+        if self.beta <= 0.0 {
+            return f64::INFINITY;
+        }
+        let g = |i: usize, j: usize| self.alpha[i][j] * mean_marks[j] / self.beta;
+        let (a, b, c, d) = (g(0, 0), g(0, 1), g(1, 0), g(1, 1));
+        let discriminant = ((a - d) * (a - d) + 4.0 * b * c).max(0.0);
+        0.5 * (a + d + discriminant.sqrt())
+    }
+}
+
+/// How event marks are measured. α scales with `1/E[m]`, so a kernel only
+/// transfers between estimators that mark events the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkUnit {
+    /// Absolute midpoint change, as written by earlier calibrations
+    #[default]
+    MidChange,
+    /// Event size over its running mean, about 1 on average whether the
+    /// events are trade prints or midpoint moves
+    Relative,
+}
+
+/// Mark unit `HawkesFlowEngine` feeds its estimator, and so the unit
+/// `bot calibrate-hawkes` fits in
+pub const ENGINE_MARK_UNIT: MarkUnit = MarkUnit::Relative;
+
+/// Marks event sizes relative to their EWMA
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelativeMarks {
+    avg: Option<f64>,
+}
+
+impl RelativeMarks {
+    pub fn mark(&mut self, size: f64) -> f64 {
+        let avg = *self.avg.get_or_insert(size);
+        self.avg = Some(0.95 * avg + 0.05 * size);
+        size / avg
+    }
+}
+
+/// One event: seconds, stream (`BUY`/`SELL`) and mark
+#[derive(Debug, Clone, Copy)]
+pub struct HawkesEvent {
+    pub t: f64,
+    pub stream: usize,
+    pub mark: f64,
+}
+
+/// Events observed over `[start, end]`, in time order
+#[derive(Debug, Clone)]
+pub struct EventSequence {
+    pub start: f64,
+    pub end: f64,
+    pub events: Vec<HawkesEvent>,
+}
+
+impl EventSequence {
+    fn duration(&self) -> f64 {
+        (self.end - self.start).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HawkesFit {
+    pub params: HawkesParams,
+    pub log_likelihood: f64,
+    pub events: usize,
+    pub mean_marks: [f64; 2],
+    pub branching_ratio: f64,
+}
+
+/// Default decay grid, 0.02/s to 20/s
+pub fn beta_grid() -> Vec<f64> {
+    (0..16).map(|i| 0.02 * 10f64.powf(i as f64 * 0.2)).collect()
+}
+
+/// Flow events inferred from midpoint moves, the way `HawkesFlowEngine`
+/// does without a trade stream: direction from the sign, mark from the size
+/// of the move relative to recent moves. `mids` are `(seconds, midpoint)` in
+/// time order.
+pub fn quote_flow_events(mids: &[(f64, f64)]) -> Vec<HawkesEvent> {
+    let mut marks = RelativeMarks::default();
+    mids.windows(2)
+        .filter_map(|pair| {
+            let ((prev_ts, prev_mid), (ts, mid)) = (pair[0], pair[1]);
+            let change = mid - prev_mid;
+            if ts <= prev_ts || change.abs() < 1e-6 {
+                return None;
+            }
+            Some(HawkesEvent {
+                t: ts,
+                stream: if change > 0.0 { BUY } else { SELL },
+                mark: marks.mark(change.abs()),
+            })
+        })
+        .collect()
+}
+
+/// Mean mark per stream, 1.0 for a stream with no events
+pub fn mean_marks<'a>(events: impl IntoIterator<Item = &'a HawkesEvent>) -> [f64; 2] {
+    let (mut sums, mut counts) = ([0.0; 2], [0usize; 2]);
+    for event in events {
+        sums[event.stream] += event.mark;
+        counts[event.stream] += 1;
+    }
+    [0, 1].map(|i| if counts[i] > 0 { sums[i] / counts[i] as f64 } else { 1.0 })
+}
+
+/// Integrated kernel mass of each stream up to its sequence end
+fn compensators(sequences: &[EventSequence], beta: f64) -> [f64; 2] {
+    let mut comp = [0.0; 2];
+    for seq in sequences {
+        for event in &seq.events {
+            comp[event.stream] += event.mark * (1.0 - (-beta * (seq.end - event.t).max(0.0)).exp()) / beta;
+        }
+    }
+    comp
+}
+
+/// Walk every event with the decayed mark sums just before it
+fn for_each_event(sequences: &[EventSequence], beta: f64, mut f: impl FnMut(&HawkesEvent, [f64; 2])) {
+    for seq in sequences {
+        let mut excitation = [0.0; 2];
+        let mut last = seq.start;
+        for event in &seq.events {
+            let dt = (event.t - last).max(0.0);
+            let decay = (-beta * dt).exp();
+            excitation = excitation.map(|r| r * decay);
+            last = last.max(event.t);
+            f(event, excitation);
+            excitation[event.stream] += event.mark;
+        }
+    }
+}
+
+fn intensity(params: &HawkesParams, stream: usize, excitation: [f64; 2]) -> f64 {
+    params.mu[stream] + params.alpha[stream][0] * excitation[0] + params.alpha[stream][1] * excitation[1]
+}
+
+pub fn log_likelihood(sequences: &[EventSequence], params: &HawkesParams) -> f64 {
+    let duration: f64 = sequences.iter().map(EventSequence::duration).sum();
+    let comp = compensators(sequences, params.beta);
+    let mut ll = 0.0;
+    for_each_event(sequences, params.beta, |event, excitation| {
+        ll += intensity(params, event.stream, excitation).max(1e-300).ln();
+    });
+    for i in 0..2 {
+        ll -= params.mu[i] * duration;
+        for (j, compensator) in comp.iter().enumerate() {
+            ll -= params.alpha[i][j] * compensator;
+        }
+    }
+    ll
+}
+
+/// EM for μ and α with β held fixed
+fn fit_fixed_beta(sequences: &[EventSequence], beta: f64, marks: [f64; 2]) -> HawkesParams {
+    let duration: f64 = sequences.iter().map(EventSequence::duration).sum();
+    let comp = compensators(sequences, beta);
+    let mut counts = [0.0; 2];
+    for event in sequences.iter().flat_map(|s| &s.events) {
+        counts[event.stream] += 1.0;
+    }
+
+    // Start from a half-endogenous split of each stream
+    let mut params = HawkesParams {
+        mu: counts.map(|n| 0.5 * n / duration),
+        alpha: [[0.25 * beta / marks[0], 0.25 * beta / marks[1]]; 2],
+        beta,
+    };
+    for (alpha, &count) in params.alpha.iter_mut().zip(&counts) {
+        if count == 0.0 {
+            *alpha = [0.0; 2];
+        }
+    }
+
+    for _ in 0..EM_ITERATIONS {
+        let mut background = [0.0; 2];
+        let mut triggered = [[0.0; 2]; 2];
+        for_each_event(sequences, beta, |event, excitation| {
+            let i = event.stream;
+            let lambda = intensity(&params, i, excitation).max(1e-300);
+            background[i] += params.mu[i] / lambda;
+            for j in 0..2 {
+                triggered[i][j] += params.alpha[i][j] * excitation[j] / lambda;
+            }
+        });
+
+        let mut change: f64 = 0.0;
+        for i in 0..2 {
+            let mu = background[i] / duration;
+            change = change.max((mu - params.mu[i]).abs());
+            params.mu[i] = mu;
+            for j in 0..2 {
+                let alpha = if comp[j] > 0.0 { triggered[i][j] / comp[j] } else { 0.0 };
+                change = change.max((alpha - params.alpha[i][j]).abs() * marks[j] / beta);
+                params.alpha[i][j] = alpha;
+            }
+        }
+        if change < 1e-7 {
+            break;
+        }
+    }
+    params
+}
+
+/// Best fit over `betas`, or `None` without events or observed time
+pub fn fit(sequences: &[EventSequence], betas: &[f64]) -> Option<HawkesFit> {
+    let events: usize = sequences.iter().map(|s| s.events.len()).sum();
+    let duration: f64 = sequences.iter().map(EventSequence::duration).sum();
+    if events == 0 || duration <= 0.0 {
+        return None;
+    }
+    let marks = mean_marks(sequences.iter().flat_map(|s| &s.events));
+
+    betas
+        .iter()
+        .filter(|beta| **beta > 0.0)
+        .map(|&beta| {
+            let params = fit_fixed_beta(sequences, beta, marks);
+            (params, log_likelihood(sequences, &params))
+        })
+        .filter(|(_, ll)| ll.is_finite())
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(params, log_likelihood)| HawkesFit {
+            params,
+            log_likelihood,
+            events,
+            mean_marks: marks,
+            branching_ratio: params.branching_ratio(marks),
+        })
+}
+
+/// Fitted kernel for one asset and market duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibratedKernel {
+    pub asset: SupportedAsset,
+    pub duration: SupportedDuration,
+    pub params: HawkesParams,
+    pub branching_ratio: f64,
+    pub log_likelihood: f64,
+    pub events: usize,
+    pub markets: usize,
+    /// Files without it predate relative marks
+    #[serde(default)]
+    pub mark_unit: MarkUnit,
+}
+
+impl CalibratedKernel {
+    pub fn matches_slug(&self, slug: &str) -> bool {
+        SupportedAsset::from_slug(slug) == Some(self.asset)
+            && SupportedDuration::from_slug(slug) == Some(self.duration)
+    }
+}
+
+/// Parameter file written by `bot calibrate-hawkes`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HawkesCalibration {
+    pub kernels: Vec<CalibratedKernel>,
+}
+
+impl HawkesCalibration {
+    /// Load a parameter file, rejecting kernels fitted in another mark unit
+    /// than the engine's
+    pub fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let calibration: Self =
+            serde_json::from_str(&content).with_context(|| format!("Invalid Hawkes parameter file {}", path))?;
+        if let Some(kernel) = calibration.kernels.iter().find(|k| k.mark_unit != ENGINE_MARK_UNIT) {
+            anyhow::bail!(
+                "{} kernel for {}-{} marks events as {:?}, the engine as {:?}; rerun `bot calibrate-hawkes`",
+                path,
+                kernel.asset.as_str(),
+                kernel.duration.as_str(),
+                kernel.mark_unit,
+                ENGINE_MARK_UNIT
+            );
+        }
+        Ok(calibration)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic LCG so the simulation needs no RNG crate
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn uniform(&mut self) -> f64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((self.0 >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        }
+    }
+
+    /// Ogata thinning with unit marks
+    fn simulate(params: &HawkesParams, horizon: f64, seed: u64) -> EventSequence {
+        let mut rng = Lcg(seed);
+        let (mut t, mut excitation, mut events) = (0.0, [0.0f64; 2], Vec::new());
+        loop {
+            let bound = intensity(params, BUY, excitation) + intensity(params, SELL, excitation);
+            let dt = -rng.uniform().ln() / bound;
+            t += dt;
+            if t > horizon {
+                break;
+            }
+            excitation = excitation.map(|r| r * (-params.beta * dt).exp());
+            let (buy, sell) = (intensity(params, BUY, excitation), intensity(params, SELL, excitation));
+            let u = rng.uniform() * bound;
+            let stream = if u < buy {
+                BUY
+            } else if u < buy + sell {
+                SELL
+            } else {
+                continue;
+            };
+            events.push(HawkesEvent { t, stream, mark: 1.0 });
+            excitation[stream] += 1.0;
+        }
+        EventSequence { start: 0.0, end: horizon, events }
+    }
+
+    #[test]
+    fn fit_recovers_simulated_kernel() {
+        let truth = HawkesParams {
+            mu: [0.4, 0.3],
+            alpha: [[0.8, 0.3], [0.2, 0.6]],
+            beta: 2.0,
+        };
+        let sequences: Vec<_> = (0..4).map(|seed| simulate(&truth, 2_000.0, seed + 1)).collect();
+        let fit = fit(&sequences, &beta_grid()).unwrap();
+
+        let true_ratio = truth.branching_ratio([1.0, 1.0]);
+        assert!((fit.branching_ratio - true_ratio).abs() < 0.1, "{fit:?} vs {true_ratio}");
+        assert!((fit.params.mu[BUY] - 0.4).abs() < 0.1, "{fit:?}");
+        assert!(fit.params.alpha[BUY][BUY] > fit.params.alpha[SELL][BUY]);
+        assert!(fit.log_likelihood >= log_likelihood(&sequences, &truth) - 1e-6);
+    }
+
+    #[test]
+    fn poisson_flow_has_low_branching_ratio() {
+        let truth = HawkesParams::independent(0.5, 0.0, 1.0);
+        let sequences = vec![simulate(&truth, 4_000.0, 7)];
+        let fit = fit(&sequences, &beta_grid()).unwrap();
+        assert!(fit.branching_ratio < 0.1, "{fit:?}");
+        assert!((fit.params.mu[SELL] - 0.5).abs() < 0.05, "{fit:?}");
+    }
+
+    #[test]
+    fn quote_events_follow_midpoint_moves() {
+        let events = quote_flow_events(&[(0.0, 0.50), (1.0, 0.52), (1.0, 0.53), (2.0, 0.53), (3.0, 0.49)]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].stream, BUY);
+        assert_eq!(events[1].stream, SELL);
+        // A move twice the first one marks about 2
+        assert!((events[0].mark - 1.0).abs() < 1e-9);
+        assert!((events[1].mark - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn load_rejects_kernels_in_another_mark_unit() {
+        let kernel = CalibratedKernel {
+            asset: SupportedAsset::Btc,
+            duration: SupportedDuration::M5,
+            params: HawkesParams::independent(0.1, 0.3, 0.5),
+            branching_ratio: 0.6,
+            log_likelihood: 0.0,
+            events: 0,
+            markets: 0,
+            mark_unit: ENGINE_MARK_UNIT,
+        };
+        let dir = crate::bot::test_util::tempdir();
+        let path = format!("{dir}/hawkes.json");
+        let path = path.as_str();
+
+        HawkesCalibration { kernels: vec![kernel.clone()] }.save(path).unwrap();
+        assert_eq!(HawkesCalibration::load(path).unwrap().kernels.len(), 1);
+
+        // Files from before mark units were recorded hold midpoint changes
+        let mut legacy = serde_json::to_value(HawkesCalibration { kernels: vec![kernel] }).unwrap();
+        legacy["kernels"][0].as_object_mut().unwrap().remove("mark_unit");
+        std::fs::write(path, legacy.to_string()).unwrap();
+        assert!(HawkesCalibration::load(path).unwrap_err().to_string().contains("calibrate-hawkes"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -12,16 +12,26 @@
 //!
 //! Novel contribution: None of the existing strategies (Heuristic/FairValue/TemporalArb)
 //! use order flow excitation dynamics as a signal source.
+//!
+//! Flow events come from websocket trade prints (`Observation::trades`) signed by
+//! taker side. Without a trade stream, e.g. in PMXT replays, direction is inferred
+//! from midpoint moves instead.
 
 use super::{
     Confidence, Direction, EntryReason, ExitReason, Observation, SignalSource, StrategyDecision,
     StrategyEngine,
 };
+use super::hawkes_calibration::{
+    beta_grid, fit as fit_hawkes, mean_marks, CalibratedKernel, EventSequence, HawkesEvent,
+    HawkesFit, HawkesParams, RelativeMarks, BUY, SELL,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::thread::JoinHandle;
 
 /// Configuration for the Hawkes Flow strategy
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct HawkesFlowConfig {
     /// Decay rate for exponential Hawkes kernel (higher = faster decay)
     pub kernel_decay: f64,
@@ -42,6 +52,19 @@ pub struct HawkesFlowConfig {
     pub min_bb_width: f64,
     /// Cooldown after exit (observations)
     pub cooldown_observations: usize,
+    /// Shares per VPIN bucket when driven by trade prints
+    pub trade_bucket_volume: f64,
+    /// Refit the kernel by MLE after this many new flow events; 0 keeps it fixed
+    pub refit_every: usize,
+    /// Minimum events in the window before a refit
+    pub min_fit_events: usize,
+    /// Rolling window the kernel is refit over (seconds)
+    pub fit_window_secs: i64,
+    /// Block on a running refit at the next observation instead of deciding
+    /// on the old kernel until it finishes, so backtests are reproducible
+    pub deterministic_refit: bool,
+    /// Starting kernels per asset/duration from `bot calibrate-hawkes`
+    pub kernels: Vec<CalibratedKernel>,
 }
 
 impl Default for HawkesFlowConfig {
@@ -57,91 +80,181 @@ impl Default for HawkesFlowConfig {
             min_entry_prob: 0.12,
             min_bb_width: 0.01,       // minimum spread (0.01 = 1%)
             cooldown_observations: 3, // 3 observations after exit
+            trade_bucket_volume: 200.0,
+            refit_every: 50,
+            min_fit_events: 50,
+            fit_window_secs: 300,
+            deterministic_refit: false,
+            kernels: Vec::new(),
         }
     }
 }
 
-/// A single order flow event (buy or sell inference from price movement)
+/// A single order flow event, from a trade print or inferred from price movement
 #[derive(Debug, Clone, Copy)]
 struct FlowEvent {
+    /// Milliseconds
     timestamp: i64,
-    is_buy: bool,   // inferred from price movement direction
-    magnitude: f64, // absolute price change
+    is_buy: bool,   // YES-side pressure: taker side of the print, or price direction
+    magnitude: f64, // trade size or price change relative to its running mean
 }
 
-/// Hawkes intensity estimator with exponential kernel
-#[derive(Debug, Clone)]
+/// Normalize a seconds or milliseconds timestamp to milliseconds
+fn to_millis(ts: f64) -> i64 {
+    if ts > 1e11 { ts as i64 } else { (ts * 1000.0) as i64 }
+}
+
+/// Bivariate (buy/sell) Hawkes intensity estimator with exponential kernel,
+/// refit online by maximum likelihood over its rolling event window. Fits
+/// run on a worker thread and are applied once they finish.
+#[derive(Debug)]
 struct HawkesEstimator {
-    /// Base intensity (background rate)
-    mu: f64,
-    /// Excitation coefficient
-    alpha: f64,
-    /// Decay rate
-    beta: f64,
-    /// History of events
-    events: VecDeque<FlowEvent>,
-    /// Current intensity value
-    current_intensity: f64,
+    params: HawkesParams,
+    /// Decayed mark sums per stream, `Σ m_k e^{-β (t - t_k)}`
+    excitation: [f64; 2],
+    /// Events inside the fit window
+    events: VecDeque<(FlowEvent, usize)>,
     /// Last update timestamp
     last_ts: Option<i64>,
+    /// Refit after this many new events; 0 keeps the kernel fixed
+    refit_every: usize,
+    min_fit_events: usize,
+    window_ms: i64,
+    since_fit: usize,
+    /// Last accepted fit
+    fit: Option<HawkesFit>,
+    /// Fit running on a worker thread
+    pending: Option<JoinHandle<Option<HawkesFit>>>,
+    /// Wait for `pending` instead of polling it
+    wait_for_fit: bool,
 }
 
 impl HawkesEstimator {
-    fn new(alpha: f64, beta: f64) -> Self {
+    fn new(params: HawkesParams, config: &HawkesFlowConfig) -> Self {
         Self {
-            mu: 0.1,
-            alpha,
-            beta,
+            params,
+            excitation: [0.0; 2],
             events: VecDeque::with_capacity(200),
-            current_intensity: 0.1,
             last_ts: None,
+            refit_every: config.refit_every,
+            min_fit_events: config.min_fit_events,
+            window_ms: config.fit_window_secs * 1000,
+            since_fit: 0,
+            fit: None,
+            pending: None,
+            wait_for_fit: config.deterministic_refit,
         }
     }
 
-    /// Update intensity with a new event
-    fn update(&mut self, event: FlowEvent) {
-        // Decay existing intensity since last update
-        if let Some(last) = self.last_ts {
-            let dt = (event.timestamp - last) as f64 / 1000.0; // convert ms to seconds
-            if dt > 0.0 {
-                self.current_intensity =
-                    self.mu + (self.current_intensity - self.mu) * (-self.beta * dt).exp();
+    fn decayed(&self, ts: i64) -> [f64; 2] {
+        match self.last_ts {
+            Some(last) if ts > last => {
+                let decay = (-self.params.beta * (ts - last) as f64 / 1000.0).exp();
+                self.excitation.map(|r| r * decay)
             }
+            _ => self.excitation,
         }
+    }
 
-        // Add excitation from new event
-        self.current_intensity += self.alpha * event.magnitude;
+    /// Update intensities with a new event
+    fn update(&mut self, event: FlowEvent) {
+        let stream = if event.is_buy { BUY } else { SELL };
 
-        self.events.push_back(event);
-        self.last_ts = Some(event.timestamp);
+        // Decay existing excitation since last update, then add the new event
+        self.excitation = self.decayed(event.timestamp);
+        self.excitation[stream] += event.magnitude;
+        self.last_ts = Some(self.last_ts.map_or(event.timestamp, |last| last.max(event.timestamp)));
 
-        // Keep only recent events (last 60 seconds)
-        let cutoff = event.timestamp - 60_000;
-        while self.events.front().map_or(false, |e| e.timestamp < cutoff) {
+        self.events.push_back((event, stream));
+        let cutoff = event.timestamp - self.window_ms;
+        while self.events.front().is_some_and(|(e, _)| e.timestamp < cutoff) {
             self.events.pop_front();
         }
+
+        if self.refit_every > 0 {
+            self.since_fit += 1;
+            if self.since_fit >= self.refit_every
+                && self.events.len() >= self.min_fit_events
+                && self.pending.is_none()
+            {
+                self.since_fit = 0;
+                self.start_refit();
+            }
+        }
     }
 
-    /// Get current intensity
-    fn intensity(&self) -> f64 {
-        self.current_intensity
+    fn window_events(&self) -> impl Iterator<Item = HawkesEvent> + '_ {
+        self.events.iter().map(|(e, stream)| HawkesEvent {
+            t: e.timestamp as f64 / 1000.0,
+            stream: *stream,
+            mark: e.magnitude,
+        })
     }
 
-    /// Decay intensity to a future timestamp
-    fn intensity_at(&self, future_ts: i64) -> f64 {
-        if let Some(last) = self.last_ts {
-            let dt = (future_ts - last) as f64 / 1000.0;
-            if dt > 0.0 {
-                return self.mu + (self.current_intensity - self.mu) * (-self.beta * dt).exp();
-            }
+    /// Fit the kernel to a snapshot of the window on a worker thread. Needs
+    /// both streams present, and explosive fits (branching ratio ≥ 1) are
+    /// rejected.
+    fn start_refit(&mut self) {
+        let (Some((first, _)), Some(last)) = (self.events.front(), self.last_ts) else {
+            return;
+        };
+        let sequence = EventSequence {
+            start: first.timestamp as f64 / 1000.0,
+            end: last as f64 / 1000.0,
+            events: self.window_events().collect(),
+        };
+        let buys = sequence.events.iter().filter(|e| e.stream == BUY).count();
+        if buys < 5 || sequence.events.len() - buys < 5 {
+            return;
         }
-        self.current_intensity
+        self.pending = Some(std::thread::spawn(move || {
+            fit_hawkes(&[sequence], &beta_grid()).filter(|fit| fit.branching_ratio < 1.0)
+        }));
     }
 
-    fn reset(&mut self) {
-        self.events.clear();
-        self.current_intensity = self.mu;
-        self.last_ts = None;
+    /// Apply the fit started on an earlier observation once it is done; the
+    /// current kernel stays in use meanwhile. With `wait_for_fit` a running
+    /// fit is joined, so every run sees its fits at the same observations.
+    fn finish_refit(&mut self) {
+        if !self.wait_for_fit && !self.pending.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        let Some(handle) = self.pending.take() else {
+            return;
+        };
+        let (Ok(Some(fit)), Some(last)) = (handle.join(), self.last_ts) else {
+            return;
+        };
+
+        // The excitation state depends on β, so rebuild it from the window
+        self.params = fit.params;
+        let now = last as f64 / 1000.0;
+        let mut excitation = [0.0; 2];
+        for event in self.window_events() {
+            excitation[event.stream] += event.mark * (-fit.params.beta * (now - event.t).max(0.0)).exp();
+        }
+        self.excitation = excitation;
+        self.fit = Some(fit);
+    }
+
+    /// Current intensity of a stream
+    fn intensity(&self, stream: usize) -> f64 {
+        let r = self.excitation;
+        self.params.mu[stream] + self.params.alpha[stream][0] * r[0] + self.params.alpha[stream][1] * r[1]
+    }
+
+    /// Intensity of a stream decayed to a future timestamp
+    fn intensity_at(&self, stream: usize, future_ts: i64) -> f64 {
+        let r = self.decayed(future_ts);
+        self.params.mu[stream] + self.params.alpha[stream][0] * r[0] + self.params.alpha[stream][1] * r[1]
+    }
+
+    /// Branching ratio of the current kernel over the window's marks
+    fn branching_ratio(&self) -> f64 {
+        match &self.fit {
+            Some(fit) => fit.branching_ratio,
+            None => self.params.branching_ratio(mean_marks(&self.window_events().collect::<Vec<_>>())),
+        }
     }
 }
 
@@ -159,9 +272,12 @@ struct VpinEstimator {
     avg_bucket_volume: f64,
     /// Current bucket fill
     current_bucket_volume: f64,
+    /// Fixed bucket size; trades larger than the remaining room are split
+    bucket_volume: Option<f64>,
 }
 
 impl VpinEstimator {
+    /// Buckets sized adaptively from the running average fill
     fn new(window: usize) -> Self {
         Self {
             bucket_buy_volume: 0.0,
@@ -170,11 +286,38 @@ impl VpinEstimator {
             window,
             avg_bucket_volume: 0.0,
             current_bucket_volume: 0.0,
+            bucket_volume: None,
+        }
+    }
+
+    /// Equal-volume buckets, as in the standard VPIN construction
+    fn with_bucket_volume(window: usize, bucket_volume: f64) -> Self {
+        Self {
+            bucket_volume: Some(bucket_volume.max(1e-9)),
+            ..Self::new(window)
         }
     }
 
     /// Add a trade and potentially complete a bucket
     fn add_trade(&mut self, is_buy: bool, volume: f64) {
+        if let Some(bucket_volume) = self.bucket_volume {
+            let mut remaining = volume;
+            while remaining > 0.0 {
+                let fill = remaining.min(bucket_volume - self.current_bucket_volume);
+                if is_buy {
+                    self.bucket_buy_volume += fill;
+                } else {
+                    self.bucket_sell_volume += fill;
+                }
+                self.current_bucket_volume += fill;
+                remaining -= fill;
+                if self.current_bucket_volume >= bucket_volume - 1e-9 {
+                    self.complete_bucket();
+                }
+            }
+            return;
+        }
+
         if is_buy {
             self.bucket_buy_volume += volume;
         } else {
@@ -233,6 +376,7 @@ impl VpinEstimator {
         self.imbalances.clear();
         self.avg_bucket_volume = 0.0;
         self.current_bucket_volume = 0.0;
+        self.bucket_volume = None;
     }
 }
 
@@ -250,15 +394,18 @@ fn compute_heai(buy_intensity: f64, sell_intensity: f64) -> f64 {
 /// Hawkes Flow Excitation Engine
 ///
 /// Strategy logic:
-/// 1. Maintain two Hawkes estimators (buy-side, sell-side)
-/// 2. On each observation, infer trade direction from price movement
-/// 3. Update both estimators and compute HEAI
-/// 4. Compute VPIN from volume imbalance
+/// 1. Maintain a bivariate buy/sell Hawkes estimator, starting from the
+///    calibrated kernel for the market and refit online
+/// 2. On each observation, take signed trade prints, or infer direction from
+///    price movement when the feed has no trade stream
+/// 3. Update both intensities and compute HEAI
+/// 4. Compute VPIN from (signed trade) volume imbalance
 /// 5. Entry when |HEAI| > threshold AND VPIN > toxicity_gate AND price in range
 pub struct HawkesFlowEngine {
     config: HawkesFlowConfig,
-    buy_hawkes: HawkesEstimator,
-    sell_hawkes: HawkesEstimator,
+    hawkes: HawkesEstimator,
+    /// The starting kernel has been chosen for the current market
+    kernel_selected: bool,
     vpin: VpinEstimator,
     prev_mid: Option<f64>,
     prev_ts: Option<i64>,
@@ -269,6 +416,12 @@ pub struct HawkesFlowEngine {
     entry_direction: Option<Direction>,
     /// Rolling HEAI history for momentum detection
     heai_history: VecDeque<f64>,
+    /// Trade prints have arrived; midpoint inference is switched off
+    prints_seen: bool,
+    /// Print sizes and midpoint moves are marked relative to their own
+    /// running means, the unit calibrated kernels are fitted in
+    print_marks: RelativeMarks,
+    move_marks: RelativeMarks,
 }
 
 impl HawkesFlowEngine {
@@ -277,13 +430,11 @@ impl HawkesFlowEngine {
     }
 
     pub fn with_config(config: HawkesFlowConfig) -> Self {
-        let kernel_alpha = 0.3;
-        let kernel_beta = config.kernel_decay;
         Self {
+            hawkes: HawkesEstimator::new(Self::default_kernel(&config), &config),
+            kernel_selected: false,
+            vpin: VpinEstimator::new(config.vpin_window),
             config,
-            buy_hawkes: HawkesEstimator::new(kernel_alpha, kernel_beta),
-            sell_hawkes: HawkesEstimator::new(kernel_alpha, kernel_beta),
-            vpin: VpinEstimator::new(50),
             prev_mid: None,
             prev_ts: None,
             active_position: false,
@@ -291,11 +442,64 @@ impl HawkesFlowEngine {
             entry_price: None,
             entry_direction: None,
             heai_history: VecDeque::with_capacity(20),
+            prints_seen: false,
+            print_marks: RelativeMarks::default(),
+            move_marks: RelativeMarks::default(),
+        }
+    }
+
+    /// Uncalibrated kernel: independent buy and sell streams
+    fn default_kernel(config: &HawkesFlowConfig) -> HawkesParams {
+        HawkesParams::independent(0.1, 0.3, config.kernel_decay)
+    }
+
+    /// Start from the calibrated kernel for this market's asset and duration
+    fn select_kernel(&mut self, market_slug: &str) {
+        let params = self
+            .config
+            .kernels
+            .iter()
+            .find(|kernel| kernel.matches_slug(market_slug))
+            .map_or_else(|| Self::default_kernel(&self.config), |kernel| kernel.params);
+        self.hawkes = HawkesEstimator::new(params, &self.config);
+        self.kernel_selected = true;
+    }
+
+    /// Branching ratio of the flow kernel: the share of flow triggered by
+    /// earlier flow. Near 1 the market is in a reflexive, self-exciting regime.
+    pub fn branching_ratio(&self) -> f64 {
+        self.hawkes.branching_ratio()
+    }
+
+    /// Feed signed trade prints into the estimators. The first print switches
+    /// VPIN to fixed-volume buckets of real traded shares.
+    fn apply_trades(&mut self, obs: &Observation) {
+        if !self.prints_seen {
+            self.prints_seen = true;
+            self.vpin = VpinEstimator::with_bucket_volume(
+                self.config.vpin_window,
+                self.config.trade_bucket_volume,
+            );
+        }
+        for trade in &obs.trades {
+            let signed = trade.signed_yes_volume();
+            let size = signed.abs();
+            if size <= 0.0 {
+                continue;
+            }
+            let event = FlowEvent {
+                timestamp: to_millis(trade.ts_exchange),
+                is_buy: signed > 0.0,
+                magnitude: self.print_marks.mark(size),
+            };
+
+            self.hawkes.update(event);
+            self.vpin.add_trade(event.is_buy, size);
         }
     }
 
     /// Infer trade direction and magnitude from price movement
-    fn infer_flow_event(&self, obs: &Observation) -> Option<FlowEvent> {
+    fn infer_flow_event(&mut self, obs: &Observation) -> Option<FlowEvent> {
         let mid = obs.yes_mid;
         let ts = obs.ts;
 
@@ -312,9 +516,9 @@ impl HawkesFlowEngine {
             }
 
             Some(FlowEvent {
-                timestamp: ts,
+                timestamp: to_millis(ts as f64),
                 is_buy: price_change > 0.0,
-                magnitude,
+                magnitude: self.move_marks.mark(magnitude),
             })
         } else {
             None
@@ -388,12 +592,13 @@ impl HawkesFlowEngine {
         };
 
         let detail = format!(
-            "HawkesFlow: HEAI={:.3} VPIN={:.3} conf={:.3} buy_λ={:.4} sell_λ={:.4}",
+            "HawkesFlow: HEAI={:.3} VPIN={:.3} conf={:.3} buy_λ={:.4} sell_λ={:.4} BR={:.2}",
             heai,
             vpin_val,
             confidence_val,
-            self.buy_hawkes.intensity(),
-            self.sell_hawkes.intensity(),
+            self.hawkes.intensity(BUY),
+            self.hawkes.intensity(SELL),
+            self.branching_ratio(),
         );
 
         Some((
@@ -465,23 +670,29 @@ impl HawkesFlowEngine {
 
 impl StrategyEngine for HawkesFlowEngine {
     fn decide(&mut self, obs: &Observation) -> StrategyDecision {
-        // Infer flow event from price movement
-        if let Some(event) = self.infer_flow_event(obs) {
-            // Update Hawkes estimators
-            if event.is_buy {
-                self.buy_hawkes.update(event);
-            } else {
-                self.sell_hawkes.update(event);
+        if !self.kernel_selected {
+            self.select_kernel(&obs.market_slug);
+        }
+        self.hawkes.finish_refit();
+
+        if !obs.trades.is_empty() {
+            self.apply_trades(obs);
+        } else if !self.prints_seen {
+            // No trade stream: infer flow event from price movement
+            if let Some(event) = self.infer_flow_event(obs) {
+                // Update Hawkes estimator
+                self.hawkes.update(event);
+
+                // Update VPIN
+                let volume = self.estimate_volume(obs);
+                self.vpin.add_trade(event.is_buy, volume);
             }
-
-            // Update VPIN
-            let volume = self.estimate_volume(obs);
-            self.vpin.add_trade(event.is_buy, volume);
         }
 
-        // Compute HEAI
-        let buy_intensity = self.buy_hawkes.intensity();
-        let sell_intensity = self.sell_hawkes.intensity();
+        // Compute HEAI at the observation time
+        let now = to_millis(obs.ts as f64);
+        let buy_intensity = self.hawkes.intensity_at(BUY, now);
+        let sell_intensity = self.hawkes.intensity_at(SELL, now);
         let heai = compute_heai(buy_intensity, sell_intensity);
         let vpin_val = self.vpin.vpin();
 
@@ -499,10 +710,6 @@ impl StrategyEngine for HawkesFlowEngine {
         // Check exit first if we have a position
         if self.active_position {
             if let Some(exit_reason) = self.check_exit(obs, heai) {
-                self.active_position = false;
-                self.cooldown_counter = self.config.cooldown_observations;
-                self.entry_price = None;
-                self.entry_direction = None;
                 return StrategyDecision::Exit {
                     position_id: String::new(),
                     reason: exit_reason,
@@ -513,9 +720,6 @@ impl StrategyEngine for HawkesFlowEngine {
         // Check entry
         if !self.active_position {
             if let Some((direction, reason)) = self.check_entry(obs, heai, vpin_val) {
-                self.active_position = true;
-                self.entry_price = Some(obs.yes_mid);
-                self.entry_direction = Some(direction);
                 return StrategyDecision::Enter { direction, reason };
             }
         }
@@ -527,9 +731,26 @@ impl StrategyEngine for HawkesFlowEngine {
         StrategyDecision::Hold
     }
 
+    fn on_fill(&mut self, direction: Direction, price: f64) {
+        self.active_position = true;
+        // Exits track the YES price
+        self.entry_price = Some(match direction {
+            Direction::Yes => price,
+            Direction::No => 1.0 - price,
+        });
+        self.entry_direction = Some(direction);
+    }
+
+    fn on_exit(&mut self) {
+        self.active_position = false;
+        self.cooldown_counter = self.config.cooldown_observations;
+        self.entry_price = None;
+        self.entry_direction = None;
+    }
+
     fn reset(&mut self) {
-        self.buy_hawkes.reset();
-        self.sell_hawkes.reset();
+        self.hawkes = HawkesEstimator::new(Self::default_kernel(&self.config), &self.config);
+        self.kernel_selected = false;
         self.vpin.reset();
         self.prev_mid = None;
         self.prev_ts = None;
@@ -538,12 +759,16 @@ impl StrategyEngine for HawkesFlowEngine {
         self.entry_price = None;
         self.entry_direction = None;
         self.heai_history.clear();
+        self.prints_seen = false;
+        self.print_marks = RelativeMarks::default();
+        self.move_marks = RelativeMarks::default();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bot::feed::{BookChangeSide, OutcomeSide, TradePrintEvent};
     use crate::bot::indicators::IndicatorState;
 
     fn make_obs(ts: i64, mid: f64, time_remaining: i64) -> Observation {
@@ -566,6 +791,7 @@ mod tests {
             indicator_1m: IndicatorState::default(),
             fair_value_prob: None,
             qlib_score: None,
+            trades: Vec::new(),
         }
     }
 
@@ -577,6 +803,24 @@ mod tests {
         assert!(matches!(decision, StrategyDecision::Hold));
     }
 
+    #[test]
+    fn position_follows_fills_not_decisions() {
+        let mut engine = HawkesFlowEngine::new();
+        let _ = engine.decide(&make_obs(1000, 0.50, 300));
+        // No fill yet, so a move against a would-be NO entry exits nothing
+        assert!(!matches!(engine.decide(&make_obs(2000, 0.60, 300)), StrategyDecision::Exit { .. }));
+
+        engine.on_fill(Direction::No, 0.50);
+        let decision = engine.decide(&make_obs(3000, 0.60, 300));
+        assert!(matches!(decision, StrategyDecision::Exit { reason: ExitReason::StopLoss { .. }, .. }));
+        // The exit has not executed, so the position stands
+        assert!(engine.active_position);
+
+        engine.on_exit();
+        assert!(!engine.active_position);
+        assert_eq!(engine.cooldown_counter, engine.config.cooldown_observations);
+    }
+
     #[test]
     fn engine_builds_heai_with_directional_flow() {
         let mut engine = HawkesFlowEngine::with_config(HawkesFlowConfig {
@@ -595,8 +839,8 @@ mod tests {
         }
 
         // After sustained buy pressure, HEAI should be positive
-        let buy_intensity = engine.buy_hawkes.intensity();
-        let sell_intensity = engine.sell_hawkes.intensity();
+        let buy_intensity = engine.hawkes.intensity(BUY);
+        let sell_intensity = engine.hawkes.intensity(SELL);
         assert!(
             buy_intensity > sell_intensity,
             "Buy intensity ({}) should exceed sell intensity ({})",
@@ -605,6 +849,106 @@ mod tests {
         );
     }
 
+    fn print(ts: f64, side: OutcomeSide, taker_side: BookChangeSide, size: f64) -> TradePrintEvent {
+        TradePrintEvent {
+            market_id: None,
+            token_id: "token".to_string(),
+            side,
+            ts_exchange: ts,
+            price: 0.5,
+            size,
+            taker_side,
+            source: "test",
+        }
+    }
+
+    #[test]
+    fn trade_prints_drive_intensity_with_a_flat_mid() {
+        let mut engine = HawkesFlowEngine::with_config(HawkesFlowConfig {
+            trade_bucket_volume: 50.0,
+            ..Default::default()
+        });
+        for i in 0..20 {
+            let ts = 1_700_000_000 + i;
+            // Buying YES and selling NO both push YES up
+            let side = if i % 2 == 0 { OutcomeSide::Yes } else { OutcomeSide::No };
+            let taker = if i % 2 == 0 { BookChangeSide::Buy } else { BookChangeSide::Sell };
+            let mut obs = make_obs(ts, 0.50, 300);
+            obs.trades = vec![print(ts as f64 * 1000.0, side, taker, 20.0)];
+            let _ = engine.decide(&obs);
+        }
+
+        assert!(engine.prints_seen);
+        assert!(engine.hawkes.intensity(BUY) > engine.hawkes.intensity(SELL));
+        // 400 one-sided shares fill eight 50-share buckets
+        assert_eq!(engine.vpin.imbalances.len(), 8);
+        assert!((engine.vpin.vpin() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn calibrated_kernel_is_chosen_by_market_and_refit_online() {
+        use crate::bot::research::{SupportedAsset, SupportedDuration};
+        use crate::bot::strategy::hawkes_calibration::ENGINE_MARK_UNIT;
+
+        let kernel = HawkesParams {
+            mu: [0.2, 0.2],
+            alpha: [[0.5, 0.1], [0.1, 0.5]],
+            beta: 1.0,
+        };
+        let mut engine = HawkesFlowEngine::with_config(HawkesFlowConfig {
+            refit_every: 40,
+            min_fit_events: 40,
+            deterministic_refit: true,
+            kernels: vec![CalibratedKernel {
+                asset: SupportedAsset::Btc,
+                duration: SupportedDuration::M5,
+                params: kernel,
+                branching_ratio: 0.6,
+                log_likelihood: 0.0,
+                events: 0,
+                markets: 0,
+                mark_unit: ENGINE_MARK_UNIT,
+            }],
+            ..Default::default()
+        });
+
+        let btc_obs = |ts: i64| Observation {
+            market_slug: "btc-updown-5m-1700000000".to_string(),
+            ..make_obs(ts, 0.5, 300)
+        };
+        let _ = engine.decide(&btc_obs(1_700_000_000));
+        assert_eq!(engine.hawkes.params, kernel);
+
+        // Mixed prints the online fit can estimate
+        for i in 1..=80 {
+            let ts = 1_700_000_000 + i;
+            let taker = if i % 3 == 0 { BookChangeSide::Sell } else { BookChangeSide::Buy };
+            let mut obs = btc_obs(ts);
+            obs.trades = vec![print(ts as f64, OutcomeSide::Yes, taker, 10.0)];
+            let _ = engine.decide(&obs);
+        }
+        assert!(engine.hawkes.fit.is_some());
+        assert!(engine.branching_ratio() < 1.0);
+
+        // A market without a calibrated kernel falls back to the default
+        engine.reset();
+        let _ = engine.decide(&Observation {
+            market_slug: "eth-updown-15m-1700000000".to_string(),
+            ..make_obs(1_700_000_000, 0.5, 300)
+        });
+        assert_eq!(engine.hawkes.params, HawkesFlowEngine::default_kernel(&engine.config));
+    }
+
+    #[test]
+    fn fixed_buckets_split_large_trades() {
+        let mut vpin = VpinEstimator::with_bucket_volume(10, 100.0);
+        vpin.add_trade(true, 150.0);
+        vpin.add_trade(false, 50.0);
+        assert_eq!(vpin.imbalances.len(), 2);
+        // Second bucket holds 50 buy and 50 sell
+        assert!(vpin.imbalances[1].abs() < 1e-9);
+    }
+
     #[test]
     fn heai_computation() {
         let heai = compute_heai(0.8, 0.2);
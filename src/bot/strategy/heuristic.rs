@@ -192,8 +192,6 @@ impl StrategyEngine for HeuristicEngine {
 
         // Check for exit first
         if let Some(reason) = self.check_exit(&obs.indicator_5s) {
-            self.active_position = None;
-            self.entry_price = None;
             return StrategyDecision::Exit {
                 position_id: obs.condition_id.clone(),
                 reason,
@@ -205,11 +203,6 @@ impl StrategyEngine for HeuristicEngine {
             if let Some((direction, reason)) =
                 self.check_entry(&obs.indicator_5s, &obs.indicator_1m, p)
             {
-                self.active_position = Some(match direction {
-                    Direction::Yes => EntrySignal::Long,
-                    Direction::No => EntrySignal::Short,
-                });
-                self.entry_price = Some(p);
                 return StrategyDecision::Enter { direction, reason };
             }
         }
@@ -217,6 +210,20 @@ impl StrategyEngine for HeuristicEngine {
         StrategyDecision::Hold
     }
 
+    fn on_fill(&mut self, direction: Direction, price: f64) {
+        let (signal, yes_price) = match direction {
+            Direction::Yes => (EntrySignal::Long, price),
+            Direction::No => (EntrySignal::Short, 1.0 - price),
+        };
+        self.active_position = Some(signal);
+        self.entry_price = Some(yes_price);
+    }
+
+    fn on_exit(&mut self) {
+        self.active_position = None;
+        self.entry_price = None;
+    }
+
     fn reset(&mut self) {
         self.active_position = None;
         self.entry_price = None;
@@ -271,6 +278,7 @@ mod tests {
             indicator_1m: state.clone(),
             fair_value_prob: None,
             qlib_score: None,
+            trades: Vec::new(),
         }
     }
 
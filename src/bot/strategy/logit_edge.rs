@@ -0,0 +1,251 @@
+//! Logit Edge Strategy Engine
+//!
+//! Fair-value trading on the market's own probability path:
+//! 1. Logit observation from market midpoint
+//! 2. Kalman filter for noise reduction
+//! 3. Jump calibrator (EM) for jump parameter estimation
+//! 4. Horizon classification for parameter adaptation
+//! 5. Fair probability via risk-neutral model
+//! 6. Calibrated edge with spread/book adjustment
+//! 7. Momentum gate (filtered vs raw probability)
+//! 8. Entry/exit decisions
+//!
+//! Unlike `FairValueEngine` it needs no spot feed, so it runs unchanged in
+//! backtests, shadow and live.
+
+use super::{
+    Confidence, Direction, EntryReason, ExitReason, FairValueSignalConfig, Observation, SignalSource,
+    StrategyDecision, StrategyEngine,
+};
+use crate::bot::market_classifier::classify_market;
+use crate::bot::pricing::{
+    prob_to_logit, risk_neutral_drift, sigmoid, CalibratedProb, CalibrationConfig, JumpCalibrator,
+    KalmanFilter, LogitJumpDiffusion, LogitObservation,
+};
+use serde::{Deserialize, Serialize};
+
+/// ~1 second in years
+const DT_ONE_SECOND: f64 = 1.0 / (365.25 * 24.0 * 3600.0);
+
+/// Configuration for the logit edge strategy
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct LogitEdgeConfig {
+    /// Entry filters shared with `FairValueEngine`. `min_edge` is scaled by
+    /// the horizon multiplier and `min_time_remaining` also forces the exit;
+    /// `use_external_spot` has no effect, this engine prices off the book
+    #[serde(flatten)]
+    pub signal: FairValueSignalConfig,
+    /// Round-trip cost charged against the edge when deciding to hold
+    pub trading_cost: f64,
+    /// Spread, book imbalance and bias adjustments to the model probability
+    #[serde(default = "default_calibration")]
+    pub calibration: CalibrationConfig,
+}
+
+fn default_calibration() -> CalibrationConfig {
+    CalibrationConfig { min_prob: 0.01, max_prob: 0.99, ..Default::default() }
+}
+
+impl Default for LogitEdgeConfig {
+    fn default() -> Self {
+        Self {
+            signal: FairValueSignalConfig {
+                min_entry_prob: 0.05,
+                max_entry_prob: 0.95,
+                ..FairValueSignalConfig::default()
+            },
+            trading_cost: 0.025,
+            calibration: default_calibration(),
+        }
+    }
+}
+
+/// Filter state after pricing one observation
+#[derive(Debug, Clone, Copy)]
+struct Priced {
+    fair_prob: f64,
+    calibrated: f64,
+    /// Kalman-filtered minus raw probability
+    velocity: f64,
+    is_jump: bool,
+}
+
+pub struct LogitEdgeEngine {
+    config: LogitEdgeConfig,
+    model: LogitJumpDiffusion,
+    kalman: KalmanFilter,
+    jump_calibrator: JumpCalibrator,
+    /// Side and fill price of the open position, from executor fills
+    position: Option<(Direction, f64)>,
+    /// Pricing of the observation `decide` is about to see
+    priced: Option<Priced>,
+}
+
+impl LogitEdgeEngine {
+    pub fn new(config: LogitEdgeConfig) -> Self {
+        Self {
+            config,
+            model: LogitJumpDiffusion::new(),
+            kalman: KalmanFilter::new(0.0),
+            jump_calibrator: JumpCalibrator::with_defaults(),
+            position: None,
+            priced: None,
+        }
+    }
+
+    /// Update the filters with `obs` and calibrate the model's YES probability
+    fn price(&mut self, obs: &Observation) -> Option<CalibratedProb> {
+        let time_remaining = obs.time_remaining_s;
+        if time_remaining <= 0 {
+            return None;
+        }
+        let spread = (obs.yes_ask - obs.yes_bid).max(0.001);
+
+        // Logit observation, exponential filter and Kalman smoothing
+        let clamped_prob = obs.yes_mid.clamp(0.01, 0.99);
+        let raw_logit = prob_to_logit(clamped_prob);
+        let logit_obs = LogitObservation {
+            timestamp: obs.ts,
+            prob: clamped_prob,
+            logit: raw_logit,
+            spread,
+            volume: 0.0,
+        };
+        let filtered = self.model.update(&logit_obs);
+        self.kalman.set_measurement_noise(spread);
+        let drift = risk_neutral_drift(filtered.logit, filtered.vol, 0.0);
+        self.kalman.predict(DT_ONE_SECOND, drift, filtered.vol);
+        self.kalman.update(raw_logit, spread);
+        let kalman_logit = self.kalman.state();
+        self.jump_calibrator.update(&logit_obs);
+
+        let fair_prob = self.model.fair_prob(time_remaining).expected;
+        let calibrated = self.config.calibration.calibrate(
+            fair_prob,
+            obs.yes_ask,
+            obs.no_ask,
+            obs.yes_ask - obs.yes_bid,
+            obs.book_sum,
+            self.config.signal.min_edge,
+        );
+        let signal = &self.config.signal;
+        self.priced = Some(Priced {
+            fair_prob,
+            calibrated: calibrated.fair_prob_calibrated,
+            // Momentum gate: filtered probability as the fast line, raw as the slow
+            velocity: if signal.use_kalman_filter { sigmoid(kalman_logit) - clamped_prob } else { 0.0 },
+            is_jump: signal.enable_jump_detection
+                && self.jump_calibrator.is_jump(kalman_logit, DT_ONE_SECOND, signal.jump_threshold),
+        });
+        Some(calibrated)
+    }
+}
+
+impl StrategyEngine for LogitEdgeEngine {
+    /// Prices the observation and keeps the filter state for the `decide`
+    /// that follows
+    fn fair_probability(&mut self, obs: &Observation) -> Option<CalibratedProb> {
+        self.price(obs)
+    }
+
+    fn decide(&mut self, obs: &Observation) -> StrategyDecision {
+        let time_remaining = obs.time_remaining_s;
+        // Callers outside the driver have not priced this observation yet
+        if self.priced.is_none() {
+            self.price(obs);
+        }
+        let Some(priced) = self.priced.take() else {
+            return StrategyDecision::Hold;
+        };
+        let Priced { fair_prob, velocity, is_jump, .. } = priced;
+
+        // Horizon-scaled edge against the calibrated probability
+        let signal = self.config.signal;
+        let horizon = classify_market(time_remaining);
+        let edge_multiplier = if signal.enable_horizon_adaptation { horizon.edge_multiplier() } else { 1.0 };
+        let adjusted_fair = obs.fair_value_prob.unwrap_or(priced.calibrated);
+        let edge_yes = (adjusted_fair - obs.yes_ask) * edge_multiplier;
+        let edge_no = ((1.0 - adjusted_fair) - obs.no_ask) * edge_multiplier;
+        let momentum_allows_yes = velocity > -0.002;
+        let momentum_allows_no = velocity < 0.002;
+
+        match self.position {
+            None => {
+                if is_jump || time_remaining <= signal.min_time_remaining {
+                    return StrategyDecision::Hold;
+                }
+                let effective_min_edge = signal.min_edge * edge_multiplier;
+                let tradable = |ask: f64| ask > signal.min_entry_prob && ask < signal.max_entry_prob;
+                let (direction, edge, ask) = if edge_yes > effective_min_edge
+                    && momentum_allows_yes
+                    && tradable(obs.yes_ask)
+                {
+                    (Direction::Yes, edge_yes, obs.yes_ask)
+                } else if edge_no > effective_min_edge && momentum_allows_no && tradable(obs.no_ask) {
+                    (Direction::No, edge_no, obs.no_ask)
+                } else {
+                    return StrategyDecision::Hold;
+                };
+                StrategyDecision::Enter {
+                    direction,
+                    reason: EntryReason {
+                        source: SignalSource::FairValue,
+                        confidence: Confidence::new((edge / signal.max_edge).min(1.0)),
+                        detail: format!(
+                            "{:?} @ {:.4} | Fair: {:.4} | Adj: {:.4} | Edge: {:.4}x{:.1} | Horizon: {}",
+                            direction,
+                            ask,
+                            fair_prob,
+                            adjusted_fair,
+                            edge,
+                            edge_multiplier,
+                            horizon.name()
+                        ),
+                        fair_value_edge: Some(edge),
+                        qlib_score: None,
+                    },
+                }
+            }
+            Some((direction, entry_price)) => {
+                let (price, fair) = match direction {
+                    Direction::Yes => (obs.yes_bid, adjusted_fair),
+                    Direction::No => (obs.no_bid, 1.0 - adjusted_fair),
+                };
+                if price <= 0.0 {
+                    return StrategyDecision::Hold;
+                }
+                let current_edge = fair - price - self.config.trading_cost;
+                let pnl_pct = (price - entry_price) / entry_price.max(0.0001);
+                let reason = if current_edge < 0.0 || (is_jump && current_edge < 0.02) {
+                    ExitReason::FairValueReversal
+                } else if price > 0.95 {
+                    ExitReason::TakeProfit { pnl_pct }
+                } else if price < 0.05 {
+                    ExitReason::StopLoss { pnl_pct }
+                } else if time_remaining < signal.min_time_remaining {
+                    ExitReason::TimeExpiry {
+                        seconds_remaining: time_remaining,
+                    }
+                } else {
+                    return StrategyDecision::Hold;
+                };
+                StrategyDecision::Exit {
+                    position_id: obs.condition_id.clone(),
+                    reason,
+                }
+            }
+        }
+    }
+
+    fn on_fill(&mut self, direction: Direction, price: f64) {
+        self.position = Some((direction, price));
+    }
+
+    fn on_exit(&mut self) {
+        self.position = None;
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+}
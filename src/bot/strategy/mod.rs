@@ -6,14 +6,31 @@ mod heuristic;
 mod risk;
 mod types;
 
+pub mod constraint_engine;
 pub mod fair_value;
+pub mod graph_builder;
+pub mod hawkes_calibration;
+pub mod hawkes_flow;
+pub mod logit_edge;
+pub mod probability_engine;
+pub mod registry;
+pub mod scalper;
+pub mod temporal_arbitrage;
 
 pub use fair_value::{FairValueEngine, FairValueSignalConfig};
+pub use graph_builder::TemporalGraph;
+pub use hawkes_flow::{HawkesFlowConfig, HawkesFlowEngine};
 pub use heuristic::HeuristicEngine;
+pub use logit_edge::{LogitEdgeConfig, LogitEdgeEngine};
+pub use registry::{StrategyArgs, StrategyConfig, StrategyKind};
+pub use scalper::{BandConfig, ScalperEngine};
 pub use risk::RiskGate;
 pub use types::*;
 
+use crate::bot::feed::TradePrintEvent;
 use crate::bot::indicators::IndicatorState;
+use crate::bot::pricing::CalibratedProb;
+use crate::bot::research::FusionConfig;
 
 /// Observation snapshot for strategy decision
 #[derive(Debug, Clone)]
@@ -31,8 +48,11 @@ pub struct Observation {
     pub time_remaining_s: i64,
     pub indicator_5s: IndicatorState,
     pub indicator_1m: IndicatorState,
+    /// Calibrated YES probability from the engine's own model, in [0, 1]
     pub fair_value_prob: Option<f64>,
     pub qlib_score: Option<f64>,
+    /// Trade prints since the previous observation; empty without a trade stream
+    pub trades: Vec<TradePrintEvent>,
 }
 
 impl Default for Observation {
@@ -53,6 +73,7 @@ impl Default for Observation {
             indicator_1m: IndicatorState::default(),
             fair_value_prob: None,
             qlib_score: None,
+            trades: Vec::new(),
         }
     }
 }
@@ -62,6 +83,23 @@ pub trait StrategyEngine {
     /// Make a trading decision based on the current observation
     fn decide(&mut self, obs: &Observation) -> StrategyDecision;
 
+    /// Price `obs` with the engine's fair value model, for engines that have
+    /// one. Called before `decide`, which sees the calibrated YES
+    /// probability as `Observation::fair_value_prob`.
+    fn fair_probability(&mut self, _obs: &Observation) -> Option<CalibratedProb> {
+        None
+    }
+
+    /// The executor filled the last `Enter` at `price`, the price of the
+    /// token bought. Engines track their position from fills, not decisions.
+    fn on_fill(&mut self, _direction: Direction, _price: f64) {}
+
+    /// The last `Enter` was blocked or found no fill
+    fn on_reject(&mut self) {}
+
+    /// The executor closed the position
+    fn on_exit(&mut self) {}
+
     /// Reset internal state (e.g., when switching markets)
     fn reset(&mut self);
 }
@@ -70,7 +108,7 @@ pub trait StrategyEngine {
 pub struct FusedEngine {
     heuristic: HeuristicEngine,
     mode: FusionMode,
-    confidence_threshold: f64,
+    config: FusionConfig,
 }
 
 impl FusedEngine {
@@ -78,12 +116,12 @@ impl FusedEngine {
         Self {
             heuristic: HeuristicEngine::new(),
             mode,
-            confidence_threshold: 0.5,
+            config: FusionConfig { score_threshold: 0.5, ..FusionConfig::default() },
         }
     }
 
-    pub fn with_confidence_threshold(mut self, threshold: f64) -> Self {
-        self.confidence_threshold = threshold;
+    pub fn with_config(mut self, config: FusionConfig) -> Self {
+        self.config = config;
         self
     }
 }
@@ -95,7 +133,7 @@ impl StrategyEngine for FusedEngine {
             FusionMode::QlibOnly => {
                 // Qlib-only mode: use scores if available
                 match obs.qlib_score {
-                    Some(score) if score > self.confidence_threshold => StrategyDecision::Enter {
+                    Some(score) if score > self.config.score_threshold => StrategyDecision::Enter {
                         direction: Direction::Yes,
                         reason: EntryReason {
                             source: SignalSource::QlibScore,
@@ -105,7 +143,7 @@ impl StrategyEngine for FusedEngine {
                             qlib_score: Some(score),
                         },
                     },
-                    Some(score) if score < -self.confidence_threshold => StrategyDecision::Enter {
+                    Some(score) if score < -self.config.score_threshold => StrategyDecision::Enter {
                         direction: Direction::No,
                         reason: EntryReason {
                             source: SignalSource::QlibScore,
@@ -123,16 +161,22 @@ impl StrategyEngine for FusedEngine {
                 let heuristic_decision = self.heuristic.decide(obs);
 
                 match (&heuristic_decision, obs.qlib_score) {
+                    (StrategyDecision::Enter { reason, .. }, _)
+                        if reason.confidence.value() <= self.config.heuristic_threshold =>
+                    {
+                        StrategyDecision::Hold
+                    }
                     (StrategyDecision::Enter { direction, reason }, Some(score))
-                        if score.abs() > self.confidence_threshold =>
+                        if score.abs() > self.config.score_threshold =>
                     {
-                        // Both agree - boost confidence
+                        // Both agree - blend confidence
+                        let weight = self.config.qlib_weight.clamp(0.0, 1.0);
                         StrategyDecision::Enter {
                             direction: direction.clone(),
                             reason: EntryReason {
                                 source: SignalSource::Fused,
                                 confidence: Confidence::new(
-                                    (reason.confidence.value() + score.abs()) / 2.0,
+                                    reason.confidence.value() * (1.0 - weight) + score.abs() * weight,
                                 ),
                                 detail: format!("Fused: heuristic + qlib ({:.3})", score),
                                 fair_value_edge: reason.fair_value_edge,
@@ -144,6 +188,9 @@ impl StrategyEngine for FusedEngine {
                         // Qlib doesn't agree strongly enough - block
                         StrategyDecision::Hold
                     }
+                    (StrategyDecision::Enter { .. }, None) if !self.config.fallback_on_stale => {
+                        StrategyDecision::Hold
+                    }
                     (StrategyDecision::Enter { .. }, None) => {
                         // No Qlib score available - fall back to heuristic
                         heuristic_decision
@@ -154,6 +201,14 @@ impl StrategyEngine for FusedEngine {
         }
     }
 
+    fn on_fill(&mut self, direction: Direction, price: f64) {
+        self.heuristic.on_fill(direction, price);
+    }
+
+    fn on_exit(&mut self) {
+        self.heuristic.on_exit();
+    }
+
     fn reset(&mut self) {
         self.heuristic.reset();
     }
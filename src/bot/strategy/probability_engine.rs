@@ -9,10 +9,11 @@
 //! - Implied parent probability from partial observations
 
 use serde::{Deserialize, Serialize};
+use statrs::function::erf::erf;
 use std::fmt;
 
 /// Violation type for constraint detection
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ViolationType {
     /// Chained conditional arbitrage
     ChainedConditional,
@@ -40,6 +41,8 @@ pub enum ArbitrageAction {
         condition_id: String,
         direction: super::Direction,
         edge: f64,
+        /// Model probability that the market resolves YES
+        fair_prob: f64,
         reason: ArbitrageActionReason,
     },
     /// Hold - no action
@@ -54,6 +57,16 @@ pub enum ArbitrageActionReason {
     PriceConsistency(f64),
 }
 
+impl ArbitrageActionReason {
+    pub fn violation_type(&self) -> ViolationType {
+        match self {
+            ArbitrageActionReason::ChainedConditional(_) => ViolationType::ChainedConditional,
+            ArbitrageActionReason::LatePhaseAnomaly(_) => ViolationType::LatePhaseAnomaly,
+            ArbitrageActionReason::PriceConsistency(_) => ViolationType::PriceConsistency,
+        }
+    }
+}
+
 impl std::fmt::Display for ArbitrageActionReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -109,16 +122,15 @@ impl ProbabilityEngine {
     ) -> f64 {
         let distance = current_price - strike_price;
 
-        // If already above strike, high probability with small reversal risk
-        if distance >= 0.0 {
-            let reversal_prob = 0.02 * (time_remaining_sec / 3600.0).min(1.0);
-            return 1.0 - reversal_prob;
-        }
-
         // Calculate standard deviation
         let std = vol_per_sec * time_remaining_sec.sqrt();
         if std < 1e-6 {
-            return 0.5;
+            // Already resolved: the current price decides
+            return match distance.partial_cmp(&0.0) {
+                Some(std::cmp::Ordering::Greater) => 1.0,
+                Some(std::cmp::Ordering::Less) => 0.0,
+                _ => 0.5,
+            };
         }
 
         // Apply drift adjustment
@@ -190,15 +202,15 @@ impl ProbabilityEngine {
 
         let distance = live_btc_price - original_strike;
 
-        // If currently above original strike, high confidence
-        if distance >= 0.0 {
-            return 0.98; // High confidence, but small reversal risk
-        }
-
         // Calculate probability using Black-Scholes
         let std = vol_per_sec * remaining_time_sec.sqrt();
         if std < 1e-6 {
-            return 0.5;
+            // Already resolved: the current price decides
+            return match distance.partial_cmp(&0.0) {
+                Some(std::cmp::Ordering::Greater) => 1.0,
+                Some(std::cmp::Ordering::Less) => 0.0,
+                _ => 0.5,
+            };
         }
 
         let drift_adjustment = drift * remaining_time_sec;
@@ -397,42 +409,16 @@ impl ProbabilityEngine {
 
 /// Standard normal CDF (Cumulative Distribution Function)
 ///
-/// Uses the Abramowitz and Stegun approximation (7.1.26)
-/// with maximum error < 7.5e-8
-///
 /// # Formula
 /// ```text
-/// Φ(z) = 1 - φ(z) * (a1*t + a2*t^2 + a3*t^3 + a4*t^4 + a5*t^5)
-///
-/// where:
-/// - t = 1 / (1 + p*|z|)
-/// - p = 0.2316419
-/// - a1 = 0.319381530, a2 = -0.356563782, a3 = 1.781477937
-/// - a4 = -1.821255978, a5 = 1.330274429
+/// Φ(z) = ½ * (1 + erf(z / √2))
 /// ```
 fn norm_cdf(z: f64) -> f64 {
-    const A1: f64 = 0.254829592;
-    const A2: f64 = -0.284496736;
-    const A3: f64 = 1.421413741;
-    const A4: f64 = -1.453152027;
-    const A5: f64 = 1.061405429;
-    const P: f64 = 0.3275911;
-
-    let abs_z = z.abs();
-
-    // Handle extreme values
-    if abs_z > 37.0 {
-        return if z > 0.0 { 1.0 } else { 0.0 };
-    }
-
-    let t = 1.0 / (1.0 + P * abs_z);
-    let y =
-        1.0 - (((((A5 * t + A4) * t) + A3) * t + A2) * t + A1) * t * (-0.5 * abs_z * abs_z).exp();
-
-    if z > 0.0 {
-        y
+    let e = erf(z.abs() / std::f64::consts::SQRT_2);
+    if z >= 0.0 {
+        0.5 * (1.0 + e)
     } else {
-        1.0 - y
+        0.5 * (1.0 - e)
     }
 }
 
@@ -449,28 +435,33 @@ fn norm_pdf(z: f64) -> f64 {
 
 /// Inverse normal CDF (quantile function)
 ///
-/// Uses the Beasley-Springer-Moro approximation
+/// Acklam's rational approximation (relative error < 1.15e-9), polished
+/// with one Halley step against `norm_cdf`
 pub fn norm_inv(p: f64) -> f64 {
     debug_assert!(p > 0.0 && p < 1.0, "p must be in (0,1), got {}", p);
 
-    // Coefficients for rational approximation
-    const A: [f64; 4] = [
+    const A: [f64; 6] = [
         -3.969683028665376e+01,
         2.209460984245205e+02,
         -2.759285104469687e+02,
         1.383577518672690e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
     ];
-    const B: [f64; 4] = [
+    const B: [f64; 5] = [
         -5.447609879822406e+01,
         1.615858368580409e+02,
         -1.556989798598866e+02,
         6.680131188771972e+01,
+        -1.328068155288572e+01,
     ];
-    const C: [f64; 4] = [
+    const C: [f64; 6] = [
         -7.784894002430293e-03,
         -3.223964580411365e-01,
         -2.400758277161838e+00,
         -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
     ];
     const D: [f64; 4] = [
         7.784695709041462e-03,
@@ -478,31 +469,32 @@ pub fn norm_inv(p: f64) -> f64 {
         2.445134137142996e+00,
         3.754408661907416e+00,
     ];
+    const P_LOW: f64 = 0.02425;
 
     let p = p.clamp(1e-10, 1.0 - 1e-10);
-    let q = p - 0.5;
 
-    if q.abs() <= 0.425 {
-        // Central region
-        let r = 0.180625 - q * q;
-        let num = ((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r) * q;
-        let den = ((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r) + 1.0;
-        num / den
-    } else {
-        let r = if q < 0.0 { p } else { 1.0 - p };
-
-        let r = (-r.ln()).sqrt();
-        let num = ((((C[0] * r + C[1]) * r + C[2]) * r + C[3]) * r + C[3]) * r;
-        let den = (((D[0] * r + D[1]) * r + D[2]) * r + D[3]) * r + 1.0;
-
-        let result = num / den;
+    let tail = |r: f64| {
+        let q = (-2.0 * r.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
 
-        if q < 0.0 {
-            -result
-        } else {
-            result
-        }
-    }
+    let x = if p < P_LOW {
+        tail(p)
+    } else if p > 1.0 - P_LOW {
+        -tail(1.0 - p)
+    } else {
+        // Central region
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+
+    // Halley refinement
+    let e = norm_cdf(x) - p;
+    let u = e / norm_pdf(x);
+    x - u / (1.0 + x * u / 2.0)
 }
 
 #[cfg(test)]
@@ -727,6 +719,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_norm_cdf_and_inv_reference_values() {
+        // Reference values from scipy.stats.norm
+        for (z, p) in [
+            (1.0, 0.8413447460685429),
+            (1.96, 0.9750021048517796),
+            (-2.5, 0.006209665325776159),
+            (3.0, 0.9986501019683699),
+        ] {
+            assert!((norm_cdf(z) - p).abs() < 1e-10, "cdf({z})");
+        }
+
+        for (p, z) in [
+            (0.975, 1.9599639845400536),
+            (0.01, -2.3263478740408408),
+            (0.999, 3.090232306167813),
+            (1e-6, -4.753424308822899),
+            (0.3, -0.5244005127080407),
+        ] {
+            assert!((norm_inv(p) - z).abs() < 1e-10, "inv({p})");
+        }
+    }
+
     #[test]
     fn test_violation_type_display() {
         assert_eq!(
@@ -0,0 +1,256 @@
+//! Strategy Registry
+//!
+//! Every strategy the bot can run, registered by name behind `StrategyEngine`
+//! with a typed config. `--strategy` selects from here the same way in
+//! backtests, shadow mode and live trading.
+
+use super::hawkes_calibration::HawkesCalibration;
+use crate::bot::research::FusionConfig;
+use anyhow::Result;
+use super::{
+    BandConfig, FusedEngine, FusionMode, HawkesFlowConfig, HawkesFlowEngine, LogitEdgeConfig,
+    LogitEdgeEngine, ScalperEngine, StrategyEngine,
+};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    /// Scalper: EMA crossover + RSI + Bollinger Bands
+    Scalper,
+    /// Late window: high-prob sniping in final minutes
+    LateWindow,
+    /// Fair value: Logit jump-diffusion model for edge trading
+    FairValue,
+    /// Hawkes flow: order flow self/cross-excitation dynamics with VPIN toxicity gate
+    HawkesFlow,
+    /// Fused: heuristic indicators combined with model scores
+    Fused,
+}
+
+impl StrategyKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scalper => "scalper",
+            Self::LateWindow => "late-window",
+            Self::FairValue => "fair-value",
+            Self::HawkesFlow => "hawkes-flow",
+            Self::Fused => "fused",
+        }
+    }
+
+    /// Indicator strategies decide on 5s candle closes, the rest on every tick
+    pub fn candle_driven(self) -> bool {
+        matches!(self, Self::Scalper | Self::LateWindow | Self::Fused)
+    }
+}
+
+impl std::fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Configuration for the fused heuristic + model-score strategy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FusedConfig {
+    pub mode: FusionMode,
+    /// Thresholds and weighting; `score_threshold` is the score magnitude
+    /// needed to enter (qlib) or to confirm an entry (fused)
+    #[serde(default = "default_fusion")]
+    pub fusion: FusionConfig,
+}
+
+fn default_fusion() -> FusionConfig {
+    FusionConfig { score_threshold: 0.5, ..FusionConfig::default() }
+}
+
+impl Default for FusedConfig {
+    fn default() -> Self {
+        Self {
+            mode: FusionMode::Fused,
+            fusion: default_fusion(),
+        }
+    }
+}
+
+/// A registered strategy together with its typed parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum StrategyConfig {
+    Scalper(BandConfig),
+    LateWindow(BandConfig),
+    FairValue(LogitEdgeConfig),
+    HawkesFlow(HawkesFlowConfig),
+    Fused(FusedConfig),
+}
+
+impl StrategyConfig {
+    pub fn default_for(kind: StrategyKind) -> Self {
+        match kind {
+            StrategyKind::Scalper => Self::Scalper(BandConfig::scalper()),
+            StrategyKind::LateWindow => Self::LateWindow(BandConfig::late_window()),
+            StrategyKind::FairValue => Self::FairValue(LogitEdgeConfig::default()),
+            StrategyKind::HawkesFlow => Self::HawkesFlow(HawkesFlowConfig::default()),
+            StrategyKind::Fused => Self::Fused(FusedConfig::default()),
+        }
+    }
+
+    pub fn kind(&self) -> StrategyKind {
+        match self {
+            Self::Scalper(_) => StrategyKind::Scalper,
+            Self::LateWindow(_) => StrategyKind::LateWindow,
+            Self::FairValue(_) => StrategyKind::FairValue,
+            Self::HawkesFlow(_) => StrategyKind::HawkesFlow,
+            Self::Fused(_) => StrategyKind::Fused,
+        }
+    }
+
+    /// Fresh engine for this config
+    pub fn build(&self) -> Box<dyn StrategyEngine + Send> {
+        match self {
+            Self::Scalper(band) | Self::LateWindow(band) => Box::new(ScalperEngine::new(*band)),
+            Self::FairValue(config) => Box::new(LogitEdgeEngine::new(*config)),
+            Self::HawkesFlow(config) => Box::new(HawkesFlowEngine::with_config(config.clone())),
+            Self::Fused(config) => Box::new(
+                FusedEngine::new(config.mode).with_config(config.fusion),
+            ),
+        }
+    }
+
+    /// Variant for backtests and replays: engines that refit in the
+    /// background wait for each fit, so reruns make the same decisions
+    pub fn deterministic(mut self) -> Self {
+        if let Self::HawkesFlow(config) = &mut self {
+            config.deterministic_refit = true;
+        }
+        self
+    }
+
+    /// One-line parameter summary for run headers
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Scalper(band) | Self::LateWindow(band) => {
+                format!("entry band {:.2} - {:.2}", band.band_low, band.band_high)
+            }
+            Self::FairValue(config) => format!("min edge {:.3}", config.signal.min_edge),
+            Self::HawkesFlow(config) => {
+                format!("min HEAI {:.2}, VPIN > {:.2}", config.min_heai, config.vpin_threshold)
+            }
+            Self::Fused(config) => {
+                format!("{:?}, score threshold {:.2}", config.mode, config.fusion.score_threshold)
+            }
+        }
+    }
+}
+
+/// `--strategy` and its overrides, shared by every command that runs a strategy
+#[derive(Args, Clone, Debug)]
+pub struct StrategyArgs {
+    /// Strategy to run
+    #[arg(long, value_enum, default_value_t = StrategyKind::Scalper)]
+    pub strategy: StrategyKind,
+
+    /// Entry band low (scalper, late-window)
+    #[arg(long)]
+    pub band_low: Option<f64>,
+
+    /// Entry band high (scalper, late-window)
+    #[arg(long)]
+    pub band_high: Option<f64>,
+
+    /// Minimum edge (fair-value)
+    #[arg(long)]
+    pub min_edge: Option<f64>,
+
+    /// How indicators and model scores are combined (fused)
+    #[arg(long, value_enum)]
+    pub fusion_mode: Option<FusionMode>,
+
+    /// Model score magnitude needed to act (fused)
+    #[arg(long)]
+    pub score_threshold: Option<f64>,
+
+    /// Calibrated kernel file from `bot calibrate-hawkes` (hawkes-flow)
+    #[arg(long)]
+    pub hawkes_params: Option<String>,
+}
+
+impl StrategyArgs {
+    /// The selected strategy's default config with any overrides applied
+    pub fn config(&self) -> Result<StrategyConfig> {
+        let mut config = StrategyConfig::default_for(self.strategy);
+        match &mut config {
+            StrategyConfig::Scalper(band) | StrategyConfig::LateWindow(band) => {
+                band.band_low = self.band_low.unwrap_or(band.band_low);
+                band.band_high = self.band_high.unwrap_or(band.band_high);
+            }
+            StrategyConfig::FairValue(edge) => {
+                edge.signal.min_edge = self.min_edge.unwrap_or(edge.signal.min_edge);
+            }
+            StrategyConfig::HawkesFlow(hawkes) => {
+                if let Some(path) = &self.hawkes_params {
+                    hawkes.kernels = HawkesCalibration::load(path)?.kernels;
+                }
+            }
+            StrategyConfig::Fused(fused) => {
+                fused.mode = self.fusion_mode.unwrap_or(fused.mode);
+                fused.fusion.score_threshold = self.score_threshold.unwrap_or(fused.fusion.score_threshold);
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::strategy::{Observation, StrategyDecision};
+
+    #[test]
+    fn every_kind_builds_from_its_default_config() {
+        for kind in StrategyKind::value_variants() {
+            let config = StrategyConfig::default_for(*kind);
+            assert_eq!(config.kind(), *kind);
+            let mut engine = config.build();
+            assert!(matches!(engine.decide(&Observation::default()), StrategyDecision::Hold));
+        }
+    }
+
+    #[test]
+    fn overrides_apply_only_to_the_selected_strategy() {
+        let args = StrategyArgs {
+            strategy: StrategyKind::LateWindow,
+            band_low: Some(0.9),
+            band_high: None,
+            min_edge: Some(0.2),
+            fusion_mode: None,
+            score_threshold: None,
+            hawkes_params: None,
+        };
+        match args.config().unwrap() {
+            StrategyConfig::LateWindow(band) => {
+                assert_eq!(band.band_low, 0.9);
+                assert_eq!(band.band_high, 0.98);
+            }
+            other => panic!("unexpected config {other:?}"),
+        }
+    }
+
+    #[test]
+    fn signal_calibration_and_fusion_fields_serialize_as_searchable_paths() {
+        let fair_value = serde_json::to_value(StrategyConfig::default_for(StrategyKind::FairValue)).unwrap();
+        assert_eq!(fair_value["min_edge"], 0.05);
+        assert!(fair_value["max_entry_prob"].is_number());
+        assert!(fair_value["calibration"]["spread_adjustment"].is_number());
+
+        let mut fused = serde_json::to_value(StrategyConfig::default_for(StrategyKind::Fused)).unwrap();
+        assert_eq!(fused["fusion"]["qlib_weight"], 0.5);
+        fused["fusion"]["qlib_weight"] = serde_json::json!(0.8);
+        match serde_json::from_value::<StrategyConfig>(fused).unwrap() {
+            StrategyConfig::Fused(config) => assert_eq!(config.fusion.qlib_weight, 0.8),
+            other => panic!("unexpected config {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Scalper Strategy Engine
+//!
+//! The candle-close indicator scalper from signal.rs plus the book
+//! inefficiency fallback, behind `StrategyEngine`. Also runs late-window
+//! sniping with a high-probability entry band.
+
+use super::{
+    Confidence, Direction, EntryReason, ExitReason, Observation, SignalSource, StrategyDecision,
+    StrategyEngine,
+};
+use crate::bot::signal::{EntrySignal, ExitSignal, SignalEngine};
+use serde::{Deserialize, Serialize};
+
+/// Entry band and pre-trade filters for the scalper
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct BandConfig {
+    /// Only enter when the YES midpoint is inside [band_low, band_high]
+    pub band_low: f64,
+    pub band_high: f64,
+    /// Skip ticks with a wider YES spread
+    pub max_spread: f64,
+    /// Stop trading this many seconds before expiry
+    pub min_time_remaining: i64,
+    /// Enter on a book sum this far from 1.0 when indicators are silent
+    pub book_inefficiency: f64,
+}
+
+impl BandConfig {
+    pub fn scalper() -> Self {
+        Self {
+            band_low: 0.35,
+            band_high: 0.65,
+            max_spread: 0.08,
+            min_time_remaining: 45,
+            book_inefficiency: 0.03,
+        }
+    }
+
+    pub fn late_window() -> Self {
+        Self {
+            band_low: 0.85,
+            band_high: 0.98,
+            ..Self::scalper()
+        }
+    }
+}
+
+impl Default for BandConfig {
+    fn default() -> Self {
+        Self::scalper()
+    }
+}
+
+/// Indicator scalper driven on 5s candle closes
+pub struct ScalperEngine {
+    config: BandConfig,
+    signals: SignalEngine,
+}
+
+impl ScalperEngine {
+    pub fn new(config: BandConfig) -> Self {
+        Self {
+            config,
+            signals: SignalEngine::new_with_band(config.band_low, config.band_high),
+        }
+    }
+
+    fn book_signal(&self, obs: &Observation) -> Option<Direction> {
+        if obs.book_sum > 1.0 + self.config.book_inefficiency {
+            Some(Direction::No) // YES overpriced
+        } else if obs.book_sum < 1.0 - self.config.book_inefficiency {
+            Some(Direction::Yes) // NO overpriced
+        } else {
+            None
+        }
+    }
+}
+
+impl StrategyEngine for ScalperEngine {
+    fn decide(&mut self, obs: &Observation) -> StrategyDecision {
+        let p = obs.yes_mid;
+        if obs.yes_ask - obs.yes_bid > self.config.max_spread
+            || obs.time_remaining_s < self.config.min_time_remaining
+            || !(0.08..=0.92).contains(&p)
+        {
+            return StrategyDecision::Hold;
+        }
+
+        let signal = self.signals.update(&obs.indicator_5s, &obs.indicator_1m, p);
+        if signal.exit == ExitSignal::FullExit {
+            return StrategyDecision::Exit {
+                position_id: obs.condition_id.clone(),
+                reason: ExitReason::MomentumReversal,
+            };
+        }
+
+        let slope = obs.indicator_5s.momentum_slope.unwrap_or(0.0);
+        let indicator = match signal.entry {
+            EntrySignal::Long => Some(Direction::Yes),
+            EntrySignal::Short => Some(Direction::No),
+            EntrySignal::None => None,
+        };
+        let (direction, source, confidence, detail) = match (indicator, self.book_signal(obs)) {
+            (Some(direction), _) => (
+                direction,
+                SignalSource::Indicators,
+                Confidence::new(0.6 + slope.abs() * 10.0),
+                format!(
+                    "EMA/RSI/BB entry, slope={:.4}, RSI={:.1}",
+                    slope,
+                    obs.indicator_5s.rsi14.unwrap_or(50.0)
+                ),
+            ),
+            (None, Some(direction)) => (
+                direction,
+                SignalSource::BookInefficiency,
+                Confidence::MEDIUM,
+                format!("Book sum {:.4}", obs.book_sum),
+            ),
+            (None, None) => return StrategyDecision::Hold,
+        };
+
+        StrategyDecision::Enter {
+            direction,
+            reason: EntryReason {
+                source,
+                confidence,
+                detail,
+                fair_value_edge: None,
+                qlib_score: None,
+            },
+        }
+    }
+
+    fn reset(&mut self) {
+        self.signals.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn book_inefficiency_enters_without_indicators() {
+        let mut engine = ScalperEngine::new(BandConfig::scalper());
+        let obs = Observation {
+            yes_bid: 0.47,
+            yes_ask: 0.49,
+            no_ask: 0.45,
+            book_sum: 0.94,
+            ..Observation::default()
+        };
+        match engine.decide(&obs) {
+            StrategyDecision::Enter { direction, reason } => {
+                assert_eq!(direction, Direction::Yes);
+                assert_eq!(reason.source, SignalSource::BookInefficiency);
+            }
+            other => panic!("expected entry, got {other:?}"),
+        }
+
+        // Too close to expiry
+        let late = Observation { time_remaining_s: 30, ..obs };
+        assert!(matches!(engine.decide(&late), StrategyDecision::Hold));
+    }
+}
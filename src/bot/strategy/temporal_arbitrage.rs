@@ -10,7 +10,7 @@
 use crate::bot::strategy::constraint_engine::ConstraintEngine;
 use crate::bot::strategy::graph_builder::TemporalGraphBuilder;
 use crate::bot::strategy::probability_engine::{
-    ArbitrageAction, ArbitrageActionReason, ProbabilityEngine, ViolationType,
+    ArbitrageAction, ArbitrageActionReason, ViolationType,
 };
 use crate::bot::strategy::{
     Confidence, Direction, EntryReason, Observation, SignalSource, StrategyDecision,
@@ -18,7 +18,6 @@ use crate::bot::strategy::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::time::{Duration, SystemTime};
 
 /// Timeframe enumeration for temporal markets
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -303,12 +302,10 @@ impl VolatilityEstimator {
 
         // Calculate EWMA volatility
         let mut ewma_var = 0.0;
-        let mut weight = 1.0;
 
         for i in 1..self.recent_returns.len() {
             let ret = self.recent_returns[i] - self.recent_returns[i - 1];
             ewma_var = self.lambda * ewma_var + (1.0 - self.lambda) * ret * ret;
-            weight *= self.lambda;
         }
 
         (ewma_var.sqrt() * 100.0).max(self.base_vol_5m * 0.5)
@@ -316,9 +313,8 @@ impl VolatilityEstimator {
 
     /// Get volatility for a specific timeframe
     pub fn vol_for_timeframe(&self, timeframe: Timeframe) -> f64 {
-        let vol_per_sec = self.vol_per_second();
         let duration = timeframe.duration_secs() as f64;
-        vol_per_sec * duration.sqrt()
+        self.base_vol_5m * (duration / 300.0).sqrt()
     }
 }
 
@@ -600,6 +596,7 @@ impl StrategyEngine for TemporalArbitrageEngine {
                 direction,
                 edge,
                 reason,
+                ..
             } => {
                 // Check if we can enter
                 if self.positions.len() >= self.config.max_concurrent_positions {
@@ -626,17 +623,7 @@ impl StrategyEngine for TemporalArbitrageEngine {
                                 entry_ts: now,
                                 size_usd: 10.0, // Default size
                                 expected_edge: edge,
-                                violation_type: match reason {
-                                    ArbitrageActionReason::ChainedConditional(_) => {
-                                        ViolationType::ChainedConditional
-                                    }
-                                    ArbitrageActionReason::LatePhaseAnomaly(_) => {
-                                        ViolationType::LatePhaseAnomaly
-                                    }
-                                    ArbitrageActionReason::PriceConsistency(_) => {
-                                        ViolationType::PriceConsistency
-                                    }
-                                },
+                                violation_type: reason.violation_type(),
                             },
                         );
 
@@ -673,21 +660,21 @@ impl StrategyEngine for TemporalArbitrageEngine {
 
 // Helper for ArbitrageAction
 impl ArbitrageAction {
-    fn condition_id(&self) -> String {
+    pub fn condition_id(&self) -> String {
         match self {
             ArbitrageAction::EnterSingle { condition_id, .. } => condition_id.clone(),
             ArbitrageAction::Hold => String::new(),
         }
     }
 
-    fn edge(&self) -> f64 {
+    pub fn edge(&self) -> f64 {
         match self {
             ArbitrageAction::EnterSingle { edge, .. } => *edge,
             ArbitrageAction::Hold => 0.0,
         }
     }
 
-    fn confidence(&self) -> f64 {
+    pub fn confidence(&self) -> f64 {
         match self {
             ArbitrageAction::EnterSingle { reason, .. } => match reason {
                 ArbitrageActionReason::ChainedConditional(c)
@@ -802,6 +789,19 @@ mod tests {
         assert!((vol_15m - 250.0 * 3.0_f64.sqrt()).abs() < 1e-5);
     }
 
+    #[test]
+    fn test_vol_for_timeframe_scales_with_sqrt_duration() {
+        let estimator = VolatilityEstimator::new(250.0, 0.94);
+
+        for (timeframe, vol) in [
+            (Timeframe::M5, 250.0),
+            (Timeframe::M15, 433.0127018922193),
+            (Timeframe::H1, 866.0254037844386),
+        ] {
+            assert!((estimator.vol_for_timeframe(timeframe) - vol).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_temporal_position_unrealized_pnl() {
         let position = TemporalPosition {
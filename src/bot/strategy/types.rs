@@ -2,6 +2,7 @@
 //!
 //! Core types for trading decisions.
 
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 /// Direction for YES/NO markets
@@ -22,6 +23,8 @@ pub enum SignalSource {
     FairValue,
     /// Qlib model score above threshold
     QlibScore,
+    /// Hawkes order-flow excitation asymmetry
+    HawkesFlow,
     /// Fused combination of multiple sources
     Fused,
 }
@@ -113,11 +116,16 @@ impl Default for StrategyDecision {
 }
 
 /// Fusion mode for combining signals
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
 pub enum FusionMode {
+    /// Indicators only
     #[default]
+    #[value(name = "heuristic")]
     HeuristicOnly,
+    /// Model scores only
+    #[value(name = "qlib")]
     QlibOnly,
+    /// Indicator entries confirmed by model scores
     Fused,
 }
 
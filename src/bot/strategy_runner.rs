@@ -1,151 +1,329 @@
 use crate::bot::candles::CandleEngine;
-use crate::bot::feed::DualSnapshot;
+use crate::bot::feed::{BookDepth, DualSnapshot, TradePrintEvent};
 use crate::bot::indicators::{IndicatorEngine, IndicatorState};
 use crate::bot::logging::{EngineEvent, EngineEventLoggers};
+use crate::bot::pricing::FairValuePrediction;
+use crate::bot::research::{BookTick, CryptoBinaryMarketSpec, FeatureBuilder, FeatureRow, ScoreRow, ScoreSource};
 use crate::bot::risk::GatekeeperState;
-use crate::bot::risk::{best_ask_price, decimal_to_f64, midpoint_price};
-use crate::bot::shadow::{handle_shadow_signals, ShadowPosition, ShadowStepResult};
-use crate::bot::signal::{EntrySignal, SignalEngine};
+use crate::bot::risk::{best_ask_price, best_bid_price, decimal_to_f64, midpoint_price};
+use crate::bot::shadow::{handle_shadow_signals, ShadowFills, ShadowPosition, ShadowStepResult, TokenSide};
+use crate::bot::signal::{EntrySignal, ExitSignal, SignalState};
+use crate::bot::sizing::{ModelProbability, PositionSizer, SizingQuote};
+use crate::bot::strategy::{
+    Direction, Observation, StrategyConfig, StrategyDecision, StrategyEngine, StrategyKind,
+};
 
-#[allow(clippy::too_many_arguments)]
-pub fn run_shadow_strategy_step(
-    dual_snapshot: &DualSnapshot,
-    market_label: &str,
-    market_slug: &str,
-    market_start_ts: i64,
-    market_end_ts: i64,
-    epoch_seconds: u64,
-    position_size_usd: f64,
-    candle_engine: &mut CandleEngine,
-    ind_1m: &mut IndicatorEngine,
-    ind_5s: &mut IndicatorEngine,
-    signal_engine: &mut SignalEngine,
-    state_1m: &mut IndicatorState,
-    state_5s: &mut IndicatorState,
-    shadow: &mut ShadowPosition,
-    gatekeeper: &mut GatekeeperState,
-    event_loggers: Option<&EngineEventLoggers>,
-) -> Option<ShadowStepResult> {
-    let midpoint = midpoint_price(&dual_snapshot.yes)?;
-    let simulated_volume =
-        decimal_to_f64(dual_snapshot.yes.top5_bid_depth + dual_snapshot.yes.top5_ask_depth);
-    let spread_f64 = dual_snapshot.yes.spread.map(decimal_to_f64).unwrap_or(0.0);
+/// Feeds snapshots through candles and indicators into a registered
+/// `StrategyEngine` and translates its decisions into the `SignalState` the
+/// shadow, live and maker executors act on.
+pub struct StrategyDriver {
+    kind: StrategyKind,
+    engine: Box<dyn StrategyEngine + Send>,
+    candle_engine: CandleEngine,
+    ind_1m: IndicatorEngine,
+    ind_5s: IndicatorEngine,
+    state_1m: IndicatorState,
+    state_5s: IndicatorState,
+    /// Trade prints not yet handed to the engine
+    pending_trades: Vec<TradePrintEvent>,
+    /// Level depth behind the next snapshot, when the source tracks it
+    book_depth: Option<BookDepth>,
+    /// Fair probability and ask of the most recent entry decision
+    entry_quote: Option<SizingQuote>,
+    /// YES probability behind the most recent entry, until taken
+    entry_prediction: Option<FairValuePrediction>,
+    /// Features of the current market, built from every snapshot
+    features: Option<FeatureBuilder>,
+    /// Row of the latest snapshot, until taken
+    latest_features: Option<FeatureRow>,
+    /// Scores each feature row into `Observation::qlib_score`
+    scores: Option<Box<dyn ScoreSource + Send>>,
+    last_score: Option<ScoreRow>,
+}
 
-    let time_remaining = market_end_ts - (epoch_seconds as i64);
+impl StrategyDriver {
+    pub fn new(config: &StrategyConfig) -> Self {
+        Self {
+            kind: config.kind(),
+            engine: config.build(),
+            candle_engine: CandleEngine::new(),
+            ind_1m: IndicatorEngine::new(),
+            ind_5s: IndicatorEngine::new(),
+            state_1m: IndicatorState::default(),
+            state_5s: IndicatorState::default(),
+            pending_trades: Vec::new(),
+            book_depth: None,
+            entry_quote: None,
+            entry_prediction: None,
+            features: None,
+            latest_features: None,
+            scores: None,
+            last_score: None,
+        }
+    }
 
-    // Debug: show first few ticks
-    if epoch_seconds % 10 == 0 {
-        println!(
-            "[STEP] ts={} mid={:.4} spread={:.4}",
-            epoch_seconds, midpoint, spread_f64
-        );
+    /// Score every feature row with `source`
+    pub fn with_scores(mut self, source: Box<dyn ScoreSource + Send>) -> Self {
+        self.scores = Some(source);
+        self
     }
 
-    if spread_f64 > 0.08 {
-        if epoch_seconds % 30 == 0 {
-            println!("[DEBUG] Blocked by SPREAD: {:.4} > 0.08", spread_f64);
-        }
-        return None;
+    /// Start building features for a new market; call after `reset`
+    pub fn begin_market(&mut self, spec: CryptoBinaryMarketSpec) {
+        self.features = Some(FeatureBuilder::new(spec));
+    }
+
+    /// Feature row of the latest snapshot, once per snapshot
+    pub fn take_features(&mut self) -> Option<FeatureRow> {
+        self.latest_features.take()
+    }
+
+    /// Most recent model score, if scoring is on
+    pub fn last_score(&self) -> Option<&ScoreRow> {
+        self.last_score.as_ref()
+    }
+
+    /// Build this snapshot's features and score them; the signed score
+    fn update_features(&mut self, dual_snapshot: &DualSnapshot, ts: i64) -> Option<f64> {
+        let tick = BookTick { depth: self.book_depth, ..BookTick::from_snapshot(dual_snapshot, ts) };
+        let row = self.features.as_mut()?.update(&tick);
+        self.last_score = self.scores.as_mut().and_then(|source| source.score(&row));
+        self.latest_features = Some(row);
+        self.last_score.as_ref().map(ScoreRow::signed_score)
+    }
+
+    /// Clear engine and indicator state, e.g. on market rollover
+    pub fn reset(&mut self) {
+        self.engine.reset();
+        self.candle_engine = CandleEngine::new();
+        self.ind_1m.reset();
+        self.ind_5s.reset();
+        self.state_1m = IndicatorState::default();
+        self.state_5s = IndicatorState::default();
+        self.pending_trades.clear();
+        self.book_depth = None;
+        self.entry_quote = None;
+        self.entry_prediction = None;
+        self.features = None;
+        self.latest_features = None;
+        self.last_score = None;
+    }
+
+    /// Model probability and ask of the most recent entry, for sizing
+    pub fn entry_quote(&self) -> Option<SizingQuote> {
+        self.entry_quote
+    }
+
+    /// Fair value prediction of the latest entry that had a fair
+    /// probability, once per entry; callers add the market's condition ID,
+    /// window and outcome
+    pub fn take_prediction(&mut self) -> Option<FairValuePrediction> {
+        self.entry_prediction.take()
+    }
+
+    /// The executor filled the latest entry at `price`
+    pub fn on_fill(&mut self, direction: Direction, price: f64) {
+        self.engine.on_fill(direction, price);
+    }
+
+    /// The latest entry was blocked, sized to nothing or found no fill
+    pub fn on_reject(&mut self) {
+        self.engine.on_reject();
     }
 
-    if time_remaining < 45 {
-        return None;
+    /// The executor closed the position
+    pub fn on_exit(&mut self) {
+        self.engine.on_exit();
     }
 
-    if midpoint > 0.92 || midpoint < 0.08 {
-        if epoch_seconds % 30 == 0 {
-            println!(
-                "[DEBUG] Blocked by RANGE: {:.4} (Market trending to end)",
-                midpoint
-            );
+    /// Report one executor step: a position that appeared was filled, one
+    /// that disappeared was closed, and an entry that opened nothing was
+    /// rejected. `after` carries the fill price of a new position.
+    pub fn sync_position(&mut self, entry_sent: bool, before: Option<TokenSide>, after: Option<(TokenSide, f64)>) {
+        match (before, after) {
+            (None, Some((side, price))) => self.on_fill(direction_of(side), price),
+            (Some(_), None) => self.on_exit(),
+            (None, None) if entry_sent => self.on_reject(),
+            _ => {}
         }
-        return None;
     }
 
-    let closed_candles =
-        candle_engine.update(midpoint, spread_f64, simulated_volume, epoch_seconds)?;
+    /// Queue trade prints for the next observation the engine sees
+    pub fn record_trades(&mut self, trades: Vec<TradePrintEvent>) {
+        self.pending_trades.extend(trades);
+    }
 
-    if let Some(c) = closed_candles.one_minute {
-        *state_1m = ind_1m.update(&c);
+    /// Level depth behind the next snapshot, for the `features_v2` columns
+    pub fn record_depth(&mut self, depth: Option<BookDepth>) {
+        self.book_depth = depth;
     }
 
-    if let Some(c) = closed_candles.five_second {
-        *state_5s = ind_5s.update(&c);
+    /// Advance one snapshot. Returns a signal whenever the engine decided
+    /// this step: every 5s candle close for indicator strategies, every
+    /// tick otherwise.
+    pub fn step(
+        &mut self,
+        dual_snapshot: &DualSnapshot,
+        market_slug: &str,
+        market_end_ts: i64,
+        epoch_seconds: u64,
+        event_loggers: Option<&EngineEventLoggers>,
+    ) -> Option<SignalState> {
+        let midpoint = midpoint_price(&dual_snapshot.yes)?;
+        let simulated_volume =
+            decimal_to_f64(dual_snapshot.yes.top5_bid_depth + dual_snapshot.yes.top5_ask_depth);
+        let spread_f64 = dual_snapshot.yes.spread.map(decimal_to_f64).unwrap_or(0.0);
+        // Features need every tick, not just the ones the engine decides on
+        let qlib_score = self.update_features(dual_snapshot, epoch_seconds as i64);
 
-        let mut signal = signal_engine.update(state_5s, state_1m, midpoint);
+        let mut candle_closed = false;
+        if let Some(closed) =
+            self.candle_engine.update(midpoint, spread_f64, simulated_volume, epoch_seconds)
+        {
+            if let Some(c) = closed.one_minute {
+                self.state_1m = self.ind_1m.update(&c);
+            }
+            if let Some(c) = closed.five_second {
+                self.state_5s = self.ind_5s.update(&c);
+                candle_closed = true;
+            }
+        }
+        if self.kind.candle_driven() && !candle_closed {
+            return None;
+        }
 
-        // Orderbook Inefficiency Detection
+        let yes_bid = best_bid_price(&dual_snapshot.yes).unwrap_or(0.0);
         let yes_ask = best_ask_price(&dual_snapshot.yes).unwrap_or(1.0);
+        let no_bid = best_bid_price(&dual_snapshot.no).unwrap_or(0.0);
         let no_ask = best_ask_price(&dual_snapshot.no).unwrap_or(1.0);
-        let book_sum = yes_ask + no_ask;
-        let book_signal = if book_sum > 1.03 {
-            EntrySignal::Short // YES overpriced
-        } else if book_sum < 0.97 {
-            EntrySignal::Long // NO overpriced
-        } else {
-            EntrySignal::None
+        let mut obs = Observation {
+            ts: epoch_seconds as i64,
+            condition_id: market_slug.to_string(),
+            market_slug: market_slug.to_string(),
+            yes_bid,
+            yes_ask,
+            no_bid,
+            no_ask,
+            yes_mid: midpoint,
+            no_mid: 1.0 - midpoint,
+            book_sum: yes_ask + no_ask,
+            time_remaining_s: market_end_ts - epoch_seconds as i64,
+            indicator_5s: self.state_5s.clone(),
+            indicator_1m: self.state_1m.clone(),
+            fair_value_prob: None,
+            qlib_score,
+            trades: std::mem::take(&mut self.pending_trades),
         };
 
-        if book_sum > 1.03 || book_sum < 0.97 {
-            println!(
-                "[SIGNAL] BOOK_INEFFICIENCY: sum={:.4} signal={:?}",
-                book_sum, book_signal
-            );
-        }
+        let model = self.engine.fair_probability(&obs);
+        obs.fair_value_prob = model.as_ref().map(|p| p.yes_probability().clamp(0.0, 1.0));
 
-        let indicator_signal = signal.entry;
-        if indicator_signal != EntrySignal::None {
-            let bbw = state_5s.bb_width.unwrap_or(0.0);
-            let bbp = state_5s.bb_percent.unwrap_or(0.0);
-            println!(
-                "[SIGNAL] INDICATOR_MATCH: {:?} (BBW={:.4} BBP={:.4})",
-                indicator_signal, bbw, bbp
-            );
+        let decision = self.engine.decide(&obs);
+        if let StrategyDecision::Enter { direction, .. } = &decision {
+            let ask = match direction {
+                Direction::Yes => obs.yes_ask,
+                Direction::No => obs.no_ask,
+            };
+            // Kelly sizes on the model's calibrated probability, never on the
+            // engine's scaled edge
+            self.entry_quote = Some(match &model {
+                Some(model) => SizingQuote::from_model(model, *direction, ask),
+                None => SizingQuote { fair_prob: None, ask },
+            });
+            self.entry_prediction = obs.fair_value_prob.map(|yes_prob| {
+                FairValuePrediction::new(obs.ts, None, yes_prob, obs.yes_ask, yes_prob - obs.yes_ask)
+                    .with_direction(format!("{direction:?}"))
+                    .with_market_slug(obs.market_slug.clone())
+                    .with_time_remaining(obs.time_remaining_s)
+            });
         }
-
-        // Final Logic
-        // Final Logic: Signal if indicators confirmed OR book inefficiency detected
-        signal.entry = if indicator_signal != EntrySignal::None {
-            println!("[SIGNAL] FINAL: Using Indicators ({:?})", indicator_signal);
-            indicator_signal
-        } else if book_signal != EntrySignal::None {
-            println!(
-                "[SIGNAL] FINAL: Using Book Inefficiency ({:?})",
-                book_signal
-            );
-            book_signal
-        } else {
-            EntrySignal::None
+        let (signal, detail) = match &decision {
+            StrategyDecision::Enter { direction, reason } => (
+                SignalState {
+                    entry: match direction {
+                        Direction::Yes => EntrySignal::Long,
+                        Direction::No => EntrySignal::Short,
+                    },
+                    exit: ExitSignal::None,
+                },
+                format!("{:?}: {}", reason.source, reason.detail),
+            ),
+            StrategyDecision::Exit { reason, .. } => (
+                SignalState { entry: EntrySignal::None, exit: ExitSignal::FullExit },
+                format!("{reason:?}"),
+            ),
+            StrategyDecision::Block { reason } => (
+                SignalState { entry: EntrySignal::None, exit: ExitSignal::None },
+                format!("blocked: {reason}"),
+            ),
+            StrategyDecision::Hold => {
+                (SignalState { entry: EntrySignal::None, exit: ExitSignal::None }, String::new())
+            }
         };
 
         if let Some(loggers) = event_loggers {
-            loggers.log_strategy(EngineEvent::StrategySignal {
-                ts: epoch_seconds,
-                market_slug: market_slug.to_string(),
-                midpoint,
-                entry: format!("{:?}", signal.entry),
-                exit: format!("{:?}", signal.exit),
-                detail: format!(
-                    "indicator={indicator_signal:?}, book={book_signal:?}, yes_ask={yes_ask:.4}, no_ask={no_ask:.4}"
-                ),
-            });
+            if !matches!(decision, StrategyDecision::Hold) || candle_closed {
+                loggers.log_strategy(EngineEvent::StrategySignal {
+                    ts: epoch_seconds,
+                    market_slug: market_slug.to_string(),
+                    midpoint,
+                    entry: format!("{:?}", signal.entry),
+                    exit: format!("{:?}", signal.exit),
+                    detail: format!("{}: {}", self.kind, detail),
+                });
+            }
         }
+        Some(signal)
+    }
+}
 
-        shadow.position_size_usd = position_size_usd;
-        return Some(handle_shadow_signals(
-            &mut signal,
-            dual_snapshot,
-            shadow,
-            gatekeeper,
-            event_loggers,
-            market_label,
-            market_slug,
-            market_start_ts,
-            market_end_ts,
-            epoch_seconds,
-            midpoint,
-        ));
-    }
-
-    None
+/// One shadow step for any registered strategy: drive the engine and apply
+/// its signal to the simulated position through the shared gatekeeper,
+/// executing orders through `fills`.
+#[allow(clippy::too_many_arguments)]
+pub fn run_driver_shadow_step(
+    driver: &mut StrategyDriver,
+    dual_snapshot: &DualSnapshot,
+    market_label: &str,
+    market_slug: &str,
+    market_start_ts: i64,
+    market_end_ts: i64,
+    epoch_seconds: u64,
+    sizer: &PositionSizer,
+    shadow: &mut ShadowPosition,
+    gatekeeper: &mut GatekeeperState,
+    fills: &mut dyn ShadowFills,
+    event_loggers: Option<&EngineEventLoggers>,
+) -> Option<ShadowStepResult> {
+    let midpoint = midpoint_price(&dual_snapshot.yes)?;
+    let mut signal = driver.step(dual_snapshot, market_slug, market_end_ts, epoch_seconds, event_loggers)?;
+    let entry_sent = signal.entry != EntrySignal::None;
+    let before = shadow.token_side;
+    // An open position keeps the stake it was entered with
+    if before.is_none() {
+        shadow.position_size_usd = sizer.size_signal(&mut signal, driver.entry_quote(), shadow.bankroll_usd);
+    }
+    let result = handle_shadow_signals(
+        &mut signal,
+        dual_snapshot,
+        shadow,
+        gatekeeper,
+        fills,
+        event_loggers,
+        market_label,
+        market_slug,
+        market_start_ts,
+        market_end_ts,
+        epoch_seconds,
+        midpoint,
+    );
+    driver.sync_position(entry_sent, before, shadow.token_side.map(|side| (side, shadow.entry_price)));
+    Some(result)
+}
+
+fn direction_of(side: TokenSide) -> Direction {
+    match side {
+        TokenSide::Yes => Direction::Yes,
+        TokenSide::No => Direction::No,
+    }
 }
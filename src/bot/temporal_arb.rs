@@ -0,0 +1,838 @@
+//! Temporal Arbitrage Runner
+//!
+//! Runs the nested-market constraint checks from `strategy::constraint_engine`
+//! against live BTC Up/Down markets. Every open 5m/15m/1h market is a node of
+//! one `TemporalGraph`; markets that ended stay in the graph as resolved legs
+//! so their parents can be priced conditionally. Shadow mode logs each
+//! `ArbitrageAction` and settles paper positions at resolution, `--live`
+//! executes them through the gatekeeper.
+//!
+//! Strikes are the price to beat listed in each market's metadata, or the
+//! Chainlink spot at the window open when the market lists none. Markets
+//! joined after their open without a listed strike stay out of the graph.
+
+use crate::auth;
+use crate::bot::discovery::{discover_market_loop, MarketTarget, WatchedMarket};
+use crate::bot::execution::{
+    apply_settlement_event, get_usdc_balance, handle_live_signals, open_state_store,
+    recover_positions, try_settle_pending, LivePosition, PendingSettlement,
+};
+use crate::bot::feed::multi_market_feed::Timeframe as FeedTimeframe;
+use crate::bot::feed::{DualSnapshot, MarketSubscription, MultiMarketWebsocketFeed, UserWebsocketFeed};
+use crate::bot::logging::{EngineEvent, EngineEventLoggers};
+use crate::bot::pricing::{start_rtds_poller, PolymarketRtdsFeed, SpotFeed};
+use crate::bot::research::{SupportedAsset, SupportedDuration};
+use crate::bot::risk::{best_ask_price, best_bid_price, midpoint_price, GatekeeperState};
+use crate::bot::shadow::TokenSide;
+use crate::bot::signal::{EntrySignal, ExitSignal, SignalState};
+use crate::bot::sizing::{PositionSizer, SizingArgs, SizingQuote};
+use crate::bot::strategy::constraint_engine::ConstraintEngine;
+use crate::bot::strategy::graph_builder::TemporalGraphBuilder;
+use crate::bot::strategy::probability_engine::ArbitrageAction;
+use crate::bot::strategy::temporal_arbitrage::{
+    TemporalArbitrageConfig, TemporalNode, Timeframe, VolatilityEstimator,
+};
+use crate::bot::strategy::{Direction, TemporalGraph};
+use crate::persistence::StateStore;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Args;
+use polymarket_client_sdk::clob;
+use polymarket_client_sdk::gamma;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex as TokioMutex;
+use tokio::task::JoinSet;
+use tokio::time::{Duration, MissedTickBehavior, interval};
+
+/// A window opened at most this long ago takes the live spot as its strike
+const STRIKE_CAPTURE_SECS: i64 = 5;
+
+/// Graph, constraint and sizing settings shared by the live runner and the
+/// historical backtest
+#[derive(Args, Clone)]
+pub struct TemporalArbParams {
+    /// Market windows to put in the graph, comma separated
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "5m,15m,1h")]
+    pub durations: Vec<SupportedDuration>,
+
+    /// Minimum edge in probability units
+    #[arg(long, default_value = "0.05")]
+    pub min_edge: f64,
+
+    /// Maximum book overround (YES + NO - 1) a market may show
+    #[arg(long, default_value = "0.03")]
+    pub max_spread: f64,
+
+    /// Minimum violation confidence to act on
+    #[arg(long, default_value = "0.5")]
+    pub min_confidence: f64,
+
+    /// Base BTC volatility for the 5m timeframe, in USD
+    #[arg(long, default_value = "250")]
+    pub base_vol_5m: f64,
+
+    /// Disable chained conditional checks
+    #[arg(long)]
+    pub no_chained: bool,
+
+    /// Disable late-phase anomaly checks
+    #[arg(long)]
+    pub no_late_phase: bool,
+
+    /// Disable price consistency checks
+    #[arg(long)]
+    pub no_consistency: bool,
+
+    /// Skip constraint checks while the spot price is older than this many seconds
+    #[arg(long, default_value = "10")]
+    pub max_spot_age: i64,
+
+    /// Position size in USDC per trade
+    #[arg(long, default_value = "1.0")]
+    pub size: f64,
+
+    /// Maximum concurrently open positions
+    #[arg(long, default_value = "3")]
+    pub max_positions: usize,
+}
+
+impl TemporalArbParams {
+    pub fn config(&self) -> TemporalArbitrageConfig {
+        TemporalArbitrageConfig {
+            min_edge: self.min_edge,
+            max_spread: self.max_spread,
+            enable_chained_conditional: !self.no_chained,
+            enable_late_phase: !self.no_late_phase,
+            enable_price_consistency: !self.no_consistency,
+            base_volatility_5m: self.base_vol_5m,
+            min_confidence: self.min_confidence,
+            max_concurrent_positions: self.max_positions,
+        }
+    }
+
+    pub fn constraints(&self) -> ConstraintEngine {
+        ConstraintEngine::new(self.config()).with_max_price_age(self.max_spot_age)
+    }
+
+    /// Requested windows without duplicates; at least two are needed to nest
+    pub fn nested_durations(&self) -> Result<Vec<SupportedDuration>> {
+        let mut durations: Vec<SupportedDuration> = Vec::new();
+        for duration in &self.durations {
+            if !durations.contains(duration) {
+                durations.push(*duration);
+            }
+        }
+        if durations.len() < 2 {
+            anyhow::bail!("--durations needs at least two nested windows (e.g. 5m,15m)");
+        }
+        Ok(durations)
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct TemporalArbArgs {
+    #[command(flatten)]
+    pub params: TemporalArbParams,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    /// Starting paper bankroll in USD for shadow-mode sizing
+    #[arg(long, default_value = "5.0")]
+    pub capital: f64,
+
+    /// Place real orders (default: shadow mode, actions are only logged)
+    #[arg(long)]
+    pub live: bool,
+
+    /// Optional structured event log path or directory
+    #[arg(long)]
+    pub event_log: Option<String>,
+
+    /// Daily realized loss limit in USD (live)
+    #[arg(long, default_value = "5.0")]
+    pub daily_loss_limit: f64,
+
+    /// Cooldown duration after a losing trade (live)
+    #[arg(long, default_value = "15")]
+    pub cooldown_seconds: u64,
+
+    /// Start the engine in an emergency-halted state
+    #[arg(long)]
+    pub emergency_halt: bool,
+
+    /// SQLite database for crash-safe position and trade persistence (live)
+    #[arg(long, default_value = "bot_state.db")]
+    pub state_db: String,
+}
+
+pub(crate) fn graph_timeframe(duration: SupportedDuration) -> Timeframe {
+    match duration {
+        SupportedDuration::M5 => Timeframe::M5,
+        SupportedDuration::M15 => Timeframe::M15,
+        SupportedDuration::H1 => Timeframe::H1,
+    }
+}
+
+fn token_side(direction: Direction) -> TokenSide {
+    match direction {
+        Direction::Yes => TokenSide::Yes,
+        Direction::No => TokenSide::No,
+    }
+}
+
+/// One open market in the graph
+struct ArbSlot {
+    target: MarketTarget,
+    watched: WatchedMarket,
+    strike: Option<f64>,
+    position: LivePosition,
+    latest: Option<DualSnapshot>,
+    /// Direction of the last logged action, so each opportunity is logged once
+    last_action: Option<Direction>,
+    /// Market ended and discovery of the next one is in flight
+    rolling: bool,
+}
+
+impl ArbSlot {
+    fn new(target: MarketTarget, watched: WatchedMarket) -> Self {
+        Self {
+            target,
+            watched,
+            strike: None,
+            position: LivePosition::default(),
+            latest: None,
+            last_action: None,
+            rolling: false,
+        }
+    }
+
+    /// Key the multi-market feed reports this market's events under
+    fn feed_key(&self) -> String {
+        self.watched
+            .condition_id
+            .clone()
+            .unwrap_or_else(|| self.watched.slug.clone())
+    }
+
+    fn subscription(&self) -> MarketSubscription {
+        MarketSubscription {
+            condition_id: self.feed_key(),
+            yes_token_id: self.watched.yes_token_id.to_string(),
+            no_token_id: self.watched.no_token_id.to_string(),
+            timeframe: match self.target.duration {
+                SupportedDuration::M5 => FeedTimeframe::M5,
+                SupportedDuration::M15 => FeedTimeframe::M15,
+                SupportedDuration::H1 => FeedTimeframe::H1,
+            },
+            start_time: self.watched.start_ts(),
+            end_time: self.watched.end_time.timestamp(),
+            strike_price: self.strike.unwrap_or(0.0),
+        }
+    }
+
+    /// Graph node for this market, once its strike is known
+    fn node(&self) -> Option<TemporalNode> {
+        let strike_price = self.strike?;
+        let mids = self.latest.as_ref().and_then(|s| {
+            Some((midpoint_price(&s.yes)?, midpoint_price(&s.no)?))
+        });
+        Some(TemporalNode {
+            condition_id: self.feed_key(),
+            timeframe: graph_timeframe(self.target.duration),
+            strike_price,
+            start_time: self.watched.start_ts(),
+            end_time: self.watched.end_time.timestamp(),
+            parent: None,
+            children: Vec::new(),
+            yes_price: mids.map(|(yes, _)| yes),
+            no_price: mids.map(|(_, no)| no),
+            resolved_outcome: None,
+            close_price: None,
+        })
+    }
+
+    /// Fix the strike once the window opens: the price to beat from the
+    /// market metadata, else the spot if the window only just opened.
+    /// Returns true when the strike was set.
+    fn capture_strike(&mut self, spot: f64, now: i64) -> bool {
+        let start = self.watched.start_ts();
+        if self.rolling || self.strike.is_some() || now < start {
+            return false;
+        }
+        self.strike = self
+            .watched
+            .strike_price
+            .or_else(|| (now - start <= STRIKE_CAPTURE_SECS).then_some(spot));
+        self.strike.is_some()
+    }
+}
+
+/// Rebuild the graph from the open slots and resolved legs, keeping the spot
+/// and volatility state of the previous graph
+fn rebuild_graph(
+    builder: &mut TemporalGraphBuilder,
+    previous: &TemporalGraph,
+    slots: &[ArbSlot],
+    resolved: &[TemporalNode],
+) -> TemporalGraph {
+    let mut nodes: Vec<TemporalNode> = resolved
+        .iter()
+        .map(|node| TemporalNode {
+            parent: None,
+            children: Vec::new(),
+            ..node.clone()
+        })
+        .collect();
+    nodes.extend(slots.iter().filter_map(ArbSlot::node));
+
+    let price = &previous.price_state;
+    let mut graph = builder.build_from_nodes(nodes, price.current_price, price.last_update);
+    graph.price_state = previous.price_state.clone();
+    graph.vol_estimator = previous.vol_estimator.clone();
+    graph
+}
+
+async fn connect_feed(
+    slots: &[ArbSlot],
+) -> Result<(MultiMarketWebsocketFeed, HashMap<String, usize>)> {
+    let subscriptions = slots.iter().map(ArbSlot::subscription).collect();
+    let index = slots
+        .iter()
+        .enumerate()
+        .map(|(i, slot)| (slot.feed_key(), i))
+        .collect();
+    let feed = MultiMarketWebsocketFeed::connect(subscriptions, None)
+        .await
+        .context("Failed to connect multi-market feed")?;
+    Ok((feed, index))
+}
+
+/// Paper fill for a shadow signal at the touch. Returns realized PnL on exits.
+fn paper_fill(
+    signal: &SignalState,
+    snapshot: &DualSnapshot,
+    position: &mut LivePosition,
+    size_usd: f64,
+    label: &str,
+    timestamp: u64,
+) -> Option<f64> {
+    let side_snapshot = |side: TokenSide| match side {
+        TokenSide::Yes => &snapshot.yes,
+        TokenSide::No => &snapshot.no,
+    };
+
+    if signal.exit == ExitSignal::FullExit {
+        let side = position.token_side?;
+        let bid = best_bid_price(side_snapshot(side)).filter(|b| *b > 0.0)?;
+        let pnl = position.shares * (bid - position.entry_price);
+        println!(
+            "[SHADOW EXIT] {} | SELL {:?} @ {:.4} | PnL ${:.2}",
+            label, side, bid, pnl
+        );
+        position.reset(timestamp, pnl < 0.0);
+        return Some(pnl);
+    }
+
+    let side = match signal.entry {
+        EntrySignal::Long => TokenSide::Yes,
+        EntrySignal::Short => TokenSide::No,
+        EntrySignal::None => return None,
+    };
+    let ask = best_ask_price(side_snapshot(side)).filter(|a| *a > 0.0001)?;
+    position.token_side = Some(side);
+    position.entry_price = ask;
+    position.shares = size_usd / ask;
+    position.entry_timestamp = timestamp;
+    println!(
+        "[SHADOW ENTRY] {} | BUY {:?} @ {:.4} | ${:.2}",
+        label, side, ask, size_usd
+    );
+    None
+}
+
+#[derive(Default)]
+struct ShadowTally {
+    trades: usize,
+    wins: usize,
+    pnl: f64,
+}
+
+impl ShadowTally {
+    fn record(&mut self, pnl: f64) {
+        self.trades += 1;
+        if pnl > 0.0 {
+            self.wins += 1;
+        }
+        self.pnl += pnl;
+    }
+}
+
+pub async fn run_temporal_arb(args: TemporalArbArgs) -> Result<()> {
+    let params = &args.params;
+    let durations = params.nested_durations()?;
+    let longest_window = durations.iter().map(|d| d.seconds()).max().unwrap_or(0);
+
+    let config = params.config();
+    let constraints = params.constraints();
+    let sizer = PositionSizer::new(params.size, &args.sizing);
+    let mut builder = TemporalGraphBuilder::new();
+    let mut graph = TemporalGraph {
+        vol_estimator: VolatilityEstimator::new(config.base_volatility_5m, 0.94),
+        ..TemporalGraph::new()
+    };
+    let mut resolved: Vec<TemporalNode> = Vec::new();
+
+    let gamma_client = gamma::Client::default();
+    let read_client = clob::Client::default();
+    let event_loggers = args
+        .event_log
+        .as_deref()
+        .map(EngineEventLoggers::new)
+        .transpose()
+        .context("Failed to create structured event logs")?;
+
+    // Shadow runs never touch the account, so they need no credentials
+    let signer = if args.live {
+        Some(auth::resolve_signer(None)?)
+    } else {
+        None
+    };
+    let clob_client = match &signer {
+        Some(signer) => Some(auth::authenticate_with_signer(signer, None).await?),
+        None => None,
+    };
+    if let Some(client) = &clob_client {
+        let balance = get_usdc_balance(client).await?;
+        println!("[TEMPORAL-ARB] USDC Balance: ${:.2}", balance);
+        if balance < params.size {
+            anyhow::bail!("Insufficient USDC balance: ${:.2} < ${:.2}", balance, params.size);
+        }
+    }
+
+    let rtds = Arc::new(TokioMutex::new(PolymarketRtdsFeed::new("btc/usd")));
+    let rtds_poller = start_rtds_poller(rtds.clone());
+
+    let mut slots = Vec::with_capacity(durations.len());
+    for duration in &durations {
+        let target = MarketTarget::new(SupportedAsset::Btc, *duration);
+        let watched = discover_market_loop(&gamma_client, target).await;
+        slots.push(ArbSlot::new(target, watched));
+    }
+    let (mut feed, mut feed_index) = connect_feed(&slots).await?;
+    let mut discoveries: JoinSet<(usize, WatchedMarket)> = JoinSet::new();
+
+    let mut gatekeeper = GatekeeperState::new(args.daily_loss_limit, args.cooldown_seconds);
+    if args.emergency_halt {
+        gatekeeper.halt();
+    }
+    let mut pending_settlements: Vec<PendingSettlement> = Vec::new();
+    let mut tally = ShadowTally::default();
+
+    let state_store = match &clob_client {
+        Some(client) => {
+            let now = Utc::now();
+            let balance = get_usdc_balance(client).await?;
+            let store = open_state_store(&args.state_db, balance, &mut gatekeeper, now)?;
+            let mut markets: Vec<(&WatchedMarket, &mut LivePosition)> = slots
+                .iter_mut()
+                .map(|slot| (&slot.watched, &mut slot.position))
+                .collect();
+            let recovered =
+                recover_positions(&store, client, &mut markets, &mut pending_settlements, now)
+                    .await
+                    .context("Failed to reconcile persisted positions")?;
+            if recovered > 0 {
+                println!("[TEMPORAL-ARB] Recovered {} persisted position(s)", recovered);
+            }
+            Some(store)
+        }
+        None => None,
+    };
+    let state_store_ref = state_store.as_ref().map(|s| s as &dyn StateStore);
+    // Settlement sells are confirmed by the fills the user channel pushes
+    let mut user_feed = match &clob_client {
+        Some(client) => Some(
+            UserWebsocketFeed::connect(client.credentials(), Vec::new(), None)
+                .await
+                .context("Failed to subscribe to the user channel")?,
+        ),
+        None => None,
+    };
+
+    let mut ticker = interval(Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    println!(
+        "[TEMPORAL-ARB] {} | min edge {:.3} | max spread {:.3} | base vol ${:.0}/5m",
+        if args.live { "LIVE" } else { "SHADOW" },
+        config.min_edge,
+        config.max_spread,
+        config.base_volatility_5m
+    );
+    for slot in &slots {
+        println!("[TEMPORAL-ARB] {} | {}", slot.target, slot.watched.slug);
+    }
+    println!(
+        "[TEMPORAL-ARB] {} | Max positions: {} | Daily loss limit: ${:.2}",
+        sizer.describe(), params.max_positions, args.daily_loss_limit
+    );
+    println!("========================================");
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n[TEMPORAL-ARB] Stopping...");
+                for slot in &slots {
+                    if slot.position.is_active() {
+                        println!("[TEMPORAL-ARB] WARNING: {} position still open!", slot.watched.slug);
+                    }
+                }
+                if !pending_settlements.is_empty() {
+                    println!("[TEMPORAL-ARB] WARNING: {} pending settlements require manual resolution!", pending_settlements.len());
+                }
+                if !args.live {
+                    println!(
+                        "[TEMPORAL-ARB] Shadow trades: {} | wins: {} | PnL: ${:.2}",
+                        tally.trades, tally.wins, tally.pnl
+                    );
+                }
+                break;
+            }
+            Some(event) = feed.recv() => {
+                if !event.is_valid() {
+                    continue;
+                }
+                let Some(&idx) = feed_index.get(&event.condition_id) else {
+                    continue;
+                };
+                slots[idx].latest = Some(event.to_dual_snapshot());
+                graph.update_node_price(&event.condition_id, event.yes_mid, event.no_mid);
+
+                let now = Utc::now();
+                let ts = now.timestamp();
+                if graph.price_state.current_price <= 0.0
+                    || graph.price_state.is_stale(ts, params.max_spot_age)
+                {
+                    continue;
+                }
+
+                let actions = constraints.check_all(
+                    &graph,
+                    ts,
+                    config.enable_chained_conditional,
+                    config.enable_late_phase,
+                    config.enable_price_consistency,
+                );
+                for action in actions {
+                    let confidence = action.confidence();
+                    let ArbitrageAction::EnterSingle { condition_id, direction, edge, fair_prob, reason } = action else {
+                        continue;
+                    };
+                    if confidence < config.min_confidence {
+                        continue;
+                    }
+                    let Some(&idx) = feed_index.get(&condition_id) else {
+                        continue;
+                    };
+                    let open_positions = slots.iter().filter(|s| s.position.is_active()).count();
+                    let slot = &mut slots[idx];
+                    let Some(snapshot) = slot.latest.clone() else {
+                        continue;
+                    };
+
+                    let fresh = slot.last_action != Some(direction);
+                    if fresh {
+                        slot.last_action = Some(direction);
+                        println!(
+                            "[TEMPORAL-ARB] {} | {} | {:?} edge {:.3} | {}",
+                            slot.target, slot.watched.slug, direction, edge, reason
+                        );
+                        if let Some(loggers) = &event_loggers {
+                            loggers.log_strategy(EngineEvent::StrategySignal {
+                                ts: ts as u64,
+                                market_slug: slot.watched.slug.clone(),
+                                midpoint: midpoint_price(&snapshot.yes).unwrap_or(0.0),
+                                entry: format!("{direction:?}"),
+                                exit: "None".to_string(),
+                                detail: format!("temporal_arb:{} edge={:.4}", reason, edge),
+                            });
+                        }
+                    }
+
+                    // Hold to resolution unless the graph now prices the other side
+                    let mut signal = match slot.position.token_side {
+                        Some(side) if side == token_side(direction) => continue,
+                        Some(_) => SignalState { entry: EntrySignal::None, exit: ExitSignal::FullExit },
+                        None if open_positions >= params.max_positions => {
+                            if fresh {
+                                println!(
+                                    "[FILTER BLOCKED] {} | {:?} | Reason: max positions ({})",
+                                    slot.watched.label, direction, params.max_positions
+                                );
+                            }
+                            continue;
+                        }
+                        None => SignalState {
+                            entry: match direction {
+                                Direction::Yes => EntrySignal::Long,
+                                Direction::No => EntrySignal::Short,
+                            },
+                            exit: ExitSignal::None,
+                        },
+                    };
+
+                    // Entries are sized on the graph's probability for the side bought
+                    let book = match direction {
+                        Direction::Yes => &snapshot.yes,
+                        Direction::No => &snapshot.no,
+                    };
+                    let quote = best_ask_price(book)
+                        .map(|ask| SizingQuote::from_model(&fair_prob, direction, ask));
+                    let bankroll = state_store_ref
+                        .and_then(|store| store.get_bankroll().ok())
+                        .unwrap_or(args.capital + tally.pnl);
+                    let stake = sizer.size_signal(&mut signal, quote, bankroll);
+
+                    match (&clob_client, &signer) {
+                        (Some(client), Some(signer)) => {
+                            handle_live_signals(
+                                &signal,
+                                &snapshot,
+                                &mut slot.position,
+                                &mut gatekeeper,
+                                event_loggers.as_ref(),
+                                state_store_ref,
+                                &slot.watched,
+                                ts as u64,
+                                stake,
+                                false,
+                                client,
+                                signer,
+                            ).await;
+                        }
+                        _ => {
+                            if let Some(pnl) = paper_fill(&signal, &snapshot, &mut slot.position, stake, &slot.watched.label, ts as u64) {
+                                tally.record(pnl);
+                            }
+                        }
+                    }
+                }
+            }
+            Some(discovered) = discoveries.join_next() => {
+                let (idx, watched) = discovered.context("Market discovery task failed")?;
+                let slot = &mut slots[idx];
+                *slot = ArbSlot::new(slot.target, watched);
+                feed.add_subscription(slot.subscription()).await?;
+                feed_index.insert(slot.feed_key(), idx);
+                println!("[MARKET RESET] {} | {}", slot.target, slot.watched.slug);
+            }
+            _ = ticker.tick() => {
+                let now = Utc::now();
+                let ts = now.timestamp();
+
+                let spot = {
+                    let rtds = rtds.lock().await;
+                    rtds.get_price().filter(|_| rtds.is_healthy())
+                };
+                if let Some(price) = spot {
+                    graph.update_price_state(price, ts);
+                }
+
+                if let Some(feed) = user_feed.as_mut() {
+                    while let Some(event) = feed.try_recv() {
+                        apply_settlement_event(&mut pending_settlements, &event);
+                    }
+                }
+                if let (Some(client), Some(signer)) = (&clob_client, &signer)
+                    && !pending_settlements.is_empty()
+                {
+                    try_settle_pending(
+                        &mut pending_settlements,
+                        &read_client,
+                        client,
+                        signer,
+                        &mut gatekeeper,
+                        event_loggers.as_ref(),
+                        state_store_ref,
+                        user_feed.is_some(),
+                        now,
+                    ).await;
+                }
+
+                let mut rebuild = false;
+                if let Some(price) = spot {
+                    for slot in slots.iter_mut() {
+                        if slot.capture_strike(price, ts) {
+                            println!(
+                                "[TEMPORAL-ARB] {} | {} | strike ${:.2}",
+                                slot.target, slot.watched.slug, slot.strike.unwrap_or(0.0)
+                            );
+                            rebuild = true;
+                        }
+                    }
+                }
+
+                // Ended markets become resolved legs and roll over to the next window
+                let mut rolled = false;
+                for (idx, slot) in slots.iter_mut().enumerate() {
+                    if slot.rolling || now < slot.watched.end_time {
+                        continue;
+                    }
+                    let close = spot.or_else(|| {
+                        (graph.price_state.current_price > 0.0).then_some(graph.price_state.current_price)
+                    });
+                    let outcome = match (slot.node(), close) {
+                        (Some(mut node), Some(close)) => {
+                            let outcome = close >= node.strike_price;
+                            node.mark_resolved(outcome, close);
+                            resolved.push(node);
+                            Some(outcome)
+                        }
+                        _ => None,
+                    };
+
+                    if args.live {
+                        if let Some(pending) = PendingSettlement::from_position(&slot.watched, &slot.position, now) {
+                            println!(
+                                "[PENDING] {} | {:?} | {:.4} shares @ {:.4} | Auto-sell queued",
+                                pending.market_slug, pending.token_side, pending.shares, pending.entry_price
+                            );
+                            pending_settlements.push(pending);
+                        }
+                    } else if let Some(side) = slot.position.token_side {
+                        match outcome {
+                            Some(up) => {
+                                let won = (side == TokenSide::Yes) == up;
+                                let payout = if won { slot.position.shares } else { 0.0 };
+                                let pnl = payout - slot.position.shares * slot.position.entry_price;
+                                println!(
+                                    "[SHADOW SETTLE] {} | {:?} {} | PnL ${:.2}",
+                                    slot.watched.slug, side, if won { "won" } else { "lost" }, pnl
+                                );
+                                tally.record(pnl);
+                            }
+                            None => eprintln!(
+                                "[warn] {} ended without a spot price; shadow position left unsettled",
+                                slot.watched.slug
+                            ),
+                        }
+                    }
+
+                    println!("[TEMPORAL-ARB] Market {} ended. Looking for next {} market...", slot.watched.slug, slot.target);
+                    let feed_key = slot.feed_key();
+                    feed.remove_subscription(&feed_key).await?;
+                    feed_index.remove(&feed_key);
+                    slot.strike = None;
+                    slot.position = LivePosition::default();
+                    slot.latest = None;
+                    slot.last_action = None;
+                    slot.rolling = true;
+                    let (client, target) = (gamma_client.clone(), slot.target);
+                    discoveries.spawn(async move { (idx, discover_market_loop(&client, target).await) });
+                    rolled = true;
+                }
+                if rolled {
+                    resolved.retain(|node| node.end_time > ts - longest_window);
+                    rebuild = true;
+                }
+                if rebuild {
+                    graph = rebuild_graph(&mut builder, &graph, &slots, &resolved);
+                }
+
+                if ts % 30 == 0 {
+                    let stats = graph.stats(ts);
+                    println!(
+                        "[TEMPORAL-ARB] nodes {} active / {} resolved | spot {} | open {} | {}",
+                        stats.active_nodes,
+                        stats.resolved_nodes,
+                        spot.map_or("stale".to_string(), |p| format!("${:.2}", p)),
+                        slots.iter().filter(|s| s.position.is_active()).count(),
+                        if args.live {
+                            format!("daily PnL ${:.2}{}", gatekeeper.daily_pnl, if gatekeeper.emergency_halt { " | HALTED" } else { "" })
+                        } else {
+                            format!("shadow PnL ${:.2} over {} trades", tally.pnl, tally.trades)
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    rtds_poller.abort();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use polymarket_client_sdk::types::U256;
+
+    fn slot(duration: SupportedDuration, start: i64) -> ArbSlot {
+        let target = MarketTarget::new(SupportedAsset::Btc, duration);
+        let watched = WatchedMarket {
+            label: target.to_string(),
+            slug: format!("{}{}", target.slug_prefix(), start),
+            yes_token_id: U256::from(1u8),
+            no_token_id: U256::from(2u8),
+            condition_id: None,
+            end_time: Utc.timestamp_opt(start + duration.seconds(), 0).unwrap(),
+            duration_seconds: duration.seconds(),
+            strike_price: None,
+        };
+        ArbSlot::new(target, watched)
+    }
+
+    #[test]
+    fn late_joined_market_takes_the_strike_from_its_metadata() {
+        let mut joined = slot(SupportedDuration::M15, 1_000);
+        joined.watched.strike_price = Some(70_850.0);
+        assert!(joined.capture_strike(71_000.0, 1_300));
+        assert_eq!(joined.strike, Some(70_850.0));
+
+        // Without a listed strike a late join has no strike at all
+        let mut unlisted = slot(SupportedDuration::M15, 1_000);
+        assert!(!unlisted.capture_strike(71_000.0, 1_300));
+        assert!(unlisted.node().is_none());
+
+        // A window that just opened takes the spot as is
+        let mut fresh = slot(SupportedDuration::M5, 1_300);
+        assert!(fresh.capture_strike(71_000.0, 1_302));
+        assert_eq!(fresh.strike, Some(71_000.0));
+        assert!(!fresh.capture_strike(72_000.0, 1_303));
+
+        // A slot waiting on its next market keeps no strike
+        let mut rolling = slot(SupportedDuration::M5, 1_300);
+        rolling.rolling = true;
+        assert!(!rolling.capture_strike(71_000.0, 1_301));
+    }
+
+    #[test]
+    fn resolved_legs_nest_under_their_open_parent() {
+        let mut parent = slot(SupportedDuration::M15, 900);
+        parent.strike = Some(71_000.0);
+        let mut child = slot(SupportedDuration::M5, 1_200);
+        child.strike = Some(71_040.0);
+
+        let mut leg = slot(SupportedDuration::M5, 900);
+        leg.strike = Some(71_000.0);
+        let mut leg = leg.node().unwrap();
+        leg.mark_resolved(true, 71_040.0);
+
+        let mut previous = TemporalGraph::new();
+        previous.price_state.update(71_050.0, 1_250);
+        let graph = rebuild_graph(
+            &mut TemporalGraphBuilder::new(),
+            &previous,
+            &[parent, child],
+            &[leg],
+        );
+
+        let parent_key = format!("btc-updown-15m-{}", 900);
+        assert_eq!(graph.roots, vec![parent_key.clone()]);
+        assert_eq!(graph.get_children(&parent_key).len(), 2);
+        assert_eq!(graph.price_state.current_price, 71_050.0);
+    }
+}
@@ -0,0 +1,17 @@
+//! Test Helpers
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Fresh empty directory under the system temp dir, unique per call.
+/// Callers remove it when done.
+pub(crate) fn tempdir() -> String {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "polymarket-test-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("failed to create test directory");
+    dir.to_string_lossy().into_owned()
+}
@@ -1,27 +1,38 @@
 use crate::auth;
-use crate::bot::candles::CandleEngine;
 use crate::bot::discovery::{
-    discover_market_loop, FIVE_MINUTES_SECONDS,
+    discover_market_loop, MarketTarget,
 };
 use crate::bot::pipeline::{
     self, BacktestArgs, MonteCarloArgs, SweepArgs, FetchPmxtArgs,
     ListMarketsArgs, ExtractMidpointsArgs, InspectParquetArgs, BacktestPipelineArgs,
-    BacktestPmxtArgs, run_backtest_pmxt,
+    BacktestPmxtArgs, run_backtest_pmxt, BacktestRecordingArgs, run_backtest_recording,
+    BacktestTemporalArgs, run_backtest_temporal, CalibrateHawkesArgs, run_calibrate_hawkes,
+    OptimizeArgs, run_optimize, WalkForwardArgs, run_walk_forward, DataArgs, run_data, CryptoAsset,
 };
 use crate::bot::feed::{
-    LiveFeedMode, LiveStrategyInputSource, StrategyInputSource,
+    LiveFeedMode, LiveStrategyInputSource, StrategyInputSource, UserWebsocketFeed,
 };
 use crate::bot::execution::{
-    handle_live_signals, get_usdc_balance, try_settle_pending,
-    PendingSettlement, LivePosition,
+    handle_live_signals, get_usdc_balance, open_state_store, recover_positions,
+    apply_settlement_event, try_settle_pending, ExecutionStyle, PendingSettlement, LivePosition,
 };
-use crate::bot::indicators::{IndicatorEngine, IndicatorState};
 use crate::bot::logging::{EngineEvent, EngineEventLoggers};
-use crate::bot::risk::{best_ask_price, best_bid_price, midpoint_price, GatekeeperState, decimal_to_f64};
-use crate::bot::shadow::{ShadowPosition, TokenSide};
-use crate::bot::signal::SignalEngine;
-use crate::bot::strategy_runner::run_shadow_strategy_step;
+use crate::bot::maker::{cancel_all, handle_maker_signals, MakerConfig, MakerState};
+use crate::bot::pricing::{load_predictions, CalibrationReport, FairValuePrediction, PredictionLog};
+use crate::bot::research::feature_export::FeatureLog;
+use crate::bot::research::{LabelKind, ScoreSource, SupportedAsset, SupportedDuration};
+use crate::bot::resolution::ResolutionArgs;
+use crate::bot::risk::{best_ask_price, best_bid_price, midpoint_price, GatekeeperState};
+use crate::bot::shadow::{ShadowPosition, TokenSide, TouchFills};
+use crate::bot::signal::EntrySignal;
+use crate::bot::sizing::{PositionSizer, SizingArgs};
+use crate::bot::strategy::StrategyArgs;
+use crate::bot::strategy_runner::{run_driver_shadow_step, StrategyDriver};
+use crate::bot::portfolio::{run_portfolio, PortfolioArgs};
+use crate::bot::replay_log::{run_replay_log, ReplayLogArgs};
+use crate::bot::temporal_arb::{run_temporal_arb, TemporalArbArgs};
 use crate::bot::validation::ValidationTracker;
+use crate::persistence::StateStore;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::{Args, Subcommand};
@@ -37,22 +48,34 @@ pub struct BotArgs {
 
 #[derive(Subcommand)]
 pub enum BotCommand {
-    /// Watch the active 5-minute BTC "Up or Down" market and print live orderbook stats
+    /// Watch the active "Up or Down" market (BTC 5m by default) and print live orderbook stats
+    #[command(alias = "watch")]
     WatchBtc(LiveShadowArgs),
     /// Automated 20-market validation run with metrics export
     ValidateBtc(LiveShadowArgs),
     /// LIVE TRADING: Same strategy as watch-btc but places real market orders
+    #[command(alias = "trade")]
     TradeBtc(TradeBtcArgs),
-    /// LIVE TRADING: 15-minute BTC markets - same strategy as 5m
+    /// LIVE TRADING: 15-minute markets - shorthand for trade-btc --duration 15m
     TradeBtc15m(TradeBtcArgs),
+    /// LIVE TRADING: Several markets concurrently with a shared risk budget
+    TradePortfolio(PortfolioArgs),
+    /// Temporal arbitrage across nested 5m/15m/1h BTC markets (shadow unless --live)
+    TemporalArb(TemporalArbArgs),
     /// Run historical backtest with Becker dataset or mock data
     Backtest(BacktestArgs),
     /// Run Monte Carlo simulation on backtest results
     MonteCarlo(MonteCarloArgs),
     /// Run parameter sweep across entry bands
     Sweep(SweepArgs),
+    /// Grid/random/TPE search over any strategy's config against PMXT backtests
+    Optimize(OptimizeArgs),
+    /// Rolling train/test validation: optimize on each train window, score out of sample
+    WalkForward(WalkForwardArgs),
     /// Fetch filtered BTC Up/Down data from remote PMXT Parquet archive
     FetchPmxt(FetchPmxtArgs),
+    /// Sync and inspect the local PMXT data catalog
+    Data(DataArgs),
     /// Extract BTC midpoints from local Parquet using Gamma API for ID mapping
     ExtractMidpoints(ExtractMidpointsArgs),
     /// Inspect Parquet file schema and sample data
@@ -63,18 +86,36 @@ pub enum BotCommand {
     BacktestPipeline(BacktestPipelineArgs),
     /// Backtest across all PMXT parquet files in a directory with rolling bankroll
     BacktestPmxt(BacktestPmxtArgs),
+    /// Backtest TickRecorder session CSVs with the same metrics as backtest-pmxt
+    BacktestRecording(BacktestRecordingArgs),
+    /// Backtest temporal arbitrage over PMXT hours of nested 5m/15m/1h BTC markets
+    BacktestTemporal(BacktestTemporalArgs),
+    /// Fit Hawkes flow kernels per asset/duration from PMXT history for --hawkes-params
+    CalibrateHawkes(CalibrateHawkesArgs),
     /// Export features from PMXT archive to parquet for ML training
     ExportFeatures(ExportFeaturesArgs),
     /// Inspect exported feature parquet file
     InspectFeatures(InspectFeaturesArgs),
     /// Run backtest with Qlib scores
     BacktestScores(BacktestScoresArgs),
-    /// Shadow mode with Qlib scores (no live trading)
+    /// Shadow mode with Qlib scores, precomputed or inferred live (no live trading)
     ScoreShadow(ScoreShadowArgs),
+    /// Re-run a strategy over a recorded --event-log and diff its decisions
+    ReplayLog(ReplayLogArgs),
+    /// Brier decomposition, log loss and calibration curves over logged fair value predictions
+    CalibrationReport(CalibrationReportArgs),
 }
 
 #[derive(Args, Clone)]
 pub struct LiveShadowArgs {
+    /// Underlying asset of the Up/Down market family
+    #[arg(long, value_enum, default_value_t = SupportedAsset::Btc)]
+    pub asset: SupportedAsset,
+
+    /// Market window duration
+    #[arg(long, value_enum, default_value_t = SupportedDuration::M5)]
+    pub duration: SupportedDuration,
+
     /// Live feed mode for shadow validation
     #[arg(long, value_enum, default_value_t = LiveFeedMode::Websocket)]
     pub feed: LiveFeedMode,
@@ -103,9 +144,20 @@ pub struct LiveShadowArgs {
     #[arg(long, default_value = "recordings")]
     pub recordings_dir: String,
 
-    /// Strategy mode
-    #[arg(long, value_enum, default_value_t = crate::bot::pipeline::BtStrategy::Scalper)]
-    pub strategy: crate::bot::pipeline::BtStrategy,
+    /// Append every tick's feature row to this JSON-lines file
+    #[arg(long)]
+    pub features_out: Option<String>,
+
+    /// Append each entry's fair value prediction to this JSON-lines file,
+    /// for `bot calibration-report`
+    #[arg(long)]
+    pub predictions_out: Option<String>,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
 }
 
 
@@ -114,6 +166,14 @@ pub struct LiveShadowArgs {
 
 #[derive(Args, Clone)]
 pub struct TradeBtcArgs {
+    /// Underlying asset of the Up/Down market family
+    #[arg(long, value_enum, default_value_t = SupportedAsset::Btc)]
+    pub asset: SupportedAsset,
+
+    /// Market window duration (5m when omitted; trade-btc15m always uses 15m)
+    #[arg(long, value_enum)]
+    pub duration: Option<SupportedDuration>,
+
     /// Position size in USDC per trade
     #[arg(long, default_value = "1.0")]
     pub size: f64,
@@ -141,6 +201,41 @@ pub struct TradeBtcArgs {
     /// Start the engine in an emergency-halted state
     #[arg(long)]
     pub emergency_halt: bool,
+
+    /// SQLite database for crash-safe position and trade persistence
+    #[arg(long, default_value = "bot_state.db")]
+    pub state_db: String,
+
+    /// Order execution style: cross the spread (taker) or rest limit orders (maker)
+    #[arg(long, value_enum, default_value_t = ExecutionStyle::Taker)]
+    pub execution: ExecutionStyle,
+
+    /// Maker mode: ticks to improve on the best price (0 = join the touch)
+    #[arg(long, default_value = "0")]
+    pub maker_improve_ticks: u32,
+
+    /// Maker mode: ticks the quote may drift before a resting order is repriced
+    #[arg(long, default_value = "1")]
+    pub maker_reprice_ticks: u32,
+
+    /// Maker mode: cancel resting orders this many seconds before market end
+    #[arg(long, default_value = "20")]
+    pub maker_cancel_before_expiry: i64,
+
+    /// Append every tick's feature row to this JSON-lines file
+    #[arg(long)]
+    pub features_out: Option<String>,
+
+    /// Append each filled entry's fair value prediction to this JSON-lines
+    /// file, for `bot calibration-report`
+    #[arg(long)]
+    pub predictions_out: Option<String>,
+
+    #[command(flatten)]
+    pub sizing: SizingArgs,
+
+    #[command(flatten)]
+    pub strategy: StrategyArgs,
 }
 
 // Migrated to crate::bot::pipeline
@@ -174,13 +269,18 @@ pub struct ExportFeaturesArgs {
     #[arg(long)]
     pub manifest_out: Option<String>,
 
-    /// Asset filter (btc, eth, sol, xrp)
-    #[arg(long)]
-    pub asset: Option<String>,
+    /// Asset filter
+    #[arg(long, value_enum, default_value_t = CryptoAsset::All)]
+    pub asset: CryptoAsset,
 
     /// Duration filter (5m, 15m, 1h)
+    #[arg(long, value_enum)]
+    pub duration: Option<SupportedDuration>,
+
+    /// PMXT data directory whose catalog supplies the market windows,
+    /// instead of looking markets up one by one
     #[arg(long)]
-    pub duration: Option<String>,
+    pub catalog: Option<String>,
 
     /// Include labels in output
     #[arg(long)]
@@ -189,6 +289,33 @@ pub struct ExportFeaturesArgs {
     /// Include spot price data
     #[arg(long)]
     pub with_spot: bool,
+
+    /// Optional label families, comma separated
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub labels: Vec<LabelKind>,
+
+    /// Triple-barrier take-profit, as net edge per share
+    #[arg(long, default_value_t = 0.05)]
+    pub take_profit: f64,
+
+    /// Triple-barrier stop-loss, as net loss per share
+    #[arg(long, default_value_t = 0.05)]
+    pub stop_loss: f64,
+
+    /// Triple-barrier time barrier in seconds
+    #[arg(long, default_value_t = 60)]
+    pub max_holding: i64,
+
+    /// Taker fee rate the barrier, outcome and expected-value labels pay
+    #[arg(long, default_value_t = 0.02)]
+    pub taker_fee: f64,
+
+    /// Slippage rate those labels pay on entry
+    #[arg(long, default_value_t = 0.005)]
+    pub slippage: f64,
+
+    #[command(flatten)]
+    pub resolution: ResolutionArgs,
 }
 
 #[derive(Args, Clone)]
@@ -200,6 +327,32 @@ pub struct InspectFeaturesArgs {
     /// Number of samples to show
     #[arg(long, default_value = "10")]
     pub sample: usize,
+
+    /// Manifest to check the features against
+    #[arg(long)]
+    pub manifest: Option<String>,
+}
+
+#[derive(Args, Clone)]
+pub struct CalibrationReportArgs {
+    /// Prediction logs or monitor exports (repeat for more sessions)
+    #[arg(long, required = true)]
+    pub input: Vec<String>,
+
+    /// Probability bins of the calibration curves
+    #[arg(long, default_value = "10")]
+    pub bins: usize,
+
+    /// Write the calibration curves as CSV, one row per bin
+    #[arg(long)]
+    pub csv_out: Option<String>,
+
+    /// Write the full report as JSON
+    #[arg(long)]
+    pub json_out: Option<String>,
+
+    #[command(flatten)]
+    pub resolution: ResolutionArgs,
 }
 
 #[derive(Args, Clone)]
@@ -223,42 +376,61 @@ pub struct BacktestScoresArgs {
 
 #[derive(Args, Clone)]
 pub struct ScoreShadowArgs {
-    /// Scores parquet path
-    #[arg(long)]
-    pub scores: String,
+    /// Precomputed scores parquet from export_scores.py
+    #[arg(long, required_unless_present = "model", conflicts_with = "model")]
+    pub scores: Option<String>,
 
-    /// Asset to trade
-    #[arg(long, default_value = "btc")]
-    pub asset: String,
+    /// Model JSON from export_model.py, scored in process on features built
+    /// from the live book (repeat for more targets)
+    #[arg(long)]
+    pub model: Vec<String>,
 
-    /// Market duration
-    #[arg(long, default_value = "5m")]
-    pub duration: String,
+    /// Seconds a score stays fresh
+    #[arg(long, default_value = "300")]
+    pub score_ttl: i64,
 
-    /// Strategy mode (heuristic, qlib, fused)
-    #[arg(long, default_value = "fused")]
-    pub strategy: String,
+    #[command(flatten)]
+    pub live: LiveShadowArgs,
 }
 
 pub async fn execute(args: BotArgs) -> Result<()> {
     match args.command {
-        BotCommand::WatchBtc(live_args) => watch_btc_market(None, live_args).await,
-        BotCommand::ValidateBtc(live_args) => watch_btc_market(Some(20), live_args).await,
+        BotCommand::WatchBtc(live_args) => watch_btc_market(None, live_args, None).await,
+        BotCommand::ValidateBtc(live_args) => watch_btc_market(Some(20), live_args, None).await,
         BotCommand::TradeBtc(trade_args) => trade_btc_live(trade_args).await,
-        BotCommand::TradeBtc15m(trade_args) => trade_btc_live(trade_args).await,
+        BotCommand::TradeBtc15m(mut trade_args) => {
+            if let Some(duration) = trade_args.duration.filter(|d| *d != SupportedDuration::M15) {
+                anyhow::bail!(
+                    "trade-btc15m only trades 15m markets; use trade-btc --duration {} instead",
+                    duration.as_str()
+                );
+            }
+            trade_args.duration = Some(SupportedDuration::M15);
+            trade_btc_live(trade_args).await
+        }
+        BotCommand::TradePortfolio(portfolio_args) => run_portfolio(portfolio_args).await,
+        BotCommand::TemporalArb(arb_args) => run_temporal_arb(arb_args).await,
         BotCommand::Backtest(backtest_args) => run_backtest(backtest_args).await,
         BotCommand::MonteCarlo(mc_args) => run_monte_carlo(mc_args),
         BotCommand::Sweep(sweep_args) => run_parameter_sweep(sweep_args),
+        BotCommand::Optimize(optimize_args) => run_optimize(optimize_args).await,
+        BotCommand::WalkForward(walk_args) => run_walk_forward(walk_args).await,
         BotCommand::FetchPmxt(pmxt_args) => run_fetch_pmxt(pmxt_args).await,
+        BotCommand::Data(data_args) => run_data(data_args).await,
         BotCommand::ExtractMidpoints(extract_args) => run_extract_midpoints(extract_args).await,
         BotCommand::InspectParquet(inspect_args) => run_inspect_parquet(inspect_args),
         BotCommand::ListMarkets(list_args) => run_list_markets(list_args).await,
         BotCommand::BacktestPipeline(pipeline_args) => run_backtest_pipeline(pipeline_args).await,
         BotCommand::BacktestPmxt(pmxt_args) => run_backtest_pmxt(pmxt_args).await,
+        BotCommand::BacktestRecording(recording_args) => run_backtest_recording(recording_args).await,
+        BotCommand::BacktestTemporal(temporal_args) => run_backtest_temporal(temporal_args).await,
+        BotCommand::CalibrateHawkes(calibrate_args) => run_calibrate_hawkes(calibrate_args).await,
         BotCommand::ExportFeatures(export_args) => run_export_features(export_args).await,
         BotCommand::InspectFeatures(inspect_args) => run_inspect_features(inspect_args),
         BotCommand::BacktestScores(backtest_args) => run_backtest_scores(backtest_args).await,
         BotCommand::ScoreShadow(shadow_args) => run_score_shadow(shadow_args).await,
+        BotCommand::ReplayLog(replay_args) => run_replay_log(replay_args).await,
+        BotCommand::CalibrationReport(report_args) => run_calibration_report(report_args).await,
     }
 }
 
@@ -291,30 +463,64 @@ async fn create_live_input_source<'a>(
     }
 }
 
-async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs) -> Result<()> {
+/// Write the driver's feature row for the latest snapshot, if logging
+fn append_features(feature_log: &mut Option<FeatureLog>, driver: &mut StrategyDriver) {
+    if let (Some(log), Some(row)) = (feature_log.as_mut(), driver.take_features()) {
+        if let Err(err) = log.append(&row) {
+            eprintln!("[warn] Failed to write feature row: {err:#}");
+        }
+    }
+}
+
+/// Write the fair value prediction of an entry just taken, if logging
+fn append_prediction(
+    prediction_log: &mut Option<PredictionLog>,
+    prediction: Option<FairValuePrediction>,
+    watched: &crate::bot::discovery::WatchedMarket,
+) {
+    let (Some(log), Some(prediction)) = (prediction_log.as_mut(), prediction) else {
+        return;
+    };
+    let mut prediction = prediction.with_market_duration(watched.end_time.timestamp() - watched.start_ts());
+    prediction.condition_id = watched.condition_id.clone();
+    if let Err(err) = log.append(&prediction) {
+        eprintln!("[warn] Failed to write prediction: {err:#}");
+    }
+}
+
+async fn watch_btc_market(
+    max_markets: Option<usize>,
+    live_args: LiveShadowArgs,
+    scores: Option<Box<dyn ScoreSource + Send>>,
+) -> Result<()> {
     let gamma_client = gamma::Client::default();
     let clob_client = clob::Client::default();
     let event_loggers = create_event_loggers(live_args.event_log.as_deref())?;
+    let target = MarketTarget::new(live_args.asset, live_args.duration);
 
-    let mut watched = discover_market_loop(&gamma_client).await;
+    let mut watched = discover_market_loop(&gamma_client, target).await;
     let mut input_source = create_live_input_source(live_args.feed, &clob_client, &watched)
         .await
         .context("Failed to create live strategy input source")?;
 
     let mut validator = max_markets.map(ValidationTracker::new);
 
-    let mut ind_1m = IndicatorEngine::new();
-    let mut ind_5s = IndicatorEngine::new();
-
-    let (band_low, band_high) = match live_args.strategy {
-        crate::bot::pipeline::BtStrategy::Scalper => (0.35, 0.65),
-        crate::bot::pipeline::BtStrategy::LateWindow => (0.85, 0.98),
-        crate::bot::pipeline::BtStrategy::FairValue => (0.05, 0.95),
-    };
-    let mut signal_engine = SignalEngine::new_with_band(band_low, band_high);
-
-    let mut candle_engine = CandleEngine::new();
-    candle_engine.set_debug(false);
+    let strategy = live_args.strategy.config()?;
+    let mut driver = StrategyDriver::new(&strategy);
+    if let Some(source) = scores {
+        driver = driver.with_scores(source);
+    }
+    if let Some(spec) = watched.research_spec() {
+        driver.begin_market(spec);
+    }
+    let mut feature_log = live_args.features_out.as_deref().map(FeatureLog::create).transpose()?;
+    let sizer = PositionSizer::new(1.0, &live_args.sizing);
+    let session = format!("shadow-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
+    let mut prediction_log = live_args
+        .predictions_out
+        .as_deref()
+        .map(|path| PredictionLog::create(path, session))
+        .transpose()?;
 
     let mut shadow = ShadowPosition::default();
     let mut gatekeeper =
@@ -323,31 +529,15 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
         gatekeeper.halt();
     }
 
-    let mut state_1m = IndicatorState::default();
-    let mut state_5s = IndicatorState::default();
-
     let mut last_yes_bid = 0.0;
     let mut last_no_bid = 0.0;
     let mut current_slug = watched.slug.clone();
 
-    // FairValue model state (created per market)
-    let mut fv_model = crate::bot::pricing::LogitJumpDiffusion::new();
-    let mut fv_kalman = Some(crate::bot::pricing::KalmanFilter::new(0.0));
-    let mut fv_jump_calibrator = Some(crate::bot::pricing::JumpCalibrator::with_defaults());
-    let mut fv_calibrated = {
-        let base_model = crate::bot::pricing::FairValueModel::default();
-        crate::bot::pricing::CalibratedFairValue::with_defaults(base_model)
-    };
-    let mut fv_metrics = crate::bot::pipeline::PipelineMetrics::new(shadow.bankroll_usd);
-    let mut fv_cumulative_wins: Vec<f64> = Vec::new();
-    let mut fv_cumulative_losses: Vec<f64> = Vec::new();
-
     let mut ticker = interval(Duration::from_secs(1));
     ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
-    println!("[SHADOW MODE] Strategy: {:?}", live_args.strategy);
-    println!("[SHADOW MODE] Entry bands: {:.2} - {:.2}", band_low, band_high);
-    println!("[SHADOW MODE] Size: $1.00 | Feed: {:?}", live_args.feed);
+    println!("[SHADOW MODE] Strategy: {} ({})", strategy.kind(), strategy.describe());
+    println!("[SHADOW MODE] Size: {} | Feed: {:?}", sizer.describe(), live_args.feed);
     println!("========================================");
 
     // Create tick recorder if --record flag is set
@@ -431,26 +621,21 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
                     }
 
                     println!(
-                        "Market {} reached resolution time. Looking for next active BTC 5m market...",
-                        watched.slug
+                        "Market {} reached resolution time. Looking for next active {} market...",
+                        watched.slug, target
                     );
-                    watched = discover_market_loop(&gamma_client).await;
+                    watched = discover_market_loop(&gamma_client, target).await;
                     current_slug = watched.slug.clone();
                     input_source.shutdown().await;
                     input_source = create_live_input_source(live_args.feed, &clob_client, &watched)
                         .await
                         .context("Failed to recreate live strategy input source")?;
 
-                    signal_engine.reset();
-                    ind_1m.reset();
-                    ind_5s.reset();
+                    driver.reset();
+                    if let Some(spec) = watched.research_spec() {
+                        driver.begin_market(spec);
+                    }
                     shadow.full_reset();
-                    fv_model = crate::bot::pricing::LogitJumpDiffusion::new();
-                    fv_kalman = Some(crate::bot::pricing::KalmanFilter::new(0.0));
-                    fv_jump_calibrator = Some(crate::bot::pricing::JumpCalibrator::with_defaults());
-                    fv_metrics = crate::bot::pipeline::PipelineMetrics::new(shadow.bankroll_usd);
-                    state_1m = IndicatorState::default();
-                    state_5s = IndicatorState::default();
                     last_yes_bid = 0.0;
                     last_no_bid = 0.0;
 
@@ -466,6 +651,8 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
                         continue;
                     }
                 };
+                driver.record_trades(input_source.take_trades());
+                driver.record_depth(input_source.book_depth());
 
                 if let Some(midpoint) = midpoint_price(&dual_snapshot.yes) {
                     last_yes_bid = best_bid_price(&dual_snapshot.yes).unwrap_or(last_yes_bid);
@@ -511,54 +698,32 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
                         let no_max = no_ask * 0.10;
                         println!("[BOOK] YES: bid={:.4} ask={:.4} spread={:.4} max={:.4} | NO: bid={:.4} ask={:.4} spread={:.4} max={:.4} | mid={:.4}",
                             yes_bid, yes_ask, yes_spread, yes_max, no_bid, no_ask, no_spread, no_max, midpoint);
+                        if let Some(score) = driver.last_score() {
+                            println!("[SCORE] yes30={:.4} no30={:.4} signed={:+.4} | {}",
+                                score.score_yes_30s, score.score_no_30s, score.signed_score(), score.model_version);
+                        }
                     }
 
-                    if live_args.strategy == crate::bot::pipeline::BtStrategy::FairValue {
-                        // FairValue: use logit jump-diffusion model
-                        let prev_trades = fv_metrics.trades_taken;
-                        crate::bot::pipeline::process_fairvalue_snapshot(
-                            &mut fv_model,
-                            &mut fv_kalman,
-                            &mut fv_jump_calibrator,
-                            &mut fv_calibrated,
-                            &dual_snapshot,
-                            epoch_seconds,
-                            watched.end_time.timestamp() - FIVE_MINUTES_SECONDS,
-                            watched.end_time.timestamp(),
-                            &mut shadow,
-                            &mut fv_metrics,
-                            1.0,
-                            0.05,
-                            true,
-                            &mut fv_cumulative_wins,
-                            &mut fv_cumulative_losses,
-                        );
-                        // Update validator if trade happened
-                        if fv_metrics.trades_taken > prev_trades {
-                            if let Some(v) = &mut validator {
-                                v.record_signal();
-                                v.record_entry_taken();
-                            }
-                        }
-                    } else {
-                    if let Some(step) = run_shadow_strategy_step(
+                    let step = run_driver_shadow_step(
+                        &mut driver,
                         &dual_snapshot,
                         &watched.label,
                         &watched.slug,
-                        watched.end_time.timestamp() - FIVE_MINUTES_SECONDS,
+                        watched.start_ts(),
                         watched.end_time.timestamp(),
                         epoch_seconds,
-                        1.0,
-                        &mut candle_engine,
-                        &mut ind_1m,
-                        &mut ind_5s,
-                        &mut signal_engine,
-                        &mut state_1m,
-                        &mut state_5s,
+                        &sizer,
                         &mut shadow,
                         &mut gatekeeper,
+                        &mut TouchFills,
                         event_loggers.as_ref(),
-                    ) {
+                    );
+                    append_features(&mut feature_log, &mut driver);
+                    let prediction = driver.take_prediction();
+                    if step.as_ref().is_some_and(|step| step.entry_taken) {
+                        append_prediction(&mut prediction_log, prediction, &watched);
+                    }
+                    if let Some(step) = step {
                         if step.signal_seen {
                             if let Some(v) = &mut validator {
                                 v.record_signal();
@@ -589,7 +754,6 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
                             }
                         }
                     }
-                    } // end else (scalper/late-window strategy)
 
                     if shadow.is_active() {
                         let exit_price = match shadow.token_side {
@@ -629,6 +793,10 @@ async fn watch_btc_market(max_markets: Option<usize>, live_args: LiveShadowArgs)
 // Migrated to crate::bot::execution
 
 async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
+    if args.execution == ExecutionStyle::Maker && args.dry_run {
+        anyhow::bail!("--execution maker needs real resting orders and cannot be combined with --dry-run");
+    }
+
     let signer = auth::resolve_signer(None)?;
     let clob_client = auth::authenticate_with_signer(&signer, None).await?;
     let gamma_client = gamma::Client::default();
@@ -642,48 +810,123 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
         anyhow::bail!("Insufficient USDC balance: ${:.2} < ${:.2}", balance, args.size);
     }
 
-    let mut watched = discover_market_loop(&gamma_client).await;
+    let target = MarketTarget::new(args.asset, args.duration.unwrap_or(SupportedDuration::M5));
+    let mut watched = discover_market_loop(&gamma_client, target).await;
     let mut input_source = create_live_input_source(args.feed, &read_client, &watched)
         .await
         .context("Failed to create live trading input source")?;
 
-    let mut ind_1m = IndicatorEngine::new();
-    let mut ind_5s = IndicatorEngine::new();
-    let mut signal_engine = SignalEngine::new();
-    let mut candle_engine = CandleEngine::new();
-    candle_engine.set_debug(false);
+    let strategy = args.strategy.config()?;
+    let mut driver = StrategyDriver::new(&strategy);
+    if let Some(spec) = watched.research_spec() {
+        driver.begin_market(spec);
+    }
+    let mut feature_log = args.features_out.as_deref().map(FeatureLog::create).transpose()?;
+    let sizer = PositionSizer::new(args.size, &args.sizing);
+    let session = format!(
+        "{}-{}",
+        if args.dry_run { "dry-run" } else { "live" },
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    let mut prediction_log = args
+        .predictions_out
+        .as_deref()
+        .map(|path| PredictionLog::create(path, session))
+        .transpose()?;
 
     let mut position = LivePosition::default();
+    let mut maker_state = MakerState::default();
+    // Fills on resting orders and settlement sells are pushed over the user
+    // channel; no market filter so it survives rollovers without resubscribing
+    let mut user_feed = if args.dry_run {
+        None
+    } else {
+        maker_state.streamed = args.execution == ExecutionStyle::Maker;
+        Some(
+            UserWebsocketFeed::connect(clob_client.credentials(), Vec::new(), None)
+                .await
+                .context("Failed to subscribe to the user channel")?,
+        )
+    };
+    let maker_config = MakerConfig {
+        improve_ticks: args.maker_improve_ticks,
+        reprice_ticks: args.maker_reprice_ticks,
+        cancel_before_expiry_s: args.maker_cancel_before_expiry,
+        ..MakerConfig::default()
+    };
     let mut gatekeeper = GatekeeperState::new(args.daily_loss_limit, args.cooldown_seconds);
     if args.emergency_halt {
         gatekeeper.halt();
     }
-    let mut state_1m = IndicatorState::default();
-    let mut state_5s = IndicatorState::default();
     let mut current_slug = watched.slug.clone();
 
     let mut pending_settlements: Vec<PendingSettlement> = Vec::new();
 
+    // Dry runs never hold real tokens, so they skip persistence entirely
+    let state_store = if args.dry_run {
+        None
+    } else {
+        let now = Utc::now();
+        let store = open_state_store(&args.state_db, balance, &mut gatekeeper, now)?;
+
+        let recovered = recover_positions(
+            &store,
+            &clob_client,
+            &mut [(&watched, &mut position)],
+            &mut pending_settlements,
+            now,
+        )
+        .await
+        .context("Failed to reconcile persisted positions")?;
+        if recovered > 0 {
+            println!("[LIVE] Recovered {} persisted position(s)", recovered);
+        }
+        Some(store)
+    };
+    let state_store_ref = state_store.as_ref().map(|s| s as &dyn StateStore);
+
     let mut ticker = interval(Duration::from_secs(1));
     ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     if args.dry_run {
         println!("[LIVE *** DRY RUN ***] No orders will be placed");
     }
-    println!("[LIVE] Probability Expansion Scalper");
-    println!("[LIVE] Entry: slope > 0.002 + breakout | Exit: slope flip");
-    println!("[LIVE] Range: 0.35 - 0.65 | Size: ${:.2}", args.size);
+    println!("[LIVE] Strategy: {} ({}) | {}", strategy.kind(), strategy.describe(), target);
+    println!("[LIVE] Size: {}", sizer.describe());
     println!("[LIVE] Auto-sell enabled for pending positions");
-    println!("[LIVE] Feed: {:?}", args.feed);
+    println!("[LIVE] Feed: {:?} | Execution: {:?}", args.feed, args.execution);
     println!("========================================");
 
     loop {
         tokio::select! {
             _ = tokio::signal::ctrl_c() => {
                 println!("\n[LIVE] Stopping bot...");
+                if let Some(feed) = user_feed.take() {
+                    feed.shutdown().await;
+                }
+                if maker_state.has_resting() {
+                    match cancel_all(
+                        &clob_client,
+                        &mut maker_state,
+                        &mut position,
+                        &mut gatekeeper,
+                        event_loggers.as_ref(),
+                        state_store_ref,
+                        &watched,
+                        Utc::now().timestamp() as u64,
+                    )
+                    .await
+                    {
+                        Ok(()) => println!("[LIVE] Cancelled resting maker orders"),
+                        Err(err) => eprintln!("[LIVE] WARNING: Failed to cancel resting orders: {err:#}"),
+                    }
+                }
                 if position.is_active() {
                     println!("[LIVE] WARNING: Position still open! Manual exit required.");
                 }
+                if state_store.is_some() && (position.is_active() || !pending_settlements.is_empty()) {
+                    println!("[LIVE] Open positions are persisted in {} and will be recovered on restart.", args.state_db);
+                }
                 if !pending_settlements.is_empty() {
                     println!("[LIVE] WARNING: {} pending settlements require manual resolution!", pending_settlements.len());
                     for p in &pending_settlements {
@@ -695,6 +938,13 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
             _ = ticker.tick() => {
                 let now = Utc::now();
 
+                if let Some(feed) = user_feed.as_mut() {
+                    while let Some(event) = feed.try_recv() {
+                        maker_state.apply_user_event(&event);
+                        apply_settlement_event(&mut pending_settlements, &event);
+                    }
+                }
+
                 // Try to settle pending positions
                 if !pending_settlements.is_empty() && !args.dry_run {
                     try_settle_pending(
@@ -704,35 +954,36 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
                         &signer,
                         &mut gatekeeper,
                         event_loggers.as_ref(),
+                        state_store_ref,
+                        user_feed.is_some(),
                         now,
                     ).await;
                 }
 
                 // Check if current market ended
                 if now >= watched.end_time || watched.slug != current_slug {
-                    // Move active position to pending settlements
-                    if position.is_active() {
-                        let (token_id, token_side) = match position.token_side {
-                            Some(TokenSide::Yes) => (watched.yes_token_id, TokenSide::Yes),
-                            Some(TokenSide::No) => (watched.no_token_id, TokenSide::No),
-                            None => {
-                                position.full_reset();
-                                return Ok(());
-                            }
-                        };
-
-                        let pending = PendingSettlement {
-                            market_slug: watched.slug.clone(),
-                            token_side,
-                            token_id,
-                            shares: position.shares,
-                            entry_price: position.entry_price,
-                            condition_id: watched.condition_id.clone(),
-                            end_time: watched.end_time,
-                            sell_attempts: 0,
-                            created_at: now,
-                        };
+                    if maker_state.has_resting() {
+                        if let Err(err) = cancel_all(
+                            &clob_client,
+                            &mut maker_state,
+                            &mut position,
+                            &mut gatekeeper,
+                            event_loggers.as_ref(),
+                            state_store_ref,
+                            &watched,
+                            now.timestamp() as u64,
+                        )
+                        .await
+                        {
+                            eprintln!("[warn] Failed to cancel resting orders for {}: {err:#}", watched.slug);
+                        }
+                    }
 
+                    // Move active position to pending settlements, along with
+                    // any partial exits so the round trip is booked as one trade
+                    let prior_exits = std::mem::take(&mut maker_state.round_trip);
+                    if let Some(mut pending) = PendingSettlement::from_position(&watched, &position, now) {
+                        pending.prior_exits = prior_exits;
                         println!(
                             "[PENDING] {} | {:?} | {:.4} shares @ {:.4} | Auto-sell queued",
                             pending.market_slug, pending.token_side, pending.shares, pending.entry_price
@@ -745,18 +996,17 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
                     position.full_reset();
 
                     println!("[LIVE] Market {} ended. Looking for next market...", watched.slug);
-                    watched = discover_market_loop(&gamma_client).await;
+                    watched = discover_market_loop(&gamma_client, target).await;
                     current_slug = watched.slug.clone();
                     input_source.shutdown().await;
                     input_source = create_live_input_source(args.feed, &read_client, &watched)
                         .await
                         .context("Failed to recreate live trading input source")?;
 
-                    signal_engine.reset();
-                    ind_1m.reset();
-                    ind_5s.reset();
-                    state_1m = IndicatorState::default();
-                    state_5s = IndicatorState::default();
+                    driver.reset();
+                    if let Some(spec) = watched.research_spec() {
+                        driver.begin_market(spec);
+                    }
 
                     println!("[MARKET RESET] All engines cleared | {}", watched.slug);
                     if !pending_settlements.is_empty() {
@@ -774,10 +1024,10 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
                         continue;
                     }
                 };
+                driver.record_trades(input_source.take_trades());
+                driver.record_depth(input_source.book_depth());
 
                 if let Some(midpoint) = midpoint_price(&dual_snapshot.yes) {
-                    let spread_f64 = dual_snapshot.yes.spread.map(decimal_to_f64).unwrap_or(0.0);
-                    let simulated_volume = decimal_to_f64(dual_snapshot.yes.top5_bid_depth + dual_snapshot.yes.top5_ask_depth);
                     let epoch_seconds = input_source.current_time().unwrap_or(now.timestamp() as u64);
 
                     if let Some(loggers) = &event_loggers {
@@ -801,42 +1051,70 @@ async fn trade_btc_live(args: TradeBtcArgs) -> Result<()> {
                             yes_bid, yes_ask, no_bid, no_ask, midpoint);
                     }
 
-                    let closed_candles = candle_engine.update(midpoint, spread_f64, simulated_volume, epoch_seconds);
-
-                    if let Some(closed) = closed_candles {
-                        if let Some(c) = closed.one_minute {
-                            state_1m = ind_1m.update(&c);
+                    let mut fresh_signal = driver.step(
+                        &dual_snapshot,
+                        &watched.slug,
+                        watched.end_time.timestamp(),
+                        epoch_seconds,
+                        event_loggers.as_ref(),
+                    );
+                    append_features(&mut feature_log, &mut driver);
+                    let open_before = position.token_side;
+                    // Resting maker entries are not rejections; they report once filled
+                    let entry_sent = args.execution == ExecutionStyle::Taker
+                        && fresh_signal.as_ref().is_some_and(|signal| signal.entry != EntrySignal::None);
+                    // Entries are staked against the persisted bankroll when there is one
+                    let entry_size = match fresh_signal.as_mut() {
+                        Some(signal) if !position.is_active() => {
+                            let bankroll = state_store_ref
+                                .and_then(|store| store.get_bankroll().ok())
+                                .unwrap_or(balance);
+                            sizer.size_signal(signal, driver.entry_quote(), bankroll)
                         }
+                        _ => args.size,
+                    };
+                    if let (Some(signal), ExecutionStyle::Taker) = (&fresh_signal, args.execution) {
+                        handle_live_signals(
+                            signal,
+                            &dual_snapshot,
+                            &mut position,
+                            &mut gatekeeper,
+                            event_loggers.as_ref(),
+                            state_store_ref,
+                            &watched,
+                            epoch_seconds,
+                            entry_size,
+                            args.dry_run,
+                            &clob_client,
+                            &signer,
+                        ).await;
+                    }
 
-                        if let Some(c) = closed.five_second {
-                            state_5s = ind_5s.update(&c);
-
-                            let signal = signal_engine.update(&state_5s, &state_1m, midpoint);
-                            if let Some(loggers) = &event_loggers {
-                                loggers.log_strategy(EngineEvent::StrategySignal {
-                                    ts: epoch_seconds,
-                                    market_slug: watched.slug.clone(),
-                                    midpoint,
-                                    entry: format!("{:?}", signal.entry),
-                                    exit: format!("{:?}", signal.exit),
-                                    detail: "live_signal_engine".to_string(),
-                                });
-                            }
-
-                            handle_live_signals(
-                                &signal,
-                                &dual_snapshot,
-                                &mut position,
-                                &mut gatekeeper,
-                                event_loggers.as_ref(),
-                                &watched,
-                                epoch_seconds,
-                                args.size,
-                                args.dry_run,
-                                &clob_client,
-                                &signer,
-                            ).await;
-                        }
+                    // Maker orders are managed every tick, not only when the strategy decides
+                    if args.execution == ExecutionStyle::Maker {
+                        handle_maker_signals(
+                            fresh_signal.as_ref(),
+                            &dual_snapshot,
+                            &mut maker_state,
+                            &mut position,
+                            &mut gatekeeper,
+                            event_loggers.as_ref(),
+                            state_store_ref,
+                            &watched,
+                            &maker_config,
+                            epoch_seconds,
+                            entry_size,
+                            &clob_client,
+                            &signer,
+                        ).await;
+                    }
+                    driver.sync_position(
+                        entry_sent,
+                        open_before,
+                        position.token_side.map(|side| (side, position.entry_price)),
+                    );
+                    if open_before.is_none() && position.token_side.is_some() {
+                        append_prediction(&mut prediction_log, driver.take_prediction(), &watched);
                     }
 
                     if position.is_active() {
@@ -900,14 +1178,42 @@ async fn run_backtest_pipeline(args: BacktestPipelineArgs) -> Result<()> {
 // ============================================================================
 
 async fn run_export_features(args: ExportFeaturesArgs) -> Result<()> {
-    use crate::bot::research::{FeatureExporter, ResearchConfig};
+    use crate::bot::research::{BarrierConfig, CostModel, FeatureExporter, LabelConfig, ResearchConfig};
     use std::path::PathBuf;
 
     println!("[EXPORT] Input: {}", args.input);
     println!("[EXPORT] Output: {}", args.out);
 
     let config = ResearchConfig::default();
-    let exporter = FeatureExporter::new(config);
+    let label_config = LabelConfig {
+        kinds: args.labels.clone(),
+        barrier: BarrierConfig {
+            take_profit: args.take_profit,
+            stop_loss: args.stop_loss,
+            max_holding_s: args.max_holding,
+        },
+        ..Default::default()
+    };
+    if !label_config.kinds.is_empty() {
+        println!("[EXPORT] Extra labels: {:?}", label_config.kinds);
+    }
+    let cost_model = CostModel::new(args.taker_fee, CostModel::default().maker_fee, args.slippage);
+    let mut exporter = FeatureExporter::new(config).with_label_config(label_config, cost_model);
+    let markets = pipeline::resolve_pmxt_markets(&args.input, args.catalog.as_deref(), args.asset, args.duration.as_slice(), false).await?;
+    if markets.is_empty() {
+        anyhow::bail!("No markets with known windows in {}", args.input);
+    }
+    println!("[EXPORT] Markets: {}", markets.len());
+    let rows = exporter.read(&args.input, &markets)?;
+    let resolver = args.resolution.resolver()?;
+    if resolver.is_none() && args.labels.contains(&LabelKind::Outcome) {
+        eprintln!("[warn] Outcome labels need official resolutions; they stay empty with --no-resolve");
+    }
+    if let Some(mut resolver) = resolver {
+        resolver.resolve(rows.iter().map(|r| r.condition_id.as_str())).await?;
+        println!("[EXPORT] Official outcomes: {} markets resolved", resolver.resolutions().len());
+        exporter = exporter.with_resolutions(resolver.into_resolutions());
+    }
 
     let manifest_path = args.manifest_out
         .map(PathBuf::from)
@@ -915,10 +1221,11 @@ async fn run_export_features(args: ExportFeaturesArgs) -> Result<()> {
 
     let manifest_str = manifest_path.to_str().unwrap_or("");
     let (feature_count, label_count) = exporter
-        .export(&args.input, &args.out, manifest_str)?;
+        .export(&rows, &args.out, manifest_str)?;
 
-    println!("[EXPORT] Wrote {} feature rows", feature_count);
-    println!("[EXPORT] Wrote {} label rows", label_count);
+    let out = std::path::Path::new(&args.out);
+    println!("[EXPORT] Wrote {} feature rows to {}", feature_count, FeatureExporter::features_path(out).display());
+    println!("[EXPORT] Wrote {} label rows to {}", label_count, FeatureExporter::labels_path(out).display());
     println!("[EXPORT] Manifest: {:?}", manifest_path);
 
     Ok(())
@@ -926,7 +1233,49 @@ async fn run_export_features(args: ExportFeaturesArgs) -> Result<()> {
 
 fn run_inspect_features(args: InspectFeaturesArgs) -> Result<()> {
     use crate::bot::research::feature_export::inspect_features;
-    inspect_features(&args.input, args.sample)
+    inspect_features(&args.input, args.sample, args.manifest.as_deref())
+}
+
+async fn run_calibration_report(args: CalibrationReportArgs) -> Result<()> {
+    let mut predictions = Vec::new();
+    for path in &args.input {
+        let loaded = load_predictions(path)?;
+        println!("[CALIBRATION] {}: {} predictions", path, loaded.len());
+        predictions.extend(loaded);
+    }
+
+    // Fill outcomes the sessions could not know when they ran
+    if let Some(mut resolver) = args.resolution.resolver()? {
+        let pending: Vec<String> = predictions
+            .iter()
+            .filter(|p| p.outcome.is_none())
+            .filter_map(|p| p.condition_id.clone())
+            .collect();
+        if !pending.is_empty() {
+            resolver.resolve(pending.iter().map(String::as_str)).await?;
+        }
+        let resolutions = resolver.into_resolutions();
+        let mut filled = 0;
+        for prediction in predictions.iter_mut().filter(|p| p.outcome.is_none()) {
+            if let Some(yes_won) = prediction.condition_id.as_deref().and_then(|id| resolutions.yes_won(id)) {
+                prediction.outcome = Some(yes_won);
+                filled += 1;
+            }
+        }
+        println!("[CALIBRATION] Official outcomes: {} predictions resolved", filled);
+    }
+
+    let report = CalibrationReport::build(&predictions, args.bins);
+    println!("{}", report);
+    if let Some(path) = &args.csv_out {
+        report.write_csv(path)?;
+        println!("[CALIBRATION] Curves: {}", path);
+    }
+    if let Some(path) = &args.json_out {
+        report.write_json(path)?;
+        println!("[CALIBRATION] Report: {}", path);
+    }
+    Ok(())
 }
 
 async fn run_backtest_scores(args: BacktestScoresArgs) -> Result<()> {
@@ -953,28 +1302,29 @@ async fn run_backtest_scores(args: BacktestScoresArgs) -> Result<()> {
 }
 
 async fn run_score_shadow(args: ScoreShadowArgs) -> Result<()> {
-    use crate::bot::research::{FusionMode, FusionEngine, FusionConfig};
-
-    println!("[SHADOW] Asset: {}", args.asset);
-    println!("[SHADOW] Duration: {}", args.duration);
-    println!("[SHADOW] Scores: {}", args.scores);
-    println!("[SHADOW] Strategy: {}", args.strategy);
-
-    let mode = match args.strategy.to_lowercase().as_str() {
-        "heuristic" => FusionMode::HeuristicOnly,
-        "qlib" => FusionMode::QlibOnly,
-        "fused" => FusionMode::Fused,
-        _ => anyhow::bail!("Invalid strategy mode: {}", args.strategy),
+    use crate::bot::research::{ModelScorer, ScoreLoader};
+    use crate::bot::strategy::StrategyKind;
+
+    let source: Box<dyn ScoreSource + Send> = match &args.scores {
+        Some(path) => {
+            let mut loader = ScoreLoader::new(path, args.score_ttl);
+            let count = loader.load(std::path::Path::new(path))?;
+            println!("[SHADOW] Scores: {} ({} precomputed rows)", path, count);
+            Box::new(loader)
+        }
+        None => {
+            let scorer = ModelScorer::load(&args.model, args.score_ttl)?;
+            println!("[SHADOW] Models: {}", scorer.describe());
+            Box::new(scorer)
+        }
     };
+    println!("[SHADOW] Asset: {}", args.live.asset.as_str());
+    println!("[SHADOW] Duration: {}", args.live.duration.as_str());
+    if args.live.strategy.strategy != StrategyKind::Fused {
+        eprintln!("[warn] --strategy {} ignores model scores; use --strategy fused", args.live.strategy.strategy);
+    }
 
-    let config = FusionConfig::default();
-    let _engine = FusionEngine::new(mode, config);
-
-    println!("[SHADOW] Mode: {:?}", mode);
-    println!("[SHADOW] Note: Shadow mode runs without live trading");
-    println!("[SHADOW] Decision logging enabled");
-
-    Ok(())
+    watch_btc_market(None, args.live, Some(source)).await
 }
 
 // Migrated to crate::bot::pipeline
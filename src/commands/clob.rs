@@ -16,6 +16,7 @@ use polymarket_client_sdk::types::{Decimal, U256};
 
 use super::parse_condition_id;
 use crate::auth;
+use crate::bot::feed::UserWebsocketFeed;
 use crate::output::OutputFormat;
 use crate::output::clob::{
     print_account_status, print_api_keys, print_balance, print_batch_prices, print_cancel_result,
@@ -26,7 +27,7 @@ use crate::output::clob::{
     print_order_scoring, print_orders, print_orders_scoring, print_post_order_result,
     print_post_orders_result, print_price, print_price_history, print_reward_percentages,
     print_rewards, print_server_time, print_simplified_markets, print_spread, print_spreads,
-    print_tick_size, print_trades, print_user_earnings_markets,
+    print_tick_size, print_trades, print_user_earnings_markets, print_user_event,
 };
 
 #[derive(Args)]
@@ -293,6 +294,13 @@ pub enum ClobCommand {
         cursor: Option<String>,
     },
 
+    /// Stream our order placements, matches and cancellations (authenticated)
+    WatchOrders {
+        /// Only watch these market condition IDs (comma-separated; default: all)
+        #[arg(long)]
+        markets: Option<String>,
+    },
+
     /// Get balance and allowance (authenticated)
     Balance {
         /// Asset type: collateral or conditional
@@ -529,6 +537,7 @@ pub async fn execute(
         | ClobCommand::CancelAll
         | ClobCommand::CancelMarket { .. }
         | ClobCommand::Trades { .. }
+        | ClobCommand::WatchOrders { .. }
         | ClobCommand::Balance { .. }
         | ClobCommand::UpdateBalance { .. }
         | ClobCommand::Notifications
@@ -929,6 +938,24 @@ async fn execute_trade(
             print_trades(&result, output)?;
         }
 
+        ClobCommand::WatchOrders { markets } => {
+            let client = auth::authenticated_clob_client(private_key, signature_type).await?;
+            let markets = markets
+                .map(|m| m.split(',').map(|id| id.trim().to_string()).collect())
+                .unwrap_or_default();
+            let mut feed = UserWebsocketFeed::connect(client.credentials(), markets, None).await?;
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => break,
+                    event = feed.recv() => {
+                        let Some(event) = event else { break };
+                        print_user_event(&event, output)?;
+                    }
+                }
+            }
+            feed.shutdown().await;
+        }
+
         ClobCommand::Balance { asset_type, token } => {
             let is_collateral = matches!(asset_type, CliAssetType::Collateral);
             let client = auth::authenticated_clob_client(private_key, signature_type).await?;
@@ -17,6 +17,7 @@ use tabled::settings::Style;
 use tabled::{Table, Tabled};
 
 use super::{OutputFormat, format_decimal, truncate};
+use crate::bot::feed::UserEvent;
 
 /// Base64-encoded empty cursor returned by the CLOB API when there are no more pages.
 const END_CURSOR: &str = "LTE=";
@@ -1465,3 +1466,32 @@ pub fn print_account_status(
     }
     Ok(())
 }
+
+/// One line per event so `--output json` streams as JSON Lines
+pub fn print_user_event(event: &UserEvent, output: &OutputFormat) -> anyhow::Result<()> {
+    match output {
+        OutputFormat::Table => match event {
+            UserEvent::Order(order) => println!(
+                "[ORDER {:?}] {} | {:?} {:.2}/{:.2} @ {:.4} | asset {}",
+                order.action,
+                order.order_id,
+                order.side,
+                order.size_matched,
+                order.original_size,
+                order.price,
+                truncate(&order.asset_id, 16),
+            ),
+            UserEvent::Trade(trade) => println!(
+                "[TRADE {}] {} | {:?} {:.2} @ {:.4} | asset {}",
+                trade.status,
+                trade.trade_id,
+                trade.side,
+                trade.size,
+                trade.price,
+                truncate(&trade.asset_id, 16),
+            ),
+        },
+        OutputFormat::Json => println!("{}", serde_json::to_string(event)?),
+    }
+    Ok(())
+}
@@ -28,6 +28,24 @@ impl From<&str> for TokenSide {
     }
 }
 
+impl From<crate::bot::shadow::TokenSide> for TokenSide {
+    fn from(side: crate::bot::shadow::TokenSide) -> Self {
+        match side {
+            crate::bot::shadow::TokenSide::Yes => TokenSide::Yes,
+            crate::bot::shadow::TokenSide::No => TokenSide::No,
+        }
+    }
+}
+
+impl From<&TokenSide> for crate::bot::shadow::TokenSide {
+    fn from(side: &TokenSide) -> Self {
+        match side {
+            TokenSide::Yes => crate::bot::shadow::TokenSide::Yes,
+            TokenSide::No => crate::bot::shadow::TokenSide::No,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeLog {
     pub id: Option<i64>,
@@ -41,10 +59,11 @@ pub struct TradeLog {
     pub timestamp_exit: i64,
     pub order_id_entry: String,
     pub order_id_exit: Option<String>,
-    pub slippage_entry_bps: i64,
-    pub slippage_exit_bps: i64,
-    pub latency_entry_ms: i64,
-    pub latency_exit_ms: i64,
+    /// Fill slippage and submit-to-fill latency; `None` when not measured
+    pub slippage_entry_bps: Option<i64>,
+    pub slippage_exit_bps: Option<i64>,
+    pub latency_entry_ms: Option<i64>,
+    pub latency_exit_ms: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,8 +76,8 @@ pub struct PositionState {
     pub size_usd: f64,
     pub entry_order_id: String,
     pub entry_timestamp: i64,
-    pub entry_slippage_bps: i64,
-    pub entry_latency_ms: i64,
+    pub entry_slippage_bps: Option<i64>,
+    pub entry_latency_ms: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +116,8 @@ impl Default for BotState {
 pub trait StateStore: Send + Sync {
     fn save_position(&self, pos: &PositionState) -> Result<()>;
     fn load_active_position(&self) -> Result<Option<PositionState>>;
+    fn load_positions(&self) -> Result<Vec<PositionState>>;
+    fn remove_position(&self, token_id: U256) -> Result<()>;
     fn clear_position(&self) -> Result<()>;
     
     fn save_trade(&self, trade: &TradeLog) -> Result<i64>;
@@ -5,6 +5,41 @@ use std::sync::Mutex;
 
 use super::{BotState, PositionState, StateStore, TokenSide, TradeLog};
 
+const CREATE_POSITIONS: &str = r#"
+    CREATE TABLE IF NOT EXISTS positions (
+        token_id TEXT PRIMARY KEY,
+        market_slug TEXT NOT NULL,
+        token_side TEXT NOT NULL,
+        size REAL NOT NULL,
+        entry_price REAL NOT NULL,
+        size_usd REAL NOT NULL,
+        entry_order_id TEXT NOT NULL,
+        entry_timestamp INTEGER NOT NULL,
+        entry_slippage_bps INTEGER,
+        entry_latency_ms INTEGER
+    );
+"#;
+
+const CREATE_TRADES: &str = r#"
+    CREATE TABLE IF NOT EXISTS trades (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        market_slug TEXT NOT NULL,
+        token_side TEXT NOT NULL,
+        entry_price REAL NOT NULL,
+        exit_price REAL NOT NULL,
+        size_usd REAL NOT NULL,
+        pnl_usd REAL NOT NULL,
+        timestamp_entry INTEGER NOT NULL,
+        timestamp_exit INTEGER NOT NULL,
+        order_id_entry TEXT NOT NULL,
+        order_id_exit TEXT,
+        slippage_entry_bps INTEGER,
+        slippage_exit_bps INTEGER,
+        latency_entry_ms INTEGER,
+        latency_exit_ms INTEGER
+    );
+"#;
+
 pub struct SqliteStore {
     conn: Mutex<Connection>,
 }
@@ -41,39 +76,15 @@ impl SqliteStore {
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?;
 
+        // Older databases declared the fill metrics NOT NULL
+        Self::rebuild_if_not_null(&conn, "positions", "entry_slippage_bps", CREATE_POSITIONS)?;
+        Self::rebuild_if_not_null(&conn, "trades", "slippage_exit_bps", CREATE_TRADES)?;
+
+        conn.execute_batch(CREATE_POSITIONS)
+            .and_then(|_| conn.execute_batch(CREATE_TRADES))
+            .context("Failed to initialize database schema")?;
         conn.execute_batch(
             r#"
-            CREATE TABLE IF NOT EXISTS positions (
-                token_id TEXT PRIMARY KEY,
-                market_slug TEXT NOT NULL,
-                token_side TEXT NOT NULL,
-                size REAL NOT NULL,
-                entry_price REAL NOT NULL,
-                size_usd REAL NOT NULL,
-                entry_order_id TEXT NOT NULL,
-                entry_timestamp INTEGER NOT NULL,
-                entry_slippage_bps INTEGER NOT NULL,
-                entry_latency_ms INTEGER NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS trades (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                market_slug TEXT NOT NULL,
-                token_side TEXT NOT NULL,
-                entry_price REAL NOT NULL,
-                exit_price REAL NOT NULL,
-                size_usd REAL NOT NULL,
-                pnl_usd REAL NOT NULL,
-                timestamp_entry INTEGER NOT NULL,
-                timestamp_exit INTEGER NOT NULL,
-                order_id_entry TEXT NOT NULL,
-                order_id_exit TEXT,
-                slippage_entry_bps INTEGER NOT NULL,
-                slippage_exit_bps INTEGER NOT NULL,
-                latency_entry_ms INTEGER NOT NULL,
-                latency_exit_ms INTEGER NOT NULL
-            );
-
             CREATE TABLE IF NOT EXISTS bot_state (
                 id INTEGER PRIMARY KEY CHECK (id = 1),
                 bankroll REAL NOT NULL,
@@ -98,6 +109,34 @@ impl SqliteStore {
         Ok(())
     }
 
+    /// Recreate `table` from `create_sql`, keeping its rows, when `column`
+    /// still carries a NOT NULL constraint
+    fn rebuild_if_not_null(conn: &Connection, table: &str, column: &str, create_sql: &str) -> Result<()> {
+        let not_null: bool = conn
+            .query_row(
+                "SELECT \"notnull\" FROM pragma_table_info(?1) WHERE name = ?2",
+                params![table, column],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("Failed to inspect table {}", table))?
+            .unwrap_or(false);
+        if !not_null {
+            return Ok(());
+        }
+
+        conn.execute_batch(&format!(
+            "BEGIN;
+             ALTER TABLE {table} RENAME TO {table}_old;
+             {create_sql}
+             INSERT INTO {table} SELECT * FROM {table}_old;
+             DROP TABLE {table}_old;
+             COMMIT;"
+        ))
+        .with_context(|| format!("Failed to migrate table {}", table))?;
+        Ok(())
+    }
+
     fn initialize_bot_state_if_missing(&self) -> Result<()> {
         let conn = self
             .conn
@@ -137,6 +176,25 @@ impl SqliteStore {
     fn format_token_id(id: polymarket_client_sdk::types::U256) -> String {
         id.to_string()
     }
+
+    fn row_to_position(row: &rusqlite::Row<'_>) -> rusqlite::Result<PositionState> {
+        let token_id_str: String = row.get(0)?;
+        let token_id = Self::parse_token_id(&token_id_str)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+
+        Ok(PositionState {
+            token_id,
+            market_slug: row.get(1)?,
+            token_side: TokenSide::from(row.get::<_, String>(2)?.as_str()),
+            size: row.get(3)?,
+            entry_price: row.get(4)?,
+            size_usd: row.get(5)?,
+            entry_order_id: row.get(6)?,
+            entry_timestamp: row.get(7)?,
+            entry_slippage_bps: row.get(8)?,
+            entry_latency_ms: row.get(9)?,
+        })
+    }
 }
 
 impl StateStore for SqliteStore {
@@ -191,30 +249,55 @@ impl StateStore for SqliteStore {
             .context("Failed to prepare position query")?;
 
         let result = stmt
-            .query_row([], |row| {
-                let token_id_str: String = row.get(0)?;
-                let token_id = Self::parse_token_id(&token_id_str)
-                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
-
-                Ok(PositionState {
-                    token_id,
-                    market_slug: row.get(1)?,
-                    token_side: TokenSide::from(row.get::<_, String>(2)?.as_str()),
-                    size: row.get(3)?,
-                    entry_price: row.get(4)?,
-                    size_usd: row.get(5)?,
-                    entry_order_id: row.get(6)?,
-                    entry_timestamp: row.get(7)?,
-                    entry_slippage_bps: row.get(8)?,
-                    entry_latency_ms: row.get(9)?,
-                })
-            })
+            .query_row([], Self::row_to_position)
             .optional()
             .context("Failed to load position")?;
 
         Ok(result)
     }
 
+    fn load_positions(&self) -> Result<Vec<PositionState>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?;
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT token_id, market_slug, token_side, size, entry_price,
+                       size_usd, entry_order_id, entry_timestamp,
+                       entry_slippage_bps, entry_latency_ms
+                FROM positions
+                ORDER BY entry_timestamp ASC
+                "#,
+            )
+            .context("Failed to prepare positions query")?;
+
+        let positions = stmt
+            .query_map([], Self::row_to_position)
+            .context("Failed to query positions")?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to collect positions")?;
+
+        Ok(positions)
+    }
+
+    fn remove_position(&self, token_id: polymarket_client_sdk::types::U256) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?;
+
+        conn.execute(
+            "DELETE FROM positions WHERE token_id = ?1",
+            params![Self::format_token_id(token_id)],
+        )
+        .context("Failed to remove position")?;
+
+        Ok(())
+    }
+
     fn clear_position(&self) -> Result<()> {
         let conn = self
             .conn
@@ -483,8 +566,8 @@ mod tests {
             size_usd: 1.0,
             entry_order_id: "order-123".to_string(),
             entry_timestamp: 1700000000,
-            entry_slippage_bps: 5,
-            entry_latency_ms: 150,
+            entry_slippage_bps: Some(5),
+            entry_latency_ms: Some(150),
         };
 
         store.save_position(&pos).expect("Failed to save position");
@@ -514,8 +597,8 @@ mod tests {
             size_usd: 1.0,
             entry_order_id: "order-456".to_string(),
             entry_timestamp: 1700000000,
-            entry_slippage_bps: 3,
-            entry_latency_ms: 200,
+            entry_slippage_bps: Some(3),
+            entry_latency_ms: Some(200),
         };
 
         store.save_position(&pos).expect("Failed to save");
@@ -529,6 +612,39 @@ mod tests {
         assert!(loaded.is_none());
     }
 
+    #[test]
+    fn test_load_and_remove_positions() {
+        let store = SqliteStore::in_memory().expect("Failed to create store");
+
+        for (id, ts) in [(111u64, 1700000300), (222u64, 1700000000)] {
+            let pos = PositionState {
+                token_id: polymarket_client_sdk::types::U256::from(id),
+                market_slug: format!("btc-updown-5m-{}", ts),
+                token_side: TokenSide::Yes,
+                size: 2.0,
+                entry_price: 0.50,
+                size_usd: 1.0,
+                entry_order_id: format!("order-{}", id),
+                entry_timestamp: ts,
+                entry_slippage_bps: None,
+                entry_latency_ms: None,
+            };
+            store.save_position(&pos).expect("Failed to save");
+        }
+
+        let positions = store.load_positions().expect("Failed to load");
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].entry_order_id, "order-222");
+
+        store
+            .remove_position(polymarket_client_sdk::types::U256::from(222u64))
+            .expect("Failed to remove");
+
+        let positions = store.load_positions().expect("Failed to load");
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].entry_order_id, "order-111");
+    }
+
     #[test]
     fn test_save_and_get_trades() {
         let store = SqliteStore::in_memory().expect("Failed to create store");
@@ -545,10 +661,10 @@ mod tests {
             timestamp_exit: 1700000100,
             order_id_entry: "entry-123".to_string(),
             order_id_exit: Some("exit-456".to_string()),
-            slippage_entry_bps: 5,
-            slippage_exit_bps: 3,
-            latency_entry_ms: 150,
-            latency_exit_ms: 120,
+            slippage_entry_bps: Some(5),
+            slippage_exit_bps: None,
+            latency_entry_ms: Some(150),
+            latency_exit_ms: None,
         };
 
         let id = store.save_trade(&trade).expect("Failed to save trade");
@@ -558,6 +674,8 @@ mod tests {
         assert_eq!(trades.len(), 1);
         assert_eq!(trades[0].market_slug, "btc-updown-5m-test");
         assert_eq!(trades[0].pnl_usd, 0.10);
+        assert_eq!(trades[0].slippage_entry_bps, Some(5));
+        assert_eq!(trades[0].slippage_exit_bps, None);
 
         let count = store.get_trade_count().expect("Failed to count");
         assert_eq!(count, 1);
@@ -622,6 +740,49 @@ mod tests {
         assert_eq!(state.daily_pnl, 0.0);
     }
 
+    #[test]
+    fn test_not_null_metrics_are_migrated() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                market_slug TEXT NOT NULL,
+                token_side TEXT NOT NULL,
+                entry_price REAL NOT NULL,
+                exit_price REAL NOT NULL,
+                size_usd REAL NOT NULL,
+                pnl_usd REAL NOT NULL,
+                timestamp_entry INTEGER NOT NULL,
+                timestamp_exit INTEGER NOT NULL,
+                order_id_entry TEXT NOT NULL,
+                order_id_exit TEXT,
+                slippage_entry_bps INTEGER NOT NULL,
+                slippage_exit_bps INTEGER NOT NULL,
+                latency_entry_ms INTEGER NOT NULL,
+                latency_exit_ms INTEGER NOT NULL
+            );
+            INSERT INTO trades VALUES (1, 'old', 'YES', 0.5, 0.6, 1.0, 0.2, 1, 2, 'e', NULL, 4, 2, 100, 90);
+            "#,
+        )
+        .unwrap();
+        let store = SqliteStore {
+            conn: Mutex::new(conn),
+        };
+        store.initialize_schema().expect("Failed to migrate schema");
+        store.initialize_bot_state_if_missing().unwrap();
+
+        let mut trade = store.get_trades(None).unwrap().remove(0);
+        assert_eq!(trade.market_slug, "old");
+        assert_eq!(trade.latency_exit_ms, Some(90));
+
+        trade.id = None;
+        trade.slippage_exit_bps = None;
+        trade.latency_exit_ms = None;
+        store.save_trade(&trade).expect("NULL metrics should be accepted");
+        assert_eq!(store.get_trade_count().unwrap(), 2);
+    }
+
     #[test]
     fn test_token_side_conversion() {
         assert!(matches!(TokenSide::from("YES"), TokenSide::Yes));
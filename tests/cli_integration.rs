@@ -496,3 +496,12 @@ fn bot_help_lists_subcommands() {
         .success()
         .stdout(predicate::str::contains("watch-btc"));
 }
+
+#[test]
+fn trade_btc15m_rejects_other_durations() {
+    polymarket()
+        .args(["bot", "trade-btc15m", "--duration", "5m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only trades 15m markets"));
+}